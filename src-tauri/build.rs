@@ -1,3 +1,21 @@
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
 fn main() {
+  bundle_mermaid_runtime();
   tauri_build::build()
 }
+
+/// 把前端依赖里的 mermaid 运行时拷到 OUT_DIR，导出 HTML 时内嵌进去
+///
+/// 只编译 Rust、没装前端依赖时写一个空文件，导出时会提示运行时不可用
+fn bundle_mermaid_runtime() {
+  let source = Path::new("../node_modules/mermaid/dist/mermaid.min.js");
+  println!("cargo:rerun-if-changed=../package.json");
+  if source.exists() {
+    println!("cargo:rerun-if-changed={}", source.display());
+  }
+  let out = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo")).join("mermaid.min.js");
+  let runtime = fs::read_to_string(source).unwrap_or_default();
+  fs::write(out, runtime).expect("failed to write mermaid runtime to OUT_DIR");
+}
@@ -0,0 +1,456 @@
+// ============================
+// 命令行参数：从终端打开文件 / 文件夹
+// ============================
+//
+//   cc-md-preview README.md docs/guide.md      两个文件各开一个标签
+//   cc-md-preview docs/                        打开文件夹（卡片网格）
+//   cc-md-preview spec.md --anchor install     打开后跳到 #install 标题
+//   cc-md-preview spec.md --line 120           打开后跳到第 120 行附近
+//   gen-report | cc-md-preview - --base-dir .  预览 stdin 的内容（不落盘，见 InlineDocument）
+//
+// 子命令（不开窗口，直接在终端输出，见 headless.rs）：
+//   cc-md-preview list docs/ --json            列出文件夹里的 Markdown 文件
+//   cc-md-preview stats README.md              字数、阅读时间等统计
+//   cc-md-preview cache info --profile "Profile 1"
+//   cc-md-preview cache clean --all --dry-run
+//   cc-md-preview export html spec.md -o spec.html --theme nord
+//   cc-md-preview export site docs/ -o public/
+//   cc-md-preview export pdf spec.md --page-size letter --margin "1in 0.75in"
+//   cc-md-preview export epub book/ -o book.epub
+//
+// 传递给前端的方式：「存起来 + 事件通知」
+//   dispatch() 把 OpenRequest 存进 PendingLaunch（按窗口 label 区分），再发 cli://open 事件；
+//   前端在挂载时和收到事件时都调用 take_launch_request 取走请求。
+//   这样无论前端是否已经加载完，请求都不会丢。
+
+use crate::export::html::{image_data_uri, image_mime, MermaidMode};
+use crate::export::pdf::{DEFAULT_FOOTER, DEFAULT_MARGINS, DEFAULT_PAGE_SIZE};
+use crate::export::themes::DEFAULT_THEME;
+use crate::markdown::headings;
+use crate::markdown::links::{
+    is_external, normalize_path, percent_decode, rebase_dest, rewrite_link_targets, split_fragment,
+};
+use crate::platform::cache_paths;
+use clap::{Args, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_fs::FsExt;
+
+/// 通知前端有新的打开请求（payload 为空，前端调用 take_launch_request 取）
+pub const CLI_OPEN_EVENT: &str = "cli://open";
+
+/// 主窗口的 label（tauri.conf.json 里第一个窗口的默认 label）
+pub const MAIN_WINDOW: &str = "main";
+
+#[derive(Parser, Debug, Clone, Default)]
+#[command(
+    name = "cc-md-preview",
+    version,
+    about = "Lightweight Markdown preview",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    /// Run a command in the terminal instead of opening a window
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Markdown files to open as tabs, or a folder to open in the card grid.
+    /// Use "-" to read a document from stdin
+    pub paths: Vec<PathBuf>,
+
+    /// Resolve relative links in a stdin document against this folder
+    /// (default: the current directory)
+    #[arg(long)]
+    pub base_dir: Option<PathBuf>,
+
+    /// Scroll the first file to this line after opening
+    #[arg(long, conflicts_with = "anchor")]
+    pub line: Option<u32>,
+
+    /// Scroll the first file to this heading anchor (e.g. "installation")
+    #[arg(long)]
+    pub anchor: Option<String>,
+
+    /// Open in a new window instead of the current one
+    #[arg(long)]
+    pub new_window: bool,
+
+    /// Start a separate instance instead of handing off to a running one
+    #[arg(long)]
+    pub standalone: bool,
+}
+
+/// 不开窗口的子命令，输出写到 stdout，错误写到 stderr
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// List Markdown files under a folder
+    List {
+        dir: PathBuf,
+        /// Print MarkdownFileInfo records as a JSON array
+        #[arg(long)]
+        json: bool,
+        /// Only list files matching a metadata query, e.g. "tag:rfc AND date>=2026-01-01"
+        #[arg(long)]
+        filter: Option<String>,
+    },
+    /// Print word count, reading time and structure counts of a Markdown file
+    Stats {
+        file: PathBuf,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Inspect or clean the browser cache
+    #[command(subcommand)]
+    Cache(CacheCommand),
+    /// Export a document to another format
+    #[command(subcommand)]
+    Export(ExportCommand),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ExportCommand {
+    /// Export a single self-contained HTML file
+    Html {
+        file: PathBuf,
+        /// Output file (default: next to the source with a .html extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Theme id, e.g. "github", "nord", "dracula"
+        #[arg(long, default_value = DEFAULT_THEME)]
+        theme: String,
+        /// How to handle ```mermaid blocks
+        #[arg(long, value_enum, default_value_t = MermaidMode::Code)]
+        mermaid: MermaidMode,
+    },
+    /// Export a paginated PDF with bookmarks and clickable links
+    Pdf {
+        file: PathBuf,
+        /// Output file (default: next to the source with a .pdf extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// "a4", "letter", "a5-landscape", or explicit "210x297mm" / "8.5x11in"
+        #[arg(long, default_value = DEFAULT_PAGE_SIZE)]
+        page_size: String,
+        /// 1–4 lengths like CSS: "20mm", "1in 0.75in"
+        #[arg(long = "margin", default_value = DEFAULT_MARGINS)]
+        margins: String,
+        /// Header text; placeholders: {page} {pages} {title} {file} {date}
+        #[arg(long)]
+        header: Option<String>,
+        /// Footer text (same placeholders; "" for none)
+        #[arg(long, default_value = DEFAULT_FOOTER)]
+        footer: String,
+        /// Body font file (.ttf / .otf / .ttc) instead of the system default
+        #[arg(long)]
+        font: Option<PathBuf>,
+        /// Monospace font file for code
+        #[arg(long)]
+        mono_font: Option<PathBuf>,
+        /// Fallback font file for Chinese / Japanese / Korean text
+        #[arg(long)]
+        cjk_font: Option<PathBuf>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export a folder as a static site (incremental when run again)
+    Site {
+        root: PathBuf,
+        /// Output folder
+        #[arg(short, long)]
+        output: PathBuf,
+        #[arg(long, default_value = DEFAULT_THEME)]
+        theme: String,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export a document or a folder as an EPUB 3 e-book (chapter order from SUMMARY.md if present)
+    Epub {
+        path: PathBuf,
+        /// Output file (default: next to the source with a .epub extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(long, default_value = DEFAULT_THEME)]
+        theme: String,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum CacheCommand {
+    /// Show cache sizes per category
+    Info {
+        #[command(flatten)]
+        target: CacheTarget,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete cached files
+    Clean {
+        #[command(flatten)]
+        target: CacheTarget,
+        /// Category to clean, e.g. "HTTP Cache" (repeatable)
+        #[arg(long = "category", required_unless_present = "all")]
+        categories: Vec<String>,
+        /// Clean every category
+        #[arg(long, conflicts_with = "categories")]
+        all: bool,
+        /// Report what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct CacheTarget {
+    /// Browser name: "Chrome" or "Chrome Canary"
+    #[arg(long, default_value = "Chrome")]
+    pub browser: String,
+    /// Profile directory name, e.g. "Default" or "Profile 1"
+    #[arg(long, default_value = "Default", value_parser = parse_profile)]
+    pub profile: String,
+}
+
+/// --profile 只能是一个目录名：带路径分隔符或 ".." 的会跑到浏览器数据目录外面去
+fn parse_profile(value: &str) -> Result<String, String> {
+    if cache_paths::is_valid_profile(value) {
+        Ok(value.to_string())
+    } else {
+        Err("must be a single profile directory name, e.g. \"Profile 1\"".to_string())
+    }
+}
+
+/// 解析好的打开请求（路径都已经是绝对路径），发给前端
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenRequest {
+    /// 要作为标签打开的文件
+    pub files: Vec<String>,
+    /// 要以卡片网格打开的文件夹
+    pub folder: Option<String>,
+    /// 不对应磁盘文件的文档（从 stdin 读入）
+    #[serde(default)]
+    pub documents: Vec<InlineDocument>,
+    /// 第一个文件打开后跳转的行号
+    pub line: Option<u32>,
+    /// 第一个文件打开后跳转的标题锚点
+    pub anchor: Option<String>,
+    pub new_window: bool,
+}
+
+/// 只存在于内存里的文档，前端作为一个普通标签打开，但不会写进会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineDocument {
+    /// 标签的唯一标识，形如 "stdin://1"（不是文件路径）
+    pub id: String,
+    /// 标签标题：文档的第一个标题，没有标题时为 "stdin"
+    pub title: String,
+    /// 文档内容：相对链接已经改写成基于 base_dir 的绝对路径，本地图片内嵌为 data: URI
+    pub content: String,
+    pub base_dir: String,
+}
+
+/// InlineDocument id 的序号，保证同一个进程里多次从 stdin 打开的标签互不覆盖
+static NEXT_DOCUMENT: AtomicU32 = AtomicU32::new(1);
+
+/// 内嵌为 data: URI 的图片格式：前端 markdown-it 默认只放行这几种 data: 图片
+const INLINE_IMAGE_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// 单张图片内嵌的大小上限，更大的保留为路径（不显示）
+const MAX_INLINE_IMAGE: u64 = 8 * 1024 * 1024;
+
+impl InlineDocument {
+    pub fn new(content: &str, base_dir: &Path) -> InlineDocument {
+        let title = headings::parse_headings(content)
+            .into_iter()
+            .next()
+            .map(|h| h.text)
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| "stdin".to_string());
+        InlineDocument {
+            id: format!("stdin://{}", NEXT_DOCUMENT.fetch_add(1, Ordering::Relaxed)),
+            title,
+            content: rewrite_link_targets(content, |dest, image| {
+                let inlined = if image {
+                    inline_image(dest, base_dir)
+                } else {
+                    None
+                };
+                inlined.or_else(|| rebase_dest(dest, base_dir))
+            }),
+            base_dir: base_dir.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// stdin 文档里的本地图片 → `<data:…>`
+///
+/// stdin 文档没有文件位置，webview 按路径加载不到图片，也不为它开放 fs 插件的目录权限，
+/// 所以直接把图片内容放进文档
+fn inline_image(dest: &str, base_dir: &Path) -> Option<String> {
+    if is_external(dest) {
+        return None;
+    }
+    let (target, _) = split_fragment(dest);
+    let file = base_dir.join(percent_decode(target));
+    if !INLINE_IMAGE_TYPES.contains(&image_mime(&file)?) {
+        return None;
+    }
+    if file.metadata().ok()?.len() > MAX_INLINE_IMAGE {
+        return None;
+    }
+    image_data_uri(dest, base_dir).map(|uri| format!("<{}>", uri))
+}
+
+impl OpenRequest {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.folder.is_none() && self.documents.is_empty()
+    }
+
+    /// 前端还没来得及取走上一个请求时，把新请求合并进去（文件追加，其余以新的为准）
+    fn merge(&mut self, newer: OpenRequest) {
+        self.files.extend(newer.files);
+        self.documents.extend(newer.documents);
+        if newer.folder.is_some() {
+            self.folder = newer.folder;
+        }
+        if newer.line.is_some() || newer.anchor.is_some() {
+            self.line = newer.line;
+            self.anchor = newer.anchor;
+        }
+    }
+}
+
+impl Cli {
+    /// 参数里有没有 "-"（需要读 stdin）
+    pub fn reads_stdin(&self) -> bool {
+        self.paths.iter().any(|p| p.as_os_str() == "-")
+    }
+
+    /// 把参数里的路径解析成绝对路径
+    ///
+    /// cwd 是「调用命令的那个 shell」的工作目录：第一次启动时就是本进程的 cwd，
+    /// 单实例转发时则是第二个进程传过来的 cwd。stdin 同理，是调用方进程读到的内容。
+    /// 不存在的路径打印警告后跳过，不影响其他参数。
+    pub fn into_request(self, cwd: &Path, stdin: Option<String>) -> OpenRequest {
+        let mut request = OpenRequest {
+            line: self.line,
+            anchor: self.anchor.map(|a| a.trim_start_matches('#').to_string()),
+            new_window: self.new_window,
+            ..Default::default()
+        };
+        let base_dir = normalize_path(&cwd.join(self.base_dir.unwrap_or_default()));
+        let mut stdin = stdin;
+
+        for path in self.paths {
+            if path.as_os_str() == "-" {
+                // 重复写了多个 "-" 时只有第一个能读到内容
+                if let Some(content) = stdin.take() {
+                    request.documents.push(InlineDocument::new(&content, &base_dir));
+                }
+                continue;
+            }
+            let absolute = normalize_path(&cwd.join(&path));
+            if absolute.is_dir() {
+                match request.folder {
+                    None => request.folder = Some(absolute.to_string_lossy().to_string()),
+                    Some(_) => eprintln!(
+                        "cc-md-preview: only one folder can be opened, ignoring '{}'",
+                        path.display()
+                    ),
+                }
+            } else if absolute.is_file() {
+                request.files.push(absolute.to_string_lossy().to_string());
+            } else {
+                eprintln!("cc-md-preview: '{}' does not exist", path.display());
+            }
+        }
+        request
+    }
+}
+
+/// 还没被前端取走的打开请求，键是窗口 label
+#[derive(Default)]
+pub struct PendingLaunch(pub Mutex<HashMap<String, OpenRequest>>);
+
+/// 把命令行传进来的路径加入 fs 插件的允许范围
+///
+/// 前端之后可能直接用 plugin-fs 读这些文件（或文件夹里的图片），
+/// 用户在命令行里明确指定了它们，等同于在对话框里选中
+pub fn grant_access(app: &AppHandle, request: &OpenRequest) {
+    let scope = app.fs_scope();
+    for file in &request.files {
+        let _ = scope.allow_file(file);
+        // 同目录的图片等相对资源也要能读
+        if let Some(dir) = Path::new(file).parent() {
+            let _ = scope.allow_directory(dir, false);
+        }
+    }
+    if let Some(folder) = &request.folder {
+        let _ = scope.allow_directory(folder, true);
+    }
+    // stdin 文档不开放任何目录：它的图片已经内嵌（见 inline_image），
+    // 按 --base-dir（默认是 cwd，可能就是整个家目录）开放等于让前端能读那下面的所有文件
+}
+
+/// 把打开请求交给窗口：--new-window 时新建窗口，否则交给主窗口并聚焦
+///
+/// 没有任何路径时只聚焦主窗口（单实例下再次运行 `cc-md-preview` 的效果）
+pub fn dispatch(app: &AppHandle, request: OpenRequest) -> tauri::Result<()> {
+    if request.is_empty() && !request.new_window {
+        if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        }
+        return Ok(());
+    }
+    grant_access(app, &request);
+
+    let label = if request.new_window {
+        let label = next_window_label(app);
+        WebviewWindowBuilder::new(app, &label, WebviewUrl::default())
+            .title("CC MD Preview")
+            .inner_size(1200.0, 800.0)
+            .min_inner_size(800.0, 600.0)
+            .build()?;
+        label
+    } else {
+        MAIN_WINDOW.to_string()
+    };
+
+    if let Some(pending) = app.try_state::<PendingLaunch>() {
+        if let Ok(mut map) = pending.0.lock() {
+            match map.get_mut(&label) {
+                Some(existing) => existing.merge(request),
+                None => {
+                    map.insert(label.clone(), request);
+                }
+            }
+        }
+    }
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    app.emit_to(label.as_str(), CLI_OPEN_EVENT, ())
+}
+
+/// 新窗口的 label：window-2、window-3……（capabilities 里用 "window-*" 匹配）
+fn next_window_label(app: &AppHandle) -> String {
+    (2..)
+        .map(|n| format!("window-{}", n))
+        .find(|label| app.get_webview_window(label).is_none())
+        .expect("unbounded range always yields a free label")
+}
+
+/// 当前工作目录；取不到时（比如目录已经被删掉）退回用户主目录
+pub fn current_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| dirs::home_dir().unwrap_or_default())
+}
@@ -55,9 +55,19 @@ pub async fn detect_browsers() -> Result<Vec<BrowserInfo>, String> {
 }
 
 #[tauri::command]
-pub async fn get_cache_info(browser: Option<String>) -> Result<CacheInfo, String> {
-    let browser = browser.as_deref().unwrap_or("Chrome");
-    let categories_config = cache_paths::get_cache_categories_for_browser(browser, "Default");
+pub async fn get_cache_info(
+    browser: Option<String>,
+    profile: Option<String>,
+) -> Result<CacheInfo, String> {
+    Ok(cache_info(
+        browser.as_deref().unwrap_or("Chrome"),
+        profile.as_deref().unwrap_or("Default"),
+    ))
+}
+
+/// Computes cache sizes for a browser profile. Shared by the IPC command and the headless CLI.
+pub fn cache_info(browser: &str, profile: &str) -> CacheInfo {
+    let categories_config = cache_paths::get_cache_categories_for_browser(browser, profile);
     let mut categories = Vec::new();
     let mut total_size: u64 = 0;
     let mut total_files: u64 = 0;
@@ -80,12 +90,12 @@ pub async fn get_cache_info(browser: Option<String>) -> Result<CacheInfo, String
         });
     }
 
-    Ok(CacheInfo {
-        profile: "Default".to_string(),
+    CacheInfo {
+        profile: profile.to_string(),
         categories,
         total_size,
         total_files,
-    })
+    }
 }
 
 #[tauri::command]
@@ -129,7 +139,7 @@ pub async fn list_cache_entries(
         }
     }
 
-    entries.sort_by(|a, b| b.size.cmp(&a.size));
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size));
     Ok(entries)
 }
 
@@ -137,14 +147,32 @@ pub async fn list_cache_entries(
 pub async fn clean_cache(
     cache_types: Vec<String>,
     browser: Option<String>,
+    profile: Option<String>,
 ) -> Result<CleanResult, String> {
-    let browser = browser.as_deref().unwrap_or("Chrome");
-    let categories = cache_paths::get_cache_categories_for_browser(browser, "Default");
+    Ok(clean(
+        &cache_types,
+        browser.as_deref().unwrap_or("Chrome"),
+        profile.as_deref().unwrap_or("Default"),
+        false,
+    ))
+}
+
+/// Deletes the contents of the given cache categories. With `dry_run`, only counts
+/// what would be deleted. Shared by the IPC command and the headless CLI.
+pub fn clean(cache_types: &[String], browser: &str, profile: &str, dry_run: bool) -> CleanResult {
+    if !cache_paths::is_valid_profile(profile) {
+        return CleanResult {
+            deleted_files: 0,
+            freed_bytes: 0,
+            errors: vec![format!("Invalid profile name: {}", profile)],
+        };
+    }
+    let categories = cache_paths::get_cache_categories_for_browser(browser, profile);
     let mut deleted_files: u64 = 0;
     let mut freed_bytes: u64 = 0;
     let mut errors = Vec::new();
 
-    for cache_type in &cache_types {
+    for cache_type in cache_types {
         if let Some((_, path)) = categories.iter().find(|(name, _)| name == cache_type) {
             if path.exists() {
                 match fs::read_dir(path) {
@@ -158,7 +186,9 @@ pub async fn clean_cache(
                                     } else {
                                         calculate_dir_size(&entry_path).0
                                     };
-                                    let result = if meta.is_file() {
+                                    let result = if dry_run {
+                                        Ok(())
+                                    } else if meta.is_file() {
                                         fs::remove_file(&entry_path)
                                     } else {
                                         fs::remove_dir_all(&entry_path)
@@ -186,14 +216,16 @@ pub async fn clean_cache(
                     Err(e) => errors.push(format!("{}: {}", path.display(), e)),
                 }
             }
+        } else {
+            errors.push(format!("Unknown cache type: {}", cache_type));
         }
     }
 
-    Ok(CleanResult {
+    CleanResult {
         deleted_files,
         freed_bytes,
         errors,
-    })
+    }
 }
 
 #[tauri::command]
@@ -0,0 +1,20 @@
+// ============================
+// 命令行打开请求 — Rust IPC 命令
+// ============================
+//
+// 前端调用：invoke("take_launch_request")
+// 返回：当前窗口待处理的 OpenRequest（取走后清空），没有则为 null
+//
+// 前端在挂载时调用一次，之后每收到 cli://open 事件再调用一次，详见 src/cli.rs
+
+use crate::cli::{OpenRequest, PendingLaunch};
+use tauri::{State, Window};
+
+#[tauri::command]
+pub async fn take_launch_request(
+    window: Window,
+    pending: State<'_, PendingLaunch>,
+) -> Result<Option<OpenRequest>, String> {
+    let mut map = pending.0.lock().map_err(|e| e.to_string())?;
+    Ok(map.remove(window.label()))
+}
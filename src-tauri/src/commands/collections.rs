@@ -0,0 +1,131 @@
+// ============================
+// 智能集合 — Rust IPC 命令
+// ============================
+//
+// 集合定义存在设置里（state/settings.rs 的 SmartCollection），
+// 求值、索引和文件监听在 state/collections.rs，这里只是命令包装。
+//
+// 前端的用法：
+//   1. 打开文件夹后调用 open_collections，拿到各集合的计数
+//   2. 监听 collections://changed，文件或集合定义变化时计数自动更新
+//   3. 点某个集合时调用 get_collection_files 列出匹配的文件
+
+use crate::commands::markdown::{file_info, spawn_git_history, MarkdownFileInfo};
+use crate::git::{self, History};
+use crate::markdown::headings::slugify;
+use crate::markdown::query::Query;
+use crate::state::collections::{self, CollectionIndex, CollectionsUpdate};
+use crate::state::settings::{Settings, SettingsStore, SmartCollection, SETTINGS_CHANGED_EVENT};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+
+// 前端调用：invoke("open_collections", { root: "/abs/path/to/notes" })
+// 返回：CollectionsUpdate（每个集合在 root 里的匹配数）
+//
+// 同时开始监听 root，之后的变化通过 collections://changed 推送
+#[tauri::command]
+pub async fn open_collections(
+    root: String,
+    app: AppHandle,
+    index: State<'_, CollectionIndex>,
+    store: State<'_, SettingsStore>,
+) -> Result<CollectionsUpdate, String> {
+    index.open(&app, Path::new(&root))?;
+    index
+        .counts(&store.get().collections)
+        .ok_or_else(|| "Collection index is not available".to_string())
+}
+
+// 前端调用：invoke("get_collection_files", { root: "/abs/path/to/notes", id: "recent-rfcs" })
+// 返回：匹配的文件（和 list_markdown_files 的格式一样），按相对路径排序
+#[tauri::command]
+pub async fn get_collection_files(
+    root: String,
+    id: String,
+    app: AppHandle,
+    index: State<'_, CollectionIndex>,
+    store: State<'_, SettingsStore>,
+) -> Result<Vec<MarkdownFileInfo>, String> {
+    let collection = store
+        .get()
+        .collections
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| format!("Unknown collection '{}'", id))?;
+    let root_path = Path::new(&root);
+    let paths = index.matching(&app, root_path, &collection)?;
+    let mut git_infos = git::file_infos(root_path, &paths, History::Cached);
+    let mut files: Vec<MarkdownFileInfo> = paths
+        .iter()
+        .filter_map(|path| file_info(root_path, path, git_infos.remove(path)))
+        .collect();
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    spawn_git_history(&app, root_path, paths);
+    Ok(files)
+}
+
+// 前端调用：invoke("save_collection", { collection: { id: "", name: "Recent RFCs", query: "path:rfcs/** modified>=this-month" } })
+// 返回：保存后的全部集合
+//
+// id 已存在时替换（保持原来的位置），否则加到末尾；id 为空时按名字生成。
+// 查询写错时返回错误，不保存
+#[tauri::command]
+pub async fn save_collection(
+    collection: SmartCollection,
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+) -> Result<Vec<SmartCollection>, String> {
+    let mut collection = collection;
+    collection.name = collection.name.trim().to_string();
+    if collection.name.is_empty() {
+        return Err("Collection name is empty".to_string());
+    }
+    Query::parse(&collection.query)?;
+
+    let settings = store.modify(|settings| {
+        let list = &mut settings.collections;
+        if collection.id.is_empty() {
+            collection.id = unique_id(list, &collection.name);
+        }
+        match list.iter_mut().find(|c| c.id == collection.id) {
+            Some(existing) => *existing = collection,
+            None => list.push(collection),
+        }
+    })?;
+    broadcast(&app, settings)
+}
+
+// 前端调用：invoke("delete_collection", { id: "recent-rfcs" })
+// 返回：删除后的全部集合
+#[tauri::command]
+pub async fn delete_collection(
+    id: String,
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+) -> Result<Vec<SmartCollection>, String> {
+    let settings = store.modify(|settings| settings.collections.retain(|c| c.id != id))?;
+    broadcast(&app, settings)
+}
+
+/// 设置已经写回：广播 settings://changed 和新的计数
+fn broadcast(app: &AppHandle, settings: Settings) -> Result<Vec<SmartCollection>, String> {
+    app.emit(SETTINGS_CHANGED_EVENT, &settings)
+        .map_err(|e| e.to_string())?;
+    collections::notify_changed(app);
+    Ok(settings.collections)
+}
+
+/// 名字的 slug，和已有的重复时加 -1、-2……
+fn unique_id(list: &[SmartCollection], name: &str) -> String {
+    let base = match slugify(name) {
+        slug if slug.is_empty() => "collection".to_string(),
+        slug => slug,
+    };
+    let mut id = base.clone();
+    let mut n = 0;
+    while list.iter().any(|c| c.id == id) {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+    id
+}
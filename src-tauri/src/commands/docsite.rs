@@ -0,0 +1,23 @@
+// ============================
+// 文档站点 — Rust IPC 命令
+// ============================
+//
+// 打开的文件夹是 mdBook / MkDocs / VitePress / Docusaurus 站点（或在站点里面）时，
+// 侧边栏按站点配置里的目录显示，而不是按文件名排序。识别和解析都在 docsite 模块里。
+
+use crate::docsite::SiteToc;
+use crate::state::roots;
+use std::path::Path;
+
+// 前端调用：invoke("get_doc_site", { root: "/abs/path/to/book" })
+// 返回：SiteToc（站点类型、源文件目录、有序目录、阅读顺序），不是文档站点时返回 null
+//
+// root 可以是站点里的任意目录或文件，会往上找最近的站点配置
+#[tauri::command]
+pub async fn get_doc_site(root: String) -> Result<Option<SiteToc>, String> {
+    let path = Path::new(&root);
+    if !path.exists() {
+        return Err(format!("'{}' does not exist", root));
+    }
+    Ok(roots::site_for(path).map(|site| site.table_of_contents()))
+}
@@ -0,0 +1,147 @@
+// ============================
+// 导出模块 — Rust IPC 命令
+// ============================
+//
+// 渲染逻辑都在 export/ 下，这里只负责补全默认选项（主题取当前设置）
+
+use crate::export::epub::{self, EpubExport, EpubOptions};
+use crate::export::html::{self, HtmlOptions, MermaidMode};
+use crate::export::pdf::{self, FontPaths, PdfExport, PdfOptions};
+use crate::export::site::{self, SiteExport, SiteOptions};
+use crate::state::settings::SettingsStore;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// export_html 的选项，字段都可以省略
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportHtmlOptions {
+    /// 主题 id；省略时用设置里的当前主题
+    pub theme: Option<String>,
+    pub mermaid: MermaidMode,
+    /// 输出文件路径；省略时写到源文件旁边的同名 .html
+    pub output: Option<String>,
+}
+
+// 前端调用：invoke("export_html", { path: "/abs/spec.md", options: { mermaid: "embed" } })
+// 返回：写出的 .html 文件的绝对路径
+#[tauri::command]
+pub async fn export_html(
+    path: String,
+    options: ExportHtmlOptions,
+    settings: State<'_, SettingsStore>,
+) -> Result<String, String> {
+    let source = Path::new(&path);
+    let output = options
+        .output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| html::default_output(source));
+    let options = HtmlOptions {
+        theme: options.theme.unwrap_or_else(|| settings.get().theme),
+        mermaid: options.mermaid,
+    };
+    html::export_html(source, &output, &options)?;
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// export_pdf 的选项，字段都可以省略
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportPdfOptions {
+    /// "a4"（默认）/ "letter" / "a5-landscape" / "210x297mm"
+    pub page_size: Option<String>,
+    /// 和 CSS 一样 1–4 个值，默认 "20mm"
+    pub margins: Option<String>,
+    /// 页眉 / 页脚模板，可用 {page} {pages} {title} {file} {date}；
+    /// 页脚省略时为 "{page} / {pages}"，传空字符串表示不要
+    pub header: Option<String>,
+    pub footer: Option<String>,
+    /// 字体文件，省略时自动在系统字体里找
+    pub font: Option<String>,
+    pub mono_font: Option<String>,
+    pub cjk_font: Option<String>,
+    /// 输出文件路径；省略时写到源文件旁边的同名 .pdf
+    pub output: Option<String>,
+}
+
+// 前端调用：invoke("export_pdf", { path: "/abs/spec.md", options: { pageSize: "letter" } })
+// 返回：PdfExport（输出路径、页数、警告）
+//
+// 排版和写文件都在后端完成，不经过 WebView 打印
+#[tauri::command]
+pub async fn export_pdf(path: String, options: ExportPdfOptions) -> Result<PdfExport, String> {
+    let source = Path::new(&path);
+    let output = options
+        .output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| pdf::default_output(source));
+    let fonts = FontPaths {
+        regular: options.font.map(PathBuf::from),
+        mono: options.mono_font.map(PathBuf::from),
+        cjk: options.cjk_font.map(PathBuf::from),
+    };
+    let options = PdfOptions::parse(
+        options
+            .page_size
+            .as_deref()
+            .unwrap_or(pdf::DEFAULT_PAGE_SIZE),
+        options.margins.as_deref().unwrap_or(pdf::DEFAULT_MARGINS),
+        options.header,
+        Some(
+            options
+                .footer
+                .unwrap_or_else(|| pdf::DEFAULT_FOOTER.to_string()),
+        ),
+        fonts,
+    )?;
+    pdf::export_pdf(source, &output, &options)
+}
+
+// 前端调用：invoke("export_site", { root: "/abs/docs", outDir: "/abs/site" })
+// 返回：SiteExport 摘要（页面数、这次重新渲染了几个、警告……）
+//
+// 再次导出到同一个目录时是增量的，只重新渲染改动过的文件
+#[tauri::command]
+pub async fn export_site(
+    root: String,
+    out_dir: String,
+    theme: Option<String>,
+    settings: State<'_, SettingsStore>,
+) -> Result<SiteExport, String> {
+    let options = SiteOptions {
+        theme: theme.unwrap_or_else(|| settings.get().theme),
+    };
+    site::export_site(Path::new(&root), Path::new(&out_dir), &options)
+}
+
+/// export_epub 的选项，字段都可以省略
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportEpubOptions {
+    /// 主题 id；省略时用设置里的当前主题
+    pub theme: Option<String>,
+    /// 输出文件路径；省略时文件导出到旁边的同名 .epub，文件夹导出到旁边的 "<文件夹名>.epub"
+    pub output: Option<String>,
+}
+
+// 前端调用：invoke("export_epub", { path: "/abs/docs", options: {} })
+// 返回：EpubExport（输出路径、书名、章节数、警告……）
+//
+// path 可以是单个文档，也可以是文件夹（是文档站点时按站点目录排章节顺序）
+#[tauri::command]
+pub async fn export_epub(
+    path: String,
+    options: ExportEpubOptions,
+    settings: State<'_, SettingsStore>,
+) -> Result<EpubExport, String> {
+    let source = Path::new(&path);
+    let output = options
+        .output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| epub::default_output(source));
+    let options = EpubOptions {
+        theme: options.theme.unwrap_or_else(|| settings.get().theme),
+    };
+    epub::export_epub(source, &output, &options)
+}
@@ -0,0 +1,28 @@
+// ============================
+// 元数据分面 — 按标签、分类、日期浏览文件夹
+// ============================
+//
+// 前端调用：invoke("get_facets", { root: "/abs/path/to/notes" })
+//
+// 汇总整个文件夹的 front matter tags / categories、正文 #话题 和 front matter 日期字段，
+// 每个取值带文档数。点某个分面后用 list_markdown_files 的 filter 参数（如 "tag:rfc"）列出对应文件。
+// 提取和计数规则见 markdown/facets.rs，查询语法见 markdown/query.rs。
+
+use crate::commands::markdown::collect_markdown_paths;
+use crate::markdown::facets::{collect_facets, DocumentMeta, Facets};
+use std::path::Path;
+
+// 返回：Facets（文档数、各分面的取值和计数，按数量从多到少）
+#[tauri::command]
+pub async fn get_facets(root: String) -> Result<Facets, String> {
+    let root_path = Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(format!("'{}' is not a directory", root));
+    }
+    // 读不了的文件（权限、非 UTF-8）直接跳过，和 list_markdown_files 的容错策略一致
+    let docs: Vec<DocumentMeta> = collect_markdown_paths(root_path)
+        .iter()
+        .filter_map(|path| DocumentMeta::read(root_path, path))
+        .collect();
+    Ok(collect_facets(&docs))
+}
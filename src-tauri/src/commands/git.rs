@@ -0,0 +1,101 @@
+// ============================
+// 文件历史 — Rust IPC 命令
+// ============================
+//
+// 审阅文档时看它是怎么一步步改过来的：提交记录（跟随改名）、某个修订版的内容、两个版本之间的块级 diff。
+// 全部直接读本地 .git 目录（见 git/），不需要安装 git。
+
+use crate::git::log::{self, FileRevision};
+use crate::git::Repository;
+use crate::markdown::diff::{self, MarkdownDiff};
+use std::fs;
+use std::path::Path;
+
+/// 默认返回的最大记录数
+const DEFAULT_HISTORY_LIMIT: usize = 200;
+
+/// path 所在的仓库和仓库内路径
+fn open_repository(path: &str) -> Result<(Repository, String), String> {
+    let file = Path::new(path);
+    let repo = Repository::discover(file)
+        .ok_or_else(|| format!("'{}' is not inside a git repository", path))?;
+    let relative = repo
+        .relative_path(file)
+        .ok_or_else(|| format!("'{}' is not inside a git repository", path))?;
+    Ok((repo, relative))
+}
+
+// 前端调用：invoke("get_file_history", { path: "/abs/spec.md", limit: 50 })
+// 返回：FileRevision 数组，从新到旧；改名之前的记录 path 是旧路径
+#[tauri::command]
+pub async fn get_file_history(
+    path: String,
+    limit: Option<usize>,
+) -> Result<Vec<FileRevision>, String> {
+    let (repo, relative) = open_repository(&path)?;
+    Ok(log::file_log(
+        &repo,
+        &relative,
+        limit.unwrap_or(DEFAULT_HISTORY_LIMIT),
+    ))
+}
+
+// 前端调用：invoke("read_file_at_revision", { path: "/abs/spec.md", revision: "a1b2c3d" })
+// 返回：文件在该修订版里的内容
+//
+// revision 支持提交 ID（可缩写）、分支 / 标签名、HEAD~2 这类写法。
+// revisionPath 是该修订版里的仓库内路径（get_file_history 返回的 path）；
+// 省略时先按当前路径找，找不到再按历史推算改名前的路径
+#[tauri::command]
+pub async fn read_file_at_revision(
+    path: String,
+    revision: String,
+    revision_path: Option<String>,
+) -> Result<String, String> {
+    let (repo, relative) = open_repository(&path)?;
+    let commit = repo.resolve_revision(&revision)?;
+    read_revision(&repo, &relative, &commit, revision_path.as_deref())
+        .ok_or_else(|| format!("'{}' does not exist at revision '{}'", path, revision))
+}
+
+fn read_revision(
+    repo: &Repository,
+    relative: &str,
+    commit: &crate::git::Oid,
+    revision_path: Option<&str>,
+) -> Option<String> {
+    let content = match revision_path {
+        Some(p) => repo.file_at(commit, p),
+        None => repo.file_at(commit, relative).or_else(|| {
+            let old = log::path_at(repo, relative, commit)?;
+            repo.file_at(commit, &old)
+        }),
+    }?;
+    Some(String::from_utf8_lossy(&content).to_string())
+}
+
+// 前端调用：invoke("diff_file_revisions", { path: "/abs/spec.md", from: "HEAD~3", to: null })
+// 返回：MarkdownDiff —— 按标题对齐的块级 hunk（新增 / 删除 / 修改）
+//
+// to 省略时和工作区里的当前内容比较；两边都按修订版里的路径读取（跟随改名）
+#[tauri::command]
+pub async fn diff_file_revisions(
+    path: String,
+    from: String,
+    to: Option<String>,
+) -> Result<MarkdownDiff, String> {
+    let (repo, relative) = open_repository(&path)?;
+    let load = |revision: &str| -> Result<String, String> {
+        let commit = repo.resolve_revision(revision)?;
+        // 某一边文件还不存在（比如 from 早于文件创建）时按空文档比较
+        Ok(read_revision(&repo, &relative, &commit, None).unwrap_or_default())
+    };
+    let old = load(&from)?;
+    let new = match to {
+        Some(revision) => load(&revision)?,
+        None => {
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read '{}': {}", path, e))?
+        }
+    };
+    Ok(diff::diff_markdown(&old, &new))
+}
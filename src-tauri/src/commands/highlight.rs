@@ -0,0 +1,36 @@
+// ============================
+// 代码高亮 — Rust IPC 命令
+// ============================
+//
+// 预览里的代码块交给后端高亮（export/highlight），前端只负责把结果换进页面。
+// 一次渲染的所有代码块打包成一个请求，避免几百个代码块各走一次 IPC。
+
+use crate::export::highlight;
+use serde::Deserialize;
+
+/// 一个待高亮的代码块
+#[derive(Debug, Clone, Deserialize)]
+pub struct CodeBlockInput {
+    pub code: String,
+    /// 围栏后面的整个信息串，如 "rust {2,5-7} showLineNumbers"
+    #[serde(default)]
+    pub info: String,
+}
+
+// 前端调用：invoke("highlight_code_blocks", { blocks: [{ code: "fn main() {}", info: "rust" }] })
+// 返回：和 blocks 一一对应的 `<pre class="code-block">` HTML
+#[tauri::command]
+pub async fn highlight_code_blocks(blocks: Vec<CodeBlockInput>) -> Result<Vec<String>, String> {
+    let pairs: Vec<(&str, &str)> = blocks
+        .iter()
+        .map(|block| (block.code.as_str(), block.info.as_str()))
+        .collect();
+    Ok(highlight::highlight_many(&pairs))
+}
+
+// 前端调用：invoke("get_code_highlight_css")
+// 返回：所有主题的代码配色 CSS（按 .markdown-body.theme-xxx 区分），启动时加载一次即可
+#[tauri::command]
+pub async fn get_code_highlight_css() -> Result<String, String> {
+    Ok(highlight::all_themes_css())
+}
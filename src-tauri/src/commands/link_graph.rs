@@ -0,0 +1,210 @@
+// ============================
+// 链接图 — 正向链接、反向链接、孤立页面、坏链接报告
+// ============================
+//
+// 前端调用：invoke("build_link_graph", { root: "/abs/path/to/docs" })
+//
+// 流程：
+//   1. 用 collect_markdown_paths 拿到和 list_markdown_files 相同的一批文件
+//   2. 每个文件完整解析一次：标题 slug 集合 + 所有链接
+//   3. 相对链接按「所在文件的目录」解析，`/` 开头的按根目录解析
+//   4. 能对上 Markdown 文件的记为一条边；对不上的进入坏链接列表
+//
+// 根目录是文档站点（mdBook、MkDocs……）时，字面路径找不到的链接再按站点规则解析一次：
+// "/guide/intro"、"intro.html"、"guide/" 这种写法在生成的站点里是通的，不算坏链接。
+
+use crate::commands::markdown::collect_markdown_paths;
+use crate::markdown::headings;
+use crate::markdown::links::{self, Link};
+use crate::state::roots;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkGraph {
+    /// 每个文件的正向 / 反向链接，顺序与 list_markdown_files 一致
+    pub files: Vec<FileLinks>,
+    /// 没有任何其他页面链接过来的文件（绝对路径）
+    pub orphans: Vec<String>,
+    /// 解析失败的链接
+    pub broken: Vec<BrokenLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileLinks {
+    pub path: String,
+    pub relative_path: String,
+    /// 本文件链接到的其他 Markdown 文件（绝对路径，去重）
+    pub links: Vec<String>,
+    /// 链接到本文件的其他 Markdown 文件（绝对路径，去重）
+    pub backlinks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenLink {
+    /// 链接所在文件（绝对路径）
+    pub file: String,
+    /// 链接所在行号（1-based）
+    pub line: usize,
+    /// 文档里写的原始链接目标
+    pub target: String,
+    pub reason: BrokenReason,
+}
+
+/// 坏链接的原因，序列化为 "missingFile" / "missingAnchor" / "escapesRoot"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BrokenReason {
+    /// 目标文件不存在
+    MissingFile,
+    /// 目标文件存在，但没有这个 `#锚点` 对应的标题
+    MissingAnchor,
+    /// 目标路径跑出了打开的根目录（如 `../../etc/passwd`）
+    EscapesRoot,
+}
+
+/// 解析过的单个文档
+struct Doc {
+    path: PathBuf,
+    relative_path: String,
+    slugs: HashSet<String>,
+    links: Vec<Link>,
+}
+
+#[tauri::command]
+pub async fn build_link_graph(root: String) -> Result<LinkGraph, String> {
+    let root_path = Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(format!("'{}' is not a directory", root));
+    }
+    let root_norm = links::normalize_path(root_path);
+    let site = roots::site_for(root_path);
+
+    // 读不了的文件（权限、非 UTF-8）直接跳过，和 list_markdown_files 的容错策略一致
+    let docs: Vec<Doc> = collect_markdown_paths(root_path)
+        .into_iter()
+        .filter_map(|path| {
+            let source = fs::read_to_string(&path).ok()?;
+            let relative_path = path
+                .strip_prefix(root_path)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            Some(Doc {
+                path: links::normalize_path(&path),
+                relative_path,
+                slugs: headings::parse_headings(&source)
+                    .into_iter()
+                    .map(|h| h.slug)
+                    .collect(),
+                links: links::extract_links(&source),
+            })
+        })
+        .collect();
+
+    // 规范化路径 → 文档下标，用于判断链接目标是不是已知的 Markdown 文件
+    let index: HashMap<&Path, usize> = docs
+        .iter()
+        .enumerate()
+        .map(|(i, d)| (d.path.as_path(), i))
+        .collect();
+
+    // BTreeSet：自动去重 + 输出顺序稳定
+    let mut forward: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); docs.len()];
+    let mut backward: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); docs.len()];
+    let mut broken = Vec::new();
+
+    for (i, doc) in docs.iter().enumerate() {
+        for link in &doc.links {
+            if links::is_external(&link.dest) {
+                continue;
+            }
+            let mut report = |reason| {
+                broken.push(BrokenLink {
+                    file: doc.path.to_string_lossy().to_string(),
+                    line: link.line,
+                    target: link.dest.clone(),
+                    reason,
+                })
+            };
+
+            let (raw_path, fragment) = links::split_fragment(&link.dest);
+            let decoded = links::percent_decode(raw_path);
+            let mut target = resolve_target(&root_norm, &doc.path, &decoded);
+            if !target.exists() {
+                if let Some(found) = site.as_ref().and_then(|s| s.resolve(&doc.path, &decoded)) {
+                    target = found;
+                }
+            }
+            if !target.starts_with(&root_norm) {
+                report(BrokenReason::EscapesRoot);
+                continue;
+            }
+
+            match index.get(target.as_path()) {
+                Some(&j) => {
+                    // 指向自身的链接（`#锚点`）只检查锚点，不算作图里的边
+                    if j != i {
+                        forward[i].insert(j);
+                        backward[j].insert(i);
+                    }
+                    if let Some(fragment) = fragment.filter(|f| !f.is_empty()) {
+                        let anchor = links::percent_decode(fragment).to_lowercase();
+                        if !docs[j].slugs.contains(&anchor) {
+                            report(BrokenReason::MissingAnchor);
+                        }
+                    }
+                }
+                // 不是 Markdown 的目标（图片、PDF、目录）只检查是否存在
+                None => {
+                    if !target.exists() {
+                        report(BrokenReason::MissingFile);
+                    }
+                }
+            }
+        }
+    }
+
+    let path_of = |i: &usize| docs[*i].path.to_string_lossy().to_string();
+    let orphans = (0..docs.len())
+        .filter(|&i| backward[i].is_empty())
+        .map(|i| path_of(&i))
+        .collect();
+    let files = docs
+        .iter()
+        .enumerate()
+        .map(|(i, doc)| FileLinks {
+            path: path_of(&i),
+            relative_path: doc.relative_path.clone(),
+            links: forward[i].iter().map(path_of).collect(),
+            backlinks: backward[i].iter().map(path_of).collect(),
+        })
+        .collect();
+
+    Ok(LinkGraph {
+        files,
+        orphans,
+        broken,
+    })
+}
+
+/// 把链接路径解析为规范化的绝对路径
+///
+///   ""            → 当前文件本身（纯 `#锚点` 链接）
+///   "/guide/a.md" → 相对于打开的根目录
+///   "../a.md"     → 相对于当前文件所在目录
+pub fn resolve_target(root: &Path, from_file: &Path, link_path: &str) -> PathBuf {
+    if link_path.is_empty() {
+        return from_file.to_path_buf();
+    }
+    let joined = match link_path.strip_prefix('/') {
+        Some(rest) => root.join(rest),
+        None => from_file.parent().unwrap_or(root).join(link_path),
+    };
+    links::normalize_path(&joined)
+}
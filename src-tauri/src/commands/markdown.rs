@@ -10,9 +10,24 @@
 // - 文件夹扫描只读元数据 + 前 8KB 预览，不加载全文（性能）
 // - 全文内容在用户点击卡片时才通过 read_markdown_file 按需加载
 
+use crate::git::{self, CommitSummary, GitFileInfo, History};
+use crate::markdown::diff::{self, MarkdownDiff};
+use crate::markdown::facets::DocumentMeta;
+use crate::markdown::headings::{self, Heading};
+use crate::markdown::links::normalize_path;
+use crate::markdown::query::Query;
+use crate::state::recent::{self, RecentKind};
+use crate::state::roots;
+use crate::state::settings;
+use crate::state::workspace;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+use tauri::Emitter;
 use tauri_plugin_dialog::{DialogExt, FilePath};
 use walkdir::WalkDir; // 第三方库：递归遍历目录树
 
@@ -38,6 +53,11 @@ pub struct MarkdownFileInfo {
     pub preview: String,
     /// 估算字数（基于前 8KB 内容，按空白字符分割计数）
     pub word_count: u32,
+    /// 文件夹在 git 仓库里时附带的状态和提交信息（见 git/），否则为 null
+    ///
+    /// 新克隆的仓库里所有文件的修改时间都是克隆的时间，按「最近提交」排序才有意义
+    #[serde(default)]
+    pub git: Option<GitFileInfo>,
 }
 
 // ─────────────────────────────────────────────
@@ -50,11 +70,40 @@ pub struct MarkdownFileInfo {
 //   1. 把这个函数注册为 IPC 命令
 //   2. 把参数从 JSON 反序列化
 //   3. 把返回值序列化为 JSON
+//
+// 成功读取后记入「最近打开」（见 state/recent.rs）
+//
+// Obsidian vault 兼容：如果文件位于 vault 中（某个上级目录有 .obsidian/），
+// 返回前把 [[wiki 链接]] 和 ![[嵌入]] 改写成标准 Markdown，详见 markdown/vault.rs
 #[tauri::command]
-pub async fn read_markdown_file(path: String) -> Result<String, String> {
+pub async fn read_markdown_file(app: tauri::AppHandle, path: String) -> Result<String, String> {
+    let content = load_document(&path)?;
+    recent::track(&app, &path, RecentKind::File);
+    Ok(content)
+}
+
+/// 读取文档全文（含文档站点和 vault 展开），但不记入「最近打开」
+///
+/// 恢复会话等「不是用户主动打开」的场景直接用这个函数
+pub fn load_document(path: &str) -> Result<String, String> {
     // fs::read_to_string 读取整个文件为 String
     // .map_err() 把 std::io::Error 转为 String（Tauri IPC 要求错误类型是 String）
-    fs::read_to_string(&path).map_err(|e| format!("Failed to read '{}': {}", path, e))
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+
+    // 文档站点：展开 mdBook include，站点绝对链接改写成指向源文件的相对链接
+    let content = match roots::site_for(Path::new(path)) {
+        Some(site) => site.expand(Path::new(path), &content),
+        None => content,
+    };
+    // vault：没有 wiki 链接的文档不用找 vault，名称索引按 vault 缓存
+    if !content.contains("[[") {
+        return Ok(content);
+    }
+    match roots::vault_for(Path::new(path)) {
+        Some(vault) => Ok(vault.expand(Path::new(path), &content)),
+        None => Ok(content),
+    }
 }
 
 // ─────────────────────────────────────────────
@@ -112,111 +161,612 @@ pub async fn open_folder_dialog(app: tauri::AppHandle) -> Result<Option<String>,
 // ─────────────────────────────────────────────
 // 命令 4：递归扫描文件夹，列出所有 Markdown 文件
 // ─────────────────────────────────────────────
-// 前端调用：invoke("list_markdown_files", { folderPath: "/abs/path/to/folder" })
+// 前端调用：invoke("list_markdown_files", { folderPath: "/abs/path/to/folder", filter: "tag:rfc" })
 // 返回：MarkdownFileInfo 数组，按 relativePath 字母排序
 //
+// filter 可省略：元数据过滤查询（语法见 markdown/query.rs），只返回匹配的文件；
+// 查询写错时返回错误，不会当成「没有匹配」
+//
 // 性能考虑：
 //   - 只读每个文件的前 8KB（不是全文），用于预览和字数估算
 //   - 全文内容在用户点击卡片时才通过 read_markdown_file 按需加载
 //   - walkdir 库非常高效，扫描数千个文件也很快
+//   - git 提交历史（最近提交、提交数）没缓存时先留空，后台算好后通过 git://history 事件补上
 #[tauri::command]
-pub async fn list_markdown_files(folder_path: String) -> Result<Vec<MarkdownFileInfo>, String> {
+pub async fn list_markdown_files(
+    app: tauri::AppHandle,
+    folder_path: String,
+    filter: Option<String>,
+) -> Result<Vec<MarkdownFileInfo>, String> {
     let root = Path::new(&folder_path);
 
     // 安全检查：确保传入的路径是一个目录
     if !root.is_dir() {
         return Err(format!("'{}' is not a directory", folder_path));
     }
+    recent::track(&app, &folder_path, RecentKind::Folder);
+    workspace::track_root(&app, root);
 
-    // 支持的 Markdown 文件扩展名
-    let md_extensions = ["md", "markdown", "mdx"];
+    let files = scan_folder(root, History::Cached);
+    let files = match filter.as_deref() {
+        Some(filter) => filter_files(root, files, filter)?,
+        None => files,
+    };
+    spawn_git_history(
+        &app,
+        root,
+        files.iter().map(|f| PathBuf::from(&f.path)).collect(),
+    );
+    Ok(files)
+}
+
+/// 后台补上文件列表里的提交历史时广播的事件名，payload 是 GitHistoryUpdate
+pub const GIT_HISTORY_EVENT: &str = "git://history";
+
+/// git://history 的 payload：root 下一批文件的提交历史
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHistoryUpdate {
+    pub root: String,
+    pub files: Vec<FileHistory>,
+}
+
+/// 一个文件的提交历史，对应 GitFileInfo 的 lastCommit / commitCount
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileHistory {
+    pub path: String,
+    pub last_commit: Option<CommitSummary>,
+    pub commit_count: u32,
+}
+
+/// 文件列表先带着 git 状态返回（History::Cached），提交历史在后台线程里算
+///
+/// 没有缓存时要遍历整个提交历史，大仓库里要几秒；算完通过 git://history 事件发给前端补上。
+/// 不在 git 仓库里、或者没有已跟踪的文件时不广播
+pub fn spawn_git_history(app: &tauri::AppHandle, root: &Path, files: Vec<PathBuf>) {
+    if files.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    let root = root.to_path_buf();
+    thread::spawn(move || {
+        let Some(histories) = git::file_histories(&root, &files) else {
+            return;
+        };
+        if histories.is_empty() {
+            return;
+        }
+        let update = GitHistoryUpdate {
+            root: root.to_string_lossy().to_string(),
+            files: histories
+                .into_iter()
+                .map(|(path, history)| FileHistory {
+                    path: path.to_string_lossy().to_string(),
+                    last_commit: history.last_commit,
+                    commit_count: history.commit_count,
+                })
+                .collect(),
+        };
+        let _ = app.emit(GIT_HISTORY_EVENT, &update);
+    });
+}
+
+/// 按元数据过滤查询筛选 scan_folder 的结果；要读全文，只在有查询时调用
+pub fn filter_files(
+    root: &Path,
+    files: Vec<MarkdownFileInfo>,
+    filter: &str,
+) -> Result<Vec<MarkdownFileInfo>, String> {
+    let query = Query::parse(filter)?;
+    Ok(files
+        .into_iter()
+        .filter(|file| {
+            DocumentMeta::read(root, Path::new(&file.path)).is_some_and(|doc| query.matches(&doc))
+        })
+        .collect())
+}
+
+/// 扫描文件夹，返回所有 Markdown 文件的元数据（按 relative_path 排序）
+///
+/// list_markdown_files 和命令行的 `list` 子命令共用；history 决定 git 提交历史是当场算还是只用缓存
+pub fn scan_folder(root: &Path, history: History) -> Vec<MarkdownFileInfo> {
     let mut files: Vec<MarkdownFileInfo> = Vec::new();
+    let paths = collect_markdown_paths(root);
+    // 不在 git 仓库里时是空表，每个文件的 git 字段都是 None
+    let mut git_infos = git::file_infos(root, &paths, history);
+
+    for path in &paths {
+        let git = git_infos.remove(path);
+        if let Some(info) = file_info(root, path, git) {
+            files.push(info);
+        }
+    }
+
+    // 按相对路径排序，保证前端展示顺序稳定
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+    files
+}
+
+/// 单个文件的元数据；读不到文件信息时返回 None
+pub fn file_info(root: &Path, path: &Path, git: Option<GitFileInfo>) -> Option<MarkdownFileInfo> {
+    // 读取文件元数据（大小、修改时间等）
+    // match 模式：成功则绑定到 m，失败则返回 None 跳过这个文件
+    let metadata = match fs::metadata(path) {
+        Ok(m) => m,
+        Err(_) => return None,
+    };
+
+    // 获取最后修改时间，转为 RFC 3339 字符串（如 "2026-02-21T10:30:00+08:00"）
+    // chrono::Local 使用本地时区，前端显示更直观
+    let modified = metadata
+        .modified()
+        .ok() // SystemTimeError 转为 None
+        .map(|t| {
+            let dt: chrono::DateTime<chrono::Local> = t.into();
+            dt.to_rfc3339()
+        })
+        .unwrap_or_default(); // 获取不到时间就用空字符串
+
+    // 计算相对路径：去掉文件夹根路径前缀
+    // 例如 root="/Users/x/docs", path="/Users/x/docs/notes/a.md"
+    // → relative_path = "notes/a.md"
+    let relative_path = path
+        .strip_prefix(root)
+        .unwrap_or(path) // strip 失败就用完整路径兜底
+        .to_string_lossy() // OsStr → Cow<str>，非 UTF-8 字符用 ? 替代
+        .to_string();
+
+    // 提取文件名（如 "a.md"）
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    // 读取预览文本和估算字数（只读前 8KB）
+    let (preview, word_count) = read_preview(path);
 
+    Some(MarkdownFileInfo {
+        path: path.to_string_lossy().to_string(),
+        relative_path,
+        name,
+        size: metadata.len(),
+        modified,
+        preview,
+        word_count,
+        git,
+    })
+}
+
+// ─────────────────────────────────────────────
+// 辅助函数：递归收集文件夹下所有 Markdown 文件的路径
+// ─────────────────────────────────────────────
+// list_markdown_files 和其他需要「整个文件夹」的命令（链接图等）共用，
+// 保证它们看到的是同一批文件
+
+pub fn collect_markdown_paths(root: &Path) -> Vec<PathBuf> {
+    // 扫描选项（settings.scan）：跳过哪些目录、是否跟随符号链接
+    let scan = settings::scan_options();
     // WalkDir::new(root) — 从 root 开始递归遍历目录树
-    //   .follow_links(true)  — 跟随符号链接（symlink）
-    //   .into_iter()         — 转为迭代器
+    //   .follow_links(..)    — 是否跟随符号链接（symlink）
+    //   .filter_entry(..)    — 跳过设置里忽略的目录（整棵子树都不进去）；root 本身不算
     //   .filter_map(|e| e.ok()) — 跳过无法访问的条目（权限不足等），不报错
-    for entry in WalkDir::new(root)
-        .follow_links(true)
+    WalkDir::new(root)
+        .follow_links(scan.follow_links)
         .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !scan
+                    .ignored_dirs
+                    .iter()
+                    .any(|dir| entry.file_name() == dir.as_str())
+        })
         .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
+        // 跳过目录，只处理 Markdown 文件
+        .filter(|entry| entry.path().is_file() && is_markdown_path(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect()
+}
 
-        // 跳过目录，只处理文件
-        if !path.is_file() {
-            continue;
+/// 根据扩展名判断是否是 Markdown 文件（.md / .markdown / .mdx，不区分大小写）
+pub fn is_markdown_path(path: &Path) -> bool {
+    // 取文件扩展名，转小写后检查是否是 Markdown
+    // 链式调用解释：
+    //   .extension()         → Option<&OsStr>，可能没有扩展名
+    //   .and_then(|e| ...)   → 如果是 Some 则继续处理，None 则短路
+    //   .to_str()            → OsStr 转 &str，非 UTF-8 时返回 None
+    //   .unwrap_or("")       → None 时用空字符串兜底
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    MARKDOWN_EXTENSIONS.contains(&ext.as_str())
+}
+
+/// 支持的 Markdown 文件扩展名
+pub const MARKDOWN_EXTENSIONS: [&str; 3] = ["md", "markdown", "mdx"];
+
+// ─────────────────────────────────────────────
+// 命令 5：按标题读取文档中的某一章节
+// ─────────────────────────────────────────────
+// 前端调用：invoke("read_markdown_section", { path: "/abs/spec.md", anchor: "installation" })
+// 返回：MarkdownSection —— 该标题到下一个同级或更高级标题之间的 Markdown，
+//       以及面包屑（祖先标题）和前后兄弟标题，方便在超长文档里逐节跳转
+//
+// 性能：标题结构按 (路径, 修改时间, 大小) 缓存在 SectionCache 里，
+//       文件没变时重复跳转不会再读盘、再解析
+#[tauri::command]
+pub async fn read_markdown_section(
+    path: String,
+    anchor: String,
+    cache: tauri::State<'_, SectionCache>,
+) -> Result<MarkdownSection, String> {
+    let doc = cache.load(&path)?;
+    // 兼容前端直接传 "#installation" 的写法
+    let anchor = anchor.trim_start_matches('#');
+
+    let index = doc
+        .headings
+        .iter()
+        .position(|h| h.slug == anchor)
+        .ok_or_else(|| format!("Heading '#{}' not found in '{}'", anchor, path))?;
+    let heading = &doc.headings[index];
+
+    // 面包屑：沿着 parent 一路往上走，再反转成「从顶层到直接父级」的顺序
+    let mut breadcrumbs = Vec::new();
+    let mut parent = heading.parent;
+    while let Some(p) = parent {
+        breadcrumbs.push(HeadingRef::from(&doc.headings[p]));
+        parent = doc.headings[p].parent;
+    }
+    breadcrumbs.reverse();
+
+    // 兄弟标题：父标题相同的前一个 / 后一个标题
+    let prev_sibling = doc.headings[..index]
+        .iter()
+        .rev()
+        .find(|h| h.parent == heading.parent)
+        .map(HeadingRef::from);
+    let next_sibling = doc.headings[index + 1..]
+        .iter()
+        .find(|h| h.parent == heading.parent)
+        .map(HeadingRef::from);
+
+    Ok(MarkdownSection {
+        anchor: heading.slug.clone(),
+        title: heading.text.clone(),
+        level: heading.level,
+        line: heading.line,
+        content: doc.source[heading.start..heading.end].to_string(),
+        breadcrumbs,
+        prev_sibling,
+        next_sibling,
+    })
+}
+
+/// read_markdown_section 的返回值
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownSection {
+    /// 章节标题的锚点（GitHub 风格 slug）
+    pub anchor: String,
+    /// 章节标题文本
+    pub title: String,
+    /// 标题级别 1~6
+    pub level: u8,
+    /// 标题所在行号（1-based）
+    pub line: usize,
+    /// 章节的原始 Markdown（包含标题本身，不包含下一个同级标题）
+    pub content: String,
+    /// 祖先标题，从顶层到直接父级
+    pub breadcrumbs: Vec<HeadingRef>,
+    /// 前一个兄弟标题（同一父标题下）
+    pub prev_sibling: Option<HeadingRef>,
+    /// 后一个兄弟标题（同一父标题下）
+    pub next_sibling: Option<HeadingRef>,
+}
+
+/// 对某个标题的简短引用（面包屑 / 兄弟导航用）
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadingRef {
+    pub anchor: String,
+    pub title: String,
+    pub level: u8,
+}
+
+impl From<&Heading> for HeadingRef {
+    fn from(h: &Heading) -> Self {
+        HeadingRef {
+            anchor: h.slug.clone(),
+            title: h.text.clone(),
+            level: h.level,
         }
+    }
+}
 
-        // 取文件扩展名，转小写后检查是否是 Markdown
-        // 链式调用解释：
-        //   .extension()         → Option<&OsStr>，可能没有扩展名
-        //   .and_then(|e| ...)   → 如果是 Some 则继续处理，None 则短路
-        //   .to_str()            → OsStr 转 &str，非 UTF-8 时返回 None
-        //   .unwrap_or("")       → None 时用空字符串兜底
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-
-        // 不是 Markdown 文件就跳过
-        if !md_extensions.contains(&ext.as_str()) {
-            continue;
+// ─────────────────────────────────────────────
+// 章节缓存：文件内容 + 解析好的标题结构
+// ─────────────────────────────────────────────
+// 通过 tauri::Builder::manage() 注册为全局状态，命令里用 State<SectionCache> 取出
+//
+// 缓存键是文件路径，失效条件是修改时间或文件大小变化。
+// 最多保留 MAX_CACHED_DOCS 个文档，超出时淘汰最久没访问的那个。
+const MAX_CACHED_DOCS: usize = 32;
+
+/// 已解析的文档（源文本 + 标题列表）
+pub struct ParsedDoc {
+    pub source: String,
+    pub headings: Vec<Heading>,
+}
+
+struct CachedDoc {
+    modified: Option<SystemTime>,
+    size: u64,
+    last_used: SystemTime,
+    doc: Arc<ParsedDoc>,
+}
+
+#[derive(Default)]
+pub struct SectionCache {
+    entries: Mutex<HashMap<String, CachedDoc>>,
+}
+
+impl SectionCache {
+    /// 读取并解析文档；文件未变化时直接返回缓存
+    ///
+    /// 返回 Arc：调用方拿到的是共享引用，不需要在持锁期间复制整份源文本
+    pub fn load(&self, path: &str) -> Result<Arc<ParsedDoc>, String> {
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+        let modified = metadata.modified().ok();
+        let size = metadata.len();
+        let now = SystemTime::now();
+
+        // lock() 只会在其他线程持锁时 panic 才失败，这里把它转成普通错误
+        let mut entries = self.entries.lock().map_err(|e| e.to_string())?;
+        if let Some(entry) = entries.get_mut(path) {
+            if entry.modified == modified && entry.size == size {
+                entry.last_used = now;
+                return Ok(entry.doc.clone());
+            }
         }
 
-        // 读取文件元数据（大小、修改时间等）
-        // match 模式：成功则绑定到 m，失败则 continue 跳过这个文件
-        let metadata = match fs::metadata(path) {
-            Ok(m) => m,
-            Err(_) => continue,
+        let source =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+        let doc = Arc::new(ParsedDoc {
+            headings: headings::parse_headings(&source),
+            source,
+        });
+
+        if entries.len() >= MAX_CACHED_DOCS && !entries.contains_key(path) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(key) = oldest {
+                entries.remove(&key);
+            }
+        }
+        entries.insert(
+            path.to_string(),
+            CachedDoc {
+                modified,
+                size,
+                last_used: now,
+                doc: doc.clone(),
+            },
+        );
+
+        Ok(doc)
+    }
+}
+
+// ─────────────────────────────────────────────
+// 命令 6：并排比较两个 Markdown 文件
+// ─────────────────────────────────────────────
+// 前端调用：invoke("diff_markdown", { left: "/abs/spec-v1.md", right: "/abs/spec-v2.md" })
+// 返回：MarkdownDiff —— 按标题对齐的 hunk 列表（新增 / 删除 / 修改 / 移动），
+//       修改 hunk 带词级 diff（中日韩文字逐字比较），详见 markdown/diff.rs
+//
+// left 是旧版本，right 是新版本；两边都按预览时的内容读取（含 vault 展开）。
+// hunk 两边都带行号和原文，前端既可以左右并排渲染，也可以按 words 渲染成行内标注
+#[tauri::command]
+pub async fn diff_markdown(left: String, right: String) -> Result<MarkdownDiff, String> {
+    let old = load_document(&left)?;
+    let new = load_document(&right)?;
+    Ok(diff::diff_markdown(&old, &new))
+}
+
+// ─────────────────────────────────────────────
+// 命令 7：按目录层级返回 Markdown 文件树
+// ─────────────────────────────────────────────
+// 前端调用：invoke("get_markdown_tree", { root: "/abs/docs", path: "/abs/docs/guide", depth: 1 })
+// 返回：MarkdownTreeNode —— path 这个目录（省略时是 root 本身），往下展开 depth 层（默认 1）
+//
+// 懒加载：超出 depth 的子目录 dirs / files 为 null，但文件数、总大小、索引文档照样给出；
+// 用户点开时再用它的 path 调一次，深层目录不用一次全部加载。
+// 只列出含有 Markdown 文件的目录；文件的 relativePath 始终相对 root，和 list_markdown_files 一致
+#[tauri::command]
+pub async fn get_markdown_tree(
+    app: tauri::AppHandle,
+    root: String,
+    path: Option<String>,
+    depth: Option<usize>,
+) -> Result<MarkdownTreeNode, String> {
+    let root_dir = Path::new(&root);
+    if !root_dir.is_dir() {
+        return Err(format!("'{}' is not a directory", root));
+    }
+    // 先规范化再比较，"/docs/../../etc" 这种用 ".." 跳出 root 的路径不能通过
+    let root_dir = normalize_path(root_dir);
+    let dir = normalize_path(Path::new(path.as_deref().unwrap_or(&root)));
+    if !dir.is_dir() || !dir.starts_with(&root_dir) {
+        return Err(format!(
+            "'{}' is not a directory inside '{}'",
+            dir.display(),
+            root
+        ));
+    }
+    let node = markdown_tree(&root_dir, &dir, depth.unwrap_or(1));
+    let mut files = Vec::new();
+    node.collect_files(&mut files);
+    spawn_git_history(&app, &root_dir, files);
+    Ok(node)
+}
+
+/// 目录的索引文档，按优先级排列（不区分大小写）
+pub const INDEX_DOCUMENTS: [&str; 3] = ["README.md", "index.md", "_index.md"];
+
+/// 文件树里的一个目录
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownTreeNode {
+    /// 目录的绝对路径，展开时作为 get_markdown_tree 的 path 参数
+    pub path: String,
+    /// 相对于 root 的路径，root 本身是空串
+    pub relative_path: String,
+    pub name: String,
+    /// 整个子树里的 Markdown 文件数
+    pub file_count: usize,
+    /// 整个子树里 Markdown 文件的总大小（字节）
+    pub total_size: u64,
+    /// 整个子树里最新的修改时间（RFC 3339），没有文件时为空串
+    pub modified: String,
+    /// 目录的索引文档（README.md / index.md / _index.md）的绝对路径
+    pub index: Option<String>,
+    /// 子目录，按名字排序；没展开时为 null
+    pub dirs: Option<Vec<MarkdownTreeNode>>,
+    /// 直接位于这个目录里的文件，按名字排序；没展开时为 null
+    pub files: Option<Vec<MarkdownFileInfo>>,
+}
+
+impl MarkdownTreeNode {
+    /// 已经展开的文件的路径
+    fn collect_files(&self, out: &mut Vec<PathBuf>) {
+        out.extend(self.files.iter().flatten().map(|f| PathBuf::from(&f.path)));
+        for dir in self.dirs.iter().flatten() {
+            dir.collect_files(out);
+        }
+    }
+}
+
+/// dir 的文件树，展开 depth 层（root、dir 都应是规范化的路径）
+///
+/// 聚合数字要看整个子树，用的是按 root 缓存的 stat 信息（state/roots.rs），逐层展开不会反复遍历磁盘；
+/// 预览和 git 状态只给展开范围内的文件（提交历史只用缓存的，见 spawn_git_history）
+pub fn markdown_tree(root: &Path, dir: &Path, depth: usize) -> MarkdownTreeNode {
+    let mut tree = DirTree::default();
+    for (path, size, modified) in roots::folder_files(root).iter() {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
         };
+        let mut node = &mut tree;
+        for component in relative.parent().into_iter().flat_map(Path::components) {
+            let name = component.as_os_str().to_string_lossy().to_string();
+            node = node.dirs.entry(name).or_default();
+        }
+        node.files.push((path.clone(), *size, *modified));
+    }
 
-        // 获取最后修改时间，转为 RFC 3339 字符串（如 "2026-02-21T10:30:00+08:00"）
-        // chrono::Local 使用本地时区，前端显示更直观
-        let modified = metadata
-            .modified()
-            .ok() // SystemTimeError 转为 None
-            .map(|t| {
-                let dt: chrono::DateTime<chrono::Local> = t.into();
-                dt.to_rfc3339()
+    let mut expanded = Vec::new();
+    tree.expanded_files(depth, &mut expanded);
+    let mut git_infos = git::file_infos(root, &expanded, History::Cached);
+    tree.into_node(root, dir, depth, &mut git_infos).0
+}
+
+/// 建树用的中间结构
+#[derive(Default)]
+struct DirTree {
+    /// 直接包含的文件：(路径, 大小, 修改时间)
+    files: Vec<(PathBuf, u64, Option<SystemTime>)>,
+    dirs: BTreeMap<String, DirTree>,
+}
+
+impl DirTree {
+    /// 展开范围内（depth 层以内）的文件
+    fn expanded_files(&self, depth: usize, out: &mut Vec<PathBuf>) {
+        if depth == 0 {
+            return;
+        }
+        out.extend(self.files.iter().map(|(path, ..)| path.clone()));
+        for child in self.dirs.values() {
+            child.expanded_files(depth - 1, out);
+        }
+    }
+
+    /// 转成节点，同时返回子树里最新的修改时间
+    fn into_node(
+        self,
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+        git_infos: &mut HashMap<PathBuf, GitFileInfo>,
+    ) -> (MarkdownTreeNode, Option<SystemTime>) {
+        let mut file_count = self.files.len();
+        let mut total_size: u64 = self.files.iter().map(|(_, size, _)| size).sum();
+        let mut latest = self
+            .files
+            .iter()
+            .filter_map(|(.., modified)| *modified)
+            .max();
+
+        let index = INDEX_DOCUMENTS.iter().find_map(|index| {
+            self.files.iter().find(|(path, ..)| {
+                path.file_name()
+                    .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(index))
             })
-            .unwrap_or_default(); // 获取不到时间就用空字符串
-
-        // 计算相对路径：去掉文件夹根路径前缀
-        // 例如 root="/Users/x/docs", path="/Users/x/docs/notes/a.md"
-        // → relative_path = "notes/a.md"
-        let relative_path = path
-            .strip_prefix(root)
-            .unwrap_or(path) // strip 失败就用完整路径兜底
-            .to_string_lossy() // OsStr → Cow<str>，非 UTF-8 字符用 ? 替代
-            .to_string();
-
-        // 提取文件名（如 "a.md"）
-        let name = path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-
-        // 读取预览文本和估算字数（只读前 8KB）
-        let (preview, word_count) = read_preview(path);
-
-        files.push(MarkdownFileInfo {
-            path: path.to_string_lossy().to_string(),
-            relative_path,
-            name,
-            size: metadata.len(),
-            modified,
-            preview,
-            word_count,
         });
-    }
+        let index = index.map(|(path, ..)| path.to_string_lossy().to_string());
 
-    // 按相对路径排序，保证前端展示顺序稳定
-    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        let mut dirs = Vec::new();
+        for (name, child) in self.dirs {
+            let (node, modified) =
+                child.into_node(root, &dir.join(name), depth.saturating_sub(1), git_infos);
+            file_count += node.file_count;
+            total_size += node.total_size;
+            latest = latest.max(modified);
+            dirs.push(node);
+        }
 
-    Ok(files)
+        let files = (depth > 0).then(|| {
+            let mut files: Vec<MarkdownFileInfo> = self
+                .files
+                .iter()
+                .filter_map(|(path, ..)| file_info(root, path, git_infos.remove(path)))
+                .collect();
+            files.sort_by(|a, b| a.name.cmp(&b.name));
+            files
+        });
+
+        let node = MarkdownTreeNode {
+            path: dir.to_string_lossy().to_string(),
+            relative_path: dir
+                .strip_prefix(root)
+                .unwrap_or(dir)
+                .to_string_lossy()
+                .to_string(),
+            name: dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            file_count,
+            total_size,
+            modified: latest
+                .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+                .unwrap_or_default(),
+            index,
+            dirs: (depth > 0).then_some(dirs),
+            files,
+        };
+        (node, latest)
+    }
 }
 
 // ─────────────────────────────────────────────
@@ -271,7 +821,7 @@ fn read_preview(path: &Path) -> (String, u32) {
     // 预览文本超过 200 字符就截断，加省略号
     if preview.len() > 200 {
         preview.truncate(200);
-        preview.push_str("…");
+        preview.push('…');
     }
 
     (preview, word_count)
@@ -1,2 +1,15 @@
 pub mod chrome_cache;
+pub mod cli;
+pub mod collections;
+pub mod docsite;
+pub mod export;
+pub mod facets;
+pub mod git;
+pub mod highlight;
+pub mod link_graph;
 pub mod markdown;
+pub mod recent;
+pub mod session;
+pub mod settings;
+pub mod tasks;
+pub mod workspace;
@@ -0,0 +1,53 @@
+// ============================
+// 最近打开 — Rust IPC 命令
+// ============================
+//
+// 记录逻辑在 state/recent.rs，这里提供给前端查询和管理列表。
+// 每次修改后都会重建原生菜单并广播 recent://changed。
+
+use crate::state::recent::{self, RecentEntry, RecentStore};
+use tauri::{AppHandle, State};
+
+// 前端调用：invoke("get_recent")
+// 返回：固定的条目在前，其余按最近打开排序
+#[tauri::command]
+pub async fn get_recent(store: State<'_, RecentStore>) -> Result<Vec<RecentEntry>, String> {
+    Ok(store.list())
+}
+
+// 前端调用：invoke("pin_recent", { path, pinned: true })
+#[tauri::command]
+pub async fn pin_recent(
+    path: String,
+    pinned: bool,
+    app: AppHandle,
+    store: State<'_, RecentStore>,
+) -> Result<Vec<RecentEntry>, String> {
+    store.set_pinned(&path, pinned)?;
+    recent::notify_changed(&app);
+    Ok(store.list())
+}
+
+// 前端调用：invoke("remove_recent", { path })
+#[tauri::command]
+pub async fn remove_recent(
+    path: String,
+    app: AppHandle,
+    store: State<'_, RecentStore>,
+) -> Result<Vec<RecentEntry>, String> {
+    store.remove(&path)?;
+    recent::notify_changed(&app);
+    Ok(store.list())
+}
+
+// 前端调用：invoke("clear_recent")
+// 固定的条目不会被清掉
+#[tauri::command]
+pub async fn clear_recent(
+    app: AppHandle,
+    store: State<'_, RecentStore>,
+) -> Result<Vec<RecentEntry>, String> {
+    store.clear()?;
+    recent::notify_changed(&app);
+    Ok(store.list())
+}
@@ -0,0 +1,132 @@
+// ============================
+// 会话恢复 — Rust IPC 命令
+// ============================
+//
+// 前端调用：
+//   invoke("save_session", { session })  标签 / 滚动变化后调用（前端自己做防抖）
+//   invoke("restore_session")            启动时调用，拿回上次的标签页
+//
+// 两个命令都按「调用它的窗口」区分会话，窗口由 Tauri 自动注入，前端不用传
+
+use crate::commands::markdown::{collect_markdown_paths, load_document};
+use crate::state::session::{ScrollAnchor, SessionStore, WindowSession};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{State, Window};
+
+/// 恢复出来的单个标签（已经读好了内容，前端直接显示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredTab {
+    pub path: String,
+    pub content: String,
+    pub scroll: Option<ScrollAnchor>,
+}
+
+/// 上次打开、但现在找不到的文件或文件夹
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingPath {
+    pub path: String,
+    /// "file" 或 "folder"
+    pub kind: String,
+    /// 失败原因（不存在、无权限等）
+    pub reason: String,
+    /// 文件被移动时的可能新位置：在会话文件夹里找到的同名文件
+    pub suggestion: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSession {
+    pub tabs: Vec<RestoredTab>,
+    /// 已经根据丢失的标签调整过的下标
+    pub active_index: usize,
+    pub folder: Option<String>,
+    /// 没能恢复的条目，由前端提示给用户，而不是悄悄丢掉
+    pub missing: Vec<MissingPath>,
+}
+
+#[tauri::command]
+pub async fn save_session(
+    window: Window,
+    session: WindowSession,
+    store: State<'_, SessionStore>,
+) -> Result<(), String> {
+    store.update(window.label(), session);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn restore_session(
+    window: Window,
+    store: State<'_, SessionStore>,
+) -> Result<RestoredSession, String> {
+    let Some(session) = store.get(window.label()) else {
+        return Ok(RestoredSession {
+            tabs: Vec::new(),
+            active_index: 0,
+            folder: None,
+            missing: Vec::new(),
+        });
+    };
+
+    let mut missing = Vec::new();
+
+    // 文件夹不在了就不恢复网格视图，但要报告
+    let folder = match session.folder {
+        Some(folder) if Path::new(&folder).is_dir() => Some(folder),
+        Some(folder) => {
+            missing.push(MissingPath {
+                path: folder,
+                kind: "folder".to_string(),
+                reason: "Folder no longer exists".to_string(),
+                suggestion: None,
+            });
+            None
+        }
+        None => None,
+    };
+
+    let mut tabs = Vec::new();
+    let mut active_index = 0;
+    for (i, tab) in session.tabs.into_iter().enumerate() {
+        match load_document(&tab.path) {
+            Ok(content) => {
+                // 当前标签之前（含）每恢复一个，新下标就往后挪一个
+                if i <= session.active_index && !tabs.is_empty() {
+                    active_index += 1;
+                }
+                tabs.push(RestoredTab {
+                    path: tab.path,
+                    content,
+                    scroll: tab.scroll,
+                });
+            }
+            Err(reason) => missing.push(MissingPath {
+                suggestion: folder
+                    .as_deref()
+                    .and_then(|f| find_moved_file(Path::new(f), &tab.path)),
+                path: tab.path,
+                kind: "file".to_string(),
+                reason,
+            }),
+        }
+    }
+
+    Ok(RestoredSession {
+        tabs,
+        active_index,
+        folder,
+        missing,
+    })
+}
+
+/// 在文件夹里找同名的 Markdown 文件，作为「可能被移动到了这里」的提示
+fn find_moved_file(folder: &Path, old_path: &str) -> Option<String> {
+    let name = Path::new(old_path).file_name()?;
+    collect_markdown_paths(folder)
+        .into_iter()
+        .find(|p| p.file_name() == Some(name))
+        .map(|p| p.to_string_lossy().to_string())
+}
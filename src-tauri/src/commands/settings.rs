@@ -0,0 +1,33 @@
+// ============================
+// 设置模块 — Rust IPC 命令
+// ============================
+//
+// 数据结构、迁移和文件读写都在 state/settings.rs，这里只是薄薄一层命令包装
+
+use crate::state::collections;
+use crate::state::settings::{Settings, SettingsStore, SETTINGS_CHANGED_EVENT};
+use tauri::{AppHandle, Emitter, State};
+
+// 前端调用：invoke("get_settings")
+// 返回：当前的完整设置
+#[tauri::command]
+pub async fn get_settings(store: State<'_, SettingsStore>) -> Result<Settings, String> {
+    Ok(store.get())
+}
+
+// 前端调用：invoke("update_settings", { patch: { theme: "nord" } })
+// 返回：合并后的完整设置；同时广播 settings://changed，让其他窗口同步
+//
+// patch 是「部分设置」：只写要改的字段，嵌套对象按字段合并
+#[tauri::command]
+pub async fn update_settings(
+    patch: serde_json::Value,
+    app: AppHandle,
+    store: State<'_, SettingsStore>,
+) -> Result<Settings, String> {
+    let settings = store.update(patch)?;
+    app.emit(SETTINGS_CHANGED_EVENT, &settings)
+        .map_err(|e| e.to_string())?;
+    collections::notify_changed(&app);
+    Ok(settings)
+}
@@ -0,0 +1,126 @@
+// ============================
+// 任务汇总 — 跨文件收集 GFM 任务列表
+// ============================
+//
+// 前端调用：invoke("collect_tasks", { root: "/abs/path/to/notes", filter: { state: "open" } })
+//
+// 只读：不改任何文件。会议记录、规格文档里零散的 `- [ ]` 汇总成一张待办表，
+// 每条带上所在文件、行号和最近的标题，前端可以直接跳到对应位置。
+// 解析规则（@负责人、due:日期）见 markdown/tasks.rs。
+
+use crate::commands::markdown::collect_markdown_paths;
+use crate::markdown::tasks::extract_tasks;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// 所在文件（绝对路径）
+    pub file: String,
+    pub relative_path: String,
+    /// 列表项所在行号（1-based）
+    pub line: usize,
+    pub checked: bool,
+    pub text: String,
+    /// 最近的上级标题文字，文档开头没有标题时为 null
+    pub heading: Option<String>,
+    /// 该标题的锚点，用于打开文档后跳转
+    pub heading_slug: Option<String>,
+    /// 负责人（不含 @）
+    pub owners: Vec<String>,
+    /// 截止日期 "YYYY-MM-DD"
+    pub due: Option<String>,
+    /// 未完成且截止日期早于今天
+    pub overdue: bool,
+}
+
+/// 任务状态筛选，序列化为 "open" / "done"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskState {
+    Open,
+    Done,
+}
+
+/// 筛选条件，字段都省略时返回全部任务
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskFilter {
+    pub state: Option<TaskState>,
+    /// 负责人，不区分大小写，带不带 @ 都行
+    pub owner: Option<String>,
+    /// true 只要逾期的，false 只要没逾期的
+    pub overdue: Option<bool>,
+}
+
+impl TaskFilter {
+    fn matches(&self, task: &Task) -> bool {
+        let state_ok = match self.state {
+            Some(TaskState::Open) => !task.checked,
+            Some(TaskState::Done) => task.checked,
+            None => true,
+        };
+        let owner_ok = self.owner.as_deref().map_or(true, |owner| {
+            let owner = owner.trim().trim_start_matches('@');
+            task.owners.iter().any(|o| o.eq_ignore_ascii_case(owner))
+        });
+        let overdue_ok = self.overdue.map_or(true, |overdue| task.overdue == overdue);
+        state_ok && owner_ok && overdue_ok
+    }
+}
+
+// 返回：符合条件的任务，按相对路径、行号排序
+#[tauri::command]
+pub async fn collect_tasks(root: String, filter: Option<TaskFilter>) -> Result<Vec<Task>, String> {
+    let root_path = Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(format!("'{}' is not a directory", root));
+    }
+    let filter = filter.unwrap_or_default();
+    let mut tasks = collect(root_path, Local::now().date_naive());
+    tasks.retain(|task| filter.matches(task));
+    Ok(tasks)
+}
+
+/// root 下所有文档的任务；today 用来判断是否逾期
+fn collect(root: &Path, today: NaiveDate) -> Vec<Task> {
+    let mut files: Vec<(String, std::path::PathBuf)> = collect_markdown_paths(root)
+        .into_iter()
+        .map(|path| {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            (relative, path)
+        })
+        .collect();
+    files.sort();
+
+    let mut tasks = Vec::new();
+    // 读不了的文件（权限、非 UTF-8）直接跳过，和 list_markdown_files 的容错策略一致
+    for (relative_path, path) in files {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for item in extract_tasks(&source) {
+            let (heading, heading_slug) = item.heading.unzip();
+            tasks.push(Task {
+                file: path.to_string_lossy().to_string(),
+                relative_path: relative_path.clone(),
+                line: item.line,
+                checked: item.checked,
+                text: item.text,
+                heading,
+                heading_slug,
+                owners: item.owners,
+                due: item.due.map(|d| d.format("%Y-%m-%d").to_string()),
+                overdue: !item.checked && item.due.is_some_and(|d| d < today),
+            });
+        }
+    }
+    tasks
+}
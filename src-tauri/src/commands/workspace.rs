@@ -0,0 +1,64 @@
+// ============================
+// 快速打开 / 跳转到标题 — Rust IPC 命令
+// ============================
+//
+// 候选列表、打分和增量更新在 state/workspace.rs，这里只是命令包装。
+// 打开过的文件夹（list_markdown_files）会自动加进索引，前端不用单独登记。
+
+use crate::markdown::links::normalize_path;
+use crate::state::recent::{RecentKind, RecentStore};
+use crate::state::workspace::{HeadingMatch, QuickOpenMatch, WorkspaceIndex};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// 不指定 limit 时返回的条数
+const DEFAULT_LIMIT: usize = 50;
+
+// 前端调用：invoke("quick_open", { query: "rdme inst", limit: 20 })
+// 返回：QuickOpenMatch[]，分数从高到低；query 为空时是最近打开过的文件
+//
+// 每敲一个字符调用一次，只做内存里的打分，不读磁盘
+#[tauri::command]
+pub async fn quick_open(
+    query: String,
+    limit: Option<usize>,
+    index: State<'_, WorkspaceIndex>,
+    recent: State<'_, RecentStore>,
+) -> Result<Vec<QuickOpenMatch>, String> {
+    Ok(index.search(
+        &query,
+        &recent_files(&recent),
+        limit.unwrap_or(DEFAULT_LIMIT),
+    ))
+}
+
+// 前端调用：invoke("search_headings", { query: "inst", file: null, limit: 20 })
+// 返回：HeadingMatch[]，分数从高到低；打开 path 后跳到 #slug
+//
+// file 传当前文档的路径时只搜这个文件，query 为空时按顺序列出它的所有标题
+#[tauri::command]
+pub async fn search_headings(
+    query: String,
+    file: Option<String>,
+    limit: Option<usize>,
+    index: State<'_, WorkspaceIndex>,
+    recent: State<'_, RecentStore>,
+) -> Result<Vec<HeadingMatch>, String> {
+    let file = file.map(|f| normalize_path(Path::new(&f)));
+    Ok(index.search_headings(
+        &query,
+        file.as_deref(),
+        &recent_files(&recent),
+        limit.unwrap_or(DEFAULT_LIMIT),
+    ))
+}
+
+/// 最近打开的文件（不含文件夹），最近的在前
+fn recent_files(recent: &RecentStore) -> Vec<PathBuf> {
+    recent
+        .list()
+        .into_iter()
+        .filter(|entry| entry.kind == RecentKind::File)
+        .map(|entry| normalize_path(Path::new(&entry.path)))
+        .collect()
+}
@@ -0,0 +1,280 @@
+// ─────────────────────────────────────────────
+// Docusaurus：sidebars.js / sidebars.ts
+// ─────────────────────────────────────────────
+//
+// 文档 ID：相对 docs 目录的路径去掉扩展名，每一段去掉数字前缀（"01-intro" → "intro"），
+// front matter 的 id 替换最后一段。路由默认等于 ID；front matter 的 slug 可以改写
+// （"/" 开头的相对 docs 根，否则相对所在目录）；目录的索引文档（index、README、和目录同名）路由是目录本身。
+// 站点链接是 baseUrl + routeBasePath（默认 "docs"）+ 路由，两者都从 docusaurus.config.* 里读。
+//
+// sidebars 的写法：
+//   { mySidebar: [ 'intro',
+//                  { type: 'category', label, items, link: { type: 'doc', id } },
+//                  { type: 'doc', id, label }, { type: 'link', label, href },
+//                  { type: 'autogenerated', dirName } ] }
+//   { mySidebar: { 'Getting started': ['a', 'b'] } }   简写：键是分类名
+// 只有一个侧边栏时直接用它的项目，有多个时每个侧边栏作为一个分组。
+
+use super::js::{Module, Value};
+use super::{directory_entries, doc_title, DocSite, SiteKind, TocEntry, INDEX_NAMES};
+use crate::commands::markdown::collect_markdown_paths;
+use crate::markdown::front_matter::FrontMatter;
+use crate::markdown::links::is_external;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_NAMES: [&str; 3] = [
+    "docusaurus.config.ts",
+    "docusaurus.config.js",
+    "docusaurus.config.mjs",
+];
+const SIDEBAR_NAMES: [&str; 4] = ["sidebars.ts", "sidebars.js", "sidebars.mjs", "sidebars.cjs"];
+
+/// dir 下有没有 Docusaurus 配置或侧边栏定义
+pub(super) fn has_config(dir: &Path) -> bool {
+    CONFIG_NAMES
+        .iter()
+        .chain(SIDEBAR_NAMES.iter())
+        .any(|name| dir.join(name).is_file())
+}
+
+pub(super) fn open(dir: &Path) -> Option<DocSite> {
+    let config = CONFIG_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file());
+    let sidebars = SIDEBAR_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file());
+    if config.is_none() && sidebars.is_none() {
+        return None;
+    }
+
+    let value = config
+        .as_deref()
+        .and_then(Module::read)
+        .and_then(|m| m.default_export());
+    // preset-classic 的 docs 选项：有 sidebarPath 或 routeBasePath 的那个对象
+    let docs = value.as_ref().and_then(|v| {
+        v.find_object(&|o| o.get("sidebarPath").is_some() || o.get("routeBasePath").is_some())
+    });
+    let option = |key: &str| docs.and_then(|d| d.get(key)).and_then(string_value);
+    let docs_dir = dir.join(option("path").unwrap_or("docs"));
+    let route_base = option("routeBasePath").unwrap_or("docs");
+    let base_url = value
+        .as_ref()
+        .and_then(|v| v.find("baseUrl"))
+        .and_then(string_value)
+        .unwrap_or("/");
+    let sidebars = option("sidebarPath")
+        .map(|p| dir.join(p))
+        .or(sidebars)
+        .unwrap_or_else(|| dir.join("sidebars.js"));
+
+    let base = format!(
+        "{}/{}",
+        base_url.trim_end_matches('/'),
+        route_base.trim_matches('/')
+    );
+    Some(DocSite::new(
+        SiteKind::Docusaurus,
+        dir,
+        docs_dir,
+        sidebars,
+        &base,
+    ))
+}
+
+/// 字符串，或者 `require.resolve('./sidebars.js')` 这种只有一个字符串参数的调用
+fn string_value(value: &Value) -> Option<&str> {
+    match value {
+        Value::Str(s) => Some(s),
+        Value::Call(_, args) => args.first().and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+/// 文档 ID / 路由 → 文件
+#[derive(Default)]
+pub(super) struct Routes {
+    ids: HashMap<String, PathBuf>,
+    routes: HashMap<String, PathBuf>,
+}
+
+impl Routes {
+    pub(super) fn scan(docs_dir: &Path) -> Routes {
+        let mut table = Routes::default();
+        for path in collect_markdown_paths(docs_dir) {
+            let Ok(relative) = path.strip_prefix(docs_dir) else {
+                continue;
+            };
+            let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let dirs: Vec<String> = relative
+                .parent()
+                .into_iter()
+                .flat_map(Path::components)
+                .map(|c| strip_number_prefix(&c.as_os_str().to_string_lossy()).to_string())
+                .collect();
+            let dir_id = dirs.join("/");
+            let join = |last: &str| {
+                if dir_id.is_empty() {
+                    last.to_string()
+                } else {
+                    format!("{}/{}", dir_id, last)
+                }
+            };
+
+            let source = fs::read_to_string(&path).unwrap_or_default();
+            let front_matter = FrontMatter::parse(&source);
+            let name = strip_number_prefix(&stem).to_string();
+            let id = join(&front_matter.text("id").unwrap_or_else(|| name.clone()));
+            let is_index = INDEX_NAMES.iter().any(|i| i.eq_ignore_ascii_case(&name))
+                || dirs.last().is_some_and(|d| d.eq_ignore_ascii_case(&name));
+            let route = match front_matter.text("slug") {
+                Some(slug) if slug.starts_with('/') => slug.trim_matches('/').to_string(),
+                Some(slug) => join(slug.trim_matches('/')),
+                None if is_index => dir_id.clone(),
+                None => id.clone(),
+            };
+            table.ids.insert(id, path.clone());
+            table.routes.insert(route, path);
+        }
+        table
+    }
+
+    /// 路由（不含 base）对应的文件，也接受文档 ID
+    pub(super) fn route(&self, route: &str) -> Option<&Path> {
+        let route = route.trim_matches('/');
+        self.routes
+            .get(route)
+            .or_else(|| self.ids.get(route))
+            .map(PathBuf::as_path)
+    }
+}
+
+/// "01-intro" / "2_setup" / "03. faq" → "intro" / "setup" / "faq"
+fn strip_number_prefix(name: &str) -> &str {
+    let digits = name.len() - name.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    match name[digits..].chars().next() {
+        Some('-' | '_' | '.') if digits > 0 => name[digits + 1..].trim_start(),
+        _ => name,
+    }
+}
+
+pub(super) fn toc(site: &DocSite) -> Vec<TocEntry> {
+    let routes = site.routes.get_or_init(|| Routes::scan(&site.docs_dir));
+    let value = Module::read(&site.config).and_then(|m| m.default_export());
+    let Some(Value::Object(sidebars)) = value else {
+        return directory_entries(&site.docs_dir);
+    };
+    let mut groups: Vec<TocEntry> = sidebars
+        .iter()
+        .map(|(name, items)| TocEntry {
+            title: name.clone(),
+            children: sidebar_items(site, routes, items),
+            ..TocEntry::default()
+        })
+        .collect();
+    if groups.len() == 1 {
+        return groups.pop().map(|g| g.children).unwrap_or_default();
+    }
+    groups
+}
+
+fn sidebar_items(site: &DocSite, routes: &Routes, value: &Value) -> Vec<TocEntry> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .flat_map(|item| item_entries(site, routes, item))
+            .collect(),
+        // 简写：{ '分类名': [...] }
+        Value::Object(categories) => categories
+            .iter()
+            .map(|(label, items)| TocEntry {
+                title: label.clone(),
+                children: sidebar_items(site, routes, items),
+                ..TocEntry::default()
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn item_entries(site: &DocSite, routes: &Routes, item: &Value) -> Vec<TocEntry> {
+    let label = item.str_field("label");
+    match item {
+        Value::Str(id) => vec![doc(routes, id, None)],
+        Value::Object(_) => match item.str_field("type") {
+            Some("doc" | "ref") => item
+                .str_field("id")
+                .map(|id| doc(routes, id, label))
+                .into_iter()
+                .collect(),
+            Some("link") => {
+                let href = item.str_field("href").unwrap_or_default();
+                let mut entry = TocEntry {
+                    title: label.unwrap_or(href).to_string(),
+                    ..TocEntry::default()
+                };
+                if is_external(href) {
+                    entry.url = Some(href.to_string());
+                } else {
+                    entry.path = site
+                        .resolve(&site.docs_dir, href)
+                        .map(|p| p.to_string_lossy().to_string());
+                }
+                vec![entry]
+            }
+            Some("autogenerated") => {
+                directory_entries(&site.docs_dir.join(item.str_field("dirName").unwrap_or(".")))
+            }
+            Some("category") => vec![category(site, routes, item)],
+            None if item.get("items").is_some() => vec![category(site, routes, item)],
+            // 简写的分类
+            None => sidebar_items(site, routes, item),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn category(site: &DocSite, routes: &Routes, item: &Value) -> TocEntry {
+    // link: { type: 'doc', id } 时点分类名打开那篇文档
+    let path = item
+        .get("link")
+        .and_then(|link| link.str_field("id"))
+        .and_then(|id| routes.ids.get(id))
+        .map(|p| p.to_string_lossy().to_string());
+    TocEntry {
+        title: item.str_field("label").unwrap_or_default().to_string(),
+        path,
+        url: None,
+        children: item
+            .get("items")
+            .map(|items| sidebar_items(site, routes, items))
+            .unwrap_or_default(),
+    }
+}
+
+/// 文档 ID → 目录项；标题依次取 label、front matter 的 sidebar_label、文档标题
+fn doc(routes: &Routes, id: &str, label: Option<&str>) -> TocEntry {
+    let path = routes.ids.get(id);
+    let title = label.map(str::to_string).or_else(|| {
+        let path = path?;
+        let source = fs::read_to_string(path).ok()?;
+        Some(
+            FrontMatter::parse(&source)
+                .text("sidebar_label")
+                .unwrap_or_else(|| doc_title(path)),
+        )
+    });
+    TocEntry {
+        title: title.unwrap_or_else(|| id.to_string()),
+        path: path.map(|p| p.to_string_lossy().to_string()),
+        ..TocEntry::default()
+    }
+}
@@ -0,0 +1,781 @@
+// ─────────────────────────────────────────────
+// 宽松地读取 JS / TS 配置文件里的字面量
+// ─────────────────────────────────────────────
+//
+// VitePress 和 Docusaurus 的配置是代码，不是数据。这里不执行代码，只认对象、数组、字符串字面量，
+// 其余（函数体、表达式、类型标注、`as const`）一律跳过，读到哪算哪，不会报错。
+//
+// 侧边栏常常写在别处再引用，所以值是标识符或无参函数调用时，到同一个文件里找定义：
+//   const guide = [...]                    → 数组本身
+//   function sidebarGuide() { return [...] } → return 后面的值
+// 展开语法（`...common`）也按同样的方式找到定义后合并进来。
+
+use std::fs;
+use std::path::Path;
+
+/// 追定义的最大层数，防止互相引用时死循环
+const MAX_RESOLVE_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// 属性按出现顺序排列；`...x` 展开记成键为 "..." 的一项，resolve 之后会被合并掉
+    Object(Vec<(String, Value)>),
+    Array(Vec<Value>),
+    Str(String),
+    /// 标识符（可以带点，如 "sidebars.guide"）
+    Ident(String),
+    /// 函数调用：函数名 + 参数
+    Call(String, Vec<Value>),
+    /// 数组里的 `...x`，resolve 之后会被展开掉
+    Spread(Box<Value>),
+    /// 数字、布尔值和读不懂的表达式
+    Other,
+}
+
+impl Value {
+    /// 对象的属性
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// 对象的文本属性
+    pub fn str_field(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Value::as_str)
+    }
+
+    /// 深度优先找第一个满足条件的对象（也会进函数调用的参数里找）
+    pub fn find_object(&self, wanted: &dyn Fn(&Value) -> bool) -> Option<&Value> {
+        if matches!(self, Value::Object(_)) && wanted(self) {
+            return Some(self);
+        }
+        let children: Vec<&Value> = match self {
+            Value::Object(entries) => entries.iter().map(|(_, v)| v).collect(),
+            Value::Array(items) | Value::Call(_, items) => items.iter().collect(),
+            Value::Spread(inner) => vec![inner.as_ref()],
+            _ => Vec::new(),
+        };
+        children.into_iter().find_map(|v| v.find_object(wanted))
+    }
+
+    /// 深度优先找第一个名为 key 的属性的值
+    pub fn find(&self, key: &str) -> Option<&Value> {
+        self.find_object(&|v| v.get(key).is_some())
+            .and_then(|object| object.get(key))
+    }
+}
+
+/// 一个读进来的 JS / TS 文件
+pub struct Module {
+    source: String,
+}
+
+impl Module {
+    pub fn read(path: &Path) -> Option<Module> {
+        Some(Module {
+            source: fs::read_to_string(path).ok()?,
+        })
+    }
+
+    /// `export default …` 或 `module.exports = …` 的值，引用都已换成定义
+    pub fn default_export(&self) -> Option<Value> {
+        let start = if let Some(at) = self.source.find("export default") {
+            at + "export default".len()
+        } else {
+            let at = self.source.find("module.exports")? + "module.exports".len();
+            let rest = &self.source[at..];
+            at + rest.find('=')? + 1
+        };
+        let value = Reader::new(&self.source, start).value();
+        Some(self.resolve(value, 0))
+    }
+
+    /// 把标识符、无参调用和展开换成同一文件里的定义（递归处理整个值）
+    fn resolve(&self, value: Value, depth: usize) -> Value {
+        if depth > MAX_RESOLVE_DEPTH {
+            return Value::Other;
+        }
+        match value {
+            Value::Ident(name) => match self.definition(&name) {
+                Some(found) => self.resolve(found, depth + 1),
+                None => Value::Ident(name),
+            },
+            Value::Call(name, args) if args.is_empty() => match self.definition(&name) {
+                Some(found) => self.resolve(found, depth + 1),
+                None => Value::Call(name, args),
+            },
+            Value::Call(name, args) => Value::Call(
+                name,
+                args.into_iter().map(|v| self.resolve(v, depth)).collect(),
+            ),
+            Value::Array(items) => {
+                let mut out = Vec::with_capacity(items.len());
+                for item in items {
+                    match item {
+                        Value::Spread(inner) => {
+                            if let Value::Array(spread) = self.resolve(*inner, depth + 1) {
+                                out.extend(spread);
+                            }
+                        }
+                        other => out.push(self.resolve(other, depth)),
+                    }
+                }
+                Value::Array(out)
+            }
+            Value::Object(entries) => {
+                let mut out: Vec<(String, Value)> = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    if key == "..." {
+                        if let Value::Object(spread) = self.resolve(value, depth + 1) {
+                            for (k, v) in spread {
+                                out.retain(|(existing, _)| *existing != k);
+                                out.push((k, v));
+                            }
+                        }
+                        continue;
+                    }
+                    out.retain(|(existing, _)| *existing != key);
+                    out.push((key, self.resolve(value, depth)));
+                }
+                Value::Object(out)
+            }
+            Value::Spread(inner) => self.resolve(*inner, depth),
+            other => other,
+        }
+    }
+
+    /// 同一文件里 `const / let / var 名字 = 值` 或 `function 名字() { return 值 }` 的值
+    fn definition(&self, name: &str) -> Option<Value> {
+        if name.is_empty() || name.contains('.') {
+            return None;
+        }
+        let source = self.source.as_str();
+        for (at, _) in source.match_indices(name) {
+            let end = at + name.len();
+            let before = source[..at].chars().next_back();
+            let after = source[end..].chars().next();
+            if before.is_some_and(is_ident_char) || after.is_some_and(is_ident_char) {
+                continue;
+            }
+            let keyword = source[..at]
+                .trim_end()
+                .rsplit(|c: char| !is_ident_char(c))
+                .next()
+                .unwrap_or("");
+            match keyword {
+                "const" | "let" | "var" => {
+                    // 跳过类型标注，找到赋值的 "="（不是 "=>"）
+                    let mut reader = Reader::new(source, end);
+                    while let Some(c) = reader.peek() {
+                        if c == '=' && !reader.rest().starts_with("=>") {
+                            reader.pos += 1;
+                            return Some(reader.value());
+                        }
+                        if c == ';' || (c == '\n' && reader.depth_zero_line_end()) {
+                            break;
+                        }
+                        reader.skip_token();
+                    }
+                }
+                "function" => {
+                    // 函数体里第一个 return 的值
+                    let body = end + source[end..].find('{')?;
+                    let at = body + source[body..].find("return")? + "return".len();
+                    return Some(Reader::new(source, at).value());
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// 从某个位置开始读一个值
+struct Reader<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(source: &'a str, pos: usize) -> Reader<'a> {
+        Reader { source, pos }
+    }
+
+    fn rest(&self) -> &'a str {
+        self.source.get(self.pos..).unwrap_or("")
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// 跳过空白和注释
+    fn skip_space(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                self.pos += trimmed.find("*/").map_or(trimmed.len(), |i| i + 2);
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// 在类型标注里遇到换行：后面不是续行的符号时，声明就到此为止
+    fn depth_zero_line_end(&self) -> bool {
+        let next = self.rest()[1..].trim_start();
+        !next.starts_with(['|', '&', '.', '<', '>', ',', '='])
+    }
+
+    fn value(&mut self) -> Value {
+        self.skip_space();
+        let Some(c) = self.peek() else {
+            return Value::Other;
+        };
+        match c {
+            '{' => self.object(),
+            '[' => self.array(),
+            '\'' | '"' | '`' => Value::Str(self.string()),
+            '(' => {
+                // 箭头函数 (x) => 值，或者带括号的表达式（`/** @type … */ ({ … })`）取括号里的值
+                let open = self.pos;
+                self.skip_balanced();
+                self.skip_space();
+                if self.rest().starts_with("=>") {
+                    self.pos += 2;
+                    self.value()
+                } else {
+                    Reader::new(self.source, open + 1).value()
+                }
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => self.identifier_value(),
+            _ => {
+                self.skip_token();
+                Value::Other
+            }
+        }
+    }
+
+    fn identifier_value(&mut self) -> Value {
+        let name = self.identifier();
+        match name.as_str() {
+            "true" | "false" | "null" | "undefined" => return Value::Other,
+            // new X(...)、await x、satisfies 之类的前缀直接看后面的值
+            "new" | "await" | "async" => return self.value(),
+            "function" => {
+                // 匿名函数表达式：取函数体里 return 的值
+                while let Some(c) = self.peek() {
+                    if c == '{' {
+                        break;
+                    }
+                    self.skip_token();
+                }
+                let body = self.rest();
+                return match body.find("return") {
+                    Some(at) => {
+                        self.pos += at + "return".len();
+                        self.value()
+                    }
+                    None => Value::Other,
+                };
+            }
+            _ => {}
+        }
+        self.skip_space();
+        if self.rest().starts_with("=>") {
+            self.pos += 2;
+            return self.value();
+        }
+        // 泛型参数 defineConfig<Foo>(...)
+        if self.peek() == Some('<') {
+            let save = self.pos;
+            self.skip_angle();
+            self.skip_space();
+            if self.peek() != Some('(') {
+                self.pos = save;
+            }
+        }
+        if self.peek() == Some('(') {
+            return Value::Call(name, self.arguments());
+        }
+        Value::Ident(name)
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if is_ident_char(c) || c == '.' {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        self.source[start..self.pos].to_string()
+    }
+
+    fn object(&mut self) -> Value {
+        self.bump();
+        let mut entries = Vec::new();
+        loop {
+            self.skip_space();
+            match self.peek() {
+                None => break,
+                Some('}') => {
+                    self.bump();
+                    break;
+                }
+                Some(',') => {
+                    self.bump();
+                    continue;
+                }
+                _ => {}
+            }
+            if self.rest().starts_with("...") {
+                self.pos += 3;
+                entries.push(("...".to_string(), self.value()));
+                self.skip_to_separator();
+                continue;
+            }
+            let key = match self.peek() {
+                Some('\'' | '"' | '`') => self.string(),
+                Some('[') => {
+                    self.skip_balanced();
+                    String::new()
+                }
+                Some(c) if is_ident_char(c) => self.identifier(),
+                _ => {
+                    self.skip_token();
+                    continue;
+                }
+            };
+            self.skip_space();
+            let value = match self.peek() {
+                Some(':') => {
+                    self.bump();
+                    self.value()
+                }
+                // 方法简写 key() { ... }
+                Some('(') => {
+                    self.skip_balanced();
+                    Value::Other
+                }
+                // 属性简写 { sidebar }
+                _ => Value::Ident(key.clone()),
+            };
+            entries.push((key, value));
+            self.skip_to_separator();
+        }
+        Value::Object(entries)
+    }
+
+    fn array(&mut self) -> Value {
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            self.skip_space();
+            match self.peek() {
+                None => break,
+                Some(']') => {
+                    self.bump();
+                    break;
+                }
+                Some(',') => {
+                    self.bump();
+                    continue;
+                }
+                _ => {}
+            }
+            if self.rest().starts_with("...") {
+                self.pos += 3;
+                items.push(Value::Spread(Box::new(self.value())));
+            } else {
+                items.push(self.value());
+            }
+            self.skip_to_separator();
+        }
+        Value::Array(items)
+    }
+
+    fn arguments(&mut self) -> Vec<Value> {
+        self.bump();
+        let mut args = Vec::new();
+        loop {
+            self.skip_space();
+            match self.peek() {
+                None => break,
+                Some(')') => {
+                    self.bump();
+                    break;
+                }
+                Some(',') => {
+                    self.bump();
+                    continue;
+                }
+                _ => {}
+            }
+            args.push(self.value());
+            self.skip_to_separator();
+        }
+        args
+    }
+
+    /// 字符串字面量（当前位置是引号）；模板字符串里的 ${...} 原样保留
+    fn string(&mut self) -> String {
+        let quote = self.peek().unwrap_or('"');
+        self.bump();
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            self.bump();
+            match c {
+                '\\' => {
+                    if let Some(escaped) = self.peek() {
+                        self.bump();
+                        out.push(match escaped {
+                            'n' => '\n',
+                            't' => '\t',
+                            other => other,
+                        });
+                    }
+                }
+                c if c == quote => break,
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// 跳过当前的一个记号：括号整体、字符串整体、注释，或者一个字符
+    fn skip_token(&mut self) {
+        match self.peek() {
+            Some('{' | '[' | '(') => self.skip_balanced(),
+            Some('\'' | '"' | '`') => {
+                self.string();
+            }
+            Some('/') if self.rest().starts_with("//") || self.rest().starts_with("/*") => {
+                self.skip_space()
+            }
+            _ => self.bump(),
+        }
+    }
+
+    /// 跳过一对括号（当前位置是左括号），里面的字符串和注释不参与配对
+    fn skip_balanced(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '{' | '[' | '(' => {
+                    depth += 1;
+                    self.bump();
+                }
+                '}' | ']' | ')' => {
+                    self.bump();
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                '\'' | '"' | '`' => {
+                    self.string();
+                }
+                '/' if self.rest().starts_with("//") || self.rest().starts_with("/*") => {
+                    self.skip_space()
+                }
+                _ => self.bump(),
+            }
+        }
+    }
+
+    /// 跳过泛型参数 <...>
+    fn skip_angle(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            self.bump();
+            match c {
+                '<' => depth += 1,
+                '>' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 值后面可能还有 `as const`、`.map(...)`、类型标注等，跳到下一个 "," 或右括号
+    fn skip_to_separator(&mut self) {
+        loop {
+            self.skip_space();
+            match self.peek() {
+                None | Some(',' | '}' | ']' | ')') => return,
+                _ => self.skip_token(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(source: &str) -> Value {
+        let module = Module {
+            source: source.to_string(),
+        };
+        module.default_export().expect("default export")
+    }
+
+    fn texts(items: &Value, key: &str) -> Vec<String> {
+        match items {
+            Value::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::Str(s) => s.clone(),
+                    other => other.str_field(key).unwrap_or("?").to_string(),
+                })
+                .collect(),
+            other => panic!("not an array: {:?}", other),
+        }
+    }
+
+    // VitePress 自己文档站的写法：defineConfig、类型标注的函数、多个侧边栏
+    const VITEPRESS: &str = r#"
+import { defineConfig, type DefaultTheme } from 'vitepress'
+
+const pkg = require('vitepress/package.json')
+
+export default defineConfig({
+  lang: 'en-US',
+  base: '/docs/',
+  themeConfig: {
+    nav: nav(),
+    sidebar: {
+      '/guide/': { base: '/guide/', items: sidebarGuide() },
+      '/reference/': { base: '/reference/', items: sidebarReference() }
+    },
+    editLink: {
+      pattern: 'https://github.com/vuejs/vitepress/edit/main/docs/:path',
+      text: 'Edit this page on GitHub'
+    },
+  }
+})
+
+function nav(): DefaultTheme.NavItem[] {
+  return [
+    { text: 'Guide', link: '/guide/what-is-vitepress', activeMatch: '/guide/' },
+    { text: pkg.version, items: [{ text: 'Changelog', link: 'https://github.com/vuejs/vitepress/blob/main/CHANGELOG.md' }] }
+  ]
+}
+
+function sidebarGuide(): DefaultTheme.SidebarItem[] {
+  return [
+    {
+      text: 'Introduction',
+      collapsed: false,
+      items: [
+        { text: 'What is VitePress?', link: 'what-is-vitepress' },
+        { text: 'Getting Started', link: 'getting-started' },
+        { text: 'Routing', link: 'routing' },
+      ]
+    },
+    { text: 'Config & API Reference', base: '/reference/', link: 'site-config' }
+  ]
+}
+
+function sidebarReference(): DefaultTheme.SidebarItem[] {
+  return [
+    {
+      text: 'Reference',
+      items: [
+        { text: 'Site Config', link: 'site-config' },
+        { text: 'Frontmatter Config', link: 'frontmatter-config' },
+        ...runtimeItems
+      ]
+    }
+  ]
+}
+
+const runtimeItems = [{ text: 'Runtime API', link: 'runtime-api' }] as const
+"#;
+
+    #[test]
+    fn vitepress_sidebar() {
+        let config = module(VITEPRESS);
+        let Value::Call(name, args) = &config else {
+            panic!("not a call: {:?}", config);
+        };
+        assert_eq!(name, "defineConfig");
+        let options = &args[0];
+        assert_eq!(options.str_field("base"), Some("/docs/"));
+
+        let sidebar = options.get("themeConfig").and_then(|t| t.get("sidebar"));
+        let sidebar = sidebar.expect("sidebar");
+        let guide = sidebar.get("/guide/").expect("guide sidebar");
+        assert_eq!(guide.str_field("base"), Some("/guide/"));
+        let sections = guide.get("items").expect("items");
+        assert_eq!(
+            texts(sections, "text"),
+            ["Introduction", "Config & API Reference"]
+        );
+        let Value::Array(sections) = sections else {
+            unreachable!()
+        };
+        assert_eq!(
+            texts(sections[0].get("items").unwrap(), "link"),
+            ["what-is-vitepress", "getting-started", "routing"]
+        );
+
+        // 展开语法引用的常量（带 as const）也合并进来
+        let reference = sidebar.get("/reference/").and_then(|r| r.get("items"));
+        let Some(Value::Array(reference)) = reference else {
+            panic!("reference sidebar: {:?}", reference);
+        };
+        assert_eq!(
+            texts(reference[0].get("items").unwrap(), "text"),
+            ["Site Config", "Frontmatter Config", "Runtime API"]
+        );
+    }
+
+    // create-docusaurus 的 classic 模板（JS 版）
+    const DOCUSAURUS_CONFIG: &str = r#"
+// @ts-check
+import {themes as prismThemes} from 'prism-react-renderer';
+
+/** @type {import('@docusaurus/types').Config} */
+const config = {
+  title: 'My Site',
+  url: 'https://your-docusaurus-site.example.com',
+  baseUrl: '/',
+  onBrokenLinks: 'throw',
+  presets: [
+    [
+      'classic',
+      /** @type {import('@docusaurus/preset-classic').Options} */
+      ({
+        docs: {
+          sidebarPath: './sidebars.js',
+          editUrl:
+            'https://github.com/facebook/docusaurus/tree/main/packages/create-docusaurus/templates/shared/',
+        },
+        theme: {
+          customCss: './src/css/custom.css',
+        },
+      }),
+    ],
+  ],
+  themeConfig:
+    /** @type {import('@docusaurus/preset-classic').ThemeConfig} */
+    ({
+      navbar: { title: 'My Site', items: [{type: 'docSidebar', sidebarId: 'tutorialSidebar', label: 'Tutorial'}] },
+      prism: { theme: prismThemes.github },
+    }),
+};
+
+export default config;
+"#;
+
+    #[test]
+    fn docusaurus_config() {
+        let config = module(DOCUSAURUS_CONFIG);
+        assert_eq!(config.str_field("baseUrl"), Some("/"));
+        // 带括号、前面有类型注释的 preset 选项
+        let docs = config
+            .find_object(&|o| o.get("sidebarPath").is_some())
+            .expect("docs options");
+        assert_eq!(docs.str_field("sidebarPath"), Some("./sidebars.js"));
+        let navbar = config.find("navbar").expect("navbar");
+        assert_eq!(navbar.str_field("title"), Some("My Site"));
+    }
+
+    // 手写的 sidebars.js：简写的分类、category 对象、注释掉的旧写法、module.exports
+    const DOCUSAURUS_SIDEBARS: &str = r#"
+/**
+ * Creating a sidebar enables you to:
+ - create an ordered group of docs
+ */
+
+// @ts-check
+
+/** @type {import('@docusaurus/plugin-content-docs').SidebarsConfig} */
+const sidebars = {
+  docs: [
+    'intro',
+    {
+      type: 'category',
+      label: 'Guides',
+      link: {type: 'doc', id: 'guides/index'},
+      items: ['guides/install', 'guides/deploy', ...extra],
+    },
+    {type: 'link', label: 'Blog', href: 'https://example.com/blog'},
+  ],
+  api: {
+    'Getting Started': ['api/overview'],
+    Reference: [{type: 'autogenerated', dirName: 'api/reference'}],
+  },
+  // tutorialSidebar: [{type: 'autogenerated', dirName: '.'}],
+};
+
+const extra = ['guides/faq'];
+
+module.exports = sidebars;
+"#;
+
+    #[test]
+    fn docusaurus_sidebars() {
+        let sidebars = module(DOCUSAURUS_SIDEBARS);
+        let Value::Object(entries) = &sidebars else {
+            panic!("not an object: {:?}", sidebars);
+        };
+        let names: Vec<&str> = entries.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(names, ["docs", "api"]);
+
+        let docs = sidebars.get("docs").unwrap();
+        assert_eq!(texts(docs, "label"), ["intro", "Guides", "Blog"]);
+        let Value::Array(docs) = docs else {
+            unreachable!()
+        };
+        assert_eq!(
+            docs[1].get("link").unwrap().str_field("id"),
+            Some("guides/index")
+        );
+        assert_eq!(
+            texts(docs[1].get("items").unwrap(), "id"),
+            ["guides/install", "guides/deploy", "guides/faq"]
+        );
+        assert_eq!(docs[2].str_field("href"), Some("https://example.com/blog"));
+
+        let api = sidebars.get("api").unwrap();
+        assert_eq!(
+            texts(api.get("Getting Started").unwrap(), "id"),
+            ["api/overview"]
+        );
+        assert_eq!(
+            texts(api.get("Reference").unwrap(), "dirName"),
+            ["api/reference"]
+        );
+    }
+}
@@ -0,0 +1,298 @@
+// ─────────────────────────────────────────────
+// mdBook：book.toml + SUMMARY.md，以及 {{#include}}
+// ─────────────────────────────────────────────
+//
+// SUMMARY.md 的结构：
+//   # Summary                   开头的标题是书名，忽略
+//   [Introduction](README.md)   列表之外的链接：前言 / 后记章节，放在顶层
+//   # Part I                    其余标题是「部分」，后面的章节都归到它下面
+//   - [Chapter](ch1.md)         编号章节，嵌套列表是子章节
+//   - [Draft]()                 草稿章节：有标题，没有文件
+//   ---                         分隔线，忽略
+//
+// include 的写法：{{#include file}}、{{#include file:anchor}}、{{#include file:10:20}}
+// （行号从 1 开始，"2:" 到文件末尾，":10" 从开头，单个数字只取那一行）。
+// 锚点区间在 "ANCHOR: name" 和 "ANCHOR_END: name" 两行之间；所有 ANCHOR 标记行都不输出。
+// 被包含的文件里的 include 相对它自己的目录继续展开；`\{{#include …}}` 是转义，输出时去掉反斜杠。
+// 只能包含站点根目录（book.toml 所在目录）里的文件：绝对路径、用 .. 或符号链接跑到外面的都不展开。
+
+use super::{DocSite, SiteKind, TocEntry};
+use crate::markdown::links::{is_external, normalize_path, percent_decode, split_fragment};
+use crate::markdown::parser_options;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use std::fs;
+use std::path::Path;
+
+/// include 的最大嵌套层数（和 mdBook 一样）
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+/// dir 下有没有 book.toml
+pub(super) fn has_config(dir: &Path) -> bool {
+    dir.join("book.toml").is_file()
+}
+
+/// dir 下有 book.toml：源文件在 [book] src（默认 src），站点前缀取 [output.html] site-url
+pub(super) fn open(dir: &Path) -> Option<DocSite> {
+    let config = fs::read_to_string(dir.join("book.toml")).ok()?;
+    let src = toml_string(&config, "book", "src").unwrap_or_else(|| "src".to_string());
+    let base = toml_string(&config, "output.html", "site-url").unwrap_or_default();
+    let docs_dir = dir.join(src);
+    let summary = docs_dir.join("SUMMARY.md");
+    Some(DocSite::new(
+        SiteKind::MdBook,
+        dir,
+        docs_dir,
+        summary,
+        &base,
+    ))
+}
+
+/// 没有 book.toml、只有 SUMMARY.md（GitBook 风格）：SUMMARY.md 所在目录就是源文件目录
+pub(super) fn open_summary(dir: &Path) -> Option<DocSite> {
+    let summary = dir.join("SUMMARY.md");
+    if !summary.is_file() {
+        return None;
+    }
+    Some(DocSite::new(
+        SiteKind::MdBook,
+        dir,
+        dir.to_path_buf(),
+        summary,
+        "/",
+    ))
+}
+
+/// book.toml 里某一节的字符串值（只认 `key = "value"` 这种最简单的写法）
+fn toml_string(text: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name.trim().to_string();
+            continue;
+        }
+        if current != section {
+            continue;
+        }
+        let Some((k, v)) = line.split_once('=') else {
+            continue;
+        };
+        if k.trim() == key {
+            let v = v.trim();
+            return Some(v.trim_matches(|c| c == '"' || c == '\'').to_string());
+        }
+    }
+    None
+}
+
+pub(super) fn toc(site: &DocSite) -> Vec<TocEntry> {
+    let Ok(source) = fs::read_to_string(&site.config) else {
+        return Vec::new();
+    };
+
+    let mut root: Vec<TocEntry> = Vec::new();
+    // root 里当前「部分」的下标
+    let mut part: Option<usize> = None;
+    // 还没结束的列表项，最后一个是最内层
+    let mut open: Vec<TocEntry> = Vec::new();
+    // 正在收集文字的标题或链接
+    let mut text: Option<String> = None;
+    let mut link: Option<String> = None;
+    let mut started = false;
+
+    for event in Parser::new_ext(&source, parser_options()) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => text = Some(String::new()),
+            Event::End(TagEnd::Heading(_)) => {
+                let title = text.take().unwrap_or_default();
+                // 开头的标题是书名
+                if !started {
+                    started = true;
+                    continue;
+                }
+                root.push(TocEntry {
+                    title,
+                    ..TocEntry::default()
+                });
+                part = Some(root.len() - 1);
+            }
+            Event::Start(Tag::Item) => {
+                started = true;
+                open.push(TocEntry::default());
+            }
+            Event::End(TagEnd::Item) => {
+                if let Some(entry) = open.pop() {
+                    if !entry.title.is_empty() || !entry.children.is_empty() {
+                        attach(entry, &mut root, part, &mut open);
+                    }
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                started = true;
+                link = Some(dest_url.to_string());
+                text = Some(String::new());
+            }
+            Event::End(TagEnd::Link) => {
+                let title = text.take().unwrap_or_default();
+                let entry = chapter(site, title, &link.take().unwrap_or_default());
+                match open.last_mut() {
+                    Some(item) if item.title.is_empty() => {
+                        item.title = entry.title;
+                        item.path = entry.path;
+                        item.url = entry.url;
+                    }
+                    Some(_) => {}
+                    // 列表之外的链接：前言 / 后记章节，不属于任何部分
+                    None => root.push(entry),
+                }
+            }
+            Event::Text(t) | Event::Code(t) => {
+                if let Some(buf) = text.as_mut() {
+                    buf.push_str(&t);
+                }
+            }
+            _ => {}
+        }
+    }
+    root
+}
+
+/// 挂到最内层的列表项下；不在列表里时挂到当前部分下，没有部分时放在顶层
+fn attach(entry: TocEntry, root: &mut Vec<TocEntry>, part: Option<usize>, open: &mut [TocEntry]) {
+    if let Some(parent) = open.last_mut() {
+        parent.children.push(entry);
+    } else if let Some(p) = part {
+        root[p].children.push(entry);
+    } else {
+        root.push(entry);
+    }
+}
+
+/// 章节链接（相对源文件目录）→ 目录项；空链接是草稿章节
+fn chapter(site: &DocSite, title: String, dest: &str) -> TocEntry {
+    if is_external(dest) {
+        return TocEntry {
+            title,
+            url: Some(dest.to_string()),
+            ..TocEntry::default()
+        };
+    }
+    let (path, _) = split_fragment(dest);
+    let path = (!path.is_empty())
+        .then(|| normalize_path(&site.docs_dir.join(percent_decode(path))))
+        .filter(|p| p.is_file());
+    TocEntry {
+        title,
+        path: path.map(|p| p.to_string_lossy().to_string()),
+        ..TocEntry::default()
+    }
+}
+
+// ─────────────────────────────────────────────
+// {{#include}}
+// ─────────────────────────────────────────────
+
+/// 展开 file 里的 include；读不到或在 root 之外的文件保留原来的写法
+pub(super) fn expand_includes(root: &Path, file: &Path, source: &str) -> String {
+    let Ok(root) = fs::canonicalize(root) else {
+        return source.to_string();
+    };
+    expand(&root, file, source, 0)
+}
+
+fn expand(root: &Path, file: &Path, source: &str, depth: usize) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(at) = rest.find("{{#") {
+        let Some(close) = rest[at..].find("}}").map(|i| at + i) else {
+            break;
+        };
+        let directive = &rest[at..close + 2];
+        if rest[..at].ends_with('\\') {
+            out.push_str(&rest[..at - 1]);
+            out.push_str(directive);
+        } else {
+            out.push_str(&rest[..at]);
+            match include(root, file, &rest[at + 3..close], depth) {
+                Some(text) => out.push_str(&text),
+                None => out.push_str(directive),
+            }
+        }
+        rest = &rest[close + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 单个 {{#…}} 指令（不含花括号）；不是 include、文件读不到或不在 root（已规范化）里时返回 None
+fn include(root: &Path, file: &Path, directive: &str, depth: usize) -> Option<String> {
+    let (name, args) = directive.trim().split_once(char::is_whitespace)?;
+    if !matches!(name, "include" | "rustdoc_include") {
+        return None;
+    }
+    let mut parts = args.trim().split(':');
+    let target = parts.next()?.trim();
+    let selector: Vec<&str> = parts.map(str::trim).collect();
+    if target.is_empty() || Path::new(target).has_root() {
+        return None;
+    }
+    let path = normalize_path(&file.parent()?.join(target));
+    // 解析符号链接后再比较，链接到站点外面的文件也不行
+    if !fs::canonicalize(&path).is_ok_and(|real| real.starts_with(root)) {
+        return None;
+    }
+    let content = fs::read_to_string(&path).ok()?;
+    let selected = select_lines(&content, &selector)?;
+    if depth + 1 >= MAX_INCLUDE_DEPTH {
+        return Some(selected);
+    }
+    Some(expand(root, &path, &selected, depth + 1))
+}
+
+/// 按锚点或行号范围取出部分内容，去掉 ANCHOR 标记行
+fn select_lines(content: &str, selector: &[&str]) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let number = |s: &str| s.parse::<usize>().ok();
+    let range = match selector {
+        [] | [""] => 0..lines.len(),
+        [anchor] if number(anchor).is_none() => {
+            let start = lines
+                .iter()
+                .position(|l| anchor_name(l, "ANCHOR:") == Some(*anchor))?
+                + 1;
+            let end = lines[start..]
+                .iter()
+                .position(|l| anchor_name(l, "ANCHOR_END:") == Some(*anchor))
+                .map_or(lines.len(), |i| start + i);
+            start..end
+        }
+        [line] => {
+            let n = number(line)?.max(1);
+            n - 1..n
+        }
+        [start, end] => {
+            let start = number(start).unwrap_or(1).max(1) - 1;
+            let end = number(end).unwrap_or(lines.len());
+            start..end
+        }
+        _ => return None,
+    };
+    let end = range.end.min(lines.len());
+    let start = range.start.min(end);
+    let selected: Vec<&str> = lines[start..end]
+        .iter()
+        .copied()
+        .filter(|l| anchor_name(l, "ANCHOR:").is_none() && anchor_name(l, "ANCHOR_END:").is_none())
+        .collect();
+    Some(selected.join("\n"))
+}
+
+/// 标记行里的锚点名："// ANCHOR: setup" → "setup"
+fn anchor_name<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let at = line.find(marker)?;
+    let rest = line[at + marker.len()..].trim_start();
+    let len = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(rest.len());
+    (len > 0).then(|| &rest[..len])
+}
@@ -0,0 +1,124 @@
+// ─────────────────────────────────────────────
+// MkDocs：mkdocs.yml 的 nav
+// ─────────────────────────────────────────────
+//
+// nav 的每一项可以是：
+//   - intro.md                 只写文件，标题取文档自己的
+//   - Intro: intro.md          标题: 文件
+//   - GitHub: https://…        标题: 外部链接
+//   - Guide:                   标题: 子列表（分组）
+//       - guide/a.md
+// 没写 nav 时 MkDocs 按文件夹结构生成导航，这里也一样。
+// mkdocs.yml 里常有 `!!python/name:…`、`!ENV [...]` 这类 YAML 标签，yaml-rust 不认识，解析前先去掉。
+
+use super::{directory_entries, DocSite, SiteKind, TocEntry};
+use crate::markdown::links::is_external;
+use std::fs;
+use std::path::Path;
+use yaml_rust::{Yaml, YamlLoader};
+
+const CONFIG_NAMES: [&str; 2] = ["mkdocs.yml", "mkdocs.yaml"];
+
+/// dir 下有没有 mkdocs.yml
+pub(super) fn has_config(dir: &Path) -> bool {
+    CONFIG_NAMES.iter().any(|name| dir.join(name).is_file())
+}
+
+pub(super) fn open(dir: &Path) -> Option<DocSite> {
+    let config = CONFIG_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())?;
+    let yaml = load(&config);
+    let field = |key: &str| {
+        yaml.as_ref()
+            .and_then(|y| y[key].as_str())
+            .map(str::to_string)
+    };
+    let docs_dir = field("docs_dir").unwrap_or_else(|| "docs".to_string());
+    // site_url 的路径部分就是站点前缀："https://example.com/project/" → "/project/"
+    let base = field("site_url")
+        .and_then(|url| {
+            let after_scheme = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+            after_scheme
+                .find('/')
+                .map(|i| after_scheme[i..].to_string())
+        })
+        .unwrap_or_default();
+    Some(DocSite::new(
+        SiteKind::MkDocs,
+        dir,
+        dir.join(docs_dir),
+        config,
+        &base,
+    ))
+}
+
+fn load(path: &Path) -> Option<Yaml> {
+    let text = fs::read_to_string(path).ok()?;
+    YamlLoader::load_from_str(&strip_tags(&text))
+        .ok()?
+        .into_iter()
+        .next()
+}
+
+/// 去掉值前面的 YAML 标签（`key: !tag value`、`- !tag value`）
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        let mut rest = line;
+        while let Some(at) = rest.find(" !") {
+            let before = rest[..at].trim_end();
+            out.push_str(&rest[..at + 1]);
+            let after = &rest[at + 1..];
+            if before.ends_with(':') || before.ends_with('-') {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                rest = &after[end..];
+            } else {
+                out.push('!');
+                rest = &after[1..];
+            }
+        }
+        out.push_str(rest);
+        out.push('\n');
+    }
+    out
+}
+
+pub(super) fn toc(site: &DocSite) -> Vec<TocEntry> {
+    match load(&site.config).map(|yaml| yaml["nav"].clone()) {
+        Some(Yaml::Array(items)) => items
+            .iter()
+            .filter_map(|item| nav_item(site, None, item))
+            .collect(),
+        _ => directory_entries(&site.docs_dir),
+    }
+}
+
+fn nav_item(site: &DocSite, title: Option<&str>, item: &Yaml) -> Option<TocEntry> {
+    match item {
+        Yaml::String(target) if is_external(target) => Some(TocEntry {
+            title: title.unwrap_or(target).to_string(),
+            url: Some(target.clone()),
+            ..TocEntry::default()
+        }),
+        Yaml::String(target) => Some(site.doc_entry(title, target)),
+        // 「标题: 值」是只有一个键的映射
+        Yaml::Hash(map) => {
+            let (key, value) = map.iter().next()?;
+            let title = key.as_str()?;
+            match value {
+                Yaml::Array(children) => Some(TocEntry {
+                    title: title.to_string(),
+                    children: children
+                        .iter()
+                        .filter_map(|child| nav_item(site, None, child))
+                        .collect(),
+                    ..TocEntry::default()
+                }),
+                other => nav_item(site, Some(title), other),
+            }
+        }
+        _ => None,
+    }
+}
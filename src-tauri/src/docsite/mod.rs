@@ -0,0 +1,361 @@
+// ============================
+// 文档站点：mdBook / MkDocs / VitePress / Docusaurus
+// ============================
+//
+// 文档仓库真正的阅读顺序写在站点生成器的配置里，而不是文件名里：
+//   - mdbook.rs：book.toml + <src>/SUMMARY.md（只有 SUMMARY.md、没有 book.toml 时按 GitBook 风格处理）
+//   - mkdocs.rs：mkdocs.yml 的 nav
+//   - vitepress.rs：.vitepress/config.* 里的 themeConfig.sidebar
+//   - docusaurus.rs：sidebars.js / sidebars.ts
+//
+// 识别出站点后提供三件事：
+//   1. 有序目录（TocEntry 树）和按目录排的阅读顺序
+//   2. 站点绝对链接映射回源文件："/guide/intro"、"/guide/intro.html"、"/guide/" → docs/guide/intro.md 等
+//   3. 读文档时展开 mdBook 的 {{#include file:anchor}}，并把站点链接改写成指向源文件的相对链接
+//
+// JS / TS 配置不执行，只用 js.rs 宽松地读出其中的字面量；侧边栏是运行时生成的读不到，目录为空。
+
+mod docusaurus;
+mod js;
+mod mdbook;
+mod mkdocs;
+mod vitepress;
+
+use crate::commands::markdown::MARKDOWN_EXTENSIONS;
+use crate::export::html::document_title;
+use crate::markdown::front_matter::FrontMatter;
+use crate::markdown::links::{
+    is_external, normalize_path, percent_decode, relative_link, rewrite_links, split_fragment,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 目录链接指向文件夹时依次尝试的索引文档
+const INDEX_NAMES: [&str; 3] = ["index", "README", "_index"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SiteKind {
+    MdBook,
+    MkDocs,
+    VitePress,
+    Docusaurus,
+}
+
+/// 目录里的一项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TocEntry {
+    pub title: String,
+    /// 对应的源文件（绝对路径）；分组标题、草稿章节、外部链接、找不到的文件为 null
+    pub path: Option<String>,
+    /// 外部链接
+    pub url: Option<String>,
+    pub children: Vec<TocEntry>,
+}
+
+/// get_doc_site 的返回值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteToc {
+    pub kind: SiteKind,
+    /// 配置文件所在的目录
+    pub root: String,
+    /// 源文件目录（mdBook 的 src、MkDocs 的 docs_dir……）
+    pub docs_dir: String,
+    pub entries: Vec<TocEntry>,
+    /// 目录里出现的源文件，按阅读顺序，去重
+    pub order: Vec<String>,
+}
+
+pub struct DocSite {
+    pub kind: SiteKind,
+    pub root: PathBuf,
+    pub docs_dir: PathBuf,
+    /// 导航定义所在的文件（SUMMARY.md、mkdocs.yml、VitePress 配置、sidebars.js）
+    config: PathBuf,
+    /// 站点绝对链接前面要去掉的路径前缀，以 "/" 开头和结尾；没有时是 "/"
+    base: String,
+    /// Docusaurus：路由 / 文档 ID → 文件，第一次用到时才扫描
+    routes: OnceLock<docusaurus::Routes>,
+}
+
+impl DocSite {
+    /// 从 path（文件或目录）往上找最近的文档站点的根目录
+    ///
+    /// 只看配置文件在不在，不读内容；读文档时每次都要找，打开站点的结果按根目录缓存（state/roots.rs）
+    pub fn find_root(path: &Path) -> Option<PathBuf> {
+        let start = normalize_path(if path.is_dir() { path } else { path.parent()? });
+        // mdBook 的 src/SUMMARY.md 比 book.toml 低一层，先找有配置文件的站点
+        start
+            .ancestors()
+            .find(|dir| has_config(dir))
+            .or_else(|| {
+                start
+                    .ancestors()
+                    .find(|dir| dir.join("SUMMARY.md").is_file())
+            })
+            .map(Path::to_path_buf)
+    }
+
+    /// dir 本身是不是文档站点的根目录
+    pub fn open(dir: &Path) -> Option<DocSite> {
+        let dir = normalize_path(dir);
+        open_configured(&dir).or_else(|| mdbook::open_summary(&dir))
+    }
+
+    fn new(kind: SiteKind, root: &Path, docs_dir: PathBuf, config: PathBuf, base: &str) -> Self {
+        let base = format!("/{}/", base.trim_matches('/')).replace("//", "/");
+        DocSite {
+            kind,
+            root: root.to_path_buf(),
+            docs_dir: normalize_path(&docs_dir),
+            config,
+            base,
+            routes: OnceLock::new(),
+        }
+    }
+
+    /// 解析导航，得到有序目录
+    pub fn entries(&self) -> Vec<TocEntry> {
+        match self.kind {
+            SiteKind::MdBook => mdbook::toc(self),
+            SiteKind::MkDocs => mkdocs::toc(self),
+            SiteKind::VitePress => vitepress::toc(self),
+            SiteKind::Docusaurus => docusaurus::toc(self),
+        }
+    }
+
+    pub fn table_of_contents(&self) -> SiteToc {
+        let entries = self.entries();
+        let order = reading_order(&entries)
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        SiteToc {
+            kind: self.kind,
+            root: self.root.to_string_lossy().to_string(),
+            docs_dir: self.docs_dir.to_string_lossy().to_string(),
+            entries,
+            order,
+        }
+    }
+
+    /// 目录里出现的源文件，按阅读顺序
+    pub fn reading_order(&self) -> Vec<PathBuf> {
+        reading_order(&self.entries())
+    }
+
+    /// 把链接路径（已去掉锚点、已解码）解析成源文件
+    ///
+    /// "/" 开头的是站点绝对链接：去掉 base 后相对源文件目录解析；
+    /// 其他的相对 from_file 所在目录，按站点的习惯补全（省略 .md、写成 .html、指向目录）
+    pub fn resolve(&self, from_file: &Path, link_path: &str) -> Option<PathBuf> {
+        match link_path.strip_prefix('/') {
+            Some(_) => {
+                let route = link_path
+                    .strip_prefix(self.base.as_str())
+                    .or_else(|| link_path.strip_prefix(self.base.trim_end_matches('/')))
+                    .unwrap_or(link_path);
+                self.resolve_route(route)
+            }
+            None => {
+                let dir = from_file.parent().unwrap_or(&self.docs_dir);
+                source_file(&dir.join(link_path), link_path.ends_with('/'))
+            }
+        }
+    }
+
+    /// 站点路由（不含 base，相对源文件目录）→ 源文件
+    fn resolve_route(&self, route: &str) -> Option<PathBuf> {
+        let route = route.trim_start_matches('/');
+        if self.kind == SiteKind::Docusaurus {
+            let routes = self
+                .routes
+                .get_or_init(|| docusaurus::Routes::scan(&self.docs_dir));
+            if let Some(path) = routes.route(route) {
+                return Some(path.to_path_buf());
+            }
+        }
+        source_file(
+            &self.docs_dir.join(route),
+            route.is_empty() || route.ends_with('/'),
+        )
+    }
+
+    /// 读文档时的改写：展开 mdBook include，站点链接改成指向源文件的相对链接
+    ///
+    /// 只处理站点根目录下的文档；原本就能找到文件的链接保持不变
+    pub fn expand(&self, file: &Path, source: &str) -> String {
+        let file = normalize_path(file);
+        if !file.starts_with(&self.root) {
+            return source.to_string();
+        }
+        let expanded = match self.kind {
+            SiteKind::MdBook => mdbook::expand_includes(&self.root, &file, source),
+            _ => source.to_string(),
+        };
+        let dir = file.parent().unwrap_or(&self.root).to_path_buf();
+        rewrite_links(&expanded, |dest| {
+            if dest.starts_with('#') || is_external(dest) {
+                return None;
+            }
+            let (path, fragment) = split_fragment(dest);
+            let path = percent_decode(path);
+            // 只认真实存在的文件：指向目录的链接在站点里是目录的索引文档
+            let exists = match path.strip_prefix('/') {
+                Some(_) => Path::new(&path).is_file(),
+                None => dir.join(&path).is_file(),
+            };
+            if path.is_empty() || exists {
+                return None;
+            }
+            let target = self.resolve(&file, &path)?;
+            let link = relative_link(&dir, &target);
+            if link.contains(['<', '>']) {
+                return None;
+            }
+            Some(match fragment {
+                Some(f) => format!("<{}#{}>", link, f),
+                None => format!("<{}>", link),
+            })
+        })
+    }
+
+    /// 目录项：path 是相对源文件目录的文件路径（或站点路由）
+    fn doc_entry(&self, title: Option<&str>, route: &str) -> TocEntry {
+        let path = self.resolve_route(route);
+        TocEntry {
+            title: title
+                .map(str::to_string)
+                .or_else(|| path.as_deref().map(doc_title))
+                .unwrap_or_else(|| route.to_string()),
+            path: path.map(|p| p.to_string_lossy().to_string()),
+            ..TocEntry::default()
+        }
+    }
+}
+
+/// dir 下有没有站点生成器的配置文件（和 open_configured 认的一样）
+fn has_config(dir: &Path) -> bool {
+    mdbook::has_config(dir)
+        || mkdocs::has_config(dir)
+        || docusaurus::has_config(dir)
+        || vitepress::has_config(dir)
+}
+
+/// dir 下有站点生成器的配置文件
+fn open_configured(dir: &Path) -> Option<DocSite> {
+    mdbook::open(dir)
+        .or_else(|| mkdocs::open(dir))
+        .or_else(|| docusaurus::open(dir))
+        .or_else(|| vitepress::open(dir))
+}
+
+/// 链接目标 → 实际存在的源文件
+///
+/// 依次尝试：原样、.html 换成 Markdown 扩展名、补上 Markdown 扩展名、当作目录找索引文档。
+/// 以 "/" 结尾的链接先找索引文档；找不到时再当文件（MkDocs 的 "guide/intro/" 就是 guide/intro.md）
+fn source_file(target: &Path, is_dir: bool) -> Option<PathBuf> {
+    let target = normalize_path(target);
+    if !is_dir && target.is_file() {
+        return Some(target);
+    }
+    let mut files = Vec::new();
+    let stem = match target.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("html") => target.with_extension(""),
+        _ => target.clone(),
+    };
+    if let Some(name) = stem.file_name().map(|n| n.to_string_lossy().to_string()) {
+        for ext in MARKDOWN_EXTENSIONS {
+            files.push(stem.with_file_name(format!("{}.{}", name, ext)));
+        }
+    }
+    let mut indexes = Vec::new();
+    for index in INDEX_NAMES {
+        for ext in MARKDOWN_EXTENSIONS {
+            indexes.push(target.join(format!("{}.{}", index, ext)));
+        }
+    }
+    let candidates = if is_dir {
+        indexes.into_iter().chain(files)
+    } else {
+        files.into_iter().chain(indexes)
+    };
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+/// 目录里没写标题时用的标题：front matter 的 title，其次第一个标题，最后是文件名
+fn doc_title(path: &Path) -> String {
+    let source = fs::read_to_string(path).unwrap_or_default();
+    FrontMatter::parse(&source)
+        .title()
+        .unwrap_or_else(|| document_title(path, &source))
+}
+
+/// 按目录的先序遍历列出源文件，去重
+fn reading_order(entries: &[TocEntry]) -> Vec<PathBuf> {
+    fn walk(entries: &[TocEntry], out: &mut Vec<PathBuf>) {
+        for entry in entries {
+            if let Some(path) = &entry.path {
+                let path = PathBuf::from(path);
+                if !out.contains(&path) {
+                    out.push(path);
+                }
+            }
+            walk(&entry.children, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(entries, &mut out);
+    out
+}
+
+/// 没有导航定义时的目录：按文件夹结构，索引文档排最前，其余按文件名排序
+fn directory_entries(dir: &Path) -> Vec<TocEntry> {
+    let Ok(read) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    for entry in read.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || (name.starts_with('_') && path.is_dir()) {
+            continue;
+        }
+        if path.is_dir() {
+            dirs.push((name, path));
+        } else if crate::commands::markdown::is_markdown_path(&path) {
+            files.push((name, path));
+        }
+    }
+    let is_index = |name: &str| {
+        let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        INDEX_NAMES.iter().any(|i| i.eq_ignore_ascii_case(stem))
+    };
+    files.sort_by_key(|(name, _)| (!is_index(name), name.clone()));
+    dirs.sort();
+
+    let mut entries: Vec<TocEntry> = files
+        .into_iter()
+        .map(|(_, path)| TocEntry {
+            title: doc_title(&path),
+            path: Some(path.to_string_lossy().to_string()),
+            ..TocEntry::default()
+        })
+        .collect();
+    for (name, path) in dirs {
+        let children = directory_entries(&path);
+        if !children.is_empty() {
+            entries.push(TocEntry {
+                title: name,
+                children,
+                ..TocEntry::default()
+            });
+        }
+    }
+    entries
+}
@@ -0,0 +1,141 @@
+// ─────────────────────────────────────────────
+// VitePress：.vitepress/config.* 里的 themeConfig.sidebar
+// ─────────────────────────────────────────────
+//
+// sidebar 有两种写法：
+//   [ { text, link?, items?: [...] } ]                  全站共用一个侧边栏
+//   { '/guide/': [...], '/api/': { base, items } }      按路径前缀分成几个侧边栏，每个作为一个分组
+// link 是站点路由（不含 config 里的 base）；项目上的 base 会加在它和下级所有 link 前面。
+// 源文件目录是 .vitepress 所在的目录，config 里写了 srcDir 时再加上它。
+
+use super::js::{Module, Value};
+use super::{DocSite, SiteKind, TocEntry};
+use crate::markdown::links::{is_external, split_fragment};
+use std::path::Path;
+
+/// 依次尝试的配置文件（相对 .vitepress）
+const CONFIG_NAMES: [&str; 6] = [
+    "config.mts",
+    "config.ts",
+    "config.mjs",
+    "config.js",
+    "config/index.mts",
+    "config/index.ts",
+];
+
+/// dir 或 dir/docs 下有没有 .vitepress 配置
+pub(super) fn has_config(dir: &Path) -> bool {
+    [dir.to_path_buf(), dir.join("docs")].iter().any(|root| {
+        CONFIG_NAMES
+            .iter()
+            .any(|name| root.join(".vitepress").join(name).is_file())
+    })
+}
+
+pub(super) fn open(dir: &Path) -> Option<DocSite> {
+    // 从仓库根目录打开时，VitePress 站点通常在 docs/ 下
+    [dir.to_path_buf(), dir.join("docs")]
+        .into_iter()
+        .find_map(|root| {
+            let config = CONFIG_NAMES
+                .iter()
+                .map(|name| root.join(".vitepress").join(name))
+                .find(|path| path.is_file())?;
+            let value = Module::read(&config).and_then(|m| m.default_export());
+            let options = value.as_ref().and_then(config_object);
+            let field = |key: &str| options.and_then(|o| o.str_field(key)).unwrap_or("");
+            let docs_dir = root.join(field("srcDir"));
+            Some(DocSite::new(
+                SiteKind::VitePress,
+                &root,
+                docs_dir,
+                config.clone(),
+                field("base"),
+            ))
+        })
+}
+
+/// 配置对象本身：`defineConfig({...})`、`withMermaid(defineConfig({...}))` 都剥到最里面的对象
+fn config_object(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Object(_) => Some(value),
+        Value::Call(_, args) => args.iter().find_map(config_object),
+        _ => None,
+    }
+}
+
+pub(super) fn toc(site: &DocSite) -> Vec<TocEntry> {
+    let Some(value) = Module::read(&site.config).and_then(|m| m.default_export()) else {
+        return Vec::new();
+    };
+    // 多语言站点的 sidebar 可能在 locales 下面，找不到 themeConfig.sidebar 时取第一个
+    let sidebar = config_object(&value)
+        .and_then(|config| config.get("themeConfig"))
+        .and_then(|theme| theme.get("sidebar"))
+        .or_else(|| value.find("sidebar"));
+    match sidebar {
+        Some(Value::Array(list)) => items(site, "", list),
+        Some(Value::Object(groups)) => groups
+            .iter()
+            .filter_map(|(prefix, group)| {
+                let (base, list) = match group {
+                    Value::Array(list) => ("", list),
+                    Value::Object(_) => match group.get("items") {
+                        Some(Value::Array(list)) => (group.str_field("base").unwrap_or(""), list),
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                let title = prefix.trim_matches('/');
+                Some(TocEntry {
+                    title: if title.is_empty() { "/" } else { title }.to_string(),
+                    children: items(site, base, list),
+                    ..TocEntry::default()
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn items(site: &DocSite, base: &str, list: &[Value]) -> Vec<TocEntry> {
+    list.iter()
+        .filter_map(|item| entry(site, base, item))
+        .collect()
+}
+
+fn entry(site: &DocSite, base: &str, item: &Value) -> Option<TocEntry> {
+    if !matches!(item, Value::Object(_)) {
+        return None;
+    }
+    let text = item.str_field("text");
+    let base = item.str_field("base").unwrap_or(base);
+    let mut entry = match item.str_field("link") {
+        Some(link) if is_external(link) => TocEntry {
+            title: text.unwrap_or(link).to_string(),
+            url: Some(link.to_string()),
+            ..TocEntry::default()
+        },
+        Some(link) => {
+            let (route, _) = split_fragment(link);
+            let route = if base.is_empty() {
+                route.to_string()
+            } else {
+                format!(
+                    "{}/{}",
+                    base.trim_end_matches('/'),
+                    route.trim_start_matches('/')
+                )
+            };
+            site.doc_entry(text, &route)
+        }
+        None => TocEntry {
+            title: text.unwrap_or_default().to_string(),
+            ..TocEntry::default()
+        },
+    };
+    if let Some(Value::Array(children)) = item.get("items") {
+        entry.children = items(site, base, children);
+    }
+    Some(entry)
+}
@@ -0,0 +1,679 @@
+// ─────────────────────────────────────────────
+// EPUB 导出：单个文档或整个文件夹打包成一本电子书
+// ─────────────────────────────────────────────
+//
+// 输出 EPUB 3（zip 包）：
+//   mimetype                  必须是第一个文件、不压缩
+//   META-INF/container.xml    指向 content.opf
+//   OEBPS/content.opf         元数据（标题、作者、语言）、文件清单、阅读顺序
+//   OEBPS/nav.xhtml           目录：每章的标题层级
+//   OEBPS/style.css           当前主题的 CSS + 阅读器适配
+//   OEBPS/text/ch001.xhtml    每个 Markdown 文档一章
+//   OEBPS/images/img001.png   本地图片
+//
+// 文件夹的章节顺序：文件夹是文档站点（mdBook、MkDocs、VitePress、Docusaurus）时按站点目录的顺序，
+// 只收录列出的文档；否则按相对路径排序收录全部文档。
+// 标题和作者优先取 front matter（文件夹取 README.md / index.md 的 title，作者合并所有章节）。
+
+use super::html::{
+    document_title, image_mime, render_body, same_document_anchor, MermaidMode, ResourceResolver,
+};
+use super::{escape_html, highlight, themes};
+use crate::commands::link_graph::resolve_target;
+use crate::commands::markdown::{collect_markdown_paths, load_document};
+use crate::docsite::DocSite;
+use crate::markdown::facets;
+use crate::markdown::front_matter::FrontMatter;
+use crate::markdown::headings::parse_headings;
+use crate::markdown::links::{is_external, normalize_path, percent_decode, split_fragment};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 目录里收录的最深标题级别
+const NAV_MAX_LEVEL: u8 = 3;
+
+/// 文件夹根目录下可以提供书名的文档
+const INDEX_FILES: [&str; 3] = ["README.md", "index.md", "_index.md"];
+
+/// 阅读器适配：背景铺满整页、长代码行折行（阅读器里不能横向滚动）、图片不超出页面
+const EPUB_CSS: &str = "
+body.markdown-body { margin: 0; padding: 0 4%; }
+.markdown-body pre { white-space: pre-wrap; word-wrap: break-word; }
+.markdown-body .code-block code { width: auto; }
+.markdown-body img { max-width: 100%; }
+nav#toc ol { list-style: none; padding-left: 1.2em; }
+nav#toc > ol { padding-left: 0; }
+";
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+#[derive(Debug, Clone)]
+pub struct EpubOptions {
+    /// 主题 id（见 themes.rs）
+    pub theme: String,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpubExport {
+    pub output: String,
+    pub title: String,
+    pub chapters: usize,
+    pub images: usize,
+    /// 读不到的文档、没能嵌入的图片等，不影响导出
+    pub warnings: Vec<String>,
+}
+
+/// 一章
+struct Chapter {
+    /// 包内路径（相对 OEBPS），如 "text/ch001.xhtml"
+    href: String,
+    title: String,
+    body: String,
+    /// (级别, 文字, slug)
+    headings: Vec<(u8, String, String)>,
+}
+
+/// 默认的输出路径：文件 → 同名 .epub；文件夹 → 旁边的 "<文件夹名>.epub"
+pub fn default_output(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "book".to_string());
+        path.with_file_name(format!("{}.epub", name))
+    } else {
+        path.with_extension("epub")
+    }
+}
+
+/// 把一个文档或一个文件夹导出为 EPUB；path 需要是绝对路径
+pub fn export_epub(
+    path: &Path,
+    output: &Path,
+    options: &EpubOptions,
+) -> Result<EpubExport, String> {
+    let css = themes::require_theme_css(&options.theme)?;
+    let path = normalize_path(path);
+    let (root, sources) = if path.is_dir() {
+        let sources = match site_order(&path) {
+            Some(sources) => sources,
+            None => {
+                let mut sources = collect_markdown_paths(&path);
+                sources.sort_by_key(|p| relative_key(&path, p));
+                sources
+            }
+        };
+        (path.clone(), sources)
+    } else if path.is_file() {
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        (root, vec![path.clone()])
+    } else {
+        return Err(format!("'{}' does not exist", path.display()));
+    };
+    if sources.is_empty() {
+        return Err(format!("No Markdown files in '{}'", path.display()));
+    }
+
+    let hrefs: HashMap<PathBuf, String> = sources
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.clone(), format!("ch{:03}.xhtml", i + 1)))
+        .collect();
+    let mut warnings = Vec::new();
+    let mut images = BTreeMap::new();
+    let mut chapters = Vec::new();
+    let mut authors: Vec<String> = Vec::new();
+    let mut first_meta: Option<FrontMatter> = None;
+
+    for file in &sources {
+        let source = match load_document(&file.to_string_lossy()) {
+            Ok(s) => s,
+            Err(e) => {
+                warnings.push(e);
+                continue;
+            }
+        };
+        let meta = FrontMatter::parse(&source);
+        for author in meta.authors() {
+            if !authors.contains(&author) {
+                authors.push(author);
+            }
+        }
+        let mut resolver = EpubResolver {
+            root: &root,
+            file,
+            chapters: &hrefs,
+            images: &mut images,
+            warnings: &mut warnings,
+        };
+        let (body, _) = render_body(&source, MermaidMode::Code, &mut resolver);
+        let title = meta
+            .title()
+            .unwrap_or_else(|| document_title(file, &source));
+        let headings = parse_headings(&source)
+            .into_iter()
+            .filter(|h| h.level <= NAV_MAX_LEVEL && !h.text.is_empty())
+            .map(|h| (h.level, h.text, h.slug))
+            .collect();
+        chapters.push(Chapter {
+            href: format!("text/{}", hrefs[file]),
+            title,
+            body,
+            headings,
+        });
+        first_meta.get_or_insert(meta);
+    }
+    if chapters.is_empty() {
+        return Err("None of the documents could be read".to_string());
+    }
+
+    // 书名：单个文件用它自己的标题；文件夹用首页文档 front matter 里的 title，没有就用文件夹名
+    let book_meta = if path.is_dir() {
+        INDEX_FILES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|p| p.is_file())
+            .and_then(|p| fs::read_to_string(p).ok())
+            .map(|s| FrontMatter::parse(&s))
+            .unwrap_or_default()
+    } else {
+        first_meta.unwrap_or_default()
+    };
+    let title = if path.is_dir() {
+        book_meta.title().unwrap_or_else(|| {
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    } else {
+        chapters[0].title.clone()
+    };
+    let language = book_meta
+        .language()
+        .unwrap_or_else(|| guess_language(&chapters[0].body).to_string());
+
+    let book = Book {
+        identifier: book_identifier(&path),
+        title,
+        authors,
+        language,
+        // "2026-01-31" 或带时间的 "2026-01-31T10:00:00Z"，其他写法不写进 dc:date
+        date: book_meta
+            .text("date")
+            .filter(|d| facets::parse_date(d).is_some()),
+        theme: options.theme.clone(),
+    };
+    write_package(
+        output,
+        &book,
+        &chapters,
+        &images,
+        &epub_css(css, &options.theme),
+    )?;
+
+    Ok(EpubExport {
+        output: output.to_string_lossy().to_string(),
+        title: book.title,
+        chapters: chapters.len(),
+        images: images.len(),
+        warnings,
+    })
+}
+
+/// 书的元数据
+struct Book {
+    identifier: String,
+    title: String,
+    authors: Vec<String>,
+    language: String,
+    date: Option<String>,
+    theme: String,
+}
+
+impl Book {
+    /// 主题选择器是 ".markdown-body.theme-xxx"，页面的 body 上带这两个类名
+    fn body_class(&self) -> String {
+        format!("markdown-body theme-{}", escape_html(&self.theme))
+    }
+}
+
+/// 章节内的链接改写规则
+struct EpubResolver<'a> {
+    root: &'a Path,
+    file: &'a Path,
+    /// 收录的文档 → 章节文件名
+    chapters: &'a HashMap<PathBuf, String>,
+    /// 图片的绝对路径 → 包内文件名（"img001.png"）
+    images: &'a mut BTreeMap<PathBuf, String>,
+    warnings: &'a mut Vec<String>,
+}
+
+impl ResourceResolver for EpubResolver<'_> {
+    fn link(&mut self, dest: &str) -> Option<String> {
+        let base_dir = self.file.parent().unwrap_or(self.root);
+        if let Some(anchor) = same_document_anchor(dest, base_dir, self.file) {
+            return Some(anchor);
+        }
+        if dest.starts_with('#') || is_external(dest) {
+            return Some(dest.to_string());
+        }
+        // 指向其他章节的链接改成章节文件；书外的本地文件没法打开，去掉链接只留文字
+        let (target, fragment) = split_fragment(dest);
+        let target = resolve_target(self.root, self.file, &percent_decode(target));
+        let href = self.chapters.get(&target)?;
+        Some(match fragment {
+            Some(f) => format!("{}#{}", href, f),
+            None => href.clone(),
+        })
+    }
+
+    fn image(&mut self, dest: &str) -> Option<String> {
+        if is_external(dest) {
+            // EPUB 3 不允许引用网络上的图片，去掉图片只留 alt 文字
+            self.warnings
+                .push(format!("Remote image not embedded: {}", dest));
+            return None;
+        }
+        let (target, _) = split_fragment(dest);
+        let target = resolve_target(self.root, self.file, &percent_decode(target));
+        if let Some(name) = self.images.get(&target) {
+            return Some(format!("../images/{}", name));
+        }
+        if !target.is_file() {
+            self.warnings
+                .push(format!("Image not found: {}", target.display()));
+            return None;
+        }
+        // EPUB 阅读器只保证支持这几种格式
+        let Some(ext) = image_mime(&target).and_then(epub_image_extension) else {
+            self.warnings.push(format!(
+                "Unsupported image format (use PNG, JPEG, GIF, SVG or WebP): {}",
+                target.display()
+            ));
+            return None;
+        };
+        let name = format!("img{:03}.{}", self.images.len() + 1, ext);
+        self.images.insert(target, name.clone());
+        Some(format!("../images/{}", name))
+    }
+
+    /// 没打包进书里的图片、书外的链接都去掉，只留文字
+    fn keep_unresolved(&self) -> bool {
+        false
+    }
+}
+
+/// EPUB 核心媒体类型对应的扩展名
+fn epub_image_extension(mime: &str) -> Option<&'static str> {
+    Some(match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/svg+xml" => "svg",
+        "image/webp" => "webp",
+        _ => return None,
+    })
+}
+
+fn image_media_type(name: &str) -> &'static str {
+    match name.rsplit('.').next() {
+        Some("png") => "image/png",
+        Some("jpg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        _ => "image/webp",
+    }
+}
+
+/// 按文档站点目录的顺序列出文档；不是文档站点或目录里没有文档时返回 None
+///
+/// 和站点生成器一样，没写进目录的文档不收录；草稿章节、外部链接跳过
+fn site_order(root: &Path) -> Option<Vec<PathBuf>> {
+    let site = DocSite::open(root)?;
+    let markdown: Vec<PathBuf> = collect_markdown_paths(root)
+        .iter()
+        .map(|p| normalize_path(p))
+        .collect();
+    let order: Vec<PathBuf> = site
+        .reading_order()
+        .into_iter()
+        .filter(|p| markdown.contains(p))
+        .collect();
+    (!order.is_empty()).then_some(order)
+}
+
+/// 相对根目录的路径，统一用 "/" 分隔（排序用）
+fn relative_key(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 主题 CSS 去掉固定像素的字号（让阅读器的字号设置生效），再加上代码配色和阅读器适配
+fn epub_css(theme_css: &str, theme: &str) -> String {
+    let mut css = String::with_capacity(theme_css.len() + EPUB_CSS.len());
+    let mut rest = theme_css;
+    while let Some(at) = rest.find("font-size:") {
+        let value_start = at + "font-size:".len();
+        let end = rest[value_start..]
+            .find([';', '}'])
+            .map(|i| value_start + i)
+            .unwrap_or(rest.len());
+        if rest[value_start..end].trim().ends_with("px") {
+            css.push_str(&rest[..at]);
+            // 连同分号一起去掉，右花括号保留
+            rest = rest[end..].strip_prefix(';').unwrap_or(&rest[end..]);
+        } else {
+            css.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    css.push_str(rest);
+    css.push_str(&highlight::theme_css(theme));
+    css.push_str(EPUB_CSS);
+    css
+}
+
+/// 没有 front matter 指定语言时，按正文里的文字猜一个
+fn guess_language(text: &str) -> &'static str {
+    let (mut kana, mut hangul, mut han) = (0, 0, 0);
+    for c in text.chars() {
+        match c {
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{AC00}'..='\u{D7AF}' => hangul += 1,
+            '\u{4E00}'..='\u{9FFF}' => han += 1,
+            _ => {}
+        }
+    }
+    if kana > 0 {
+        "ja"
+    } else if hangul > han {
+        "ko"
+    } else if han > 0 {
+        "zh"
+    } else {
+        "en"
+    }
+}
+
+/// 书的唯一标识：由源路径算出，同一个文档 / 文件夹重复导出时不变，阅读器能接上阅读进度
+fn book_identifier(path: &Path) -> String {
+    // FNV-1a，两个不同的初值拼成 128 位
+    let hash = |seed: u64| {
+        path.to_string_lossy()
+            .bytes()
+            .fold(seed, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+    };
+    let (a, b) = (hash(0xcbf29ce484222325), hash(0x84222325cbf29ce4));
+    format!(
+        "urn:uuid:{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        a >> 32,
+        (a >> 16) & 0xffff,
+        // 版本 4 / RFC 4122 变体的标记位，让它是格式合法的 UUID
+        0x4000 | (a & 0x0fff),
+        0x8000 | ((b >> 48) & 0x3fff),
+        b & 0xffff_ffff_ffff
+    )
+}
+
+// ─────────────────────────────────────────────
+// 打包
+// ─────────────────────────────────────────────
+
+fn write_package(
+    output: &Path,
+    book: &Book,
+    chapters: &[Chapter],
+    images: &BTreeMap<PathBuf, String>,
+    css: &str,
+) -> Result<(), String> {
+    let write_error =
+        |e: &dyn std::fmt::Display| format!("Failed to write '{}': {}", output.display(), e);
+    let file = File::create(output).map_err(|e| write_error(&e))?;
+    let mut zip = ZipWriter::new(file);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut entries: Vec<(String, Vec<u8>, SimpleFileOptions)> = vec![
+        (
+            "mimetype".to_string(),
+            b"application/epub+zip".to_vec(),
+            stored,
+        ),
+        (
+            "META-INF/container.xml".to_string(),
+            CONTAINER_XML.into(),
+            deflated,
+        ),
+        (
+            "OEBPS/content.opf".to_string(),
+            package_document(book, chapters, images).into(),
+            deflated,
+        ),
+        (
+            "OEBPS/nav.xhtml".to_string(),
+            nav_document(book, chapters).into(),
+            deflated,
+        ),
+        ("OEBPS/style.css".to_string(), css.into(), deflated),
+    ];
+    for chapter in chapters {
+        entries.push((
+            format!("OEBPS/{}", chapter.href),
+            chapter_document(book, chapter).into(),
+            deflated,
+        ));
+    }
+    for (path, name) in images {
+        let bytes =
+            fs::read(path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        // 图片本身已经压缩过，不再压缩
+        entries.push((format!("OEBPS/images/{}", name), bytes, stored));
+    }
+
+    for (name, bytes, options) in entries {
+        zip.start_file(name, options).map_err(|e| write_error(&e))?;
+        zip.write_all(&bytes).map_err(|e| write_error(&e))?;
+    }
+    zip.finish().map_err(|e| write_error(&e))?;
+    Ok(())
+}
+
+fn package_document(
+    book: &Book,
+    chapters: &[Chapter],
+    images: &BTreeMap<PathBuf, String>,
+) -> String {
+    let mut metadata = format!(
+        "    <dc:identifier id=\"book-id\">{}</dc:identifier>\n    <dc:title>{}</dc:title>\n    <dc:language>{}</dc:language>\n",
+        escape_html(&book.identifier),
+        escape_html(&book.title),
+        escape_html(&book.language)
+    );
+    for author in &book.authors {
+        metadata.push_str(&format!(
+            "    <dc:creator>{}</dc:creator>\n",
+            escape_html(author)
+        ));
+    }
+    if let Some(date) = &book.date {
+        metadata.push_str(&format!("    <dc:date>{}</dc:date>\n", escape_html(date)));
+    }
+    metadata.push_str(&format!(
+        "    <meta property=\"dcterms:modified\">{}</meta>\n",
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+    ));
+
+    let mut manifest = String::from(
+        "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n    <item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>\n",
+    );
+    let mut spine = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        let id = format!("ch{:03}", i + 1);
+        manifest.push_str(&format!(
+            "    <item id=\"{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+            id, chapter.href
+        ));
+        spine.push_str(&format!("    <itemref idref=\"{}\"/>\n", id));
+    }
+    for (i, name) in images.values().enumerate() {
+        manifest.push_str(&format!(
+            "    <item id=\"img{:03}\" href=\"images/{}\" media-type=\"{}\"/>\n",
+            i + 1,
+            name,
+            image_media_type(name)
+        ));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\" xml:lang=\"{}\">
+  <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">
+{}  </metadata>
+  <manifest>
+{}  </manifest>
+  <spine>
+{}  </spine>
+</package>
+",
+        escape_html(&book.language),
+        metadata,
+        manifest,
+        spine
+    )
+}
+
+/// XHTML 页面外壳（章节和目录共用）
+fn xhtml_page(book: &Book, title: &str, css_href: &str, body_class: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<!DOCTYPE html>
+<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" xml:lang=\"{lang}\" lang=\"{lang}\">
+<head>
+<meta charset=\"UTF-8\"/>
+<title>{title}</title>
+<link rel=\"stylesheet\" type=\"text/css\" href=\"{css}\"/>
+</head>
+<body class=\"{class}\">
+{body}</body>
+</html>
+",
+        lang = escape_html(&book.language),
+        title = escape_html(title),
+        css = css_href,
+        class = body_class,
+        body = body
+    )
+}
+
+fn chapter_document(book: &Book, chapter: &Chapter) -> String {
+    xhtml_page(
+        book,
+        &chapter.title,
+        "../style.css",
+        &book.body_class(),
+        &chapter.body,
+    )
+}
+
+/// 目录项
+struct NavNode {
+    label: String,
+    href: String,
+    children: Vec<NavNode>,
+}
+
+/// 目录：单个文档直接列出标题层级；多个文档时每章一项，章内标题作为子项
+fn nav_document(book: &Book, chapters: &[Chapter]) -> String {
+    // (层级, 目录项)，章节本身是第 0 层
+    let mut items: Vec<(u8, NavNode)> = Vec::new();
+    for chapter in chapters {
+        let node = |label: &str, fragment: Option<&str>| NavNode {
+            label: label.to_string(),
+            href: match fragment {
+                Some(f) => format!("{}#{}", chapter.href, f),
+                None => chapter.href.clone(),
+            },
+            children: Vec::new(),
+        };
+        let mut headings = chapter.headings.as_slice();
+        if chapters.len() > 1 || headings.is_empty() {
+            items.push((0, node(&chapter.title, None)));
+            // 第一个标题就是章节名时不重复列出
+            if headings
+                .first()
+                .is_some_and(|(_, text, _)| *text == chapter.title)
+            {
+                headings = &headings[1..];
+            }
+        }
+        for (level, text, slug) in headings {
+            items.push((*level, node(text, Some(slug))));
+        }
+    }
+
+    let mut list = String::new();
+    render_nav(&build_nav_tree(items), &mut list);
+    let body = format!(
+        "<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n{}</nav>\n",
+        escape_html(&book.title),
+        list
+    );
+    xhtml_page(book, &book.title, "style.css", &book.body_class(), &body)
+}
+
+/// 按层级把扁平的目录项组织成树：每一项挂在前面最近的、层级更小的项下面
+fn build_nav_tree(items: Vec<(u8, NavNode)>) -> Vec<NavNode> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<(u8, NavNode)> = Vec::new();
+    let close = |stack: &mut Vec<(u8, NavNode)>, roots: &mut Vec<NavNode>| {
+        if let Some((_, done)) = stack.pop() {
+            match stack.last_mut() {
+                Some((_, parent)) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+    };
+    for (level, node) in items {
+        while stack.last().is_some_and(|(top, _)| *top >= level) {
+            close(&mut stack, &mut roots);
+        }
+        stack.push((level, node));
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+    roots
+}
+
+fn render_nav(nodes: &[NavNode], out: &mut String) {
+    out.push_str("<ol>\n");
+    for node in nodes {
+        out.push_str(&format!(
+            "<li><a href=\"{}\">{}</a>",
+            escape_html(&node.href),
+            escape_html(&node.label)
+        ));
+        if !node.children.is_empty() {
+            out.push('\n');
+            render_nav(&node.children, out);
+        }
+        out.push_str("</li>\n");
+    }
+    out.push_str("</ol>\n");
+}
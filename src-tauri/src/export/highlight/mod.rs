@@ -0,0 +1,394 @@
+// ─────────────────────────────────────────────
+// 代码高亮（后端渲染，不依赖浏览器里的 highlight.js）
+// ─────────────────────────────────────────────
+//
+// 用 syntect（Sublime / TextMate 语法）把代码切成 token，归并成统一的类别（tokens.rs），
+// 语法表用 two-face 的扩展版：比 syntect 自带的多了 TypeScript / TSX、TOML、Swift、Kotlin、Dockerfile、Vue 等。
+// 输出带 class 的 HTML（"tok-keyword"），颜色由每个主题的配色决定（palettes.rs）。
+// 预览和导出共用同一套输出：预览加载所有主题的 CSS，导出只内嵌当前主题的。
+//
+// 围栏后面的信息串支持：
+//   ```rust {2,5-7}            标出第 2、5~7 行
+//   ```rust showLineNumbers    显示行号（showLineNumbers=10 从 10 开始）
+//   ```diff-rust / ```rust diff   行首的 +/- 是 diff 标记：整行标色，其余部分按 rust 高亮
+//   ```diff                    普通 diff，+/- 行同样整行标色
+//
+// 语法表加载较慢（几十毫秒），第一次用到时加载一次，之后复用。
+
+mod palettes;
+mod tokens;
+
+pub use palettes::{all_themes_css, theme_css};
+pub use tokens::TokenClass;
+
+use super::escape_html;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(two_face::syntax::extra_newlines)
+}
+
+/// 围栏信息串（"rust {2,5-7} showLineNumbers"）解析出的选项
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FenceInfo {
+    /// 语言标记，去掉了 "diff-" 前缀
+    pub lang: String,
+    /// 要标出的行（从 1 开始，闭区间）
+    pub highlight: Vec<(usize, usize)>,
+    /// 显示行号时的起始行号
+    pub line_numbers: Option<usize>,
+    /// 行首的 +/- 是 diff 标记（不属于代码本身）
+    pub diff: bool,
+}
+
+impl FenceInfo {
+    pub fn parse(info: &str) -> FenceInfo {
+        let info = info.trim();
+        let lang_end = info
+            .find(|c: char| c.is_whitespace() || c == '{')
+            .unwrap_or(info.len());
+        let (lang, mut rest) = info.split_at(lang_end);
+        let mut fence = FenceInfo::default();
+        match lang.strip_prefix("diff-") {
+            Some(lang) => {
+                fence.lang = lang.to_string();
+                fence.diff = true;
+            }
+            None => fence.lang = lang.to_string(),
+        }
+
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            if let Some(inner) = rest.strip_prefix('{') {
+                let end = inner.find('}').unwrap_or(inner.len());
+                fence
+                    .highlight
+                    .extend(inner[..end].split(',').filter_map(parse_range));
+                rest = inner.get(end + 1..).unwrap_or("");
+                continue;
+            }
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '{')
+                .unwrap_or(rest.len());
+            let (word, tail) = rest.split_at(end);
+            let (name, value) = match word.split_once('=') {
+                Some((name, value)) => (name, Some(value.trim_matches('"'))),
+                None => (word, None),
+            };
+            match name {
+                "showLineNumbers" | "lineNumbers" | "linenos" => {
+                    let start = value.and_then(|v| v.parse().ok()).unwrap_or(1);
+                    fence.line_numbers = Some(start);
+                }
+                "diff" => fence.diff = true,
+                _ => {}
+            }
+            rest = tail;
+        }
+        fence
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight
+            .iter()
+            .any(|&(start, end)| start <= line && line <= end)
+    }
+}
+
+/// "5" → (5, 5)，"5-7" → (5, 7)
+fn parse_range(item: &str) -> Option<(usize, usize)> {
+    let item = item.trim();
+    let (start, end) = item.split_once('-').unwrap_or((item, item));
+    let (start, end): (usize, usize) = (start.trim().parse().ok()?, end.trim().parse().ok()?);
+    (start >= 1 && start <= end).then_some((start, end))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffMark {
+    Inserted,
+    Deleted,
+}
+
+/// 高亮后的一行
+#[derive(Debug, Clone, Default)]
+pub struct CodeLine {
+    /// (类别, 文字)；类别为 None 的是普通文字。不含换行符，也不含 diff 标记
+    pub tokens: Vec<(Option<TokenClass>, String)>,
+    pub highlighted: bool,
+    pub diff: Option<DiffMark>,
+}
+
+/// 按语言标记找语法；空字符串或不认识的语言返回 None
+fn find_syntax(lang: &str) -> Option<&'static SyntaxReference> {
+    if lang.is_empty() {
+        return None;
+    }
+    syntax_set().find_syntax_by_token(lang)
+}
+
+/// 把代码块逐行切成 token；不认识的语言每行只有一个普通 token
+pub fn highlight_block(code: &str, fence: &FenceInfo) -> Vec<CodeLine> {
+    let code = code.strip_suffix('\n').unwrap_or(code);
+    let plain_diff = fence.lang == "diff";
+    // "diff-rust" 这种：+/- 标记从代码里拿掉，剩下的按语言高亮
+    let strip_markers = fence.diff && !plain_diff;
+    let mut parser = find_syntax(&fence.lang).map(Tokenizer::new);
+
+    let mut lines = Vec::new();
+    for (i, raw) in code.split('\n').enumerate() {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        let mut text = raw;
+        let mut diff = None;
+        if fence.diff || plain_diff {
+            diff = match raw.as_bytes().first() {
+                Some(b'+') if !raw.starts_with("+++") || strip_markers => Some(DiffMark::Inserted),
+                Some(b'-') if !raw.starts_with("---") || strip_markers => Some(DiffMark::Deleted),
+                _ => None,
+            };
+            if strip_markers && raw.starts_with(['+', '-', ' ']) {
+                text = &raw[1..];
+            }
+        }
+
+        let tokens = match parser.as_mut() {
+            Some(tokenizer) => match tokenizer.line(text) {
+                Some(tokens) => tokens,
+                None => {
+                    // 语法出错时这一行以及之后都按纯文本处理
+                    parser = None;
+                    vec![(None, text.to_string())]
+                }
+            },
+            None => vec![(None, text.to_string())],
+        };
+        lines.push(CodeLine {
+            tokens,
+            highlighted: fence.is_highlighted(i + 1),
+            diff,
+        });
+    }
+    lines
+}
+
+/// 逐行解析的状态（跨行的字符串、注释要接着上一行）
+struct Tokenizer {
+    state: ParseState,
+    stack: ScopeStack,
+    /// scope 栈 → 类别；同一个代码块里反复出现的是少数几种栈，匹配结果缓存起来
+    classes: HashMap<Vec<Scope>, Option<TokenClass>>,
+}
+
+impl Tokenizer {
+    fn new(syntax: &SyntaxReference) -> Tokenizer {
+        Tokenizer {
+            state: ParseState::new(syntax),
+            stack: ScopeStack::new(),
+            classes: HashMap::new(),
+        }
+    }
+
+    fn class(&mut self) -> Option<TokenClass> {
+        let stack = self.stack.as_slice();
+        if let Some(class) = self.classes.get(stack) {
+            return *class;
+        }
+        let class = tokens::classify(stack);
+        self.classes.insert(stack.to_vec(), class);
+        class
+    }
+
+    /// 解析一行，相邻的同类 token 合并；语法出错时返回 None
+    fn line(&mut self, text: &str) -> Option<Vec<(Option<TokenClass>, String)>> {
+        // 语法表是按「每行带换行符」加载的
+        let line = format!("{}\n", text);
+        let ops = self.state.parse_line(&line, syntax_set()).ok()?;
+        let mut out: Vec<(Option<TokenClass>, String)> = Vec::new();
+        let mut push = |class: Option<TokenClass>, piece: &str| {
+            if piece.is_empty() {
+                return;
+            }
+            match out.last_mut() {
+                Some((last, buf)) if *last == class => buf.push_str(piece),
+                _ => out.push((class, piece.to_string())),
+            }
+        };
+        let mut pos = 0;
+        for (at, op) in ops {
+            let at = at.min(text.len());
+            if at > pos {
+                push(self.class(), &text[pos..at]);
+                pos = at;
+            }
+            self.stack.apply(&op).ok()?;
+        }
+        push(self.class(), &text[pos..]);
+        Some(out)
+    }
+}
+
+/// 批量渲染 (code, info)，结果和输入一一对应
+///
+/// 预览一次会送来整篇文档的代码块，按 CPU 数切片并行解析
+pub fn highlight_many(blocks: &[(&str, &str)]) -> Vec<String> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(blocks.len());
+    if workers <= 1 {
+        return blocks
+            .iter()
+            .map(|(code, info)| highlight_code(code, info))
+            .collect();
+    }
+    let chunk = blocks.len().div_ceil(workers);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = blocks
+            .chunks(chunk)
+            .map(|part| {
+                scope.spawn(move || {
+                    part.iter()
+                        .map(|(code, info)| highlight_code(code, info))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+/// 把代码块渲染成 `<pre>` HTML；info 是围栏后面的整个信息串（"rust {2,5-7}"）
+///
+/// 输出的是 class 名，颜色要配合 theme_css / all_themes_css 生成的样式
+pub fn highlight_code(code: &str, info: &str) -> String {
+    let fence = FenceInfo::parse(info);
+    let lines = highlight_block(code, &fence);
+
+    let mut pre_class = String::from("code-block");
+    if fence.line_numbers.is_some() {
+        pre_class.push_str(" has-line-numbers");
+    }
+    if fence.diff || fence.lang == "diff" {
+        pre_class.push_str(" has-diff");
+    }
+    let (lang_attr, code_class) = if fence.lang.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let lang = escape_html(&fence.lang);
+        (
+            format!(" data-lang=\"{}\"", lang),
+            format!(" class=\"language-{}\"", lang),
+        )
+    };
+    // 行号列按最大行号的位数定宽，否则 9 → 10 行时正文会错位
+    let code_style = match fence.line_numbers {
+        Some(start) => format!(
+            " style=\"--line-number-width: {}ch\"",
+            start
+                .saturating_add(lines.len().saturating_sub(1))
+                .to_string()
+                .len()
+        ),
+        None => String::new(),
+    };
+
+    let mut html = format!(
+        "<pre class=\"{}\"{}><code{}{}>",
+        pre_class, lang_attr, code_class, code_style
+    );
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            html.push('\n');
+        }
+        html.push_str("<span class=\"line");
+        if line.highlighted {
+            html.push_str(" highlighted");
+        }
+        match line.diff {
+            Some(DiffMark::Inserted) => html.push_str(" diff-inserted"),
+            Some(DiffMark::Deleted) => html.push_str(" diff-deleted"),
+            None => {}
+        }
+        html.push_str("\">");
+        if let Some(start) = fence.line_numbers {
+            html.push_str(&format!(
+                "<span class=\"line-number\">{}</span>",
+                start.saturating_add(i)
+            ));
+        }
+        if fence.diff && fence.lang != "diff" {
+            let marker = match line.diff {
+                Some(DiffMark::Inserted) => "+",
+                Some(DiffMark::Deleted) => "-",
+                None => " ",
+            };
+            html.push_str(&format!("<span class=\"diff-marker\">{}</span>", marker));
+        }
+        for (class, text) in &line.tokens {
+            match class {
+                Some(class) => html.push_str(&format!(
+                    "<span class=\"{}\">{}</span>",
+                    class.css_class(),
+                    escape_html(text)
+                )),
+                None => html.push_str(&escape_html(text)),
+            }
+        }
+        html.push_str("</span>");
+    }
+    html.push_str("</code></pre>\n");
+    html
+}
+
+/// 一行里的一段高亮文字：(前景色 RGB, 文字)
+pub type Token = ([u8; 3], String);
+
+/// 逐行高亮（默认主题的配色），给不经过 HTML 的导出（PDF）用；不认识的语言返回 None
+///
+/// 返回的每一行不含换行符
+pub fn highlight_lines(code: &str, lang: &str) -> Option<Vec<Vec<Token>>> {
+    find_syntax(lang)?;
+    let palette = palettes::palette(super::themes::DEFAULT_THEME);
+    let fence = FenceInfo {
+        lang: lang.to_string(),
+        ..FenceInfo::default()
+    };
+    let lines = highlight_block(code, &fence)
+        .into_iter()
+        .map(|line| {
+            line.tokens
+                .into_iter()
+                .map(|(class, text)| {
+                    let color = class
+                        .and_then(|c| palette.color(c))
+                        .and_then(parse_hex_color)
+                        .unwrap_or(PLAIN_TEXT);
+                    (color, text)
+                })
+                .collect()
+        })
+        .collect();
+    Some(lines)
+}
+
+/// 默认主题的正文颜色
+const PLAIN_TEXT: [u8; 3] = [0x1f, 0x23, 0x28];
+
+/// "#rrggbb" → [r, g, b]
+fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.strip_prefix('#')?;
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    (hex.len() == 6).then_some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+}
@@ -0,0 +1,403 @@
+// ─────────────────────────────────────────────
+// 代码配色：每个主题给 token 类别配色
+// ─────────────────────────────────────────────
+//
+// 颜色尽量取自主题同名的编辑器配色（GitHub Primer、Dracula、Nord……），
+// 没有现成配色的主题（arctic、terminal、sunset）按主题 CSS 里的主色调配。
+// 生成的 CSS 选择器带 ".markdown-body.theme-xxx" 前缀，所有主题的规则可以同时加载。
+
+use super::tokens::TokenClass;
+
+pub struct Palette {
+    pub theme: &'static str,
+    pub comment: &'static str,
+    pub keyword: &'static str,
+    pub operator: &'static str,
+    pub string: &'static str,
+    pub escape: &'static str,
+    pub regexp: &'static str,
+    pub number: &'static str,
+    pub constant: &'static str,
+    pub function: &'static str,
+    pub type_name: &'static str,
+    pub variable: &'static str,
+    pub builtin: &'static str,
+    pub property: &'static str,
+    pub tag: &'static str,
+    pub attribute: &'static str,
+    pub meta: &'static str,
+    pub heading: &'static str,
+    pub link: &'static str,
+    pub inserted: &'static str,
+    pub deleted: &'static str,
+    /// `{2,5-7}` 标出的行的背景
+    pub line_highlight: &'static str,
+    /// diff 标记行的背景
+    pub inserted_line: &'static str,
+    pub deleted_line: &'static str,
+}
+
+impl Palette {
+    /// 类别的文字颜色；斜体 / 粗体类别不改颜色
+    pub fn color(&self, class: TokenClass) -> Option<&'static str> {
+        Some(match class {
+            TokenClass::Comment => self.comment,
+            TokenClass::Keyword => self.keyword,
+            TokenClass::Operator => self.operator,
+            TokenClass::String => self.string,
+            TokenClass::Escape => self.escape,
+            TokenClass::Regexp => self.regexp,
+            TokenClass::Number => self.number,
+            TokenClass::Constant => self.constant,
+            TokenClass::Function => self.function,
+            TokenClass::Type => self.type_name,
+            TokenClass::Variable => self.variable,
+            TokenClass::Builtin => self.builtin,
+            TokenClass::Property => self.property,
+            TokenClass::Tag => self.tag,
+            TokenClass::Attribute => self.attribute,
+            TokenClass::Meta => self.meta,
+            TokenClass::Heading => self.heading,
+            TokenClass::Link => self.link,
+            TokenClass::Inserted => self.inserted,
+            TokenClass::Deleted => self.deleted,
+            TokenClass::Emphasis | TokenClass::Strong => return None,
+        })
+    }
+}
+
+/// 和主题无关的代码块样式：逐行排列、行号、diff 标记
+const CODE_BLOCK_CSS: &str = "
+.markdown-body .code-block code { display: grid; min-width: 100%; width: max-content; }
+.markdown-body .code-block .line { padding: 0 16px; margin: 0 -16px; }
+.markdown-body .code-block .line:empty::after { content: \"\\200b\"; }
+.markdown-body .code-block .line-number {
+  display: inline-block;
+  width: var(--line-number-width, 2ch);
+  margin-right: 2ch;
+  text-align: right;
+  opacity: 0.5;
+  user-select: none;
+  -webkit-user-select: none;
+}
+.markdown-body .code-block .diff-marker {
+  display: inline-block;
+  width: 2ch;
+  user-select: none;
+  -webkit-user-select: none;
+}
+.markdown-body .tok-emphasis { font-style: italic; }
+.markdown-body .tok-strong, .markdown-body .tok-heading { font-weight: bold; }
+.markdown-body .tok-link { text-decoration: underline; }
+";
+
+const PALETTES: [Palette; 10] = [
+    Palette {
+        theme: "github",
+        comment: "#6e7781",
+        keyword: "#cf222e",
+        operator: "#cf222e",
+        string: "#0a3069",
+        escape: "#116329",
+        regexp: "#116329",
+        number: "#0550ae",
+        constant: "#0550ae",
+        function: "#8250df",
+        type_name: "#953800",
+        variable: "#953800",
+        builtin: "#0550ae",
+        property: "#0550ae",
+        tag: "#116329",
+        attribute: "#0550ae",
+        meta: "#8250df",
+        heading: "#0550ae",
+        link: "#0a3069",
+        inserted: "#116329",
+        deleted: "#82071e",
+        line_highlight: "rgba(212, 167, 44, 0.18)",
+        inserted_line: "rgba(46, 160, 67, 0.15)",
+        deleted_line: "rgba(248, 81, 73, 0.15)",
+    },
+    Palette {
+        theme: "solarized",
+        comment: "#93a1a1",
+        keyword: "#859900",
+        operator: "#859900",
+        string: "#2aa198",
+        escape: "#dc322f",
+        regexp: "#dc322f",
+        number: "#d33682",
+        constant: "#cb4b16",
+        function: "#268bd2",
+        type_name: "#b58900",
+        variable: "#268bd2",
+        builtin: "#cb4b16",
+        property: "#268bd2",
+        tag: "#268bd2",
+        attribute: "#b58900",
+        meta: "#6c71c4",
+        heading: "#cb4b16",
+        link: "#6c71c4",
+        inserted: "#859900",
+        deleted: "#dc322f",
+        line_highlight: "rgba(181, 137, 0, 0.15)",
+        inserted_line: "rgba(133, 153, 0, 0.15)",
+        deleted_line: "rgba(220, 50, 47, 0.12)",
+    },
+    Palette {
+        theme: "arctic",
+        comment: "#5a7a99",
+        keyword: "#1a5299",
+        operator: "#1a5299",
+        string: "#0f7b6c",
+        escape: "#b35900",
+        regexp: "#b35900",
+        number: "#9c3d9c",
+        constant: "#9c3d9c",
+        function: "#1a73c7",
+        type_name: "#00799e",
+        variable: "#b35900",
+        builtin: "#9c3d9c",
+        property: "#1a73c7",
+        tag: "#1a5299",
+        attribute: "#00799e",
+        meta: "#6b5fb5",
+        heading: "#1a5299",
+        link: "#1a73c7",
+        inserted: "#1e7f3c",
+        deleted: "#c0392b",
+        line_highlight: "rgba(26, 115, 199, 0.12)",
+        inserted_line: "rgba(30, 127, 60, 0.12)",
+        deleted_line: "rgba(192, 57, 43, 0.12)",
+    },
+    Palette {
+        theme: "github-dark",
+        comment: "#8b949e",
+        keyword: "#ff7b72",
+        operator: "#ff7b72",
+        string: "#a5d6ff",
+        escape: "#7ee787",
+        regexp: "#7ee787",
+        number: "#79c0ff",
+        constant: "#79c0ff",
+        function: "#d2a8ff",
+        type_name: "#ffa657",
+        variable: "#ffa657",
+        builtin: "#79c0ff",
+        property: "#79c0ff",
+        tag: "#7ee787",
+        attribute: "#79c0ff",
+        meta: "#d2a8ff",
+        heading: "#79c0ff",
+        link: "#a5d6ff",
+        inserted: "#aff5b4",
+        deleted: "#ffdcd7",
+        line_highlight: "rgba(187, 128, 9, 0.2)",
+        inserted_line: "rgba(46, 160, 67, 0.2)",
+        deleted_line: "rgba(248, 81, 73, 0.2)",
+    },
+    Palette {
+        theme: "dracula",
+        comment: "#6272a4",
+        keyword: "#ff79c6",
+        operator: "#ff79c6",
+        string: "#f1fa8c",
+        escape: "#ff79c6",
+        regexp: "#ff5555",
+        number: "#bd93f9",
+        constant: "#bd93f9",
+        function: "#50fa7b",
+        type_name: "#8be9fd",
+        variable: "#ffb86c",
+        builtin: "#bd93f9",
+        property: "#8be9fd",
+        tag: "#ff79c6",
+        attribute: "#50fa7b",
+        meta: "#50fa7b",
+        heading: "#bd93f9",
+        link: "#8be9fd",
+        inserted: "#50fa7b",
+        deleted: "#ff5555",
+        line_highlight: "rgba(241, 250, 140, 0.12)",
+        inserted_line: "rgba(80, 250, 123, 0.12)",
+        deleted_line: "rgba(255, 85, 85, 0.15)",
+    },
+    Palette {
+        theme: "nord",
+        comment: "#616e88",
+        keyword: "#81a1c1",
+        operator: "#81a1c1",
+        string: "#a3be8c",
+        escape: "#ebcb8b",
+        regexp: "#ebcb8b",
+        number: "#b48ead",
+        constant: "#81a1c1",
+        function: "#88c0d0",
+        type_name: "#8fbcbb",
+        variable: "#d8dee9",
+        builtin: "#81a1c1",
+        property: "#88c0d0",
+        tag: "#81a1c1",
+        attribute: "#8fbcbb",
+        meta: "#d08770",
+        heading: "#88c0d0",
+        link: "#88c0d0",
+        inserted: "#a3be8c",
+        deleted: "#bf616a",
+        line_highlight: "rgba(136, 192, 208, 0.15)",
+        inserted_line: "rgba(163, 190, 140, 0.15)",
+        deleted_line: "rgba(191, 97, 106, 0.18)",
+    },
+    Palette {
+        theme: "cobalt",
+        comment: "#0088ff",
+        keyword: "#ff9d00",
+        operator: "#ff9d00",
+        string: "#3ad900",
+        escape: "#ff628c",
+        regexp: "#80ffc2",
+        number: "#ff628c",
+        constant: "#ff628c",
+        function: "#ffc600",
+        type_name: "#80ffbb",
+        variable: "#ffee80",
+        builtin: "#fb94ff",
+        property: "#9effff",
+        tag: "#9effff",
+        attribute: "#ffc600",
+        meta: "#fb94ff",
+        heading: "#ffc600",
+        link: "#9effff",
+        inserted: "#3ad900",
+        deleted: "#ff628c",
+        line_highlight: "rgba(255, 198, 0, 0.15)",
+        inserted_line: "rgba(58, 217, 0, 0.15)",
+        deleted_line: "rgba(255, 98, 140, 0.18)",
+    },
+    Palette {
+        theme: "monokai",
+        comment: "#75715e",
+        keyword: "#f92672",
+        operator: "#f92672",
+        string: "#e6db74",
+        escape: "#ae81ff",
+        regexp: "#e6db74",
+        number: "#ae81ff",
+        constant: "#ae81ff",
+        function: "#a6e22e",
+        type_name: "#66d9ef",
+        variable: "#fd971f",
+        builtin: "#66d9ef",
+        property: "#66d9ef",
+        tag: "#f92672",
+        attribute: "#a6e22e",
+        meta: "#66d9ef",
+        heading: "#e6db74",
+        link: "#66d9ef",
+        inserted: "#a6e22e",
+        deleted: "#f92672",
+        line_highlight: "rgba(255, 255, 255, 0.08)",
+        inserted_line: "rgba(166, 226, 46, 0.15)",
+        deleted_line: "rgba(249, 38, 114, 0.18)",
+    },
+    Palette {
+        theme: "terminal",
+        comment: "#228b22",
+        keyword: "#66ff66",
+        operator: "#33ff33",
+        string: "#b8ff9f",
+        escape: "#ffff66",
+        regexp: "#ffff66",
+        number: "#00ffcc",
+        constant: "#00ffcc",
+        function: "#ccffcc",
+        type_name: "#7fffd4",
+        variable: "#99ff99",
+        builtin: "#00ffcc",
+        property: "#99ff99",
+        tag: "#66ff66",
+        attribute: "#7fffd4",
+        meta: "#9acd32",
+        heading: "#66ff66",
+        link: "#33ff33",
+        inserted: "#66ff66",
+        deleted: "#ff5f5f",
+        line_highlight: "rgba(0, 255, 0, 0.12)",
+        inserted_line: "rgba(0, 255, 0, 0.12)",
+        deleted_line: "rgba(255, 95, 95, 0.18)",
+    },
+    Palette {
+        theme: "sunset",
+        comment: "#a07060",
+        keyword: "#ff7b54",
+        operator: "#ff7b54",
+        string: "#ffd27f",
+        escape: "#ff9e80",
+        regexp: "#ff9e80",
+        number: "#e0a3ff",
+        constant: "#e0a3ff",
+        function: "#ffb347",
+        type_name: "#7fd1c7",
+        variable: "#f4a7b9",
+        builtin: "#e0a3ff",
+        property: "#ffcba4",
+        tag: "#ff7b54",
+        attribute: "#ffb347",
+        meta: "#c792ea",
+        heading: "#ffb347",
+        link: "#ffb347",
+        inserted: "#a8d68a",
+        deleted: "#ff6b6b",
+        line_highlight: "rgba(255, 179, 71, 0.15)",
+        inserted_line: "rgba(168, 214, 138, 0.15)",
+        deleted_line: "rgba(255, 107, 107, 0.18)",
+    },
+];
+
+/// 主题的代码配色；不认识的主题用第一个（默认主题 github）
+pub fn palette(theme: &str) -> &'static Palette {
+    PALETTES
+        .iter()
+        .find(|p| p.theme == theme)
+        .unwrap_or(&PALETTES[0])
+}
+
+/// 一个主题的代码高亮 CSS（含通用的代码块样式）
+pub fn theme_css(theme: &str) -> String {
+    let mut css = String::from(CODE_BLOCK_CSS);
+    push_palette_css(palette(theme), &mut css);
+    css
+}
+
+/// 所有主题的代码高亮 CSS，预览里切换主题时不用重新加载
+pub fn all_themes_css() -> String {
+    let mut css = String::from(CODE_BLOCK_CSS);
+    for palette in &PALETTES {
+        push_palette_css(palette, &mut css);
+    }
+    css
+}
+
+fn push_palette_css(palette: &Palette, css: &mut String) {
+    let scope = format!(".markdown-body.theme-{}", palette.theme);
+    for class in TokenClass::ALL {
+        if let Some(color) = palette.color(class) {
+            css.push_str(&format!(
+                "{} .{} {{ color: {}; }}\n",
+                scope,
+                class.css_class(),
+                color
+            ));
+        }
+    }
+    for (line_class, background) in [
+        ("highlighted", palette.line_highlight),
+        ("diff-inserted", palette.inserted_line),
+        ("diff-deleted", palette.deleted_line),
+    ] {
+        css.push_str(&format!(
+            "{} .code-block .line.{} {{ background: {}; }}\n",
+            scope, line_class, background
+        ));
+    }
+}
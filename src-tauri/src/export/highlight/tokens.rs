@@ -0,0 +1,184 @@
+// ─────────────────────────────────────────────
+// TextMate scope → 统一的 token 类别
+// ─────────────────────────────────────────────
+//
+// 各语言语法给出的 scope 非常细（"storage.type.function.rust"、"entity.name.tag.yaml"……），
+// 主题不可能逐个配色。这里先归并成二十来个类别，每个主题只需要给类别配色（palettes.rs），
+// HTML 里输出的也是类别的 class 名（"tok-keyword"）。
+//
+// 匹配规则和 Sublime / TextMate 主题一样：选择器匹配 scope 栈，越具体的选择器优先。
+
+use std::sync::OnceLock;
+use syntect::highlighting::ScopeSelectors;
+use syntect::parsing::Scope;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenClass {
+    Comment,
+    Keyword,
+    Operator,
+    String,
+    Escape,
+    Regexp,
+    Number,
+    Constant,
+    Function,
+    Type,
+    Variable,
+    Builtin,
+    Property,
+    Tag,
+    Attribute,
+    Meta,
+    Heading,
+    Emphasis,
+    Strong,
+    Link,
+    Inserted,
+    Deleted,
+}
+
+impl TokenClass {
+    pub const ALL: [TokenClass; 22] = [
+        TokenClass::Comment,
+        TokenClass::Keyword,
+        TokenClass::Operator,
+        TokenClass::String,
+        TokenClass::Escape,
+        TokenClass::Regexp,
+        TokenClass::Number,
+        TokenClass::Constant,
+        TokenClass::Function,
+        TokenClass::Type,
+        TokenClass::Variable,
+        TokenClass::Builtin,
+        TokenClass::Property,
+        TokenClass::Tag,
+        TokenClass::Attribute,
+        TokenClass::Meta,
+        TokenClass::Heading,
+        TokenClass::Emphasis,
+        TokenClass::Strong,
+        TokenClass::Link,
+        TokenClass::Inserted,
+        TokenClass::Deleted,
+    ];
+
+    /// HTML 里的 class 名
+    pub fn css_class(self) -> &'static str {
+        match self {
+            TokenClass::Comment => "tok-comment",
+            TokenClass::Keyword => "tok-keyword",
+            TokenClass::Operator => "tok-operator",
+            TokenClass::String => "tok-string",
+            TokenClass::Escape => "tok-escape",
+            TokenClass::Regexp => "tok-regexp",
+            TokenClass::Number => "tok-number",
+            TokenClass::Constant => "tok-constant",
+            TokenClass::Function => "tok-function",
+            TokenClass::Type => "tok-type",
+            TokenClass::Variable => "tok-variable",
+            TokenClass::Builtin => "tok-builtin",
+            TokenClass::Property => "tok-property",
+            TokenClass::Tag => "tok-tag",
+            TokenClass::Attribute => "tok-attribute",
+            TokenClass::Meta => "tok-meta",
+            TokenClass::Heading => "tok-heading",
+            TokenClass::Emphasis => "tok-emphasis",
+            TokenClass::Strong => "tok-strong",
+            TokenClass::Link => "tok-link",
+            TokenClass::Inserted => "tok-inserted",
+            TokenClass::Deleted => "tok-deleted",
+        }
+    }
+}
+
+/// 选择器 → 类别；同一个 scope 栈命中多条时取匹配度最高的
+const RULES: [(&str, TokenClass); 22] = [
+    (
+        "comment, punctuation.definition.comment",
+        TokenClass::Comment,
+    ),
+    ("keyword, storage", TokenClass::Keyword),
+    (
+        "keyword.operator, punctuation.accessor",
+        TokenClass::Operator,
+    ),
+    (
+        "string, punctuation.definition.string",
+        TokenClass::String,
+    ),
+    ("constant.character.escape", TokenClass::Escape),
+    ("string.regexp", TokenClass::Regexp),
+    ("constant.numeric", TokenClass::Number),
+    (
+        "constant.language, constant.other, constant.character, support.constant, variable.other.constant",
+        TokenClass::Constant,
+    ),
+    (
+        "entity.name.function, support.function, variable.function, meta.function-call.identifier",
+        TokenClass::Function,
+    ),
+    (
+        "entity.name.type, entity.name.class, entity.name.struct, entity.name.enum, entity.name.trait, entity.name.interface, entity.other.inherited-class, support.type, support.class, storage.type.primitive",
+        TokenClass::Type,
+    ),
+    ("variable.parameter", TokenClass::Variable),
+    (
+        "variable.language, support.function.builtin, support.variable",
+        TokenClass::Builtin,
+    ),
+    (
+        "support.type.property-name, meta.mapping.key string, entity.name.tag.yaml, meta.object-literal.key, variable.other.member, variable.other.property, entity.name.key",
+        TokenClass::Property,
+    ),
+    ("entity.name.tag", TokenClass::Tag),
+    ("entity.other.attribute-name", TokenClass::Attribute),
+    (
+        "meta.annotation, meta.decorator, meta.attribute, meta.preprocessor, punctuation.definition.annotation",
+        TokenClass::Meta,
+    ),
+    (
+        "markup.heading, entity.name.section",
+        TokenClass::Heading,
+    ),
+    ("markup.italic", TokenClass::Emphasis),
+    ("markup.bold", TokenClass::Strong),
+    (
+        "markup.underline.link, markup.link, string.other.link",
+        TokenClass::Link,
+    ),
+    (
+        "markup.inserted, meta.diff.header.to-file",
+        TokenClass::Inserted,
+    ),
+    (
+        "markup.deleted, meta.diff.header.from-file",
+        TokenClass::Deleted,
+    ),
+];
+
+fn rules() -> &'static [(ScopeSelectors, TokenClass)] {
+    static RULES_PARSED: OnceLock<Vec<(ScopeSelectors, TokenClass)>> = OnceLock::new();
+    RULES_PARSED.get_or_init(|| {
+        RULES
+            .iter()
+            .filter_map(|(selector, class)| Some((selector.parse().ok()?, *class)))
+            .collect()
+    })
+}
+
+/// 当前 scope 栈对应的类别；普通标识符、标点等不着色的返回 None
+pub fn classify(stack: &[Scope]) -> Option<TokenClass> {
+    let mut best = None;
+    for (selector, class) in rules() {
+        let Some(power) = selector.does_match(stack) else {
+            continue;
+        };
+        match best {
+            Some((best_power, _)) if best_power >= power => {}
+            _ => best = Some((power, *class)),
+        }
+    }
+    best.map(|(_, class)| class)
+}
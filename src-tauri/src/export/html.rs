@@ -0,0 +1,334 @@
+// ─────────────────────────────────────────────
+// 单文件 HTML 导出
+// ─────────────────────────────────────────────
+//
+// 生成一个自包含的 .html，发给没装本应用的人也能直接用浏览器打开：
+//   - 主题 CSS 内嵌在 <style> 里
+//   - 代码块在后端高亮成带内联样式的 HTML
+//   - 本地图片读出来转成 data: URI
+//   - 指向本文档自己的链接（"./spec.md#install"）改写成页内锚点 "#install"
+//   - mermaid 代码块按选项内嵌运行时（打开时渲染成图），或者保留为代码
+
+use super::highlight::{highlight_code, theme_css};
+use super::{escape_html, themes};
+use crate::commands::markdown::load_document;
+use crate::markdown::headings::parse_headings;
+use crate::markdown::links::{is_external, normalize_path, percent_decode, split_fragment};
+use crate::markdown::parser_options;
+use base64::Engine;
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 编译时从 node_modules 拷进来的 mermaid 运行时（见 build.rs），没有时为空字符串
+pub const MERMAID_RUNTIME: &str = include_str!(concat!(env!("OUT_DIR"), "/mermaid.min.js"));
+
+/// 页面布局：正文居中、限制最大宽度，背景色铺满整个窗口
+const PAGE_CSS: &str = "
+html, body { margin: 0; }
+.markdown-body {
+  box-sizing: border-box;
+  min-height: 100vh;
+  padding: 32px max(32px, calc((100% - 960px) / 2));
+}
+.markdown-body pre.mermaid { background: transparent; text-align: center; }
+";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "camelCase")]
+pub enum MermaidMode {
+    /// Leave diagrams as ```mermaid code blocks
+    #[default]
+    Code,
+    /// Embed the Mermaid runtime so diagrams render when the file is opened
+    Embed,
+}
+
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// 主题 id（见 themes.rs）
+    pub theme: String,
+    pub mermaid: MermaidMode,
+}
+
+/// 默认的输出路径：和源文件同目录、同名的 .html
+pub fn default_output(path: &Path) -> PathBuf {
+    path.with_extension("html")
+}
+
+/// 读取文档（含 vault 展开），渲染后写入 output
+pub fn export_html(path: &Path, output: &Path, options: &HtmlOptions) -> Result<(), String> {
+    let source = load_document(&path.to_string_lossy())?;
+    let page = render_page(path, &source, options)?;
+    fs::write(output, page).map_err(|e| format!("Failed to write '{}': {}", output.display(), e))
+}
+
+/// 渲染成完整的 HTML 页面；path 是文档的绝对路径，用来解析相对图片和链接
+pub fn render_page(path: &Path, source: &str, options: &HtmlOptions) -> Result<String, String> {
+    let css = themes::require_theme_css(&options.theme)?;
+    let self_path = normalize_path(path);
+    let mut resolver = SelfContained {
+        base_dir: self_path.parent().unwrap_or(Path::new("")).to_path_buf(),
+        self_path,
+    };
+    let (body, has_mermaid) = render_body(source, options.mermaid, &mut resolver);
+
+    let scripts = if has_mermaid {
+        if MERMAID_RUNTIME.is_empty() {
+            return Err(
+                "The Mermaid runtime is not bundled in this build; export with mermaid = code"
+                    .to_string(),
+            );
+        }
+        format!(
+            "<script>{}</script>\n<script>mermaid.initialize({{ startOnLoad: true, theme: \"{}\", securityLevel: \"strict\" }});</script>\n",
+            // 运行时里出现 "</script" 会提前结束 <script> 标签
+            MERMAID_RUNTIME.replace("</script", "<\\/script"),
+            if themes::is_dark(&options.theme) {
+                "dark"
+            } else {
+                "default"
+            }
+        )
+    } else {
+        String::new()
+    };
+
+    Ok(format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{title}</title>
+<style>
+{css}{code_css}{page_css}</style>
+</head>
+<body>
+<article class=\"markdown-body theme-{theme}\">
+{body}</article>
+{scripts}</body>
+</html>
+",
+        title = escape_html(&document_title(path, source)),
+        css = css,
+        code_css = theme_css(&options.theme),
+        page_css = PAGE_CSS,
+        theme = escape_html(&options.theme),
+        body = body,
+        scripts = scripts,
+    ))
+}
+
+/// 页面标题：第一个标题的文字，没有标题时用文件名
+pub fn document_title(path: &Path, source: &str) -> String {
+    parse_headings(source)
+        .into_iter()
+        .map(|h| h.text)
+        .find(|t| !t.is_empty())
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+}
+
+/// 渲染正文时怎样改写链接和图片地址；返回 None 表示保留原样
+pub trait ResourceResolver {
+    fn link(&mut self, dest: &str) -> Option<String>;
+    fn image(&mut self, dest: &str) -> Option<String>;
+
+    /// link / image 返回 None 时是否保留原地址。
+    /// false：去掉链接和图片本身，只留下链接文字、图片的 alt 文字（EPUB 里引用包外的文件是不合法的）
+    fn keep_unresolved(&self) -> bool {
+        true
+    }
+}
+
+/// 单文件导出：指向自己的链接改成锚点，本地图片内嵌成 data: URI
+struct SelfContained {
+    base_dir: PathBuf,
+    self_path: PathBuf,
+}
+
+impl ResourceResolver for SelfContained {
+    fn link(&mut self, dest: &str) -> Option<String> {
+        same_document_anchor(dest, &self.base_dir, &self.self_path)
+    }
+
+    fn image(&mut self, dest: &str) -> Option<String> {
+        image_data_uri(dest, &self.base_dir)
+    }
+}
+
+/// 渲染正文 HTML，返回 (HTML, 是否包含需要运行时渲染的 mermaid 图)
+pub fn render_body(
+    source: &str,
+    mermaid: MermaidMode,
+    resolver: &mut dyn ResourceResolver,
+) -> (String, bool) {
+    // 标题锚点和预览 / read_markdown_section 用同一套 slug 规则，链接里的 "#xxx" 才对得上
+    let mut slugs = parse_headings(source).into_iter().map(|h| h.slug);
+
+    let mut events: Vec<Event> = Vec::new();
+    // 正在收集的代码块：(围栏信息串, 内容)
+    let mut code: Option<(String, String)> = None;
+    let mut in_metadata = false;
+    let mut has_mermaid = false;
+    // 正在处理的链接 / 图片是否被去掉了（链接文字里可以有图片，所以用栈）
+    let mut dropped_links: Vec<bool> = Vec::new();
+    let mut dropped_images: Vec<bool> = Vec::new();
+
+    for event in Parser::new_ext(source, parser_options()) {
+        if let Some((_, text)) = code.as_mut() {
+            match event {
+                Event::Text(t) => text.push_str(&t),
+                Event::End(TagEnd::CodeBlock) => {
+                    let (info, text) = code.take().unwrap_or_default();
+                    let lang = info.split_whitespace().next().unwrap_or("");
+                    let html = if lang == "mermaid" && mermaid == MermaidMode::Embed {
+                        has_mermaid = true;
+                        format!("<pre class=\"mermaid\">{}</pre>\n", escape_html(&text))
+                    } else {
+                        highlight_code(&text, &info)
+                    };
+                    events.push(Event::Html(html.into()));
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            // front matter 不显示
+            Event::Start(Tag::MetadataBlock(_)) => in_metadata = true,
+            Event::End(TagEnd::MetadataBlock(_)) => in_metadata = false,
+            _ if in_metadata => {}
+            Event::Start(Tag::Heading {
+                level,
+                classes,
+                attrs,
+                ..
+            }) => events.push(Event::Start(Tag::Heading {
+                level,
+                id: slugs.next().map(CowStr::from),
+                classes,
+                attrs,
+            })),
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                let dest_url = match resolver.link(&dest_url) {
+                    Some(rewritten) => rewritten.into(),
+                    None if resolver.keep_unresolved() => dest_url,
+                    None => {
+                        dropped_links.push(true);
+                        continue;
+                    }
+                };
+                dropped_links.push(false);
+                events.push(Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }));
+            }
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => {
+                let dest_url = match resolver.image(&dest_url) {
+                    Some(uri) => uri.into(),
+                    None if resolver.keep_unresolved() => dest_url,
+                    None => {
+                        dropped_images.push(true);
+                        continue;
+                    }
+                };
+                dropped_images.push(false);
+                events.push(Event::Start(Tag::Image {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                }));
+            }
+            Event::End(TagEnd::Link) => {
+                if !dropped_links.pop().unwrap_or(false) {
+                    events.push(Event::End(TagEnd::Link));
+                }
+            }
+            Event::End(TagEnd::Image) => {
+                if !dropped_images.pop().unwrap_or(false) {
+                    events.push(Event::End(TagEnd::Image));
+                }
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code = Some((info, String::new()));
+            }
+            // 和前端 markdown-it 的 html: false 一致：原始 HTML 当作文本显示
+            Event::Html(html) | Event::InlineHtml(html) => events.push(Event::Text(html)),
+            other => events.push(other),
+        }
+    }
+
+    let mut body = String::new();
+    pulldown_cmark::html::push_html(&mut body, events.into_iter());
+    (body, has_mermaid)
+}
+
+/// 链接指向本文档自己时返回页内锚点（"./spec.md#install" → "#install"），否则 None
+pub fn same_document_anchor(dest: &str, base_dir: &Path, self_path: &Path) -> Option<String> {
+    if dest.starts_with('#') || is_external(dest) {
+        return None;
+    }
+    let (target, fragment) = split_fragment(dest);
+    if target.is_empty() {
+        return None;
+    }
+    let target = normalize_path(&base_dir.join(percent_decode(target)));
+    (target == self_path).then(|| format!("#{}", percent_decode(fragment.unwrap_or(""))))
+}
+
+/// 本地图片 → data: URI；外部图片、读不到的文件、不认识的格式返回 None（保留原链接）
+pub fn image_data_uri(dest: &str, base_dir: &Path) -> Option<String> {
+    if dest.starts_with('#') || is_external(dest) {
+        return None;
+    }
+    let (target, _) = split_fragment(dest);
+    let file = base_dir.join(percent_decode(target));
+    let mime = image_mime(&file)?;
+    let bytes = fs::read(&file).ok()?;
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// 根据扩展名判断图片的 MIME 类型
+pub fn image_mime(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    Some(match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        _ => return None,
+    })
+}
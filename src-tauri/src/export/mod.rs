@@ -0,0 +1,29 @@
+// ============================
+// 导出：把文档渲染成不依赖本应用也能查看的文件
+// ============================
+//
+// 和 markdown/ 一样不依赖 Tauri，IPC 命令（commands/export.rs）和命令行子命令共用。
+// 渲染规则尽量和前端预览（markdown-it）保持一致：不渲染原始 HTML、标题带 GitHub 风格锚点。
+
+pub mod epub;
+pub mod highlight;
+pub mod html;
+pub mod pdf;
+pub mod site;
+pub mod themes;
+
+/// 转义 HTML 文本 / 属性值里的特殊字符
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,405 @@
+// ─────────────────────────────────────────────
+// PDF 字体：查找系统字体、度量字形、记录用到的字形
+// ─────────────────────────────────────────────
+//
+// 不依赖 fontconfig / CoreText：按文件名在各平台的字体目录里找常见字体。
+// 正文、粗体、斜体、等宽各找一套，再找一个中日韩字体作为缺字时的后备；
+// 缺少粗体 / 斜体文件时由 writer 用描边 / 倾斜模拟。
+//
+// 排版时每查一个字就把 (字形 id → 文字) 记下来，写 PDF 时只嵌入用到的字形子集，
+// 并据此生成 ToUnicode 映射，保证 PDF 里的文字可以复制和搜索。
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ttf_parser::{name_id, Face, GlyphId};
+
+/// 用户指定的字体文件，覆盖自动查找的结果
+#[derive(Debug, Clone, Default)]
+pub struct FontPaths {
+    pub regular: Option<PathBuf>,
+    pub mono: Option<PathBuf>,
+    pub cjk: Option<PathBuf>,
+}
+
+// 候选文件名（小写），按优先级排列；覆盖 Linux 发行版、macOS、Windows 自带的字体
+const REGULAR: &[&str] = &[
+    "dejavusans.ttf",
+    "liberationsans-regular.ttf",
+    "notosans-regular.ttf",
+    "arial.ttf",
+    "helvetica.ttc",
+    "segoeui.ttf",
+];
+const BOLD: &[&str] = &[
+    "dejavusans-bold.ttf",
+    "liberationsans-bold.ttf",
+    "notosans-bold.ttf",
+    "arial bold.ttf",
+    "arialbd.ttf",
+    "segoeuib.ttf",
+];
+const ITALIC: &[&str] = &[
+    "dejavusans-oblique.ttf",
+    "liberationsans-italic.ttf",
+    "notosans-italic.ttf",
+    "arial italic.ttf",
+    "ariali.ttf",
+    "segoeuii.ttf",
+];
+const BOLD_ITALIC: &[&str] = &[
+    "dejavusans-boldoblique.ttf",
+    "liberationsans-bolditalic.ttf",
+    "notosans-bolditalic.ttf",
+    "arial bold italic.ttf",
+    "arialbi.ttf",
+    "segoeuiz.ttf",
+];
+const MONO: &[&str] = &[
+    "dejavusansmono.ttf",
+    "liberationmono-regular.ttf",
+    "notosansmono-regular.ttf",
+    "menlo.ttc",
+    "consola.ttf",
+    "courier new.ttf",
+    "cour.ttf",
+];
+const MONO_BOLD: &[&str] = &[
+    "dejavusansmono-bold.ttf",
+    "liberationmono-bold.ttf",
+    "notosansmono-bold.ttf",
+    "consolab.ttf",
+    "courier new bold.ttf",
+    "courbd.ttf",
+];
+const CJK: &[&str] = &[
+    "notosanscjk-regular.ttc",
+    "notosanscjksc-regular.otf",
+    "notosanssc-regular.otf",
+    "sourcehansanssc-regular.otf",
+    "wqy-microhei.ttc",
+    "wqy-zenhei.ttc",
+    "droidsansfallbackfull.ttf",
+    "droidsansfallback.ttf",
+    "hiragino sans gb.ttc",
+    "stheiti light.ttc",
+    "arial unicode.ttf",
+    "msyh.ttc",
+    "simsun.ttc",
+    "msgothic.ttc",
+    "malgun.ttf",
+];
+
+/// 排版用的一个字形
+#[derive(Debug, Clone, Copy)]
+pub struct Glyph {
+    /// FontSet::fonts 里的下标
+    pub font: usize,
+    pub id: u16,
+    /// 字宽，以 em 为单位（乘上字号得到 pt）
+    pub advance: f32,
+}
+
+pub struct Font {
+    data: Vec<u8>,
+    pub postscript_name: String,
+    pub units_per_em: f32,
+    pub ascender: f32,
+    pub descender: f32,
+    pub cap_height: f32,
+    /// 全局包围盒 [x_min, y_min, x_max, y_max]（字体单位）
+    pub bbox: [f32; 4],
+    pub italic_angle: f32,
+    /// 字形是 CFF（.otf）还是 TrueType 轮廓，嵌入方式不同
+    pub cff: bool,
+    pub bold: bool,
+    pub italic: bool,
+    pub monospace: bool,
+    /// 字体能显示的字：字符 → 字形 id（加载时从 cmap 一次性读出）
+    glyphs: HashMap<char, u16>,
+    /// 每个字形的字宽（字体单位），下标是字形 id
+    advances: Vec<u16>,
+    /// 用到的字形 → 对应的文字（写 ToUnicode 用；.notdef 为空字符串）
+    pub used: BTreeMap<u16, String>,
+}
+
+impl Font {
+    pub fn load(path: &Path) -> Result<Font, String> {
+        let data = fs::read(path)
+            .map_err(|e| format!("Failed to read font '{}': {}", path.display(), e))?;
+        let face = Face::parse(&data, 0)
+            .map_err(|e| format!("Failed to parse font '{}': {}", path.display(), e))?;
+        let cff = face.tables().cff.is_some();
+        if !cff && face.tables().glyf.is_none() {
+            // CFF2（可变 .otf）等轮廓格式不能直接嵌入
+            return Err(format!(
+                "Font '{}' has no TrueType or CFF outlines",
+                path.display()
+            ));
+        }
+
+        let postscript_name = face
+            .names()
+            .into_iter()
+            .filter(|n| n.name_id == name_id::POST_SCRIPT_NAME)
+            .find_map(|n| n.to_string())
+            .map(|n| {
+                n.chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+                    .collect()
+            })
+            .filter(|n: &String| !n.is_empty())
+            .unwrap_or_else(|| "Font".to_string());
+        let bbox = face.global_bounding_box();
+        let ascender = face.ascender() as f32;
+
+        // 字宽和 cmap 在加载时读好，排版时每个字都要查，不能每次重新解析字体
+        let advances = (0..face.number_of_glyphs())
+            .map(|id| face.glyph_hor_advance(GlyphId(id)).unwrap_or(0))
+            .collect();
+        let mut glyphs = HashMap::new();
+        if let Some(cmap) = face.tables().cmap {
+            for subtable in cmap.subtables.into_iter().filter(|t| t.is_unicode()) {
+                subtable.codepoints(|code| {
+                    let Some(c) = char::from_u32(code) else {
+                        return;
+                    };
+                    if let Some(id) = subtable.glyph_index(code) {
+                        glyphs.entry(c).or_insert(id.0);
+                    }
+                });
+            }
+        }
+
+        Ok(Font {
+            postscript_name,
+            units_per_em: face.units_per_em() as f32,
+            ascender,
+            descender: face.descender() as f32,
+            cap_height: face
+                .capital_height()
+                .map(f32::from)
+                .unwrap_or(ascender * 0.7),
+            bbox: [
+                bbox.x_min as f32,
+                bbox.y_min as f32,
+                bbox.x_max as f32,
+                bbox.y_max as f32,
+            ],
+            italic_angle: face.italic_angle(),
+            cff,
+            bold: face.is_bold() || face.weight().to_number() >= 600,
+            italic: face.is_italic() || face.italic_angle() != 0.0,
+            monospace: face.is_monospaced(),
+            glyphs,
+            advances,
+            used: BTreeMap::new(),
+            data,
+        })
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// 查字：返回 (字形 id, 字宽)，字体里没有时返回 None
+    fn lookup(&self, c: char) -> Option<(u16, u16)> {
+        let id = *self.glyphs.get(&c)?;
+        Some((id, self.advance(id)))
+    }
+
+    /// 字形的字宽（字体单位）
+    fn advance(&self, id: u16) -> u16 {
+        self.advances.get(id as usize).copied().unwrap_or(0)
+    }
+
+    fn notdef_advance(&self) -> u16 {
+        self.advance(0)
+    }
+
+    /// 字形宽度（PDF 字宽单位：千分之一 em）
+    pub fn width(&self, id: u16) -> f32 {
+        self.advance(id) as f32 * 1000.0 / self.units_per_em
+    }
+
+    /// 把字体单位换算成千分之一 em
+    pub fn to_pdf_units(&self, value: f32) -> f32 {
+        value * 1000.0 / self.units_per_em
+    }
+}
+
+/// 一次导出用到的全部字体
+pub struct FontSet {
+    pub fonts: Vec<Font>,
+    regular: usize,
+    bold: usize,
+    italic: usize,
+    bold_italic: usize,
+    mono: usize,
+    mono_bold: usize,
+    cjk: Option<usize>,
+    /// 哪个字体都没有的字（导出后提示用户装字体）
+    pub missing: BTreeSet<char>,
+}
+
+impl FontSet {
+    /// 按 paths 加载字体，没指定的自动在系统字体目录里找
+    pub fn load(paths: &FontPaths) -> Result<FontSet, String> {
+        let index = index_font_files();
+        let mut fonts = Vec::new();
+        let mut add = |font: Font| {
+            fonts.push(font);
+            fonts.len() - 1
+        };
+
+        // 用户指定的字体加载失败要报错，自动找到的加载失败就试下一个
+        let regular = match &paths.regular {
+            Some(path) => add(Font::load(path)?),
+            None => add(find(&index, REGULAR)
+                .ok_or("No usable font found on this system; specify one with the font option")?),
+        };
+        // 指定了正文字体时不再混用系统里的粗体 / 斜体，统一模拟
+        let mut variant = |candidates: &[&str]| match paths.regular {
+            Some(_) => regular,
+            None => find(&index, candidates).map(&mut add).unwrap_or(regular),
+        };
+        let bold = variant(BOLD);
+        let italic = variant(ITALIC);
+        let bold_italic = variant(BOLD_ITALIC);
+
+        let mono = match &paths.mono {
+            Some(path) => add(Font::load(path)?),
+            None => find(&index, MONO).map(&mut add).unwrap_or(regular),
+        };
+        let mono_bold = match paths.mono {
+            Some(_) => mono,
+            None => find(&index, MONO_BOLD).map(&mut add).unwrap_or(mono),
+        };
+        let cjk = match &paths.cjk {
+            Some(path) => Some(add(Font::load(path)?)),
+            None => find(&index, CJK).map(&mut add),
+        };
+
+        Ok(FontSet {
+            fonts,
+            regular,
+            bold,
+            italic,
+            bold_italic,
+            mono,
+            mono_bold,
+            cjk,
+            missing: BTreeSet::new(),
+        })
+    }
+
+    /// 选出某种样式的首选字体
+    pub fn select(&self, bold: bool, italic: bool, mono: bool) -> usize {
+        match (mono, bold, italic) {
+            (true, true, _) => self.mono_bold,
+            (true, false, _) => self.mono,
+            (false, true, true) => self.bold_italic,
+            (false, true, false) => self.bold,
+            (false, false, true) => self.italic,
+            (false, false, false) => self.regular,
+        }
+    }
+
+    /// 把文字转成字形；首选字体里没有的字依次退到中日韩字体、正文字体
+    pub fn shape(&mut self, text: &str, primary: usize) -> Vec<Glyph> {
+        let fallbacks = [Some(primary), self.cjk, Some(self.regular)];
+        text.chars()
+            .map(|c| {
+                for font in fallbacks.into_iter().flatten() {
+                    if let Some((id, advance)) = self.fonts[font].lookup(c) {
+                        return self.record(font, id, advance, c.to_string());
+                    }
+                }
+                // 哪个字体都没有：画 .notdef（通常是一个方框）
+                if !c.is_control() {
+                    self.missing.insert(c);
+                }
+                let advance = self.fonts[primary].notdef_advance();
+                self.record(primary, 0, advance, String::new())
+            })
+            .collect()
+    }
+
+    /// 是否有字体能显示这个字
+    pub fn covers(&mut self, c: char) -> bool {
+        let fallbacks = [Some(self.regular), self.cjk];
+        fallbacks
+            .into_iter()
+            .flatten()
+            .any(|font| self.fonts[font].lookup(c).is_some())
+    }
+
+    fn record(&mut self, font: usize, id: u16, advance: u16, text: String) -> Glyph {
+        let f = &mut self.fonts[font];
+        f.used.entry(id).or_insert(text);
+        Glyph {
+            font,
+            id,
+            advance: advance as f32 / f.units_per_em,
+        }
+    }
+}
+
+/// 按候选文件名依次查找，返回第一个能加载的字体
+fn find(index: &HashMap<String, PathBuf>, candidates: &[&str]) -> Option<Font> {
+    candidates
+        .iter()
+        .filter_map(|name| index.get(*name))
+        .find_map(|path| Font::load(path).ok())
+}
+
+/// 扫描系统字体目录：小写文件名 → 路径（同名文件取先找到的）
+fn index_font_files() -> HashMap<String, PathBuf> {
+    let mut index = HashMap::new();
+    for root in font_dirs() {
+        for entry in walkdir::WalkDir::new(&root)
+            .max_depth(6)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            if [".ttf", ".otf", ".ttc", ".otc"]
+                .iter()
+                .any(|ext| name.ends_with(ext))
+            {
+                index
+                    .entry(name)
+                    .or_insert_with(|| entry.path().to_path_buf());
+            }
+        }
+    }
+    index
+}
+
+fn font_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    if cfg!(target_os = "windows") {
+        let windir = std::env::var_os("WINDIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("C:\\Windows"));
+        let mut roots = vec![windir.join("Fonts")];
+        if let Some(local) = dirs::data_local_dir() {
+            roots.push(local.join("Microsoft").join("Windows").join("Fonts"));
+        }
+        roots
+    } else if cfg!(target_os = "macos") {
+        vec![
+            PathBuf::from("/System/Library/Fonts"),
+            PathBuf::from("/Library/Fonts"),
+            home.join("Library/Fonts"),
+        ]
+    } else {
+        vec![
+            PathBuf::from("/usr/share/fonts"),
+            PathBuf::from("/usr/local/share/fonts"),
+            home.join(".local/share/fonts"),
+            home.join(".fonts"),
+        ]
+    }
+}
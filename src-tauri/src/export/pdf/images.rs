@@ -0,0 +1,115 @@
+// ─────────────────────────────────────────────
+// PDF 图片：读取 PNG / JPEG，转成可以直接写进 PDF 的数据
+// ─────────────────────────────────────────────
+//
+// JPEG 原样嵌入（PDF 的 DCTDecode 可以直接解码），只需要从文件头读出尺寸和通道数；
+// PNG 解码成 8 位 RGB / 灰度像素，透明通道单独作为 SMask。
+// 其他格式（GIF、SVG、WebP……）不支持，排版时退回显示替代文字。
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub data: ImageData,
+}
+
+pub enum ImageData {
+    /// 原始 JPEG 文件，components 是颜色通道数（1 灰度 / 3 RGB / 4 CMYK）
+    Jpeg { bytes: Vec<u8>, components: u8 },
+    /// 解码后的像素：gray 为 true 时每像素 1 字节，否则 3 字节 RGB
+    Pixels {
+        pixels: Vec<u8>,
+        gray: bool,
+        alpha: Option<Vec<u8>>,
+    },
+}
+
+pub fn load(path: &Path) -> Result<Image, String> {
+    let bytes =
+        fs::read(path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    if bytes.starts_with(b"\x89PNG") {
+        decode_png(&bytes).map_err(|e| format!("Failed to decode '{}': {}", path.display(), e))
+    } else if bytes.starts_with(&[0xFF, 0xD8]) {
+        let (width, height, components) = jpeg_header(&bytes)
+            .ok_or_else(|| format!("Failed to decode '{}': invalid JPEG", path.display()))?;
+        Ok(Image {
+            width,
+            height,
+            data: ImageData::Jpeg { bytes, components },
+        })
+    } else {
+        Err(format!(
+            "Unsupported image format '{}' (only PNG and JPEG can be embedded in PDF)",
+            path.display()
+        ))
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Image, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    // 调色板展开成 RGB、低位深灰度扩到 8 位、16 位降到 8 位
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(|e| e.to_string())?;
+    buffer.truncate(info.buffer_size());
+
+    let (pixels, gray, alpha) = match info.color_type {
+        png::ColorType::Grayscale => (buffer, true, None),
+        png::ColorType::Rgb => (buffer, false, None),
+        png::ColorType::GrayscaleAlpha => split_alpha(&buffer, 1, true),
+        png::ColorType::Rgba => split_alpha(&buffer, 3, false),
+        // EXPAND 之后不会再出现调色板图
+        png::ColorType::Indexed => return Err("unexpected palette image".to_string()),
+    };
+    Ok(Image {
+        width: info.width,
+        height: info.height,
+        data: ImageData::Pixels {
+            pixels,
+            gray,
+            alpha,
+        },
+    })
+}
+
+/// 把交错的颜色 + 透明通道拆开；完全不透明时丢掉透明通道
+fn split_alpha(buffer: &[u8], channels: usize, gray: bool) -> (Vec<u8>, bool, Option<Vec<u8>>) {
+    let mut pixels = Vec::with_capacity(buffer.len() / (channels + 1) * channels);
+    let mut alpha = Vec::with_capacity(buffer.len() / (channels + 1));
+    for pixel in buffer.chunks_exact(channels + 1) {
+        pixels.extend_from_slice(&pixel[..channels]);
+        alpha.push(pixel[channels]);
+    }
+    let opaque = alpha.iter().all(|&a| a == 255);
+    (pixels, gray, (!opaque).then_some(alpha))
+}
+
+/// 从 JPEG 的 SOF 段读出 (宽, 高, 通道数)
+fn jpeg_header(bytes: &[u8]) -> Option<(u32, u32, u8)> {
+    let read_u16 = |at: usize| -> Option<u16> {
+        Some(u16::from_be_bytes([*bytes.get(at)?, *bytes.get(at + 1)?]))
+    };
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        match marker {
+            // 填充字节
+            0xFF => pos += 1,
+            // SOF0..SOF15，排除 DHT(C4) / JPG(C8) / DAC(CC)
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = read_u16(pos + 5)?;
+                let width = read_u16(pos + 7)?;
+                let components = *bytes.get(pos + 9)?;
+                return Some((width as u32, height as u32, components));
+            }
+            _ => pos += 2 + read_u16(pos + 2)? as usize,
+        }
+    }
+}
@@ -0,0 +1,1379 @@
+// ─────────────────────────────────────────────
+// PDF 排版：Markdown → 一页页的绘制指令
+// ─────────────────────────────────────────────
+//
+// 直接消费 pulldown-cmark 的事件流，边读边排：
+//   - 行内文字先收集成带样式的片段（Span），块结束时折行、分页
+//   - 折行：空白处可断，中日韩文字前后可断，行首禁则标点（"，。）"）不和前一个字分开
+//   - 分页：每放一行前检查剩余高度，放不下就换页；标题至少和后面两行在同一页
+//   - 表格按行分页，换页后重复表头
+//
+// 坐标：排版时用从页面顶部往下的 y，生成绘制指令时换算成 PDF 自下而上的坐标。
+
+use super::fonts::{FontSet, Glyph};
+use super::images::{self, Image};
+use super::PdfOptions;
+use crate::export::highlight::{highlight_lines, Token as CodeToken};
+use crate::export::html::same_document_anchor;
+use crate::markdown::headings::parse_headings;
+use crate::markdown::links::{is_external, normalize_path, percent_decode, split_fragment};
+use crate::markdown::parser_options;
+use crate::markdown::stats::is_cjk;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Parser, Tag, TagEnd};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [f32; 3];
+
+const TEXT: Rgb = [0.141, 0.161, 0.184];
+const MUTED: Rgb = [0.396, 0.427, 0.463];
+const LINK: Rgb = [0.035, 0.412, 0.855];
+const BORDER: Rgb = [0.816, 0.843, 0.871];
+const SHADE: Rgb = [0.965, 0.973, 0.980];
+const WHITE: Rgb = [1.0, 1.0, 1.0];
+
+const BODY_SIZE: f32 = 10.5;
+const CODE_SIZE: f32 = 9.0;
+const FURNITURE_SIZE: f32 = 8.5;
+/// 行高 / 字号
+const LINE_HEIGHT: f32 = 1.5;
+const CODE_LINE_HEIGHT: f32 = 1.45;
+const HEADING_SIZES: [f32; 6] = [22.0, 17.0, 14.0, 12.0, 11.0, 10.5];
+const LIST_INDENT: f32 = 18.0;
+const QUOTE_INDENT: f32 = 14.0;
+const QUOTE_BAR: f32 = 3.0;
+const CELL_PADDING: f32 = 5.0;
+const CODE_PADDING: f32 = 8.0;
+/// 图片像素按 96 DPI 换算成 pt
+const PX_TO_PT: f32 = 0.75;
+
+/// 一条绘制指令（PDF 坐标，原点在页面左下角）
+#[derive(Debug, Clone)]
+pub enum Item {
+    /// y 是基线；bold / italic 表示字体本身不是粗体 / 斜体，需要模拟
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        font: usize,
+        glyphs: Vec<u16>,
+        color: Rgb,
+        bold: bool,
+        italic: bool,
+    },
+    /// 填充矩形，(x, y) 是左下角
+    Rect {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        color: Rgb,
+    },
+    /// 描边矩形
+    Frame {
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        color: Rgb,
+    },
+    Line {
+        from: [f32; 2],
+        to: [f32; 2],
+        width: f32,
+        color: Rgb,
+    },
+    /// Document::images 里的第 index 张图
+    Image {
+        index: usize,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkTarget {
+    /// 外部链接或其他文件
+    Uri(String),
+    /// 本文档里的标题锚点（slug），空字符串表示文档开头
+    Anchor(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkArea {
+    /// [x1, y1, x2, y2]
+    pub rect: [f32; 4],
+    pub target: LinkTarget,
+}
+
+#[derive(Debug, Default)]
+pub struct Page {
+    pub items: Vec<Item>,
+    pub links: Vec<LinkArea>,
+}
+
+/// 书签：每个标题一项，按出现顺序排列
+#[derive(Debug, Clone)]
+pub struct OutlineEntry {
+    pub level: u8,
+    pub title: String,
+    pub page: usize,
+    /// 标题顶部的 PDF 坐标
+    pub y: f32,
+}
+
+pub struct Document {
+    pub pages: Vec<Page>,
+    pub outline: Vec<OutlineEntry>,
+    /// 锚点 → (页码下标, 顶部 PDF 坐标)
+    pub anchors: HashMap<String, (usize, f32)>,
+    pub images: Vec<Image>,
+    pub warnings: Vec<String>,
+}
+
+/// 排版整篇文档；path 是文档的绝对路径，用来解析相对图片和链接
+pub fn layout(source: &str, path: &Path, fonts: &mut FontSet, options: &PdfOptions) -> Document {
+    let self_path = normalize_path(path);
+    let base_dir = self_path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let (page_width, page_height) = options.page_size;
+    let margins = &options.margins;
+
+    let mut layout = Layout {
+        fonts,
+        page_height,
+        left: margins.left,
+        width: (page_width - margins.left - margins.right).max(72.0),
+        top: margins.top,
+        bottom: (page_height - margins.bottom).max(margins.top + 72.0),
+        pages: Vec::new(),
+        page: Page::default(),
+        y: margins.top,
+        pending_gap: 0.0,
+        indent: 0.0,
+        quotes: Vec::new(),
+        lists: Vec::new(),
+        marker: None,
+        styles: vec![Style::body()],
+        spans: Vec::new(),
+        targets: Vec::new(),
+        heading: None,
+        slugs: parse_headings(source).into_iter().map(|h| h.slug).collect(),
+        code: None,
+        image: None,
+        table: None,
+        footnotes_started: false,
+        in_metadata: false,
+        outline: Vec::new(),
+        anchors: HashMap::new(),
+        images: Vec::new(),
+        image_index: HashMap::new(),
+        warnings: Vec::new(),
+        base_dir,
+        self_path,
+    };
+    layout
+        .anchors
+        .insert(String::new(), (0, page_height - margins.top));
+
+    for event in Parser::new_ext(source, parser_options()) {
+        layout.event(event);
+    }
+    layout.flush_inline();
+    layout.close_quote_bars();
+    layout.pages.push(std::mem::take(&mut layout.page));
+
+    Document {
+        pages: layout.pages,
+        outline: layout.outline,
+        anchors: layout.anchors,
+        images: layout.images,
+        warnings: layout.warnings,
+    }
+}
+
+/// 在每页的上下页边距里画页眉 / 页脚
+///
+/// 模板里的 {page} {pages} {title} {file} {date} 会被替换
+pub fn draw_furniture(
+    document: &mut Document,
+    fonts: &mut FontSet,
+    options: &PdfOptions,
+    title: &str,
+    file: &str,
+) {
+    let (page_width, page_height) = options.page_size;
+    let total = document.pages.len().to_string();
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let font = fonts.select(false, false, false);
+
+    let slots = [
+        // 基线放在页边距的正中间
+        (
+            &options.header,
+            page_height - options.margins.top / 2.0 - FURNITURE_SIZE * 0.35,
+        ),
+        (
+            &options.footer,
+            options.margins.bottom / 2.0 - FURNITURE_SIZE * 0.35,
+        ),
+    ];
+    for (index, page) in document.pages.iter_mut().enumerate() {
+        for (template, baseline) in &slots {
+            let Some(template) = template.as_deref().filter(|t| !t.trim().is_empty()) else {
+                continue;
+            };
+            let text = template
+                .replace("{page}", &(index + 1).to_string())
+                .replace("{pages}", &total)
+                .replace("{title}", title)
+                .replace("{file}", file)
+                .replace("{date}", &date);
+            let glyphs = fonts.shape(&text, font);
+            let width: f32 = glyphs.iter().map(|g| g.advance * FURNITURE_SIZE).sum();
+            let x = (page_width - width) / 2.0;
+            page.items.extend(text_runs(
+                fonts,
+                &glyphs,
+                x,
+                *baseline,
+                FURNITURE_SIZE,
+                MUTED,
+                (false, false),
+            ));
+        }
+    }
+}
+
+/// 把字形按字体切成若干段文字指令
+fn text_runs(
+    fonts: &FontSet,
+    glyphs: &[Glyph],
+    x: f32,
+    baseline: f32,
+    size: f32,
+    color: Rgb,
+    (bold, italic): (bool, bool),
+) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut x = x;
+    for run in glyphs.chunk_by(|a, b| a.font == b.font) {
+        let font = &fonts.fonts[run[0].font];
+        items.push(Item::Text {
+            x,
+            y: baseline,
+            size,
+            font: run[0].font,
+            glyphs: run.iter().map(|g| g.id).collect(),
+            color,
+            bold: bold && !font.bold,
+            italic: italic && !font.italic,
+        });
+        x += run.iter().map(|g| g.advance * size).sum::<f32>();
+    }
+    items
+}
+
+// ─────────────────────────────────────────────
+// 行内片段与折行
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    mono: bool,
+    /// 行内代码：画浅色底
+    code: bool,
+    strike: bool,
+    size: f32,
+    color: Rgb,
+    /// Layout::targets 里的下标
+    link: Option<usize>,
+}
+
+impl Style {
+    fn body() -> Style {
+        Style {
+            bold: false,
+            italic: false,
+            mono: false,
+            code: false,
+            strike: false,
+            size: BODY_SIZE,
+            color: TEXT,
+            link: None,
+        }
+    }
+}
+
+struct Span {
+    text: String,
+    style: Style,
+}
+
+/// 同一样式的一段字形
+#[derive(Debug, Clone)]
+struct Frag {
+    glyphs: Vec<Glyph>,
+    width: f32,
+    style: Style,
+}
+
+enum Token {
+    /// 可以断行的空白
+    Space(Frag),
+    /// 不可拆开的一段（一个单词，或者一个中日韩字加上后面的标点）
+    Word(Vec<Frag>),
+    /// 硬换行
+    Break,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Line {
+    frags: Vec<Frag>,
+    width: f32,
+}
+
+impl Line {
+    /// 行里最大的字号，空行用 fallback
+    fn size(&self, fallback: f32) -> f32 {
+        self.frags
+            .iter()
+            .map(|f| f.style.size)
+            .fold(None, |max: Option<f32>, s| {
+                Some(max.map_or(s, |m| m.max(s)))
+            })
+            .unwrap_or(fallback)
+    }
+
+    fn height(&self, fallback: f32) -> f32 {
+        self.size(fallback) * LINE_HEIGHT
+    }
+}
+
+/// 行高为 height、字号为 size 的一行，基线离行顶的距离
+fn baseline_offset(height: f32, size: f32) -> f32 {
+    (height - size) / 2.0 + size * 0.8
+}
+
+/// 中日韩文字和全角标点：前后都可以断行
+fn is_wide(c: char) -> bool {
+    is_cjk(c) || ('\u{3000}'..='\u{303F}').contains(&c) || ('\u{FF00}'..='\u{FFEF}').contains(&c)
+}
+
+/// 不能出现在行首的标点
+fn no_break_before(c: char) -> bool {
+    "，。、；：？！）」』】》〉〕…·,.;:!?)]}%’”ー々〜".contains(c)
+}
+
+/// 不能出现在行尾的标点
+fn no_break_after(c: char) -> bool {
+    "（「『【《〈〔([{‘“".contains(c)
+}
+
+fn can_break_between(prev: char, next: char) -> bool {
+    !no_break_before(next) && !no_break_after(prev) && (is_wide(prev) || is_wide(next))
+}
+
+/// 按宽度 max_width 贪心折行；一个词比整行还宽时按字拆开
+fn break_lines(tokens: Vec<Token>, max_width: f32) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line = Line::default();
+    let mut space: Option<Frag> = None;
+
+    for token in tokens {
+        match token {
+            Token::Break => {
+                lines.push(std::mem::take(&mut line));
+                space = None;
+            }
+            // 行首的空白丢掉
+            Token::Space(frag) if !line.frags.is_empty() => space = Some(frag),
+            Token::Space(_) => {}
+            Token::Word(frags) => {
+                let width: f32 = frags.iter().map(|f| f.width).sum();
+                let space_width = space.as_ref().map_or(0.0, |s| s.width);
+                if !line.frags.is_empty() && line.width + space_width + width > max_width {
+                    lines.push(std::mem::take(&mut line));
+                    space = None;
+                }
+                if let Some(space) = space.take() {
+                    line.width += space.width;
+                    line.frags.push(space);
+                }
+                if line.frags.is_empty() && width > max_width {
+                    split_word(frags, max_width, &mut line, &mut lines);
+                } else {
+                    line.width += width;
+                    line.frags.extend(frags);
+                }
+            }
+        }
+    }
+    if !line.frags.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// 超长的词（URL、长代码行）逐字拆到多行
+fn split_word(frags: Vec<Frag>, max_width: f32, line: &mut Line, lines: &mut Vec<Line>) {
+    for frag in frags {
+        let mut piece = Frag {
+            glyphs: Vec::new(),
+            width: 0.0,
+            style: frag.style,
+        };
+        for glyph in frag.glyphs {
+            let advance = glyph.advance * frag.style.size;
+            let occupied = line.width + piece.width;
+            if occupied > 0.0 && occupied + advance > max_width {
+                if !piece.glyphs.is_empty() {
+                    line.width += piece.width;
+                    line.frags.push(piece.clone());
+                    piece.glyphs.clear();
+                    piece.width = 0.0;
+                }
+                lines.push(std::mem::take(line));
+            }
+            piece.glyphs.push(glyph);
+            piece.width += advance;
+        }
+        if !piece.glyphs.is_empty() {
+            line.width += piece.width;
+            line.frags.push(piece);
+        }
+    }
+}
+
+// ─────────────────────────────────────────────
+// 块级排版
+// ─────────────────────────────────────────────
+
+enum Marker {
+    Bullet(usize),
+    Number(u64),
+    Task(bool),
+    Label(String),
+}
+
+struct Quote {
+    /// 左边竖线的 x
+    x: f32,
+    /// 这一页上竖线的起点（还没放内容时为 None）
+    start: Option<f32>,
+}
+
+struct Table {
+    aligns: Vec<Alignment>,
+    rows: Vec<Row>,
+}
+
+struct Row {
+    header: bool,
+    cells: Vec<Vec<Span>>,
+}
+
+/// 折好行的表格行：每个单元格的行
+#[derive(Clone)]
+struct RowLines {
+    header: bool,
+    cells: Vec<Vec<Line>>,
+    height: f32,
+}
+
+struct Layout<'a> {
+    fonts: &'a mut FontSet,
+    page_height: f32,
+    /// 正文区域左边界和宽度
+    left: f32,
+    width: f32,
+    /// 正文区域上下边界（自上而下的 y）
+    top: f32,
+    bottom: f32,
+
+    pages: Vec<Page>,
+    page: Page,
+    /// 当前位置（自上而下）
+    y: f32,
+    /// 下一块前的间距；相邻块取较大值，页首不留
+    pending_gap: f32,
+    /// 列表 / 引用造成的缩进
+    indent: f32,
+    quotes: Vec<Quote>,
+    /// 每层列表的下一个序号（无序列表为 None）
+    lists: Vec<Option<u64>>,
+    /// 列表项的符号，放第一行时画
+    marker: Option<Marker>,
+
+    styles: Vec<Style>,
+    spans: Vec<Span>,
+    targets: Vec<LinkTarget>,
+    /// 正在收集的标题：(级别, slug)
+    heading: Option<(usize, Option<String>)>,
+    slugs: VecDeque<String>,
+    /// 正在收集的代码块：(语言, 内容)
+    code: Option<(String, String)>,
+    /// 正在收集的图片：(地址, 替代文字)
+    image: Option<(String, String)>,
+    table: Option<Table>,
+    footnotes_started: bool,
+    in_metadata: bool,
+
+    outline: Vec<OutlineEntry>,
+    anchors: HashMap<String, (usize, f32)>,
+    images: Vec<Image>,
+    image_index: HashMap<PathBuf, Option<usize>>,
+    warnings: Vec<String>,
+    base_dir: PathBuf,
+    self_path: PathBuf,
+}
+
+impl Layout<'_> {
+    fn event(&mut self, event: Event) {
+        if let Some((_, text)) = self.code.as_mut() {
+            match event {
+                Event::Text(t) | Event::Html(t) => text.push_str(&t),
+                Event::End(TagEnd::CodeBlock | TagEnd::HtmlBlock) => {
+                    let (lang, text) = self.code.take().unwrap_or_default();
+                    self.code_block(&lang, &text);
+                }
+                _ => {}
+            }
+            return;
+        }
+        if let Some((_, alt)) = self.image.as_mut() {
+            match event {
+                Event::Text(t) | Event::Code(t) => alt.push_str(&t),
+                Event::End(TagEnd::Image) => {
+                    let (dest, alt) = self.image.take().unwrap_or_default();
+                    self.image(&dest, &alt);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match event {
+            // front matter 不显示
+            Event::Start(Tag::MetadataBlock(_)) => self.in_metadata = true,
+            Event::End(TagEnd::MetadataBlock(_)) => self.in_metadata = false,
+            _ if self.in_metadata => {}
+
+            Event::Start(Tag::Paragraph) => self.flush_inline(),
+            Event::End(TagEnd::Paragraph) => {
+                self.flush_inline();
+                self.gap(BODY_SIZE * 0.75);
+            }
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.flush_inline();
+                let level = level as usize;
+                self.heading = Some((level, self.slugs.pop_front()));
+                let size = HEADING_SIZES[level - 1];
+                self.push_style(|s| {
+                    s.bold = true;
+                    s.size = size;
+                });
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                self.styles.pop();
+                self.heading_block();
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                self.flush_inline();
+                self.quotes.push(Quote {
+                    x: self.left + self.indent,
+                    start: None,
+                });
+                self.indent += QUOTE_INDENT;
+                self.push_style(|s| s.color = MUTED);
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                self.flush_inline();
+                if let Some(Quote {
+                    x,
+                    start: Some(start),
+                }) = self.quotes.pop()
+                {
+                    self.rect(x, start, QUOTE_BAR, self.y - start, BORDER);
+                }
+                self.indent -= QUOTE_INDENT;
+                self.styles.pop();
+                self.gap(BODY_SIZE * 0.75);
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                self.flush_inline();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code = Some((lang, String::new()));
+            }
+            // 和前端 markdown-it 的 html: false 一致：原始 HTML 原样显示
+            Event::Start(Tag::HtmlBlock) => {
+                self.flush_inline();
+                self.code = Some((String::new(), String::new()));
+            }
+            Event::Start(Tag::List(first)) => {
+                self.flush_inline();
+                self.lists.push(first);
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.flush_inline();
+                self.lists.pop();
+                self.gap(BODY_SIZE * 0.75);
+            }
+            Event::Start(Tag::Item) => {
+                self.flush_inline();
+                let depth = self.lists.len().saturating_sub(1);
+                self.marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        Some(Marker::Number(*number - 1))
+                    }
+                    _ => Some(Marker::Bullet(depth)),
+                };
+                self.indent += LIST_INDENT;
+            }
+            Event::End(TagEnd::Item) => {
+                self.flush_inline();
+                self.indent -= LIST_INDENT;
+                self.marker = None;
+                self.gap(BODY_SIZE * 0.2);
+            }
+            Event::TaskListMarker(done) => self.marker = Some(Marker::Task(done)),
+            Event::Start(Tag::Table(aligns)) => {
+                self.flush_inline();
+                self.table = Some(Table {
+                    aligns,
+                    rows: Vec::new(),
+                });
+            }
+            Event::Start(Tag::TableHead | Tag::TableRow) => {
+                let header = matches!(event, Event::Start(Tag::TableHead));
+                if let Some(table) = self.table.as_mut() {
+                    table.rows.push(Row {
+                        header,
+                        cells: Vec::new(),
+                    });
+                }
+            }
+            Event::Start(Tag::TableCell) => self.spans.clear(),
+            Event::End(TagEnd::TableCell) => {
+                let spans = std::mem::take(&mut self.spans);
+                if let Some(row) = self.table.as_mut().and_then(|t| t.rows.last_mut()) {
+                    row.cells.push(spans);
+                }
+            }
+            Event::End(TagEnd::Table) => {
+                if let Some(table) = self.table.take() {
+                    self.table_block(table);
+                }
+            }
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                self.flush_inline();
+                if !self.footnotes_started {
+                    self.footnotes_started = true;
+                    self.gap(BODY_SIZE);
+                    let top = self.place(BODY_SIZE * 0.5);
+                    let x = self.left + self.indent;
+                    self.hline(x, x + self.width / 3.0, top, 0.5, BORDER);
+                }
+                self.anchors.insert(
+                    format!("fn-{}", label),
+                    (self.pages.len(), self.page_height - self.y),
+                );
+                self.marker = Some(Marker::Label(format!("{}.", label)));
+                self.indent += LIST_INDENT;
+                self.push_style(|s| s.size = BODY_SIZE * 0.9);
+            }
+            Event::End(TagEnd::FootnoteDefinition) => {
+                self.flush_inline();
+                self.indent -= LIST_INDENT;
+                self.styles.pop();
+            }
+            Event::FootnoteReference(label) => {
+                let target = self.target(LinkTarget::Anchor(format!("fn-{}", label)));
+                let mut style = self.style();
+                style.size *= 0.75;
+                style.color = LINK;
+                style.link = Some(target);
+                self.spans.push(Span {
+                    text: format!("[{}]", label),
+                    style,
+                });
+            }
+
+            Event::Start(Tag::Emphasis) => self.push_style(|s| s.italic = true),
+            Event::Start(Tag::Strong) => self.push_style(|s| s.bold = true),
+            Event::Start(Tag::Strikethrough) => self.push_style(|s| s.strike = true),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let target = self.link_target(&dest_url);
+                let index = target.map(|t| self.target(t));
+                self.push_style(|s| {
+                    if index.is_some() {
+                        s.color = LINK;
+                    }
+                    s.link = index;
+                });
+            }
+            Event::End(
+                TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link,
+            ) => {
+                self.styles.pop();
+            }
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                self.image = Some((dest_url.to_string(), String::new()));
+            }
+
+            Event::Text(text) | Event::InlineHtml(text) | Event::Html(text) => {
+                self.push_text(&text)
+            }
+            Event::Code(text) => {
+                let mut style = self.style();
+                style.mono = true;
+                style.code = true;
+                style.size *= 0.9;
+                self.spans.push(Span {
+                    text: text.to_string(),
+                    style,
+                });
+            }
+            Event::SoftBreak => self.push_text(" "),
+            Event::HardBreak => self.push_text("\n"),
+            Event::Rule => {
+                self.flush_inline();
+                self.gap(BODY_SIZE * 0.75);
+                let top = self.place(BODY_SIZE);
+                let x = self.left + self.indent;
+                self.hline(
+                    x,
+                    x + self.content_width(),
+                    top + BODY_SIZE / 2.0,
+                    1.0,
+                    BORDER,
+                );
+                self.gap(BODY_SIZE * 0.75);
+            }
+            _ => {}
+        }
+    }
+
+    // ── 样式与片段 ──
+
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_else(Style::body)
+    }
+
+    fn push_style(&mut self, change: impl FnOnce(&mut Style)) {
+        let mut style = self.style();
+        change(&mut style);
+        self.styles.push(style);
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let style = self.style();
+        self.spans.push(Span {
+            text: text.to_string(),
+            style,
+        });
+    }
+
+    fn target(&mut self, target: LinkTarget) -> usize {
+        self.targets.push(target);
+        self.targets.len() - 1
+    }
+
+    /// 链接地址 → 目标：页内锚点 / 指向本文档自己的链接跳到标题，其他的作为 URI
+    fn link_target(&self, dest: &str) -> Option<LinkTarget> {
+        if let Some(fragment) = dest.strip_prefix('#') {
+            return Some(LinkTarget::Anchor(percent_decode(fragment)));
+        }
+        if is_external(dest) {
+            return Some(LinkTarget::Uri(dest.to_string()));
+        }
+        if let Some(anchor) = same_document_anchor(dest, &self.base_dir, &self.self_path) {
+            return Some(LinkTarget::Anchor(
+                anchor.trim_start_matches('#').to_string(),
+            ));
+        }
+        let (target, _) = split_fragment(dest);
+        if target.is_empty() {
+            return None;
+        }
+        // 其他本地文件：用绝对路径的 file: 链接，PDF 挪了位置也能打开
+        let file = normalize_path(&self.base_dir.join(percent_decode(target)));
+        Some(LinkTarget::Uri(format!(
+            "file://{}",
+            file.to_string_lossy()
+                .replace('\\', "/")
+                .replace(' ', "%20")
+        )))
+    }
+
+    fn frag(&mut self, text: &str, style: Style) -> Frag {
+        let font = self.fonts.select(style.bold, style.italic, style.mono);
+        let glyphs = self.fonts.shape(text, font);
+        let width = glyphs.iter().map(|g| g.advance * style.size).sum();
+        Frag {
+            glyphs,
+            width,
+            style,
+        }
+    }
+
+    /// 把带样式的片段切成折行用的单元
+    fn tokenize(&mut self, spans: &[Span]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut word: Vec<Frag> = Vec::new();
+        let mut prev: Option<char> = None;
+
+        for span in spans {
+            let mut buffer = String::new();
+            for c in span.text.chars() {
+                if c == '\n' || (c.is_whitespace() && c != '\u{a0}') {
+                    if !buffer.is_empty() {
+                        word.push(self.frag(&buffer, span.style));
+                        buffer.clear();
+                    }
+                    if !word.is_empty() {
+                        tokens.push(Token::Word(std::mem::take(&mut word)));
+                    }
+                    if c == '\n' {
+                        tokens.push(Token::Break);
+                    } else if !matches!(tokens.last(), Some(Token::Space(_))) {
+                        tokens.push(Token::Space(self.frag(" ", span.style)));
+                    }
+                    prev = None;
+                    continue;
+                }
+                if prev.is_some_and(|p| can_break_between(p, c)) {
+                    if !buffer.is_empty() {
+                        word.push(self.frag(&buffer, span.style));
+                        buffer.clear();
+                    }
+                    if !word.is_empty() {
+                        tokens.push(Token::Word(std::mem::take(&mut word)));
+                    }
+                }
+                buffer.push(c);
+                prev = Some(c);
+            }
+            // 样式边界不断词（"**Rust**acean" 是一个词）
+            if !buffer.is_empty() {
+                word.push(self.frag(&buffer, span.style));
+            }
+        }
+        if !word.is_empty() {
+            tokens.push(Token::Word(word));
+        }
+        tokens
+    }
+
+    // ── 位置与分页 ──
+
+    fn content_width(&self) -> f32 {
+        (self.width - self.indent).max(36.0)
+    }
+
+    fn gap(&mut self, amount: f32) {
+        self.pending_gap = self.pending_gap.max(amount);
+    }
+
+    /// 占用 height 高的一块，放不下就换页；返回这一块的顶部
+    fn place(&mut self, height: f32) -> f32 {
+        let at_top = self.y <= self.top + 0.01;
+        let gap = if at_top { 0.0 } else { self.pending_gap };
+        self.pending_gap = 0.0;
+        if !at_top && self.y + gap + height > self.bottom {
+            self.new_page();
+        } else {
+            self.y += gap;
+        }
+        let top = self.y;
+        self.y += height;
+        for quote in &mut self.quotes {
+            quote.start.get_or_insert(top);
+        }
+        if let Some(marker) = self.marker.take() {
+            self.draw_marker(marker, top);
+        }
+        top
+    }
+
+    /// 剩下的空间放不下 height 时提前换页
+    fn keep(&mut self, height: f32) {
+        if self.y > self.top + 0.01 && self.y + self.pending_gap + height > self.bottom {
+            self.new_page();
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.close_quote_bars();
+        self.pages.push(std::mem::take(&mut self.page));
+        self.y = self.top;
+        self.pending_gap = 0.0;
+    }
+
+    /// 引用块左边的竖线先画到这一页当前的位置
+    fn close_quote_bars(&mut self) {
+        for i in 0..self.quotes.len() {
+            if let Some(start) = self.quotes[i].start.take() {
+                let x = self.quotes[i].x;
+                self.rect(x, start, QUOTE_BAR, self.y - start, BORDER);
+            }
+        }
+    }
+
+    // ── 绘制（参数都是自上而下的坐标） ──
+
+    fn rect(&mut self, x: f32, top: f32, w: f32, h: f32, color: Rgb) {
+        self.page.items.push(Item::Rect {
+            x,
+            y: self.page_height - top - h,
+            w,
+            h,
+            color,
+        });
+    }
+
+    fn frame(&mut self, x: f32, top: f32, w: f32, h: f32, color: Rgb) {
+        self.page.items.push(Item::Frame {
+            x,
+            y: self.page_height - top - h,
+            w,
+            h,
+            color,
+        });
+    }
+
+    fn hline(&mut self, x1: f32, x2: f32, y: f32, width: f32, color: Rgb) {
+        let y = self.page_height - y;
+        self.page.items.push(Item::Line {
+            from: [x1, y],
+            to: [x2, y],
+            width,
+            color,
+        });
+    }
+
+    /// 画一行文字；top 是行顶，height 是行高
+    fn draw_line(&mut self, line: &Line, x: f32, top: f32, height: f32) {
+        let baseline = top + baseline_offset(height, line.size(BODY_SIZE));
+        let mut x = x;
+        for frag in &line.frags {
+            let style = frag.style;
+            if style.code {
+                self.rect(
+                    x - 1.0,
+                    baseline - style.size * 0.95,
+                    frag.width + 2.0,
+                    style.size * 1.25,
+                    SHADE,
+                );
+            }
+            let runs = text_runs(
+                self.fonts,
+                &frag.glyphs,
+                x,
+                self.page_height - baseline,
+                style.size,
+                style.color,
+                (style.bold, style.italic),
+            );
+            self.page.items.extend(runs);
+            if style.strike {
+                let y = baseline - style.size * 0.3;
+                self.hline(x, x + frag.width, y, style.size * 0.06, style.color);
+            }
+            if let Some(index) = style.link {
+                self.link_area(x, top, frag.width, height, index);
+            }
+            x += frag.width;
+        }
+    }
+
+    fn link_area(&mut self, x: f32, top: f32, w: f32, h: f32, index: usize) {
+        let target = self.targets[index].clone();
+        let rect = [x, self.page_height - top - h, x + w, self.page_height - top];
+        // 同一个链接相邻的几段合并成一个热区
+        if let Some(last) = self.page.links.last_mut() {
+            if last.target == target && last.rect[1] == rect[1] && (last.rect[2] - x).abs() < 0.5 {
+                last.rect[2] = rect[2];
+                return;
+            }
+        }
+        self.page.links.push(LinkArea { rect, target });
+    }
+
+    fn draw_marker(&mut self, marker: Marker, top: f32) {
+        let baseline = top + baseline_offset(BODY_SIZE * LINE_HEIGHT, BODY_SIZE);
+        let right = self.left + self.indent - 6.0;
+        let text = match marker {
+            Marker::Bullet(depth) => {
+                let bullet = ['•', '◦', '▪'][depth % 3];
+                if self.fonts.covers(bullet) {
+                    bullet
+                } else {
+                    '•'
+                }
+                .to_string()
+            }
+            Marker::Number(n) => format!("{}.", n),
+            Marker::Label(label) => label,
+            Marker::Task(done) => {
+                let size = BODY_SIZE * 0.8;
+                let (x, box_top) = (right - size, baseline - size);
+                if done {
+                    self.rect(x, box_top, size, size, LINK);
+                    let y = self.page_height - box_top;
+                    let points = [
+                        [x + size * 0.22, y - size * 0.52],
+                        [x + size * 0.42, y - size * 0.75],
+                        [x + size * 0.8, y - size * 0.25],
+                    ];
+                    for pair in points.windows(2) {
+                        self.page.items.push(Item::Line {
+                            from: pair[0],
+                            to: pair[1],
+                            width: 1.2,
+                            color: WHITE,
+                        });
+                    }
+                } else {
+                    self.frame(x, box_top, size, size, MUTED);
+                }
+                return;
+            }
+        };
+        let font = self.fonts.select(false, false, false);
+        let glyphs = self.fonts.shape(&text, font);
+        let width: f32 = glyphs.iter().map(|g| g.advance * BODY_SIZE).sum();
+        let runs = text_runs(
+            self.fonts,
+            &glyphs,
+            right - width,
+            self.page_height - baseline,
+            BODY_SIZE,
+            self.style().color,
+            (false, false),
+        );
+        self.page.items.extend(runs);
+    }
+
+    // ── 各种块 ──
+
+    /// 排出收集到的行内文字（段落、紧凑列表项、引用里的文字）
+    fn flush_inline(&mut self) {
+        if self.spans.iter().all(|s| s.text.trim().is_empty()) {
+            self.spans.clear();
+            return;
+        }
+        let spans = std::mem::take(&mut self.spans);
+        let tokens = self.tokenize(&spans);
+        let x = self.left + self.indent;
+        for line in break_lines(tokens, self.content_width()) {
+            let height = line.height(self.style().size);
+            let top = self.place(height);
+            self.draw_line(&line, x, top, height);
+        }
+    }
+
+    fn heading_block(&mut self) {
+        let Some((level, slug)) = self.heading.take() else {
+            return;
+        };
+        let spans = std::mem::take(&mut self.spans);
+        let title: String = spans.iter().map(|s| s.text.as_str()).collect();
+        let size = HEADING_SIZES[level - 1];
+
+        self.gap(size * 0.9);
+        // 标题不留在页底：至少和后面两行正文在同一页
+        self.keep(size * LINE_HEIGHT + BODY_SIZE * LINE_HEIGHT * 2.0);
+
+        let tokens = self.tokenize(&spans);
+        let x = self.left + self.indent;
+        let mut first_top = None;
+        for line in break_lines(tokens, self.content_width()) {
+            let height = line.height(size);
+            let top = self.place(height);
+            first_top.get_or_insert(top);
+            self.draw_line(&line, x, top, height);
+        }
+        let top = first_top.unwrap_or(self.y);
+        let position = (self.pages.len(), self.page_height - top);
+        if let Some(slug) = slug {
+            self.anchors.entry(slug).or_insert(position);
+        }
+        self.outline.push(OutlineEntry {
+            level: level as u8,
+            title: title.trim().to_string(),
+            page: position.0,
+            y: position.1,
+        });
+
+        // 和预览一样，一、二级标题下面有分隔线
+        if level <= 2 {
+            let top = self.place(size * 0.3);
+            self.hline(x, x + self.content_width(), top + size * 0.15, 0.75, BORDER);
+        }
+        self.gap(size * 0.5);
+    }
+
+    fn code_block(&mut self, lang: &str, text: &str) {
+        let text = text
+            .strip_suffix('\n')
+            .unwrap_or(text)
+            .replace('\t', "    ");
+        let lines: Vec<Vec<CodeToken>> = highlight_lines(&text, lang).unwrap_or_else(|| {
+            text.split('\n')
+                .map(|line| vec![([36, 41, 47], line.to_string())])
+                .collect()
+        });
+
+        let x = self.left + self.indent;
+        let width = self.content_width();
+        let line_height = CODE_SIZE * CODE_LINE_HEIGHT;
+        let mut style = Style::body();
+        style.mono = true;
+        style.size = CODE_SIZE;
+
+        self.gap(BODY_SIZE * 0.75);
+        let top = self.place(CODE_PADDING);
+        self.rect(x, top, width, CODE_PADDING, SHADE);
+        for tokens in lines {
+            let frags: Vec<Frag> = tokens
+                .iter()
+                .map(|([r, g, b], text)| {
+                    style.color = [*r as f32 / 255.0, *g as f32 / 255.0, *b as f32 / 255.0];
+                    self.frag(text, style)
+                })
+                .collect();
+            // 代码不在空格处折行，只有超宽时按字拆开
+            let mut wrapped = break_lines(vec![Token::Word(frags)], width - CODE_PADDING * 2.0);
+            if wrapped.is_empty() {
+                wrapped.push(Line::default());
+            }
+            for line in wrapped {
+                let top = self.place(line_height);
+                self.rect(x, top, width, line_height, SHADE);
+                self.draw_line(&line, x + CODE_PADDING, top, line_height);
+            }
+        }
+        let top = self.place(CODE_PADDING);
+        self.rect(x, top, width, CODE_PADDING, SHADE);
+        self.gap(BODY_SIZE * 0.75);
+    }
+
+    fn image(&mut self, dest: &str, alt: &str) {
+        // 表格和标题里的图片只显示替代文字
+        let index = if self.table.is_some() || self.heading.is_some() {
+            None
+        } else {
+            self.load_image(dest)
+        };
+        let Some(index) = index else {
+            let mut style = self.style();
+            style.italic = true;
+            if style.link.is_none() {
+                style.color = MUTED;
+            }
+            self.spans.push(Span {
+                text: format!("[{}]", if alt.is_empty() { dest } else { alt }),
+                style,
+            });
+            return;
+        };
+
+        // 图片单独占一块：前面的文字先排出去
+        self.flush_inline();
+        let image = &self.images[index];
+        let (mut w, mut h) = (
+            image.width as f32 * PX_TO_PT,
+            image.height as f32 * PX_TO_PT,
+        );
+        let max_w = self.content_width();
+        let max_h = self.bottom - self.top;
+        let scale = (max_w / w).min(max_h / h).min(1.0);
+        w *= scale;
+        h *= scale;
+
+        let x = self.left + self.indent;
+        let top = self.place(h);
+        self.page.items.push(Item::Image {
+            index,
+            x,
+            y: self.page_height - top - h,
+            w,
+            h,
+        });
+        if let Some(link) = self.style().link {
+            self.link_area(x, top, w, h, link);
+        }
+        self.gap(BODY_SIZE * 0.5);
+    }
+
+    /// 读取本地图片（同一张图只嵌入一次）；外部图片不下载
+    fn load_image(&mut self, dest: &str) -> Option<usize> {
+        if is_external(dest) || dest.starts_with('#') {
+            return None;
+        }
+        let (target, _) = split_fragment(dest);
+        let path = normalize_path(&self.base_dir.join(percent_decode(target)));
+        if let Some(index) = self.image_index.get(&path) {
+            return *index;
+        }
+        let index = match images::load(&path) {
+            Ok(image) if image.width > 0 && image.height > 0 => {
+                self.images.push(image);
+                Some(self.images.len() - 1)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                self.warnings.push(e);
+                None
+            }
+        };
+        self.image_index.insert(path, index);
+        index
+    }
+
+    fn table_block(&mut self, table: Table) {
+        let columns = table
+            .rows
+            .iter()
+            .map(|r| r.cells.len())
+            .max()
+            .unwrap_or(0)
+            .max(table.aligns.len());
+        if columns == 0 {
+            return;
+        }
+
+        // 每个单元格切好词，量出不折行时的宽度和最长的词
+        let mut cells: Vec<(bool, Vec<Vec<Token>>)> = Vec::new();
+        let mut natural = vec![0f32; columns];
+        let mut minimum = vec![0f32; columns];
+        for row in &table.rows {
+            let mut tokens = Vec::new();
+            for column in 0..columns {
+                let mut spans: Vec<Span> = Vec::new();
+                for span in row.cells.get(column).into_iter().flatten() {
+                    let mut style = span.style;
+                    style.bold |= row.header;
+                    spans.push(Span {
+                        text: span.text.clone(),
+                        style,
+                    });
+                }
+                let cell = self.tokenize(&spans);
+                let mut line = 0f32;
+                for token in &cell {
+                    match token {
+                        Token::Space(frag) => line += frag.width,
+                        Token::Word(frags) => {
+                            let width: f32 = frags.iter().map(|f| f.width).sum();
+                            line += width;
+                            minimum[column] = minimum[column].max(width);
+                        }
+                        Token::Break => line = 0.0,
+                    }
+                    natural[column] = natural[column].max(line);
+                }
+                tokens.push(cell);
+            }
+            cells.push((row.header, tokens));
+        }
+
+        let widths = column_widths(&natural, &minimum, self.content_width());
+        let rows: Vec<RowLines> = cells
+            .into_iter()
+            .map(|(header, tokens)| {
+                let cells: Vec<Vec<Line>> = tokens
+                    .into_iter()
+                    .zip(&widths)
+                    .map(|(tokens, width)| break_lines(tokens, width - CELL_PADDING * 2.0))
+                    .collect();
+                let height = cells
+                    .iter()
+                    .map(|lines| lines.iter().map(|l| l.height(BODY_SIZE)).sum::<f32>())
+                    .fold(BODY_SIZE * LINE_HEIGHT, f32::max)
+                    + CELL_PADDING * 2.0;
+                RowLines {
+                    header,
+                    cells,
+                    height,
+                }
+            })
+            .collect();
+        let header: Vec<RowLines> = rows.iter().filter(|r| r.header).cloned().collect();
+
+        self.gap(BODY_SIZE * 0.75);
+        for row in &rows {
+            if !row.header
+                && self.y > self.top + 0.01
+                && self.y + self.pending_gap + row.height > self.bottom
+            {
+                // 换页后先重复表头
+                self.new_page();
+                for header_row in &header {
+                    self.table_row(header_row, &widths, &table.aligns);
+                }
+            }
+            self.table_row(row, &widths, &table.aligns);
+        }
+        self.gap(BODY_SIZE * 0.75);
+    }
+
+    fn table_row(&mut self, row: &RowLines, widths: &[f32], aligns: &[Alignment]) {
+        let top = self.place(row.height);
+        let mut x = self.left + self.indent;
+        let total: f32 = widths.iter().sum();
+        if row.header {
+            self.rect(x, top, total, row.height, SHADE);
+        }
+        for (column, lines) in row.cells.iter().enumerate() {
+            let width = widths[column];
+            let mut line_top = top + CELL_PADDING;
+            for line in lines {
+                let height = line.height(BODY_SIZE);
+                let inner = width - CELL_PADDING * 2.0;
+                let offset = match aligns.get(column) {
+                    Some(Alignment::Right) => inner - line.width,
+                    Some(Alignment::Center) => (inner - line.width) / 2.0,
+                    _ => 0.0,
+                };
+                self.draw_line(line, x + CELL_PADDING + offset.max(0.0), line_top, height);
+                line_top += height;
+            }
+            self.frame(x, top, width, row.height, BORDER);
+            x += width;
+        }
+    }
+}
+
+/// 分配列宽：放得下就按内容宽度；放不下时每列先保证最长的词，剩下的按内容多少分
+fn column_widths(natural: &[f32], minimum: &[f32], available: f32) -> Vec<f32> {
+    let padding = CELL_PADDING * 2.0;
+    let inner = available - padding * natural.len() as f32;
+    let natural_total: f32 = natural.iter().sum();
+    if natural_total <= inner {
+        return natural.iter().map(|w| w + padding).collect();
+    }
+    let minimum_total: f32 = minimum.iter().sum();
+    if minimum_total >= inner || inner <= 0.0 {
+        let scale = inner.max(0.0) / minimum_total.max(1.0);
+        return minimum.iter().map(|w| w * scale + padding).collect();
+    }
+    let extra = inner - minimum_total;
+    let flexible: f32 = natural.iter().zip(minimum).map(|(n, m)| n - m).sum();
+    natural
+        .iter()
+        .zip(minimum)
+        .map(|(n, m)| m + (n - m) / flexible.max(1.0) * extra + padding)
+        .collect()
+}
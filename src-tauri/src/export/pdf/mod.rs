@@ -0,0 +1,216 @@
+// ─────────────────────────────────────────────
+// PDF 导出（不依赖浏览器引擎）
+// ─────────────────────────────────────────────
+//
+// 流程：fonts.rs 找系统字体 → layout.rs 把 Markdown 排成一页页的绘制指令
+//       → writer.rs 写出 PDF（字体子集嵌入、书签、链接热区）
+//
+// 不经过 WebView 打印，所以无窗口的命令行子命令里也能用，
+// 输出在不同机器上一致，不受浏览器打印设置影响。
+
+mod fonts;
+mod images;
+mod layout;
+mod writer;
+
+pub use fonts::FontPaths;
+
+use super::html::document_title;
+use crate::commands::markdown::load_document;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 默认页面尺寸和页边距
+pub const DEFAULT_PAGE_SIZE: &str = "a4";
+pub const DEFAULT_MARGINS: &str = "20mm";
+/// 默认页脚：居中的 "页码 / 总页数"
+pub const DEFAULT_FOOTER: &str = "{page} / {pages}";
+
+/// 页边距（pt）
+#[derive(Debug, Clone, Copy)]
+pub struct Margins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct PdfOptions {
+    /// 页面宽高（pt）
+    pub page_size: (f32, f32),
+    pub margins: Margins,
+    /// 页眉 / 页脚模板，None 或空字符串表示不要；可用 {page} {pages} {title} {file} {date}
+    pub header: Option<String>,
+    pub footer: Option<String>,
+    pub fonts: FontPaths,
+}
+
+impl PdfOptions {
+    /// 从字符串形式的选项构造（命令行和 IPC 共用）
+    pub fn parse(
+        page_size: &str,
+        margins: &str,
+        header: Option<String>,
+        footer: Option<String>,
+        fonts: FontPaths,
+    ) -> Result<PdfOptions, String> {
+        let size = parse_page_size(page_size)?;
+        let parsed = parse_margins(margins)?;
+        // 页边距加起来不能占满整页，否则排版区域的宽或高不是正数
+        if parsed.left + parsed.right >= size.0 || parsed.top + parsed.bottom >= size.1 {
+            return Err(format!(
+                "Margins '{}' leave no room for content on a {} page",
+                margins, page_size
+            ));
+        }
+        Ok(PdfOptions {
+            page_size: size,
+            margins: parsed,
+            header,
+            footer,
+            fonts,
+        })
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfExport {
+    pub output: String,
+    pub pages: usize,
+    /// 没能嵌入的图片、缺字等，不影响导出
+    pub warnings: Vec<String>,
+}
+
+/// 默认的输出路径：和源文件同目录、同名的 .pdf
+pub fn default_output(path: &Path) -> PathBuf {
+    path.with_extension("pdf")
+}
+
+/// 读取文档（含 vault 展开），排版后写入 output；path 需要是绝对路径
+pub fn export_pdf(path: &Path, output: &Path, options: &PdfOptions) -> Result<PdfExport, String> {
+    let source = load_document(&path.to_string_lossy())?;
+    let mut fonts = fonts::FontSet::load(&options.fonts)?;
+
+    let title = document_title(path, &source);
+    let file = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut document = layout::layout(&source, path, &mut fonts, options);
+    layout::draw_furniture(&mut document, &mut fonts, options, &title, &file);
+
+    let bytes = writer::write(&document, &fonts, options.page_size, &title);
+    fs::write(output, bytes)
+        .map_err(|e| format!("Failed to write '{}': {}", output.display(), e))?;
+
+    let mut warnings = document.warnings;
+    if !fonts.missing.is_empty() {
+        let sample: String = fonts.missing.iter().take(10).collect();
+        warnings.push(format!(
+            "{} characters have no glyph in the available fonts (e.g. \"{}\"); install a CJK font or pass a font file",
+            fonts.missing.len(),
+            sample
+        ));
+    }
+    Ok(PdfExport {
+        output: output.to_string_lossy().to_string(),
+        pages: document.pages.len(),
+        warnings,
+    })
+}
+
+/// 页面尺寸："a4" / "letter" 等名称（加 "-landscape" 横向），或 "210x297mm" / "8.5x11in"
+pub fn parse_page_size(value: &str) -> Result<(f32, f32), String> {
+    let value = value.trim().to_lowercase();
+    let (name, landscape) = match value.strip_suffix("-landscape") {
+        Some(name) => (name, true),
+        None => (value.as_str(), false),
+    };
+    let (width, height) = match name {
+        "a3" => (mm(297.0), mm(420.0)),
+        "a4" => (mm(210.0), mm(297.0)),
+        "a5" => (mm(148.0), mm(210.0)),
+        "b5" => (mm(176.0), mm(250.0)),
+        "letter" => (612.0, 792.0),
+        "legal" => (612.0, 1008.0),
+        _ => {
+            let (w, h) = name
+                .split_once('x')
+                .ok_or_else(|| format!("Invalid page size '{}'", value))?;
+            // 单位只写在最后时两边共用："210x297mm"
+            let unit: String = h.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+            let w = if w.chars().any(|c| c.is_ascii_alphabetic()) {
+                w.to_string()
+            } else {
+                format!("{}{}", w, unit)
+            };
+            (parse_length(&w)?, parse_length(h)?)
+        }
+    };
+    if width < 72.0 || height < 72.0 {
+        return Err(format!("Page size '{}' is too small", value));
+    }
+    Ok(if landscape {
+        (height, width)
+    } else {
+        (width, height)
+    })
+}
+
+/// 页边距，和 CSS 一样写 1–4 个值："20mm" / "20mm 15mm" / "1in 0.75in 1in 0.75in"
+pub fn parse_margins(value: &str) -> Result<Margins, String> {
+    let values = value
+        .split_whitespace()
+        .map(parse_length)
+        .collect::<Result<Vec<f32>, String>>()?;
+    let [top, right, bottom, left] = match values[..] {
+        [all] => [all; 4],
+        [vertical, horizontal] => [vertical, horizontal, vertical, horizontal],
+        [top, horizontal, bottom] => [top, horizontal, bottom, horizontal],
+        [top, right, bottom, left] => [top, right, bottom, left],
+        _ => return Err(format!("Invalid margins '{}'", value)),
+    };
+    Ok(Margins {
+        top,
+        right,
+        bottom,
+        left,
+    })
+}
+
+/// 长度："20mm" / "2cm" / "1in" / "72pt"，不写单位时按 mm
+fn parse_length(value: &str) -> Result<f32, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f32 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid length '{}'", value))?;
+    let points = match unit {
+        "" | "mm" => mm(number),
+        "cm" => mm(number * 10.0),
+        "in" => number * 72.0,
+        "pt" => number,
+        _ => {
+            return Err(format!(
+                "Unknown unit in '{}' (use mm, cm, in or pt)",
+                value
+            ))
+        }
+    };
+    if !points.is_finite() || points < 0.0 {
+        return Err(format!("Invalid length '{}'", value));
+    }
+    Ok(points)
+}
+
+fn mm(value: f32) -> f32 {
+    value * 72.0 / 25.4
+}
@@ -0,0 +1,439 @@
+// ─────────────────────────────────────────────
+// PDF 输出：把排好的页面写成 PDF 文件
+// ─────────────────────────────────────────────
+//
+//   - 字体：Type0 + Identity-H 编码（字符码就是字形 id），只嵌入用到的字形子集，
+//           附带 ToUnicode 映射，复制 / 搜索文字时能还原成原文
+//   - 书签：按标题级别建成树，点击跳到标题所在位置
+//   - 链接：外部链接是 URI 动作，页内锚点是 GoTo 动作
+//   - 内容流、字体、图片都用 Flate 压缩
+
+use super::fonts::FontSet;
+use super::images::ImageData;
+use super::layout::{Document, Item, LinkTarget, OutlineEntry};
+use pdf_writer::types::{
+    ActionType, AnnotationType, CidFontType, FontFlags, PageMode, SystemInfo, TextRenderingMode,
+    UnicodeCmap,
+};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+const SYSTEM_INFO: SystemInfo = SystemInfo {
+    registry: Str(b"Adobe"),
+    ordering: Str(b"Identity"),
+    supplement: 0,
+};
+
+/// 模拟斜体时的倾斜度（约 12°）
+const SKEW: f32 = 0.21;
+
+pub fn write(document: &Document, fonts: &FontSet, page_size: (f32, f32), title: &str) -> Vec<u8> {
+    let mut next = Ref::new(1);
+    let mut alloc = || next.bump();
+    let mut pdf = Pdf::new();
+
+    let catalog = alloc();
+    let tree = alloc();
+    let info = alloc();
+    let page_refs: Vec<Ref> = document.pages.iter().map(|_| alloc()).collect();
+
+    // ── 字体：只写实际用到的 ──
+    let mut font_names: Vec<Option<String>> = vec![None; fonts.fonts.len()];
+    let mut font_resources = Vec::new();
+    for (index, font) in fonts.fonts.iter().enumerate() {
+        if font.used.is_empty() {
+            continue;
+        }
+        let name = format!("F{}", index);
+        let type0 = alloc();
+        write_font(&mut pdf, &mut alloc, type0, font);
+        font_resources.push((name.clone(), type0));
+        font_names[index] = Some(name);
+    }
+
+    // ── 图片 ──
+    let mut image_resources = Vec::new();
+    for (index, image) in document.images.iter().enumerate() {
+        let id = alloc();
+        match &image.data {
+            ImageData::Jpeg { bytes, components } => {
+                let mut xobject = pdf.image_xobject(id, bytes);
+                xobject.filter(Filter::DctDecode);
+                xobject.width(image.width as i32);
+                xobject.height(image.height as i32);
+                xobject.bits_per_component(8);
+                match components {
+                    1 => {
+                        xobject.color_space_name(Name(b"DeviceGray"));
+                    }
+                    4 => {
+                        // Photoshop 写出的 CMYK JPEG 是反相存储的
+                        xobject.color_space_name(Name(b"DeviceCMYK"));
+                        xobject.decode([1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0]);
+                    }
+                    _ => {
+                        xobject.color_space_name(Name(b"DeviceRGB"));
+                    }
+                }
+            }
+            ImageData::Pixels {
+                pixels,
+                gray,
+                alpha,
+            } => {
+                let mask = alpha.as_ref().map(|alpha| {
+                    let mask = alloc();
+                    let data = deflate(alpha);
+                    let mut xobject = pdf.image_xobject(mask, &data);
+                    xobject.filter(Filter::FlateDecode);
+                    xobject.width(image.width as i32);
+                    xobject.height(image.height as i32);
+                    xobject.color_space_name(Name(b"DeviceGray"));
+                    xobject.bits_per_component(8);
+                    mask
+                });
+                let data = deflate(pixels);
+                let mut xobject = pdf.image_xobject(id, &data);
+                xobject.filter(Filter::FlateDecode);
+                xobject.width(image.width as i32);
+                xobject.height(image.height as i32);
+                xobject.color_space_name(Name(if *gray { b"DeviceGray" } else { b"DeviceRGB" }));
+                xobject.bits_per_component(8);
+                if let Some(mask) = mask {
+                    xobject.s_mask(mask);
+                }
+            }
+        }
+        image_resources.push((format!("Im{}", index), id));
+    }
+
+    // ── 页面树：尺寸和资源写在根节点上，各页继承 ──
+    let (width, height) = page_size;
+    let mut pages = pdf.pages(tree);
+    pages
+        .kids(page_refs.iter().copied())
+        .count(page_refs.len() as i32)
+        .media_box(Rect::new(0.0, 0.0, width, height));
+    let mut resources = pages.resources();
+    let mut dict = resources.fonts();
+    for (name, id) in &font_resources {
+        dict.pair(Name(name.as_bytes()), *id);
+    }
+    dict.finish();
+    let mut dict = resources.x_objects();
+    for (name, id) in &image_resources {
+        dict.pair(Name(name.as_bytes()), *id);
+    }
+    dict.finish();
+    resources.finish();
+    pages.finish();
+
+    for (index, page) in document.pages.iter().enumerate() {
+        let content_id = alloc();
+        let content = deflate(&page_content(&page.items, &font_names));
+        pdf.stream(content_id, &content).filter(Filter::FlateDecode);
+
+        let mut writer = pdf.page(page_refs[index]);
+        writer.parent(tree).contents(content_id);
+        let mut annotations = writer.annotations();
+        for link in &page.links {
+            // 指向不存在的锚点的链接不生成热区
+            let destination = match &link.target {
+                LinkTarget::Anchor(anchor) => match document.anchors.get(anchor) {
+                    Some(position) => Some(*position),
+                    None => continue,
+                },
+                LinkTarget::Uri(_) => None,
+            };
+            let [x1, y1, x2, y2] = link.rect;
+            let mut annotation = annotations.push();
+            annotation
+                .subtype(AnnotationType::Link)
+                .rect(Rect::new(x1, y1, x2, y2))
+                .border(0.0, 0.0, 0.0, None);
+            let mut action = annotation.action();
+            match (&link.target, destination) {
+                (_, Some((page, y))) => {
+                    action
+                        .action_type(ActionType::GoTo)
+                        .destination()
+                        .page(page_refs[page])
+                        .xyz(0.0, y, None);
+                }
+                (LinkTarget::Uri(uri), None) => {
+                    action.action_type(ActionType::Uri).uri(Str(uri.as_bytes()));
+                }
+                (LinkTarget::Anchor(_), None) => {}
+            }
+        }
+        annotations.finish();
+    }
+
+    // ── 书签 ──
+    let outline_root = (!document.outline.is_empty()).then(|| {
+        let root = alloc();
+        write_outline(&mut pdf, &mut alloc, root, &document.outline, &page_refs);
+        root
+    });
+
+    let mut catalog_writer = pdf.catalog(catalog);
+    catalog_writer.pages(tree);
+    if let Some(root) = outline_root {
+        catalog_writer
+            .outlines(root)
+            .page_mode(PageMode::UseOutlines);
+    }
+    catalog_writer.finish();
+
+    pdf.document_info(info)
+        .title(TextStr(title))
+        .producer(TextStr("cc-md-preview"));
+
+    pdf.finish()
+}
+
+fn write_font(
+    pdf: &mut Pdf,
+    alloc: &mut impl FnMut() -> Ref,
+    type0: Ref,
+    font: &super::fonts::Font,
+) {
+    let cid = alloc();
+    let descriptor = alloc();
+    let file = alloc();
+    let cmap = alloc();
+
+    let glyphs: Vec<u16> = font.used.keys().copied().collect();
+    // 子集化失败（少见的字体结构）时退回嵌入整个字体
+    let program = subsetter::subset(font.data(), 0, subsetter::Profile::pdf(&glyphs))
+        .unwrap_or_else(|_| font.data().to_vec());
+    // 子集字体的名字要加 6 个大写字母的前缀，按字形集合生成
+    let base_font = format!("{}+{}", subset_tag(&glyphs), font.postscript_name);
+    let base_font = Name(base_font.as_bytes());
+
+    pdf.type0_font(type0)
+        .base_font(base_font)
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid)
+        .to_unicode(cmap);
+
+    let mut cid_font = pdf.cid_font(cid);
+    cid_font
+        .subtype(if font.cff {
+            CidFontType::Type0
+        } else {
+            CidFontType::Type2
+        })
+        .base_font(base_font)
+        .system_info(SYSTEM_INFO)
+        .font_descriptor(descriptor)
+        .default_width(0.0);
+    if !font.cff {
+        cid_font.cid_to_gid_map_predefined(Name(b"Identity"));
+    }
+    let mut widths = cid_font.widths();
+    for &id in &glyphs {
+        widths.consecutive(id, [font.width(id)]);
+    }
+    widths.finish();
+    cid_font.finish();
+
+    let mut flags = FontFlags::SYMBOLIC;
+    if font.monospace {
+        flags |= FontFlags::FIXED_PITCH;
+    }
+    if font.italic {
+        flags |= FontFlags::ITALIC;
+    }
+    let [x_min, y_min, x_max, y_max] = font.bbox.map(|v| font.to_pdf_units(v));
+    let mut writer = pdf.font_descriptor(descriptor);
+    writer
+        .name(base_font)
+        .flags(flags)
+        .bbox(Rect::new(x_min, y_min, x_max, y_max))
+        .italic_angle(font.italic_angle)
+        .ascent(font.to_pdf_units(font.ascender))
+        .descent(font.to_pdf_units(font.descender))
+        .cap_height(font.to_pdf_units(font.cap_height))
+        .stem_v(if font.bold { 120.0 } else { 80.0 });
+    if font.cff {
+        writer.font_file3(file);
+    } else {
+        writer.font_file2(file);
+    }
+    writer.finish();
+
+    let data = deflate(&program);
+    let mut stream = pdf.stream(file, &data);
+    stream.filter(Filter::FlateDecode);
+    if font.cff {
+        stream.pair(Name(b"Subtype"), Name(b"OpenType"));
+    }
+    stream.finish();
+
+    let mut unicode = UnicodeCmap::new(Name(b"Custom"), SYSTEM_INFO);
+    for (&id, text) in &font.used {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => unicode.pair(id, c),
+            (Some(_), Some(_)) => unicode.pair_with_multiple(id, text.chars()),
+            (None, _) => {}
+        }
+    }
+    let data = deflate(&unicode.finish());
+    pdf.cmap(cmap, &data).filter(Filter::FlateDecode);
+}
+
+/// 由字形集合生成稳定的 6 个大写字母
+fn subset_tag(glyphs: &[u16]) -> String {
+    let mut hash: u32 = 2166136261;
+    for id in glyphs {
+        for byte in id.to_be_bytes() {
+            hash = (hash ^ byte as u32).wrapping_mul(16777619);
+        }
+    }
+    (0..6)
+        .map(|i| (b'A' + ((hash >> (i * 5)) % 26) as u8) as char)
+        .collect()
+}
+
+fn page_content(items: &[Item], font_names: &[Option<String>]) -> Vec<u8> {
+    let mut content = Content::new();
+    for item in items {
+        match item {
+            Item::Text {
+                x,
+                y,
+                size,
+                font,
+                glyphs,
+                color,
+                bold,
+                italic,
+            } => {
+                let Some(name) = &font_names[*font] else {
+                    continue;
+                };
+                let [r, g, b] = *color;
+                let bytes: Vec<u8> = glyphs.iter().flat_map(|id| id.to_be_bytes()).collect();
+                content.begin_text();
+                content.set_font(Name(name.as_bytes()), *size);
+                content.set_fill_rgb(r, g, b);
+                if *bold {
+                    // 模拟粗体：填充的同时描一圈边
+                    content.set_text_rendering_mode(TextRenderingMode::FillStroke);
+                    content.set_stroke_rgb(r, g, b);
+                    content.set_line_width(size * 0.04);
+                } else {
+                    content.set_text_rendering_mode(TextRenderingMode::Fill);
+                }
+                let skew = if *italic { SKEW } else { 0.0 };
+                content.set_text_matrix([1.0, 0.0, skew, 1.0, *x, *y]);
+                content.show(Str(&bytes));
+                content.end_text();
+            }
+            Item::Rect { x, y, w, h, color } => {
+                let [r, g, b] = *color;
+                content.set_fill_rgb(r, g, b);
+                content.rect(*x, *y, *w, *h);
+                content.fill_nonzero();
+            }
+            Item::Frame { x, y, w, h, color } => {
+                let [r, g, b] = *color;
+                content.set_stroke_rgb(r, g, b);
+                content.set_line_width(0.5);
+                content.rect(*x, *y, *w, *h);
+                content.stroke();
+            }
+            Item::Line {
+                from,
+                to,
+                width,
+                color,
+            } => {
+                let [r, g, b] = *color;
+                content.set_stroke_rgb(r, g, b);
+                content.set_line_width(*width);
+                content.move_to(from[0], from[1]);
+                content.line_to(to[0], to[1]);
+                content.stroke();
+            }
+            Item::Image { index, x, y, w, h } => {
+                let name = format!("Im{}", index);
+                content.save_state();
+                content.transform([*w, 0.0, 0.0, *h, *x, *y]);
+                content.x_object(Name(name.as_bytes()));
+                content.restore_state();
+            }
+        }
+    }
+    content.finish()
+}
+
+/// 书签树：按标题级别确定父子关系（跳级的标题挂在最近的更高级标题下）
+fn write_outline(
+    pdf: &mut Pdf,
+    alloc: &mut impl FnMut() -> Ref,
+    root: Ref,
+    entries: &[OutlineEntry],
+    page_refs: &[Ref],
+) {
+    let ids: Vec<Ref> = entries.iter().map(|_| alloc()).collect();
+    let mut parent: Vec<Option<usize>> = vec![None; entries.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        while stack
+            .last()
+            .is_some_and(|&top| entries[top].level >= entry.level)
+        {
+            stack.pop();
+        }
+        parent[i] = stack.last().copied();
+        stack.push(i);
+    }
+
+    let children = |of: Option<usize>| -> Vec<usize> {
+        (0..entries.len()).filter(|&i| parent[i] == of).collect()
+    };
+    // 子孙总数（书签全部展开）
+    let mut descendants = vec![0i32; entries.len()];
+    for i in (0..entries.len()).rev() {
+        if let Some(p) = parent[i] {
+            descendants[p] += descendants[i] + 1;
+        }
+    }
+
+    let top = children(None);
+    let mut outline = pdf.outline(root);
+    if let (Some(first), Some(last)) = (top.first(), top.last()) {
+        outline.first(ids[*first]).last(ids[*last]);
+    }
+    outline.count(entries.len() as i32);
+    outline.finish();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let siblings = children(parent[i]);
+        let position = siblings.iter().position(|&s| s == i).unwrap_or(0);
+        let kids = children(Some(i));
+
+        let mut item = pdf.outline_item(ids[i]);
+        item.title(TextStr(&entry.title))
+            .parent(parent[i].map_or(root, |p| ids[p]));
+        if position > 0 {
+            item.prev(ids[siblings[position - 1]]);
+        }
+        if let Some(&next) = siblings.get(position + 1) {
+            item.next(ids[next]);
+        }
+        if let (Some(first), Some(last)) = (kids.first(), kids.last()) {
+            item.first(ids[*first]).last(ids[*last]);
+            item.count(descendants[i]);
+        }
+        item.dest()
+            .page(page_refs[entry.page])
+            .xyz(0.0, entry.y, None);
+    }
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+}
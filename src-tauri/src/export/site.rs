@@ -0,0 +1,690 @@
+// ─────────────────────────────────────────────
+// 静态站点导出：把整个文件夹渲染成可以放到共享盘上浏览的 HTML
+// ─────────────────────────────────────────────
+//
+// 输出目录和源文件夹的结构一致（docs/intro.md → docs/intro.html），另外生成：
+//   _site/style.css         主题 + 布局样式
+//   _site/search.js         搜索框脚本
+//   _site/search-index.js   搜索索引（window.SITE_INDEX = [...]，用 file:// 打开也能加载）
+//   _site/mermaid.min.js    有 mermaid 图、且这次构建带了运行时时才有
+//   index.html              根目录没有 index.md 时生成，跳转到 README 或第一篇文档
+//   .cc-md-site.json        增量导出用的清单
+// 两个源文件对应同一个页面（a.md 和 a.mdx）时直接报错，不让后写的覆盖先写的。
+//
+// 链接：指向文件夹内 Markdown 的改成对应的 .html；指向其他本地文件（图片、附件）的
+// 把文件复制到输出目录的相同位置。文件夹外的图片内嵌成 data: URI。
+//
+// 增量：清单记录每篇文档展开后（vault 嵌入、mdBook include 都已替换进来）内容的 SHA-1，
+// 以及主题和文件列表。主题和文件列表（决定导航栏）都没变时，只重新渲染展开结果变了的文件，
+// 被嵌入的文件改了也算；否则全部重新渲染。
+// 清单里的路径只接受输出目录内的相对路径，被改过的清单不能让导出删掉目录外的文件。
+
+use super::html::{
+    document_title, image_data_uri, render_body, same_document_anchor, MermaidMode,
+    ResourceResolver, MERMAID_RUNTIME,
+};
+use super::{escape_html, highlight, themes};
+use crate::commands::link_graph::resolve_target;
+use crate::commands::markdown::{collect_markdown_paths, load_document};
+use crate::markdown::headings::parse_headings;
+use crate::markdown::links::{
+    is_external, normalize_path, percent_decode, relative_link, split_fragment,
+};
+use crate::markdown::{parser_options, strip_front_matter};
+use crate::state::write_json_atomic;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = ".cc-md-site.json";
+/// 渲染结果的格式变了（比如代码高亮从内联样式改成 class）时加一，旧站点会整个重新渲染
+const MANIFEST_VERSION: u32 = 3;
+
+/// 站点公共文件所在的子目录
+const SITE_DIR: &str = "_site";
+
+/// 每篇文档写进搜索索引的正文上限（字符数），避免超长文档撑大索引
+const MAX_INDEXED_CHARS: usize = 20_000;
+
+const SITE_CSS: &str = "
+html, body { margin: 0; height: 100%; }
+body { display: flex; }
+.site-nav {
+  box-sizing: border-box;
+  width: 280px;
+  flex-shrink: 0;
+  height: 100vh;
+  overflow-y: auto;
+  padding: 16px;
+  border-right: 1px solid rgba(127, 127, 127, 0.25);
+  font-size: 14px;
+}
+.site-nav ul { list-style: none; margin: 0; padding-left: 14px; }
+.site-nav .site-tree > ul { padding-left: 0; }
+.site-nav li { margin: 2px 0; }
+.site-nav a.active { font-weight: 600; }
+.site-nav summary { cursor: pointer; }
+.site-search {
+  box-sizing: border-box;
+  width: 100%;
+  margin-bottom: 12px;
+  padding: 6px 8px;
+  font: inherit;
+  color: inherit;
+  background: transparent;
+  border: 1px solid rgba(127, 127, 127, 0.4);
+  border-radius: 6px;
+}
+.site-nav .site-results { margin: 0 0 12px; padding-left: 18px; list-style: decimal; }
+.site-results small { display: block; opacity: 0.7; }
+.site-main { flex: 1; min-width: 0; height: 100vh; overflow-y: auto; }
+.site-main .markdown-body {
+  box-sizing: border-box;
+  min-height: 100%;
+  padding: 32px max(32px, calc((100% - 960px) / 2));
+}
+.markdown-body pre.mermaid { background: transparent; text-align: center; }
+@media (max-width: 720px) {
+  body { display: block; }
+  .site-nav, .site-main { width: auto; height: auto; border-right: 0; }
+}
+";
+
+const SEARCH_JS: &str = r#"(function () {
+  var input = document.querySelector(".site-search");
+  var results = document.querySelector(".site-results");
+  if (!input || !results) return;
+  var root = document.body.getAttribute("data-root") || "";
+  var index = (window.SITE_INDEX || []).map(function (e) {
+    return {
+      url: e.url,
+      title: e.title,
+      title_l: e.title.toLowerCase(),
+      headings_l: e.headings.join("\n").toLowerCase(),
+      text: e.text,
+      text_l: e.text.toLowerCase(),
+    };
+  });
+
+  function escape(s) {
+    return s.replace(/[&<>"]/g, function (c) {
+      return { "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;" }[c];
+    });
+  }
+
+  // 每个词都要命中；标题命中权重最高，其次是小标题，最后是正文
+  function score(e, terms) {
+    var total = 0;
+    for (var i = 0; i < terms.length; i++) {
+      var s = 0;
+      if (e.title_l.indexOf(terms[i]) >= 0) s += 10;
+      if (e.headings_l.indexOf(terms[i]) >= 0) s += 5;
+      if (e.text_l.indexOf(terms[i]) >= 0) s += 1;
+      if (s === 0) return 0;
+      total += s;
+    }
+    return total;
+  }
+
+  function snippet(e, term) {
+    var at = e.text_l.indexOf(term);
+    if (at < 0) return "";
+    var start = Math.max(0, at - 40);
+    return (start > 0 ? "…" : "") + e.text.slice(start, at + term.length + 60) + "…";
+  }
+
+  input.addEventListener("input", function () {
+    var terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    if (terms.length === 0) {
+      results.hidden = true;
+      results.innerHTML = "";
+      return;
+    }
+    var hits = index
+      .map(function (e) { return { e: e, s: score(e, terms) }; })
+      .filter(function (h) { return h.s > 0; })
+      .sort(function (a, b) { return b.s - a.s; })
+      .slice(0, 20);
+    results.innerHTML = hits.length
+      ? hits.map(function (h) {
+          return '<li><a href="' + escape(root + h.e.url) + '">' + escape(h.e.title) +
+            "</a><small>" + escape(snippet(h.e, terms[0])) + "</small></li>";
+        }).join("")
+      : "<li>No results</li>";
+    results.hidden = false;
+  });
+})();
+"#;
+
+#[derive(Debug, Clone)]
+pub struct SiteOptions {
+    pub theme: String,
+}
+
+/// 导出结果（前端 / 命令行用来显示摘要）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteExport {
+    pub out_dir: String,
+    /// 站点里的页面总数
+    pub pages: usize,
+    /// 这次重新渲染的页面数（其余沿用上次的结果）
+    pub rendered: usize,
+    /// 源文件已删除、从输出目录里删掉的页面数
+    pub removed: usize,
+    /// 新复制或更新的资源文件数
+    pub assets_copied: usize,
+    /// 跳过的文件等非致命问题
+    pub warnings: Vec<String>,
+}
+
+/// 搜索索引里的一篇文档
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SearchEntry {
+    /// 相对站点根目录的页面地址
+    url: String,
+    title: String,
+    headings: Vec<String>,
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageRecord {
+    /// 展开后源文本的 SHA-1（十六进制）
+    digest: String,
+    search: SearchEntry,
+    /// 页面引用的本地资源（相对根目录）
+    assets: Vec<String>,
+    has_mermaid: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Manifest {
+    version: u32,
+    theme: String,
+    /// 键是源文件的相对路径（"/" 分隔）
+    pages: BTreeMap<String, PageRecord>,
+}
+
+/// 把 root 下的所有 Markdown 导出为静态站点
+pub fn export_site(
+    root: &Path,
+    out_dir: &Path,
+    options: &SiteOptions,
+) -> Result<SiteExport, String> {
+    let css = themes::require_theme_css(&options.theme)?;
+    if !root.is_dir() {
+        return Err(format!("'{}' is not a directory", root.display()));
+    }
+    let root = normalize_path(root);
+    let out_dir = normalize_path(out_dir);
+    if out_dir == root {
+        return Err("The output folder must be different from the source folder".to_string());
+    }
+    fs::create_dir_all(out_dir.join(SITE_DIR))
+        .map_err(|e| format!("Failed to create '{}': {}", out_dir.display(), e))?;
+
+    let mut sources: Vec<(String, PathBuf)> = collect_markdown_paths(&root)
+        .into_iter()
+        // 输出目录放在源文件夹里面时，不要把上次导出的结果当成源文件
+        .filter(|p| !p.starts_with(&out_dir))
+        .map(|p| (relative_key(&root, &p), p))
+        .collect();
+    sources.sort();
+    check_page_collisions(&sources)?;
+    let markdown: HashSet<PathBuf> = sources.iter().map(|(_, p)| p.clone()).collect();
+
+    let previous = read_manifest(&out_dir);
+    let same_layout = previous.version == MANIFEST_VERSION
+        && previous.theme == options.theme
+        && previous.pages.keys().eq(sources.iter().map(|(rel, _)| rel));
+    let nav = NavTree::build(sources.iter().map(|(rel, _)| rel.as_str()));
+    let mermaid = if MERMAID_RUNTIME.is_empty() {
+        MermaidMode::Code
+    } else {
+        MermaidMode::Embed
+    };
+
+    let mut result = SiteExport {
+        out_dir: out_dir.to_string_lossy().to_string(),
+        pages: sources.len(),
+        rendered: 0,
+        removed: 0,
+        assets_copied: 0,
+        warnings: Vec::new(),
+    };
+    let mut manifest = Manifest {
+        version: MANIFEST_VERSION,
+        theme: options.theme.clone(),
+        pages: BTreeMap::new(),
+    };
+
+    for (rel, path) in &sources {
+        // 展开比渲染便宜得多，每次都展开一遍，嵌入的文件变了也能发现
+        let source = match load_document(&path.to_string_lossy()) {
+            Ok(s) => s,
+            Err(e) => {
+                result.warnings.push(e);
+                continue;
+            }
+        };
+        let digest = sha1_smol::Sha1::from(&source).digest().to_string();
+        let output = out_dir.join(page_url(rel));
+        if let Some(old) = previous.pages.get(rel) {
+            if same_layout && old.digest == digest && output.exists() {
+                manifest.pages.insert(rel.clone(), old.clone());
+                continue;
+            }
+        }
+
+        let mut resolver = SiteResolver {
+            root: &root,
+            file: path,
+            markdown: &markdown,
+            assets: BTreeSet::new(),
+        };
+        let (body, has_mermaid) = render_body(&source, mermaid, &mut resolver);
+        let title = document_title(path, &source);
+        let page = render_site_page(rel, &title, &body, has_mermaid, &nav, options);
+        if let Some(parent) = output.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Err(e) = fs::write(&output, page) {
+            result
+                .warnings
+                .push(format!("Failed to write '{}': {}", output.display(), e));
+            continue;
+        }
+        result.rendered += 1;
+
+        manifest.pages.insert(
+            rel.clone(),
+            PageRecord {
+                digest,
+                search: search_entry(rel, &title, &source),
+                assets: resolver.assets.into_iter().collect(),
+                has_mermaid,
+            },
+        );
+    }
+
+    // 源文件已经不在了：删掉上次生成的页面
+    let current: HashSet<&String> = sources.iter().map(|(rel, _)| rel).collect();
+    for rel in previous.pages.keys() {
+        if !current.contains(rel) && fs::remove_file(out_dir.join(page_url(rel))).is_ok() {
+            result.removed += 1;
+        }
+    }
+
+    // 资源文件每次都检查一遍：页面没变但图片换了的情况也要更新
+    let assets: BTreeSet<&String> = manifest.pages.values().flat_map(|p| &p.assets).collect();
+    // 不再被任何页面引用的资源：删掉上次复制过去的文件
+    for asset in previous.pages.values().flat_map(|p| &p.assets) {
+        if !assets.contains(asset) {
+            let _ = fs::remove_file(out_dir.join(asset));
+        }
+    }
+    for asset in assets {
+        match copy_if_changed(&root.join(asset), &out_dir.join(asset)) {
+            Ok(true) => result.assets_copied += 1,
+            Ok(false) => {}
+            Err(e) => result.warnings.push(format!("{}: {}", asset, e)),
+        }
+    }
+
+    write_site_files(&out_dir, css, &manifest, mermaid)?;
+    write_index_redirect(&out_dir, &sources)?;
+    write_json_atomic(&out_dir.join(MANIFEST_FILE), &manifest)?;
+    Ok(result)
+}
+
+/// 站点内的链接改写规则
+struct SiteResolver<'a> {
+    root: &'a Path,
+    file: &'a Path,
+    /// 站点里所有 Markdown 的绝对路径
+    markdown: &'a HashSet<PathBuf>,
+    /// 页面引用的、需要复制到输出目录的本地文件（相对根目录）
+    assets: BTreeSet<String>,
+}
+
+impl SiteResolver<'_> {
+    fn base_dir(&self) -> &Path {
+        self.file.parent().unwrap_or(self.root)
+    }
+
+    /// 本地目标 → 站点内的相对地址；文件夹外或不存在的目标返回 None
+    fn local_target(&mut self, dest: &str) -> Option<String> {
+        if dest.starts_with('#') || is_external(dest) {
+            return None;
+        }
+        let (path, fragment) = split_fragment(dest);
+        if path.is_empty() {
+            return None;
+        }
+        let target = resolve_target(self.root, self.file, &percent_decode(path));
+        let href = if self.markdown.contains(&target) {
+            relative_link(self.base_dir(), &target.with_extension("html"))
+        } else if target.starts_with(self.root) && target.is_file() {
+            self.assets.insert(relative_key(self.root, &target));
+            relative_link(self.base_dir(), &target)
+        } else {
+            return None;
+        };
+        Some(match fragment {
+            Some(f) => format!("{}#{}", href, f),
+            None => href,
+        })
+    }
+}
+
+impl ResourceResolver for SiteResolver<'_> {
+    fn link(&mut self, dest: &str) -> Option<String> {
+        let self_path = self.file.to_path_buf();
+        same_document_anchor(dest, self.base_dir(), &self_path).or_else(|| self.local_target(dest))
+    }
+
+    fn image(&mut self, dest: &str) -> Option<String> {
+        let base_dir = self.base_dir().to_path_buf();
+        self.local_target(dest)
+            .or_else(|| image_data_uri(dest, &base_dir))
+    }
+}
+
+/// 相对根目录的路径，统一用 "/" 分隔（清单的键、资源路径都用这个格式）
+fn relative_key(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 源文件相对路径 → 页面相对路径（"docs/a.md" → "docs/a.html"）
+fn page_url(rel: &str) -> String {
+    match rel.rfind('.') {
+        Some(dot) if !rel[dot..].contains('/') => format!("{}.html", &rel[..dot]),
+        _ => format!("{}.html", rel),
+    }
+}
+
+/// 同名不同扩展名的源文件（a.md 和 a.markdown）会生成同一个页面，互相覆盖；
+/// 大小写不同的也算（macOS / Windows 上是同一个文件）
+fn check_page_collisions(sources: &[(String, PathBuf)]) -> Result<(), String> {
+    let mut pages: HashMap<String, &str> = HashMap::new();
+    for (rel, _) in sources {
+        let url = page_url(rel);
+        if let Some(other) = pages.insert(url.to_lowercase(), rel) {
+            return Err(format!(
+                "'{}' and '{}' would both be exported as '{}'; rename one of them",
+                other, rel, url
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 从页面回到站点根目录的前缀（"docs/a.md" → "../"）
+fn root_prefix(rel: &str) -> String {
+    "../".repeat(rel.matches('/').count())
+}
+
+fn render_site_page(
+    rel: &str,
+    title: &str,
+    body: &str,
+    has_mermaid: bool,
+    nav: &NavTree,
+    options: &SiteOptions,
+) -> String {
+    let prefix = root_prefix(rel);
+    let theme = escape_html(&options.theme);
+    let mut tree = String::new();
+    nav.render(rel, &prefix, &mut tree);
+
+    let mermaid = if has_mermaid {
+        format!(
+            "<script src=\"{}{}/mermaid.min.js\"></script>\n<script>mermaid.initialize({{ startOnLoad: true, theme: \"{}\", securityLevel: \"strict\" }});</script>\n",
+            prefix,
+            SITE_DIR,
+            if themes::is_dark(&options.theme) { "dark" } else { "default" }
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{title}</title>
+<link rel=\"stylesheet\" href=\"{prefix}{site}/style.css\">
+</head>
+<body data-root=\"{prefix}\">
+<nav class=\"site-nav markdown-body theme-{theme}\">
+<input type=\"search\" class=\"site-search\" placeholder=\"Search\" autocomplete=\"off\">
+<ol class=\"site-results\" hidden></ol>
+<div class=\"site-tree\">{tree}</div>
+</nav>
+<main class=\"site-main\">
+<article class=\"markdown-body theme-{theme}\">
+{body}</article>
+</main>
+<script src=\"{prefix}{site}/search-index.js\"></script>
+<script src=\"{prefix}{site}/search.js\"></script>
+{mermaid}</body>
+</html>
+",
+        title = escape_html(title),
+        prefix = prefix,
+        site = SITE_DIR,
+        theme = theme,
+        tree = tree,
+        body = body,
+        mermaid = mermaid,
+    )
+}
+
+/// 导航栏：按目录结构组织的文件树
+#[derive(Default)]
+struct NavTree {
+    dirs: BTreeMap<String, NavTree>,
+    /// (文件名, 相对根目录的路径)
+    files: Vec<(String, String)>,
+}
+
+impl NavTree {
+    fn build<'a>(rels: impl Iterator<Item = &'a str>) -> NavTree {
+        let mut tree = NavTree::default();
+        for rel in rels {
+            let mut node = &mut tree;
+            let mut parts: Vec<&str> = rel.split('/').collect();
+            let name = parts.pop().unwrap_or(rel);
+            for dir in parts {
+                node = node.dirs.entry(dir.to_string()).or_default();
+            }
+            node.files.push((name.to_string(), rel.to_string()));
+        }
+        tree
+    }
+
+    /// 渲染成嵌套列表；当前页面所在的目录默认展开并高亮当前页面
+    fn render(&self, current: &str, prefix: &str, out: &mut String) {
+        self.render_dir("", current, prefix, out);
+    }
+
+    fn render_dir(&self, path: &str, current: &str, prefix: &str, out: &mut String) {
+        out.push_str("<ul>");
+        for (name, child) in &self.dirs {
+            let child_path = format!("{}{}/", path, name);
+            let open = if current.starts_with(&child_path) {
+                " open"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<li><details{}><summary>{}</summary>",
+                open,
+                escape_html(name)
+            ));
+            child.render_dir(&child_path, current, prefix, out);
+            out.push_str("</details></li>");
+        }
+        for (name, rel) in &self.files {
+            let label = name
+                .rsplit_once('.')
+                .map_or(name.as_str(), |(stem, _)| stem);
+            let class = if rel == current {
+                " class=\"active\""
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<li><a href=\"{}{}\"{}>{}</a></li>",
+                prefix,
+                escape_html(&page_url(rel)),
+                class,
+                escape_html(label)
+            ));
+        }
+        out.push_str("</ul>");
+    }
+}
+
+/// 搜索索引条目：标题、各级小标题、去掉标记后的正文
+fn search_entry(rel: &str, title: &str, source: &str) -> SearchEntry {
+    let mut text = String::new();
+    let mut in_code_block = false;
+    for event in Parser::new_ext(strip_front_matter(source), parser_options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(t) | Event::Code(t) if !in_code_block => {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(t.trim());
+            }
+            _ => {}
+        }
+    }
+    SearchEntry {
+        url: page_url(rel),
+        title: title.to_string(),
+        headings: parse_headings(source).into_iter().map(|h| h.text).collect(),
+        text: text.chars().take(MAX_INDEXED_CHARS).collect(),
+    }
+}
+
+/// 写出 _site/ 下的公共文件
+fn write_site_files(
+    out_dir: &Path,
+    theme_css: &str,
+    manifest: &Manifest,
+    mermaid: MermaidMode,
+) -> Result<(), String> {
+    let site = out_dir.join(SITE_DIR);
+    let write = |name: &str, content: &str| {
+        fs::write(site.join(name), content)
+            .map_err(|e| format!("Failed to write '{}': {}", name, e))
+    };
+    write(
+        "style.css",
+        &format!(
+            "{}{}{}",
+            theme_css,
+            highlight::theme_css(&manifest.theme),
+            SITE_CSS
+        ),
+    )?;
+    write("search.js", SEARCH_JS)?;
+
+    let index: Vec<&SearchEntry> = manifest.pages.values().map(|p| &p.search).collect();
+    let json = serde_json::to_string(&index).map_err(|e| e.to_string())?;
+    write(
+        "search-index.js",
+        &format!("window.SITE_INDEX = {};\n", json),
+    )?;
+
+    if mermaid == MermaidMode::Embed && manifest.pages.values().any(|p| p.has_mermaid) {
+        write("mermaid.min.js", MERMAID_RUNTIME)?;
+    }
+    Ok(())
+}
+
+/// 根目录没有 index.md 时，生成跳转到 README（或第一篇文档）的 index.html
+fn write_index_redirect(out_dir: &Path, sources: &[(String, PathBuf)]) -> Result<(), String> {
+    let has_index = sources.iter().any(|(rel, _)| page_url(rel) == "index.html");
+    if has_index {
+        return Ok(());
+    }
+    let target = sources
+        .iter()
+        .find(|(rel, _)| page_url(rel).eq_ignore_ascii_case("readme.html"))
+        .or_else(|| sources.first())
+        .map(|(rel, _)| escape_html(&page_url(rel)));
+    let body = match target {
+        Some(url) => format!(
+            "<meta http-equiv=\"refresh\" content=\"0; url={0}\">\n<a href=\"{0}\">{0}</a>",
+            url
+        ),
+        None => "No Markdown files.".to_string(),
+    };
+    fs::write(
+        out_dir.join("index.html"),
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n</head>\n<body>\n{}\n</body>\n</html>\n",
+            body
+        ),
+    )
+    .map_err(|e| format!("Failed to write index.html: {}", e))
+}
+
+/// 目标不存在、大小或修改时间不同时复制；返回是否真的复制了
+fn copy_if_changed(source: &Path, target: &Path) -> std::io::Result<bool> {
+    let src = fs::metadata(source)?;
+    if let Ok(dst) = fs::metadata(target) {
+        let newer = match (src.modified(), dst.modified()) {
+            (Ok(s), Ok(d)) => s > d,
+            _ => true,
+        };
+        if dst.len() == src.len() && !newer {
+            return Ok(false);
+        }
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(source, target)?;
+    Ok(true)
+}
+
+/// 读取上次导出的清单；没有或损坏时当作第一次导出
+///
+/// 导出会按清单删除不再需要的页面和资源，所以这里丢掉不在输出目录内的路径
+/// （绝对路径、带 ".." 的），它们对应的文件不会被删
+fn read_manifest(out_dir: &Path) -> Manifest {
+    let mut manifest: Manifest = fs::read_to_string(out_dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    manifest.pages.retain(|rel, _| is_relative_key(rel));
+    for page in manifest.pages.values_mut() {
+        page.assets.retain(|asset| is_relative_key(asset));
+    }
+    manifest
+}
+
+/// 是不是 relative_key 生成的那种路径：非空，每一段都是普通的文件名
+fn is_relative_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .split(['/', '\\'])
+            .all(|part| !part.is_empty() && part != "." && part != ".." && !part.contains(':'))
+}
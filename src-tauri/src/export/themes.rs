@@ -0,0 +1,58 @@
+// ─────────────────────────────────────────────
+// 主题样式：直接内嵌前端的主题 CSS（src/styles/themes/*.css）
+// ─────────────────────────────────────────────
+//
+// 导出的文件和预览里看到的是同一份样式，改主题时不需要维护两份。
+
+/// 主题 id 和对应的 CSS，顺序与前端 constants/themes.ts 一致
+const THEMES: [(&str, &str); 10] = [
+    ("github", include_str!("../../../src/styles/themes/github.css")),
+    ("solarized", include_str!("../../../src/styles/themes/solarized.css")),
+    ("arctic", include_str!("../../../src/styles/themes/arctic.css")),
+    ("github-dark", include_str!("../../../src/styles/themes/github-dark.css")),
+    ("dracula", include_str!("../../../src/styles/themes/dracula.css")),
+    ("nord", include_str!("../../../src/styles/themes/nord.css")),
+    ("cobalt", include_str!("../../../src/styles/themes/cobalt.css")),
+    ("monokai", include_str!("../../../src/styles/themes/monokai.css")),
+    ("terminal", include_str!("../../../src/styles/themes/terminal.css")),
+    ("sunset", include_str!("../../../src/styles/themes/sunset.css")),
+];
+
+/// 暗色主题（代码高亮、mermaid 据此选配色）
+const DARK_THEMES: [&str; 7] = [
+    "github-dark",
+    "dracula",
+    "nord",
+    "cobalt",
+    "monokai",
+    "terminal",
+    "sunset",
+];
+
+pub const DEFAULT_THEME: &str = "github";
+
+/// 主题的 CSS；未知主题返回 None
+pub fn theme_css(theme: &str) -> Option<&'static str> {
+    THEMES.iter().find(|(id, _)| *id == theme).map(|(_, css)| *css)
+}
+
+/// 主题的 CSS；未知主题返回带可选值列表的错误信息
+pub fn require_theme_css(theme: &str) -> Result<&'static str, String> {
+    theme_css(theme).ok_or_else(|| {
+        let ids: Vec<&str> = theme_ids().collect();
+        format!(
+            "Unknown theme '{}' (expected one of: {})",
+            theme,
+            ids.join(", ")
+        )
+    })
+}
+
+pub fn is_dark(theme: &str) -> bool {
+    DARK_THEMES.contains(&theme)
+}
+
+/// 所有主题 id（用于报错时提示可选值）
+pub fn theme_ids() -> impl Iterator<Item = &'static str> {
+    THEMES.iter().map(|(id, _)| *id)
+}
@@ -0,0 +1,273 @@
+// ─────────────────────────────────────────────
+// 按文件统计提交历史
+// ─────────────────────────────────────────────
+//
+// 从 HEAD 出发按提交时间从新到旧遍历所有可达的提交，和父提交比较 tree，
+// 记下每个关注的文件「最近一次被改的提交」和「被改过的次数」。
+//
+// - 比较 tree 时只进入含关注文件的子目录，ID 相同的子树整个跳过，大仓库里也只读很少的对象
+// - 合并提交只有在和所有父提交都不同时才算改过这个文件（合并进来的改动已经记在分支上的提交里了）
+// - 结果按 (仓库, HEAD) 缓存：HEAD 不变时重复扫描同一个文件夹不用再走一遍历史
+
+use super::objects::{Commit, ObjectDb, TreeEntry};
+use super::{Oid, Repository};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// 最多遍历的提交数；超过后最近提交仍然准确，提交次数只统计到这里为止
+const MAX_COMMITS: usize = 50_000;
+
+/// 某次提交的摘要
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitSummary {
+    /// 完整的提交 ID（40 位十六进制）
+    pub id: String,
+    /// 作者时间，RFC 3339，保留作者的时区
+    pub date: String,
+    pub author: String,
+    pub email: String,
+    /// 提交说明的第一行
+    pub subject: String,
+}
+
+impl CommitSummary {
+    pub fn new(id: &Oid, commit: &Commit) -> CommitSummary {
+        CommitSummary {
+            id: id.to_hex(),
+            date: commit.author.date_rfc3339(),
+            author: commit.author.name.clone(),
+            email: commit.author.email.clone(),
+            subject: commit.subject().to_string(),
+        }
+    }
+}
+
+/// 一个文件的历史统计
+#[derive(Debug, Clone, Default)]
+pub struct PathHistory {
+    pub last_commit: Option<CommitSummary>,
+    pub commit_count: u32,
+}
+
+type HistoryMap = HashMap<String, PathHistory>;
+
+struct CachedHistory {
+    head: Oid,
+    /// 算过的路径（没有提交过的路径不在 history 里，但也算过了）
+    paths: HashSet<String>,
+    history: Arc<HistoryMap>,
+}
+
+/// 不是 Tauri 的托管状态：命令行的 list 子命令也走这里
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedHistory>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedHistory>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// paths（仓库内路径）的历史统计；没有提交过的路径不出现在结果里
+///
+/// 没有缓存时要遍历整个提交历史，大仓库里要几秒，不要在等着返回的命令里直接调用
+pub fn path_histories(repo: &Repository, paths: &[String]) -> Arc<HistoryMap> {
+    let Some(head) = repo.head() else {
+        return Arc::default();
+    };
+
+    // 缓存里的结果算过的路径是这次要的超集就直接用；否则连同缓存里的路径一起重算
+    let mut wanted: HashSet<String> = paths.iter().cloned().collect();
+    if let Ok(entries) = cache().lock() {
+        if let Some(cached) = entries.get(&repo.git_dir) {
+            if cached.head == head {
+                if paths.iter().all(|p| cached.paths.contains(p)) {
+                    return cached.history.clone();
+                }
+                wanted.extend(cached.paths.iter().cloned());
+            }
+        }
+    }
+
+    let history = Arc::new(walk(&repo.objects, head, &wanted));
+    if let Ok(mut entries) = cache().lock() {
+        entries.insert(
+            repo.git_dir.clone(),
+            CachedHistory {
+                head,
+                paths: wanted,
+                history: history.clone(),
+            },
+        );
+    }
+    history
+}
+
+/// 缓存里已经有的历史统计：HEAD 没变、而且 paths 全都算过时返回，否则 None（不遍历）
+pub fn cached_histories(repo: &Repository, paths: &[String]) -> Option<Arc<HistoryMap>> {
+    let Some(head) = repo.head() else {
+        return Some(Arc::default());
+    };
+    let entries = cache().lock().ok()?;
+    let cached = entries.get(&repo.git_dir)?;
+    (cached.head == head && paths.iter().all(|p| cached.paths.contains(p)))
+        .then(|| cached.history.clone())
+}
+
+/// 关注的路径集合，外加它们的所有上级目录（比较 tree 时只进这些目录）
+struct Wanted<'a> {
+    files: &'a HashSet<String>,
+    dirs: HashSet<String>,
+}
+
+impl<'a> Wanted<'a> {
+    fn new(files: &'a HashSet<String>) -> Wanted<'a> {
+        let mut dirs = HashSet::new();
+        for path in files {
+            let mut end = path.len();
+            while let Some(slash) = path[..end].rfind('/') {
+                if !dirs.insert(path[..slash].to_string()) {
+                    break;
+                }
+                end = slash;
+            }
+        }
+        Wanted { files, dirs }
+    }
+}
+
+fn walk(db: &ObjectDb, head: Oid, files: &HashSet<String>) -> HistoryMap {
+    // 每个关注的路径先放一个空记录：缓存判断「算过没有」要用
+    let mut result: HistoryMap = files
+        .iter()
+        .map(|p| (p.clone(), PathHistory::default()))
+        .collect();
+    let wanted = Wanted::new(files);
+
+    // 待处理的提交按提交时间排成大顶堆；pending 里放已经读出来的提交，避免重复解析
+    // 时间相同时先进队列的先出（和 git 一样），保证子提交排在父提交前面
+    let mut queue: BinaryHeap<(i64, Reverse<usize>, Oid)> = BinaryHeap::new();
+    let mut pending: HashMap<Oid, Commit> = HashMap::new();
+    let mut seen: HashSet<Oid> = HashSet::new();
+    let mut sequence = 0;
+    if let Some(commit) = db.commit(&head) {
+        queue.push((commit.committer.time, Reverse(sequence), head));
+        pending.insert(head, commit);
+        seen.insert(head);
+    }
+
+    let mut visited = 0;
+    while let Some((_, _, id)) = queue.pop() {
+        visited += 1;
+        if visited > MAX_COMMITS {
+            break;
+        }
+        let Some(commit) = pending.remove(&id) else {
+            continue;
+        };
+
+        let mut parent_trees = Vec::new();
+        for parent in &commit.parents {
+            if seen.insert(*parent) {
+                if let Some(parent_commit) = db.commit(parent) {
+                    sequence += 1;
+                    queue.push((parent_commit.committer.time, Reverse(sequence), *parent));
+                    pending.insert(*parent, parent_commit);
+                }
+            }
+            // 父提交可能已经处理过（多条路径汇合），这时再读一次
+            let tree = match pending.get(parent) {
+                Some(c) => Some(c.tree),
+                None => db.commit(parent).map(|c| c.tree),
+            };
+            parent_trees.push(tree);
+        }
+
+        for path in touched_paths(db, &commit, &parent_trees, &wanted) {
+            if let Some(entry) = result.get_mut(&path) {
+                if entry.last_commit.is_none() {
+                    entry.last_commit = Some(CommitSummary::new(&id, &commit));
+                }
+                entry.commit_count += 1;
+            }
+        }
+    }
+    result
+}
+
+/// 这次提交改动的关注路径：和每个父提交都不同的路径（根提交和空 tree 比）
+fn touched_paths(
+    db: &ObjectDb,
+    commit: &Commit,
+    parent_trees: &[Option<Oid>],
+    wanted: &Wanted,
+) -> Vec<String> {
+    if parent_trees.is_empty() {
+        let mut changed = Vec::new();
+        diff_trees(db, None, Some(commit.tree), "", wanted, &mut changed);
+        return changed;
+    }
+    let mut common: Option<HashSet<String>> = None;
+    for parent in parent_trees {
+        let mut changed = Vec::new();
+        diff_trees(db, *parent, Some(commit.tree), "", wanted, &mut changed);
+        let changed: HashSet<String> = changed.into_iter().collect();
+        common = Some(match common {
+            Some(previous) => previous.intersection(&changed).cloned().collect(),
+            None => changed,
+        });
+        if common.as_ref().is_some_and(|c| c.is_empty()) {
+            break;
+        }
+    }
+    common.unwrap_or_default().into_iter().collect()
+}
+
+/// 比较两棵 tree，把有变化的关注文件（新增、删除、内容或权限变化）追加到 out
+fn diff_trees(
+    db: &ObjectDb,
+    old: Option<Oid>,
+    new: Option<Oid>,
+    prefix: &str,
+    wanted: &Wanted,
+    out: &mut Vec<String>,
+) {
+    if old == new {
+        return;
+    }
+    let old_tree = old.and_then(|oid| db.tree(&oid)).unwrap_or_default();
+    let new_tree = new.and_then(|oid| db.tree(&oid)).unwrap_or_default();
+
+    let mut names: Vec<&str> = old_tree
+        .iter()
+        .chain(new_tree.iter())
+        .map(|e| e.name.as_str())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+
+    for name in names {
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let is_dir = wanted.dirs.contains(&path);
+        let is_file = wanted.files.contains(&path);
+        if !is_dir && !is_file {
+            continue;
+        }
+        let before = old_tree.iter().find(|e| e.name == name);
+        let after = new_tree.iter().find(|e| e.name == name);
+
+        if is_dir {
+            let subtree = |e: Option<&TreeEntry>| e.filter(|e| e.is_tree()).map(|e| e.oid);
+            diff_trees(db, subtree(before), subtree(after), &path, wanted, out);
+        }
+        if is_file {
+            let blob = |e: Option<&TreeEntry>| e.filter(|e| e.is_blob()).map(|e| (e.mode, e.oid));
+            if blob(before) != blob(after) {
+                out.push(path);
+            }
+        }
+    }
+}
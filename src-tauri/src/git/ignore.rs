@@ -0,0 +1,243 @@
+// ─────────────────────────────────────────────
+// .gitignore 规则
+// ─────────────────────────────────────────────
+//
+// 规则来源（优先级从低到高）：.git/info/exclude、根目录 .gitignore、子目录的 .gitignore。
+// 同一优先级里后面的规则覆盖前面的；目录被忽略后，里面的文件不能再用 "!" 取消忽略。
+// 只在判断「不在暂存区里的文件」时才用到，已跟踪的文件不受 .gitignore 影响。
+//
+// 不读全局的 core.excludesFile：那是个人配置，同一个仓库在不同机器上结果会不一样。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct Rule {
+    pattern: Vec<u8>,
+    negated: bool,
+    /// 以 "/" 结尾：只匹配目录
+    dir_only: bool,
+    /// 含 "/"：相对规则所在目录匹配整个路径，否则只匹配文件名
+    anchored: bool,
+    /// 规则所在目录（仓库内路径，根目录为空串）
+    base: String,
+}
+
+impl Rule {
+    fn parse(line: &str, base: &str) -> Option<Rule> {
+        // 行尾的空格不算（除非用 "\ " 转义）
+        let mut line = line.trim_end_matches(['\r', '\n']);
+        while line.ends_with(' ') && !line.ends_with("\\ ") {
+            line = &line[..line.len() - 1];
+        }
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let pattern = line.strip_prefix('/').unwrap_or(line);
+        if pattern.is_empty() {
+            return None;
+        }
+        Some(Rule {
+            pattern: pattern.as_bytes().to_vec(),
+            negated,
+            dir_only,
+            anchored,
+            base: base.to_string(),
+        })
+    }
+
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let rel = if self.base.is_empty() {
+            path
+        } else {
+            match path
+                .strip_prefix(self.base.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                Some(rest) => rest,
+                None => return false,
+            }
+        };
+        if self.anchored {
+            wildmatch(&self.pattern, rel.as_bytes())
+        } else {
+            let name = rel.rsplit('/').next().unwrap_or(rel);
+            wildmatch(&self.pattern, name.as_bytes())
+        }
+    }
+}
+
+pub struct Ignore {
+    work_tree: PathBuf,
+    exclude: Vec<Rule>,
+    /// 目录（仓库内路径）→ 该目录 .gitignore 里的规则，按需读取
+    per_dir: HashMap<String, Vec<Rule>>,
+}
+
+impl Ignore {
+    pub fn new(work_tree: &Path, common_dir: &Path) -> Ignore {
+        let exclude = fs::read_to_string(common_dir.join("info/exclude"))
+            .map(|text| text.lines().filter_map(|l| Rule::parse(l, "")).collect())
+            .unwrap_or_default();
+        Ignore {
+            work_tree: work_tree.to_path_buf(),
+            exclude,
+            per_dir: HashMap::new(),
+        }
+    }
+
+    /// 仓库内路径（文件）是否被忽略：任何一级父目录被忽略，文件也算被忽略
+    pub fn is_ignored(&mut self, path: &str) -> bool {
+        let parts: Vec<&str> = path.split('/').collect();
+        for depth in 1..=parts.len() {
+            let prefix = parts[..depth].join("/");
+            let is_dir = depth < parts.len();
+            if self.matches(&prefix, is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 只看直接作用于 path 的规则（不考虑父目录）
+    fn matches(&mut self, path: &str, is_dir: bool) -> bool {
+        let parent = path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        let mut dirs = vec![String::new()];
+        if !parent.is_empty() {
+            let parts: Vec<&str> = parent.split('/').collect();
+            for depth in 1..=parts.len() {
+                dirs.push(parts[..depth].join("/"));
+            }
+        }
+        for dir in &dirs {
+            self.load(dir);
+        }
+
+        // 从高优先级往低找，第一条匹配的规则说了算
+        for dir in dirs.iter().rev() {
+            if let Some(rule) = self.per_dir[dir]
+                .iter()
+                .rev()
+                .find(|rule| rule.matches(path, is_dir))
+            {
+                return !rule.negated;
+            }
+        }
+        self.exclude
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .is_some_and(|rule| !rule.negated)
+    }
+
+    fn load(&mut self, dir: &str) {
+        if self.per_dir.contains_key(dir) {
+            return;
+        }
+        let file = self.work_tree.join(dir).join(".gitignore");
+        let rules = fs::read_to_string(file)
+            .map(|text| text.lines().filter_map(|l| Rule::parse(l, dir)).collect())
+            .unwrap_or_default();
+        self.per_dir.insert(dir.to_string(), rules);
+    }
+}
+
+/// gitignore 风格的通配符匹配
+///
+/// `*` 和 `?` 不跨 "/"；`**` 作为完整的一段时可以跨目录（"**/a"、"a/**"、"a/**/b"）；
+/// 支持 `[a-z]` / `[!a-z]` 字符类和 `\` 转义。
+pub fn wildmatch(pattern: &[u8], text: &[u8]) -> bool {
+    let Some(&first) = pattern.first() else {
+        return text.is_empty();
+    };
+    match first {
+        b'*' if pattern.get(1) == Some(&b'*') => {
+            let rest = &pattern[2..];
+            match rest.strip_prefix(b"/") {
+                // "**/"：零个或多个目录
+                Some(after) => {
+                    wildmatch(after, text)
+                        || text
+                            .iter()
+                            .enumerate()
+                            .any(|(i, &c)| c == b'/' && wildmatch(after, &text[i + 1..]))
+                }
+                None => (0..=text.len()).any(|i| wildmatch(rest, &text[i..])),
+            }
+        }
+        b'*' => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if wildmatch(rest, &text[i..]) {
+                    return true;
+                }
+                if i < text.len() && text[i] == b'/' {
+                    break;
+                }
+            }
+            false
+        }
+        b'?' => match text.first() {
+            Some(&c) if c != b'/' => wildmatch(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        b'[' => {
+            let Some(&c) = text.first() else {
+                return false;
+            };
+            match match_class(&pattern[1..], c) {
+                Some((true, rest)) if c != b'/' => wildmatch(rest, &text[1..]),
+                Some(_) => false,
+                // 没有配对的 "]"：当成普通字符
+                None => c == b'[' && wildmatch(&pattern[1..], &text[1..]),
+            }
+        }
+        b'\\' if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && wildmatch(&pattern[2..], &text[1..])
+        }
+        _ => text.first() == Some(&first) && wildmatch(&pattern[1..], &text[1..]),
+    }
+}
+
+/// 字符类 "[...]"（pattern 从 "[" 后面开始）；返回 (是否匹配, "]" 之后的剩余模式)
+fn match_class(pattern: &[u8], c: u8) -> Option<(bool, &[u8])> {
+    let (negated, mut pos) = match pattern.first() {
+        Some(b'!') | Some(b'^') => (true, 1),
+        _ => (false, 0),
+    };
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let &ch = pattern.get(pos)?;
+        if ch == b']' && !first {
+            return Some((matched != negated, &pattern[pos + 1..]));
+        }
+        first = false;
+        let low = if ch == b'\\' {
+            pos += 1;
+            *pattern.get(pos)?
+        } else {
+            ch
+        };
+        if pattern.get(pos + 1) == Some(&b'-') && pattern.get(pos + 2).is_some_and(|&h| h != b']') {
+            let high = pattern[pos + 2];
+            matched |= (low..=high).contains(&c);
+            pos += 3;
+        } else {
+            matched |= low == c;
+            pos += 1;
+        }
+    }
+}
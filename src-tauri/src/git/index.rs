@@ -0,0 +1,268 @@
+// ─────────────────────────────────────────────
+// 暂存区：.git/index
+// ─────────────────────────────────────────────
+//
+// 格式：12 字节头（"DIRC" + 版本 + 项数），然后是按路径排序的项，最后是扩展和校验和（都用不上）。
+// 每项记录 stat 信息（用来快速判断工作区文件有没有改过）、blob ID、标志位和路径。
+// v2 / v3 的路径以 NUL 结尾并补齐到 8 字节；v4 的路径相对上一项做了前缀压缩，不补齐。
+
+use super::Oid;
+use std::path::Path;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    /// 仓库内路径，'/' 分隔
+    pub path: String,
+    pub oid: Oid,
+    pub mode: u32,
+    /// 文件大小（截断到 32 位）
+    pub size: u32,
+    /// 修改时间（秒, 纳秒）
+    pub mtime: (u32, u32),
+    /// 合并冲突时的阶段：0 正常，1~3 是冲突的三方
+    pub stage: u8,
+    /// assume-unchanged / skip-worktree：用户要求不检查工作区
+    pub assume_unchanged: bool,
+    /// `git add -N` 加进来的占位项，还没有真正的内容
+    pub intent_to_add: bool,
+}
+
+pub struct Index {
+    pub entries: Vec<IndexEntry>,
+    /// index 文件本身的修改时间，用来识别「写 index 的同一秒里又被改过」的文件
+    pub modified: Option<SystemTime>,
+}
+
+impl Index {
+    /// 读取 git_dir/index；不存在（还没 add 过任何文件）时返回空的暂存区
+    pub fn read(git_dir: &Path) -> Option<Index> {
+        let path = git_dir.join("index");
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(_) => {
+                return Some(Index {
+                    entries: Vec::new(),
+                    modified: None,
+                })
+            }
+        };
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        Some(Index {
+            entries: parse(&data)?,
+            modified,
+        })
+    }
+}
+
+fn parse(data: &[u8]) -> Option<Vec<IndexEntry>> {
+    let be32 = |at: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+    };
+    let be16 = |at: usize| -> Option<u16> {
+        Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+    };
+    if data.get(..4)? != b"DIRC" {
+        return None;
+    }
+    let version = be32(4)?;
+    if !(2..=4).contains(&version) {
+        return None;
+    }
+    let count = be32(8)? as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    let mut pos = 12;
+    let mut previous: Vec<u8> = Vec::new();
+    for _ in 0..count {
+        let start = pos;
+        let mtime = (be32(pos + 8)?, be32(pos + 12)?);
+        let mode = be32(pos + 24)?;
+        let size = be32(pos + 36)?;
+        let oid = Oid::from_bytes(data.get(pos + 40..pos + 60)?)?;
+        let flags = be16(pos + 60)?;
+        pos += 62;
+        let mut extended = 0;
+        if version >= 3 && flags & 0x4000 != 0 {
+            extended = be16(pos)?;
+            pos += 2;
+        }
+
+        let path = if version == 4 {
+            // 先去掉上一项路径末尾的 N 个字节，再接上这一项的后缀
+            let strip = read_offset_varint(data, &mut pos)?;
+            let nul = pos + data.get(pos..)?.iter().position(|&b| b == 0)?;
+            let keep = previous.len().checked_sub(strip)?;
+            let mut path = previous[..keep].to_vec();
+            path.extend_from_slice(&data[pos..nul]);
+            pos = nul + 1;
+            path
+        } else {
+            let nul = pos + data.get(pos..)?.iter().position(|&b| b == 0)?;
+            let path = data[pos..nul].to_vec();
+            // 补齐：整项长度是 8 的倍数，至少有一个 NUL
+            pos = start + (nul - start + 8) / 8 * 8;
+            path
+        };
+
+        entries.push(IndexEntry {
+            path: String::from_utf8_lossy(&path).to_string(),
+            oid,
+            mode,
+            size,
+            mtime,
+            stage: ((flags >> 12) & 0x3) as u8,
+            assume_unchanged: flags & 0x8000 != 0 || extended & 0x4000 != 0,
+            intent_to_add: extended & 0x2000 != 0,
+        });
+        previous = path;
+    }
+    Some(entries)
+}
+
+/// v4 用的变长整数（和 pack 里 OFS_DELTA 的编码相同）
+fn read_offset_varint(data: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut byte = *data.get(*pos)?;
+    *pos += 1;
+    let mut value = (byte & 0x7f) as usize;
+    while byte & 0x80 != 0 {
+        byte = *data.get(*pos)?;
+        *pos += 1;
+        value = ((value + 1) << 7) | (byte & 0x7f) as usize;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 一项的二进制：62 字节的 stat 信息 + 标志位，再加上 v3 的扩展标志（如果有）
+    fn entry_header(oid: u8, mode: u32, size: u32, flags: u16, extended: Option<u16>) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&[0; 8]); // ctime
+        out.extend_from_slice(&1_700_000_000u32.to_be_bytes()); // mtime 秒
+        out.extend_from_slice(&42u32.to_be_bytes()); // mtime 纳秒
+        out.extend_from_slice(&[0; 8]); // dev, ino
+        out.extend_from_slice(&mode.to_be_bytes());
+        out.extend_from_slice(&[0; 8]); // uid, gid
+        out.extend_from_slice(&size.to_be_bytes());
+        out.extend_from_slice(&[oid; 20]);
+        out.extend_from_slice(&flags.to_be_bytes());
+        if let Some(extended) = extended {
+            out.extend_from_slice(&extended.to_be_bytes());
+        }
+        out
+    }
+
+    fn index(version: u32, count: u32, body: &[u8]) -> Vec<u8> {
+        let mut out = b"DIRC".to_vec();
+        out.extend_from_slice(&version.to_be_bytes());
+        out.extend_from_slice(&count.to_be_bytes());
+        out.extend_from_slice(body);
+        // 校验和不检查
+        out.extend_from_slice(&[0; 20]);
+        out
+    }
+
+    /// v2 / v3 的路径：NUL 结尾，整项补齐到 8 字节
+    fn padded(mut entry: Vec<u8>, path: &str) -> Vec<u8> {
+        entry.extend_from_slice(path.as_bytes());
+        entry.push(0);
+        while entry.len() % 8 != 0 {
+            entry.push(0);
+        }
+        entry
+    }
+
+    #[test]
+    fn parses_v2_entries_with_padding() {
+        let mut body = padded(entry_header(1, 0o100644, 12, 9, None), "README.md");
+        // 冲突的第 2 阶段 + assume-unchanged
+        let flags = 0x8000 | (2 << 12) | 13;
+        body.extend(padded(
+            entry_header(2, 0o100755, 7, flags, None),
+            "docs/intro.md",
+        ));
+
+        let entries = parse(&index(2, 2, &body)).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "README.md");
+        assert_eq!(entries[0].oid, Oid([1; 20]));
+        assert_eq!(entries[0].mode, 0o100644);
+        assert_eq!(entries[0].size, 12);
+        assert_eq!(entries[0].mtime, (1_700_000_000, 42));
+        assert_eq!(entries[0].stage, 0);
+        assert!(!entries[0].assume_unchanged);
+        assert_eq!(entries[1].path, "docs/intro.md");
+        assert_eq!(entries[1].oid, Oid([2; 20]));
+        assert_eq!(entries[1].stage, 2);
+        assert!(entries[1].assume_unchanged);
+    }
+
+    #[test]
+    fn parses_v3_extended_flags() {
+        // 0x4000：有扩展标志；扩展里 0x2000 是 intent-to-add，0x4000 是 skip-worktree
+        let body = [
+            padded(
+                entry_header(1, 0o100644, 0, 0x4000 | 4, Some(0x2000)),
+                "a.md",
+            ),
+            padded(
+                entry_header(2, 0o100644, 0, 0x4000 | 4, Some(0x4000)),
+                "b.md",
+            ),
+        ]
+        .concat();
+        let entries = parse(&index(3, 2, &body)).unwrap();
+        assert_eq!(entries[0].path, "a.md");
+        assert!(entries[0].intent_to_add);
+        assert!(!entries[0].assume_unchanged);
+        assert_eq!(entries[1].path, "b.md");
+        assert!(entries[1].assume_unchanged);
+    }
+
+    #[test]
+    fn parses_v4_prefix_compressed_paths() {
+        let mut body = Vec::new();
+        // "docs/a.md"：上一项是空的，不去掉字节
+        body.extend(entry_header(1, 0o100644, 1, 9, None));
+        body.push(0);
+        body.extend_from_slice(b"docs/a.md\0");
+        // "docs/b.md"：去掉 "a.md" 四个字节，接上 "b.md"
+        body.extend(entry_header(2, 0o100644, 2, 9, None));
+        body.push(4);
+        body.extend_from_slice(b"b.md\0");
+        // "guide.md"：整个去掉
+        body.extend(entry_header(3, 0o100644, 3, 8, None));
+        body.push(9);
+        body.extend_from_slice(b"guide.md\0");
+
+        let entries = parse(&index(4, 3, &body)).unwrap();
+        let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["docs/a.md", "docs/b.md", "guide.md"]);
+        assert_eq!(entries[2].oid, Oid([3; 20]));
+        assert_eq!(entries[2].size, 3);
+    }
+
+    #[test]
+    fn v4_varint_matches_offset_encoding() {
+        // 两个字节：((0x01 + 1) << 7) | 0x05
+        let mut pos = 0;
+        assert_eq!(read_offset_varint(&[0x81, 0x05], &mut pos), Some(261));
+        assert_eq!(pos, 2);
+    }
+
+    #[test]
+    fn rejects_bad_headers_and_truncated_data() {
+        assert!(parse(b"DIRX\0\0\0\x02\0\0\0\0").is_none());
+        assert!(parse(&index(5, 0, &[])).is_none());
+        // 声明了一项但数据不够
+        assert!(parse(b"DIRC\0\0\0\x02\0\0\0\x01").is_none());
+        // v4 要去掉的字节比上一项路径还长
+        let mut body = entry_header(1, 0o100644, 1, 4, None);
+        body.push(3);
+        body.extend_from_slice(b"a.md\0");
+        assert!(parse(&index(4, 1, &body)).is_none());
+    }
+}
@@ -0,0 +1,239 @@
+// ─────────────────────────────────────────────
+// 单个文件的提交记录（跟随改名）
+// ─────────────────────────────────────────────
+//
+// 和 `git log --follow -- <文件>` 的思路相同：从 HEAD 按提交时间往回走，
+// 文件在某次提交里「凭空出现」时，到父提交里被删掉的文件中找内容最接近的一个，
+// 相似度够高就认为是改名，之后改为跟踪旧路径。
+//
+// 相似度按行计算：两边共有的行数 × 2 / 两边总行数，和 git 的默认阈值一样取 50%。
+
+use super::history::CommitSummary;
+use super::objects::{Commit, ObjectDb, TreeEntry};
+use super::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// 最多遍历的提交数，和 history.rs 保持一致
+const MAX_COMMITS: usize = 50_000;
+/// 判定为改名的最低相似度（百分比）
+const RENAME_THRESHOLD: usize = 50;
+/// 找改名来源时最多比较的候选文件数
+const MAX_RENAME_CANDIDATES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Renamed,
+    Deleted,
+}
+
+/// 文件历史里的一条记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRevision {
+    #[serde(flatten)]
+    pub commit: CommitSummary,
+    /// 这次提交里文件的仓库内路径（改名之前的提交里是旧路径）
+    pub path: String,
+    pub change: ChangeKind,
+    /// 改名时的原路径
+    pub previous_path: Option<String>,
+}
+
+/// path（仓库内路径）的提交记录，从新到旧，最多 limit 条
+pub fn file_log(repo: &Repository, path: &str, limit: usize) -> Vec<FileRevision> {
+    walk(repo, path, limit, None).0
+}
+
+/// path（当前路径）在某个提交里叫什么；该提交不在 HEAD 的历史里时返回 None
+///
+/// 按同样的顺序往回走，走到这个提交时正在跟踪的路径就是答案：比它新的改名都已经处理过了
+pub fn path_at(repo: &Repository, path: &str, commit: &Oid) -> Option<String> {
+    walk(repo, path, usize::MAX, Some(*commit)).1
+}
+
+/// 按提交时间从新到旧遍历；时间相同时先进队列的先出（和 git 一样），保证子提交排在父提交前面
+fn walk(
+    repo: &Repository,
+    path: &str,
+    limit: usize,
+    stop_at: Option<Oid>,
+) -> (Vec<FileRevision>, Option<String>) {
+    let db = &repo.objects;
+    let Some(head) = repo.head() else {
+        return (Vec::new(), None);
+    };
+
+    let mut queue: BinaryHeap<(i64, Reverse<usize>, Oid)> = BinaryHeap::new();
+    let mut pending: HashMap<Oid, Commit> = HashMap::new();
+    let mut seen: HashSet<Oid> = HashSet::new();
+    let mut sequence = 0;
+    if let Some(commit) = db.commit(&head) {
+        queue.push((commit.committer.time, Reverse(sequence), head));
+        pending.insert(head, commit);
+        seen.insert(head);
+    }
+
+    let mut tracked = path.to_string();
+    let mut revisions = Vec::new();
+    let mut visited = 0;
+    while let Some((_, _, id)) = queue.pop() {
+        visited += 1;
+        if visited > MAX_COMMITS || revisions.len() >= limit {
+            break;
+        }
+        if stop_at == Some(id) {
+            return (revisions, Some(tracked));
+        }
+        let Some(commit) = pending.remove(&id) else {
+            continue;
+        };
+
+        let mut parent_trees = Vec::new();
+        for parent in &commit.parents {
+            if seen.insert(*parent) {
+                if let Some(parent_commit) = db.commit(parent) {
+                    sequence += 1;
+                    queue.push((parent_commit.committer.time, Reverse(sequence), *parent));
+                    pending.insert(*parent, parent_commit);
+                }
+            }
+            let tree = match pending.get(parent) {
+                Some(c) => Some(c.tree),
+                None => db.commit(parent).map(|c| c.tree),
+            };
+            parent_trees.push(tree);
+        }
+
+        let blob = |tree: Option<Oid>| {
+            tree.and_then(|t| db.entry_at(&t, &tracked))
+                .filter(TreeEntry::is_blob)
+                .map(|e| (e.mode, e.oid))
+        };
+        let current = blob(Some(commit.tree));
+        let before: Vec<_> = parent_trees.iter().map(|t| blob(*t)).collect();
+        // 合并提交只有和所有父提交都不同时才算改动
+        if before.contains(&current) || (before.is_empty() && current.is_none()) {
+            continue;
+        }
+
+        let summary = CommitSummary::new(&id, &commit);
+        let revision = |change, previous_path| FileRevision {
+            commit: summary.clone(),
+            path: tracked.clone(),
+            change,
+            previous_path,
+        };
+        match current {
+            None => revisions.push(revision(ChangeKind::Deleted, None)),
+            Some(_) if before.iter().any(Option::is_some) => {
+                revisions.push(revision(ChangeKind::Modified, None))
+            }
+            Some((_, oid)) => {
+                let source = parent_trees
+                    .first()
+                    .copied()
+                    .flatten()
+                    .and_then(|parent| rename_source(db, parent, commit.tree, &oid));
+                match source {
+                    Some(old_path) => {
+                        revisions.push(revision(ChangeKind::Renamed, Some(old_path.clone())));
+                        tracked = old_path;
+                    }
+                    None => revisions.push(revision(ChangeKind::Added, None)),
+                }
+            }
+        }
+    }
+    (revisions, None)
+}
+
+/// 在 parent → tree 之间被删掉的文件里找 blob 的来源
+fn rename_source(db: &ObjectDb, parent: Oid, tree: Oid, blob: &Oid) -> Option<String> {
+    let mut deleted = Vec::new();
+    deleted_files(db, Some(parent), Some(tree), "", &mut deleted);
+    if let Some((path, _)) = deleted.iter().find(|(_, oid)| oid == blob) {
+        return Some(path.clone());
+    }
+
+    let content = db.blob(blob)?;
+    let mut best: Option<(usize, String)> = None;
+    for (path, oid) in deleted.into_iter().take(MAX_RENAME_CANDIDATES) {
+        let Some(candidate) = db.blob(&oid) else {
+            continue;
+        };
+        // 大小差一倍以上不可能达到阈值，省得逐行比较
+        let (small, large) = if candidate.len() < content.len() {
+            (candidate.len(), content.len())
+        } else {
+            (content.len(), candidate.len())
+        };
+        if small * 2 < large {
+            continue;
+        }
+        let score = similarity(&content, &candidate);
+        if score >= RENAME_THRESHOLD && best.as_ref().map_or(true, |(s, _)| score > *s) {
+            best = Some((score, path));
+        }
+    }
+    best.map(|(_, path)| path)
+}
+
+/// old 里有、new 里没有的文件（路径, blob ID）；只进 ID 不同的子树
+fn deleted_files(
+    db: &ObjectDb,
+    old: Option<Oid>,
+    new: Option<Oid>,
+    prefix: &str,
+    out: &mut Vec<(String, Oid)>,
+) {
+    if old == new {
+        return;
+    }
+    let Some(old_tree) = old.and_then(|oid| db.tree(&oid)) else {
+        return;
+    };
+    let new_tree = new.and_then(|oid| db.tree(&oid)).unwrap_or_default();
+    for entry in old_tree.iter() {
+        let path = if prefix.is_empty() {
+            entry.name.clone()
+        } else {
+            format!("{}/{}", prefix, entry.name)
+        };
+        let counterpart = new_tree.iter().find(|e| e.name == entry.name);
+        if entry.is_tree() {
+            let new_subtree = counterpart.filter(|e| e.is_tree()).map(|e| e.oid);
+            deleted_files(db, Some(entry.oid), new_subtree, &path, out);
+        } else if entry.is_blob() && !counterpart.is_some_and(TreeEntry::is_blob) {
+            out.push((path, entry.oid));
+        }
+    }
+}
+
+/// 按行的相似度（0~100）
+fn similarity(a: &[u8], b: &[u8]) -> usize {
+    let mut counts: HashMap<&[u8], isize> = HashMap::new();
+    let a_lines: Vec<&[u8]> = a.split(|&c| c == b'\n').collect();
+    let b_lines: Vec<&[u8]> = b.split(|&c| c == b'\n').collect();
+    for line in &a_lines {
+        *counts.entry(line).or_insert(0) += 1;
+    }
+    let mut common = 0;
+    for line in &b_lines {
+        if let Some(count) = counts.get_mut(line) {
+            if *count > 0 {
+                *count -= 1;
+                common += 1;
+            }
+        }
+    }
+    let total = a_lines.len() + b_lines.len();
+    if total == 0 {
+        return 100;
+    }
+    common * 2 * 100 / total
+}
@@ -0,0 +1,259 @@
+// ============================
+// Git 仓库只读访问（不依赖 git 命令）
+// ============================
+//
+// 直接读 .git 目录，只实现预览需要的那一小部分：
+//   - objects.rs：对象库（松散对象 + pack 文件 + delta）、commit / tree 解析
+//   - index.rs：暂存区（.git/index）
+//   - ignore.rs：.gitignore 规则
+//   - status.rs：工作区状态（修改 / 暂存 / 未跟踪 / 忽略）
+//   - history.rs：按文件统计提交历史
+//   - log.rs：单个文件的提交记录（跟随改名）
+//
+// 和 markdown/ 一样不依赖 Tauri。不支持 SHA-256 仓库，遇到时当作不是 git 仓库处理。
+
+pub mod history;
+pub mod ignore;
+pub mod index;
+pub mod log;
+pub mod objects;
+pub mod status;
+
+use objects::ObjectDb;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use history::CommitSummary;
+pub use status::{file_histories, file_infos, FileStatus, GitFileInfo, History};
+
+/// 对象 ID（SHA-1）
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Oid(pub [u8; 20]);
+
+impl Oid {
+    pub fn from_bytes(bytes: &[u8]) -> Option<Oid> {
+        Some(Oid(bytes.get(..20)?.try_into().ok()?))
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Oid> {
+        let hex = hex.trim().as_bytes();
+        if hex.len() != 40 {
+            return None;
+        }
+        let mut out = [0u8; 20];
+        for (i, pair) in hex.chunks(2).enumerate() {
+            let pair = std::str::from_utf8(pair).ok()?;
+            out[i] = u8::from_str_radix(pair, 16).ok()?;
+        }
+        Some(Oid(out))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// blob 对象的 ID：sha1("blob <长度>\0<内容>")
+    pub fn hash_blob(data: &[u8]) -> Oid {
+        let mut hasher = sha1_smol::Sha1::new();
+        hasher.update(format!("blob {}\0", data.len()).as_bytes());
+        hasher.update(data);
+        Oid(hasher.digest().bytes())
+    }
+}
+
+impl fmt::Debug for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// 一个打开的仓库
+pub struct Repository {
+    /// 工作区根目录（.git 所在的目录）
+    pub work_tree: PathBuf,
+    /// .git 目录；linked worktree 时是 .git/worktrees/<name>
+    pub git_dir: PathBuf,
+    /// refs、objects、info/exclude 所在的目录；普通仓库和 git_dir 相同
+    pub common_dir: PathBuf,
+    pub objects: ObjectDb,
+}
+
+impl Repository {
+    /// 从 path（文件或目录）往上找最近的 git 仓库
+    pub fn discover(path: &Path) -> Option<Repository> {
+        let start = if path.is_dir() { path } else { path.parent()? };
+        start
+            .ancestors()
+            .find_map(|dir| Repository::open(dir, &dir.join(".git")))
+    }
+
+    /// dot_git 可以是目录，也可以是 worktree / 子模块用的 "gitdir: <路径>" 文件
+    fn open(work_tree: &Path, dot_git: &Path) -> Option<Repository> {
+        let git_dir = if dot_git.is_dir() {
+            dot_git.to_path_buf()
+        } else if dot_git.is_file() {
+            let text = fs::read_to_string(dot_git).ok()?;
+            let target = text.trim().strip_prefix("gitdir:")?.trim();
+            work_tree.join(target)
+        } else {
+            return None;
+        };
+        if !git_dir.join("HEAD").is_file() {
+            return None;
+        }
+
+        // linked worktree：commondir 指向主仓库的 .git
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(text) => git_dir.join(text.trim()),
+            Err(_) => git_dir.clone(),
+        };
+        if is_sha256(&common_dir) {
+            return None;
+        }
+
+        Some(Repository {
+            work_tree: work_tree.to_path_buf(),
+            objects: ObjectDb::open(&common_dir.join("objects")),
+            git_dir,
+            common_dir,
+        })
+    }
+
+    /// HEAD 指向的提交；新仓库还没有提交时返回 None
+    pub fn head(&self) -> Option<Oid> {
+        self.resolve_ref("HEAD")
+    }
+
+    /// 解析引用名（"HEAD"、"refs/heads/main"），跟随符号引用
+    pub fn resolve_ref(&self, name: &str) -> Option<Oid> {
+        let mut name = name.to_string();
+        // 符号引用最多跟 5 层，防止成环
+        for _ in 0..5 {
+            let Some(text) = self.read_loose_ref(&name) else {
+                return self.packed_ref(&name);
+            };
+            match text.strip_prefix("ref:") {
+                Some(target) => name = target.trim().to_string(),
+                None => return Oid::from_hex(&text),
+            }
+        }
+        None
+    }
+
+    /// HEAD 等伪引用在 git_dir，refs/ 在 common_dir
+    fn read_loose_ref(&self, name: &str) -> Option<String> {
+        let dir = if name.starts_with("refs/") {
+            &self.common_dir
+        } else {
+            &self.git_dir
+        };
+        let text = fs::read_to_string(dir.join(name)).ok()?;
+        Some(text.trim().to_string())
+    }
+
+    /// packed-refs：每行 "<oid> <引用名>"，"^<oid>" 行是上一个标签剥开后的提交
+    fn packed_ref(&self, name: &str) -> Option<Oid> {
+        let text = fs::read_to_string(self.common_dir.join("packed-refs")).ok()?;
+        text.lines()
+            .filter(|line| !line.starts_with('#') && !line.starts_with('^'))
+            .find_map(|line| {
+                let (oid, ref_name) = line.split_once(' ')?;
+                if ref_name.trim() == name {
+                    Oid::from_hex(oid)
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// 解析修订号：完整 / 缩写的提交 ID、HEAD、分支名、标签名，后面可以跟 `~N`、`^`、`^N`
+    pub fn resolve_revision(&self, spec: &str) -> Result<Oid, String> {
+        let spec = spec.trim();
+        let split = spec.find(['~', '^']).unwrap_or(spec.len());
+        let (base, mut suffix) = spec.split_at(split);
+
+        let base = match base {
+            "" | "@" => "HEAD",
+            other => other,
+        };
+        // 和 git 的查找顺序一致：原名、refs/、标签、分支、远程分支
+        let mut oid = [
+            base.to_string(),
+            format!("refs/{}", base),
+            format!("refs/tags/{}", base),
+            format!("refs/heads/{}", base),
+            format!("refs/remotes/{}", base),
+            format!("refs/remotes/{}/HEAD", base),
+        ]
+        .iter()
+        .find_map(|name| self.resolve_ref(name))
+        .or_else(|| self.objects.find_prefix(base))
+        .and_then(|oid| self.objects.peel(&oid))
+        .ok_or_else(|| format!("Unknown revision '{}'", spec))?;
+
+        // ~N：沿第一个父提交往回 N 步；^N：第 N 个父提交（^ 等于 ^1）
+        while let Some(op) = suffix.chars().next() {
+            let digits: String = suffix[1..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            suffix = &suffix[1 + digits.len()..];
+            let n: usize = if digits.is_empty() {
+                1
+            } else {
+                digits
+                    .parse()
+                    .map_err(|_| format!("Invalid revision '{}'", spec))?
+            };
+            let (steps, parent) = match op {
+                '~' => (n, 0),
+                _ if n == 0 => continue,
+                _ => (1, n - 1),
+            };
+            for _ in 0..steps {
+                let commit = self
+                    .objects
+                    .commit(&oid)
+                    .ok_or_else(|| format!("Revision '{}' is not a commit", spec))?;
+                oid = *commit
+                    .parents
+                    .get(parent)
+                    .ok_or_else(|| format!("Revision '{}' goes past the first commit", spec))?;
+            }
+        }
+        Ok(oid)
+    }
+
+    /// 某个提交里某个路径的文件内容；路径不存在或不是普通文件时返回 None
+    pub fn file_at(&self, commit: &Oid, path: &str) -> Option<Vec<u8>> {
+        let tree = self.objects.commit(commit)?.tree;
+        let entry = self.objects.entry_at(&tree, path)?;
+        if !entry.is_blob() {
+            return None;
+        }
+        self.objects.blob(&entry.oid)
+    }
+
+    /// 绝对路径 → 仓库内路径（"docs/intro.md"，统一用 '/' 分隔）
+    pub fn relative_path(&self, path: &Path) -> Option<String> {
+        let rel = path.strip_prefix(&self.work_tree).ok()?;
+        let parts: Vec<String> = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        Some(parts.join("/"))
+    }
+}
+
+/// extensions.objectFormat = sha256 的仓库对象 ID 是 32 字节，这里不支持
+fn is_sha256(common_dir: &Path) -> bool {
+    fs::read_to_string(common_dir.join("config"))
+        .map(|config| {
+            config.lines().any(|line| {
+                let line = line.trim().to_ascii_lowercase();
+                line.starts_with("objectformat") && line.ends_with("sha256")
+            })
+        })
+        .unwrap_or(false)
+}
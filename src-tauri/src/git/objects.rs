@@ -0,0 +1,929 @@
+// ─────────────────────────────────────────────
+// 对象库：松散对象 + pack 文件
+// ─────────────────────────────────────────────
+//
+// 松散对象：objects/ab/cdef…，zlib 压缩的 "<类型> <长度>\0<内容>"。
+// pack 文件：objects/pack/*.pack + *.idx（只支持 v2 索引），对象可能是基于另一个对象的 delta。
+// objects/info/alternates 里列出的其他对象库也会查。
+//
+// 读历史时同一批 tree 会反复读取、delta 链上的基对象也会反复解压，
+// 两者各有一个按字节数封顶的缓存，满了整个清空（简单，命中率也够用）。
+
+use super::Oid;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// 缓存上限（字节），tree 缓存和 delta 基对象缓存各一份
+const CACHE_BYTES: usize = 32 * 1024 * 1024;
+/// delta 链的最大长度（git 默认打包深度是 50，激进打包可以更深）
+const MAX_DELTA_CHAIN: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Commit,
+    Tree,
+    Blob,
+    Tag,
+}
+
+impl Kind {
+    fn from_name(name: &[u8]) -> Option<Kind> {
+        match name {
+            b"commit" => Some(Kind::Commit),
+            b"tree" => Some(Kind::Tree),
+            b"blob" => Some(Kind::Blob),
+            b"tag" => Some(Kind::Tag),
+            _ => None,
+        }
+    }
+
+    /// pack 里的类型编号
+    fn from_pack_type(code: u8) -> Option<Kind> {
+        match code {
+            1 => Some(Kind::Commit),
+            2 => Some(Kind::Tree),
+            3 => Some(Kind::Blob),
+            4 => Some(Kind::Tag),
+            _ => None,
+        }
+    }
+}
+
+/// 提交作者 / 提交者
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub name: String,
+    pub email: String,
+    /// Unix 时间戳（秒）
+    pub time: i64,
+    /// 时区偏移（分钟，东八区是 480）
+    pub offset_minutes: i32,
+}
+
+impl Signature {
+    /// "Name <email> 1700000000 +0800"
+    fn parse(text: &str) -> Option<Signature> {
+        let (name, rest) = text.split_once('<')?;
+        let (email, rest) = rest.split_once('>')?;
+        let mut parts = rest.split_whitespace();
+        let time = parts.next()?.parse().ok()?;
+        let offset_minutes = parts.next().map(parse_offset).unwrap_or(0);
+        Some(Signature {
+            name: name.trim().to_string(),
+            email: email.trim().to_string(),
+            time,
+            offset_minutes,
+        })
+    }
+
+    /// RFC 3339 时间，保留提交时的时区
+    pub fn date_rfc3339(&self) -> String {
+        let offset = chrono::FixedOffset::east_opt(self.offset_minutes * 60)
+            .unwrap_or_else(|| chrono::FixedOffset::east_opt(0).unwrap());
+        chrono::DateTime::from_timestamp(self.time, 0)
+            .map(|t| t.with_timezone(&offset).to_rfc3339())
+            .unwrap_or_default()
+    }
+}
+
+/// "+0800" / "-0530" → 分钟
+fn parse_offset(text: &str) -> i32 {
+    let (sign, digits) = match text.as_bytes().first() {
+        Some(b'-') => (-1, &text[1..]),
+        Some(b'+') => (1, &text[1..]),
+        _ => (1, text),
+    };
+    let value: i32 = digits.parse().unwrap_or(0);
+    sign * (value / 100 * 60 + value % 100)
+}
+
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub tree: Oid,
+    pub parents: Vec<Oid>,
+    pub author: Signature,
+    pub committer: Signature,
+    pub message: String,
+}
+
+impl Commit {
+    fn parse(data: &[u8]) -> Option<Commit> {
+        let text = String::from_utf8_lossy(data);
+        let (headers, message) = text.split_once("\n\n").unwrap_or((&text, ""));
+        let mut tree = None;
+        let mut parents = Vec::new();
+        let mut author = None;
+        let mut committer = None;
+        for line in headers.lines() {
+            // 以空格开头的是上一个头的续行（gpgsig 等），跳过
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            match key {
+                "tree" => tree = Oid::from_hex(value),
+                "parent" => parents.extend(Oid::from_hex(value)),
+                "author" => author = Signature::parse(value),
+                "committer" => committer = Signature::parse(value),
+                _ => {}
+            }
+        }
+        let author = author?;
+        Some(Commit {
+            tree: tree?,
+            parents,
+            committer: committer.unwrap_or_else(|| author.clone()),
+            author,
+            message: message.to_string(),
+        })
+    }
+
+    /// 提交说明的第一行
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or("").trim()
+    }
+}
+
+/// tree 里的一项
+#[derive(Debug, Clone)]
+pub struct TreeEntry {
+    pub mode: u32,
+    pub name: String,
+    pub oid: Oid,
+}
+
+impl TreeEntry {
+    pub fn is_tree(&self) -> bool {
+        self.mode == 0o40000
+    }
+
+    /// 普通文件（含可执行文件和符号链接），不含子模块（0o160000）
+    pub fn is_blob(&self) -> bool {
+        self.mode & 0o170000 == 0o100000 || self.mode == 0o120000
+    }
+}
+
+/// "<八进制 mode> <名字>\0<20 字节 ID>" 重复
+fn parse_tree(data: &[u8]) -> Option<Vec<TreeEntry>> {
+    let mut entries = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        // 名字在 mode 之后：空格必须出现在 \0 之前，损坏的数据返回 None 而不是越界
+        let nul = rest.iter().position(|&b| b == 0)?;
+        let space = rest[..nul].iter().position(|&b| b == b' ')?;
+        let mode = u32::from_str_radix(std::str::from_utf8(&rest[..space]).ok()?, 8).ok()?;
+        let name = String::from_utf8_lossy(&rest[space + 1..nul]).to_string();
+        let oid = Oid::from_bytes(rest.get(nul + 1..nul + 21)?)?;
+        entries.push(TreeEntry { mode, name, oid });
+        rest = rest.get(nul + 21..)?;
+    }
+    Some(entries)
+}
+
+/// 对象类型 + 解压后的内容
+type RawObject = (Kind, Vec<u8>);
+
+/// 按字节数封顶的缓存，满了整个清空
+struct Cache<K, V> {
+    map: HashMap<K, (Arc<V>, usize)>,
+    bytes: usize,
+}
+
+impl<K: std::hash::Hash + Eq, V> Cache<K, V> {
+    fn new() -> Self {
+        Cache {
+            map: HashMap::new(),
+            bytes: 0,
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<Arc<V>> {
+        self.map.get(key).map(|(value, _)| value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: Arc<V>, size: usize) {
+        if self.bytes + size > CACHE_BYTES {
+            self.map.clear();
+            self.bytes = 0;
+        }
+        self.bytes += size;
+        self.map.insert(key, (value, size));
+    }
+}
+
+pub struct ObjectDb {
+    /// 松散对象目录：自己的 objects/ 和 alternates
+    dirs: Vec<PathBuf>,
+    packs: Vec<Pack>,
+    trees: Mutex<Cache<Oid, Vec<TreeEntry>>>,
+    /// (pack 序号, 偏移) → 解好的对象
+    bases: Mutex<Cache<(usize, u64), RawObject>>,
+}
+
+impl ObjectDb {
+    pub fn open(objects_dir: &Path) -> ObjectDb {
+        let mut dirs = vec![objects_dir.to_path_buf()];
+        // alternates：每行一个对象目录，相对路径以 objects/ 为基准
+        if let Ok(text) = fs::read_to_string(objects_dir.join("info/alternates")) {
+            for line in text.lines().map(str::trim) {
+                if !line.is_empty() && !line.starts_with('#') {
+                    dirs.push(objects_dir.join(line));
+                }
+            }
+        }
+        let packs = dirs
+            .iter()
+            .flat_map(|dir| fs::read_dir(dir.join("pack")).into_iter().flatten())
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "idx"))
+            .filter_map(|idx| Pack::open(&idx))
+            .collect();
+        ObjectDb {
+            dirs,
+            packs,
+            trees: Mutex::new(Cache::new()),
+            bases: Mutex::new(Cache::new()),
+        }
+    }
+
+    /// 读取对象，返回类型和解压后的内容
+    pub fn read(&self, oid: &Oid) -> Option<RawObject> {
+        // 历史上的对象基本都在 pack 里，先查 pack 省掉大量失败的文件打开
+        match self.locate(oid) {
+            Some((pack, offset)) => {
+                let object = self.read_packed(pack, offset)?;
+                Some((object.0, object.1.clone()))
+            }
+            None => self.read_loose(oid),
+        }
+    }
+
+    /// 对象在哪个 pack 的哪个偏移
+    fn locate(&self, oid: &Oid) -> Option<(usize, u64)> {
+        self.packs
+            .iter()
+            .enumerate()
+            .find_map(|(i, pack)| Some((i, pack.find(oid)?)))
+    }
+
+    /// 缩写的对象 ID（至少 4 位）→ 完整 ID；有歧义或找不到时返回 None
+    pub fn find_prefix(&self, prefix: &str) -> Option<Oid> {
+        let prefix = prefix.to_ascii_lowercase();
+        if prefix.len() < 4 || prefix.len() > 40 || !prefix.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        if prefix.len() == 40 {
+            return Oid::from_hex(&prefix);
+        }
+        let mut found: Vec<Oid> = Vec::new();
+        for pack in &self.packs {
+            found.extend(pack.find_prefix(&prefix));
+        }
+        for dir in &self.dirs {
+            let Ok(entries) = fs::read_dir(dir.join(&prefix[..2])) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with(&prefix[2..]) {
+                    found.extend(Oid::from_hex(&format!("{}{}", &prefix[..2], name)));
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        match found.as_slice() {
+            [only] => Some(*only),
+            _ => None,
+        }
+    }
+
+    /// 标签一路剥到它指向的对象（通常是提交）
+    pub fn peel(&self, oid: &Oid) -> Option<Oid> {
+        let mut current = *oid;
+        // 标签套标签很少见，限制层数防止成环
+        for _ in 0..10 {
+            let (kind, data) = self.read(&current)?;
+            if kind != Kind::Tag {
+                return Some(current);
+            }
+            let text = String::from_utf8_lossy(&data);
+            current = Oid::from_hex(text.lines().next()?.strip_prefix("object ")?)?;
+        }
+        None
+    }
+
+    pub fn commit(&self, oid: &Oid) -> Option<Commit> {
+        match self.read(oid)? {
+            (Kind::Commit, data) => Commit::parse(&data),
+            _ => None,
+        }
+    }
+
+    pub fn tree(&self, oid: &Oid) -> Option<Arc<Vec<TreeEntry>>> {
+        if let Some(tree) = self.trees.lock().ok()?.get(oid) {
+            return Some(tree);
+        }
+        let (kind, data) = self.read(oid)?;
+        if kind != Kind::Tree {
+            return None;
+        }
+        let tree = Arc::new(parse_tree(&data)?);
+        if let Ok(mut cache) = self.trees.lock() {
+            cache.insert(*oid, tree.clone(), data.len());
+        }
+        Some(tree)
+    }
+
+    pub fn blob(&self, oid: &Oid) -> Option<Vec<u8>> {
+        match self.read(oid)? {
+            (Kind::Blob, data) => Some(data),
+            _ => None,
+        }
+    }
+
+    /// tree 里某个路径（"docs/intro.md"）对应的项
+    pub fn entry_at(&self, tree: &Oid, path: &str) -> Option<TreeEntry> {
+        let mut current = *tree;
+        let mut parts = path.split('/').peekable();
+        while let Some(part) = parts.next() {
+            let entries = self.tree(&current)?;
+            let entry = entries.iter().find(|e| e.name == part)?;
+            if parts.peek().is_none() {
+                return Some(entry.clone());
+            }
+            if !entry.is_tree() {
+                return None;
+            }
+            current = entry.oid;
+        }
+        None
+    }
+
+    fn read_loose(&self, oid: &Oid) -> Option<RawObject> {
+        let hex = oid.to_hex();
+        let compressed = self
+            .dirs
+            .iter()
+            .find_map(|dir| fs::read(dir.join(&hex[..2]).join(&hex[2..])).ok())?;
+        let raw = decompress_to_vec_zlib(&compressed).ok()?;
+        let nul = raw.iter().position(|&b| b == 0)?;
+        let header = &raw[..nul];
+        let space = header.iter().position(|&b| b == b' ')?;
+        let kind = Kind::from_name(&header[..space])?;
+        Some((kind, raw[nul + 1..].to_vec()))
+    }
+
+    /// 读 pack 里的对象；delta 先沿链找到基对象，再依次应用
+    fn read_packed(&self, pack_index: usize, offset: u64) -> Option<Arc<RawObject>> {
+        let key = (pack_index, offset);
+        if let Some(object) = self.bases.lock().ok()?.get(&key) {
+            return Some(object);
+        }
+
+        let mut deltas: Vec<Vec<u8>> = Vec::new();
+        let mut current = (pack_index, offset);
+        let base = loop {
+            if deltas.len() > MAX_DELTA_CHAIN {
+                return None;
+            }
+            if !deltas.is_empty() {
+                if let Some(object) = self.bases.lock().ok()?.get(&current) {
+                    break object;
+                }
+            }
+            let pack = &self.packs[current.0];
+            match pack.entry(current.1)? {
+                PackEntry::Object(kind, data) => break Arc::new((kind, data)),
+                PackEntry::OfsDelta(base_offset, delta) => {
+                    deltas.push(delta);
+                    current = (current.0, base_offset);
+                }
+                PackEntry::RefDelta(base, delta) => {
+                    deltas.push(delta);
+                    // 基对象可能在另一个 pack 里，也可能是松散对象（thin pack 补全后）
+                    match self.locate(&base) {
+                        Some(location) => current = location,
+                        None => break Arc::new(self.read_loose(&base)?),
+                    }
+                }
+            }
+        };
+
+        let mut object = base;
+        for delta in deltas.iter().rev() {
+            let data = apply_delta(&object.1, delta)?;
+            object = Arc::new((object.0, data));
+        }
+        if let Ok(mut cache) = self.bases.lock() {
+            cache.insert(key, object.clone(), object.1.len());
+        }
+        Some(object)
+    }
+}
+
+// ─────────────────────────────────────────────
+// pack 文件
+// ─────────────────────────────────────────────
+
+enum PackEntry {
+    Object(Kind, Vec<u8>),
+    /// 基对象在同一个 pack 的某个偏移处
+    OfsDelta(u64, Vec<u8>),
+    /// 基对象按 ID 查找
+    RefDelta(Oid, Vec<u8>),
+}
+
+struct Pack {
+    file: Mutex<File>,
+    /// pack 文件长度（末尾 20 字节是校验和）
+    len: u64,
+    fanout: [u32; 256],
+    /// 所有对象 ID，按 ID 排序，每个 20 字节
+    names: Vec<u8>,
+    /// 和 names 一一对应的偏移
+    offsets: Vec<u64>,
+    /// 排好序的偏移，用来找一个对象的结束位置（下一个对象的开头）
+    sorted_offsets: Vec<u64>,
+}
+
+impl Pack {
+    /// 解析 v2 索引：魔数 "\377tOc" + 版本 2 + fanout[256] + ID + CRC + 偏移 + 大偏移
+    fn open(idx_path: &Path) -> Option<Pack> {
+        let idx = fs::read(idx_path).ok()?;
+        if idx.get(..8)? != b"\xfftOc\x00\x00\x00\x02" {
+            return None;
+        }
+        let be32 = |at: usize| -> Option<u32> {
+            Some(u32::from_be_bytes(idx.get(at..at + 4)?.try_into().ok()?))
+        };
+        let mut fanout = [0u32; 256];
+        for (i, slot) in fanout.iter_mut().enumerate() {
+            *slot = be32(8 + i * 4)?;
+        }
+        let count = fanout[255] as usize;
+        let names_at = 8 + 256 * 4;
+        let offsets_at = names_at + count * 20 + count * 4;
+        let large_at = offsets_at + count * 4;
+        let names = idx.get(names_at..names_at + count * 20)?.to_vec();
+        let mut offsets = Vec::with_capacity(count);
+        for i in 0..count {
+            let value = be32(offsets_at + i * 4)?;
+            if value & 0x8000_0000 == 0 {
+                offsets.push(value as u64);
+            } else {
+                let at = large_at + (value & 0x7fff_ffff) as usize * 8;
+                offsets.push(u64::from_be_bytes(idx.get(at..at + 8)?.try_into().ok()?));
+            }
+        }
+        let mut sorted_offsets = offsets.clone();
+        sorted_offsets.sort_unstable();
+
+        let file = File::open(idx_path.with_extension("pack")).ok()?;
+        let len = file.metadata().ok()?.len();
+        Some(Pack {
+            file: Mutex::new(file),
+            len,
+            fanout,
+            names,
+            offsets,
+            sorted_offsets,
+        })
+    }
+
+    fn find(&self, oid: &Oid) -> Option<u64> {
+        let first = oid.0[0] as usize;
+        let start = if first == 0 {
+            0
+        } else {
+            self.fanout[first - 1] as usize
+        };
+        let end = self.fanout[first] as usize;
+        let (mut lo, mut hi) = (start, end);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            match self.names[mid * 20..mid * 20 + 20].cmp(&oid.0[..]) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Some(self.offsets[mid]),
+            }
+        }
+        None
+    }
+
+    /// 以 prefix（小写十六进制）开头的所有对象
+    fn find_prefix(&self, prefix: &str) -> Vec<Oid> {
+        let Ok(first) = u8::from_str_radix(&prefix[..2], 16) else {
+            return Vec::new();
+        };
+        let first = first as usize;
+        let start = if first == 0 {
+            0
+        } else {
+            self.fanout[first - 1] as usize
+        };
+        let end = self.fanout[first] as usize;
+        (start..end)
+            .filter_map(|i| Oid::from_bytes(&self.names[i * 20..i * 20 + 20]))
+            .filter(|oid| oid.to_hex().starts_with(prefix))
+            .collect()
+    }
+
+    /// 读出 offset 处的一项：对象头 + zlib 数据
+    fn entry(&self, offset: u64) -> Option<PackEntry> {
+        let next = self.sorted_offsets.partition_point(|&o| o <= offset);
+        let end = self
+            .sorted_offsets
+            .get(next)
+            .copied()
+            .unwrap_or(self.len.saturating_sub(20));
+        let mut raw = vec![0u8; end.checked_sub(offset)? as usize];
+        {
+            let mut file = self.file.lock().ok()?;
+            file.seek(SeekFrom::Start(offset)).ok()?;
+            file.read_exact(&mut raw).ok()?;
+        }
+
+        // 类型在第一个字节的 4~6 位，长度是变长整数（这里用不上）
+        let mut pos = 0;
+        let mut byte = *raw.first()?;
+        let code = (byte >> 4) & 0x07;
+        while byte & 0x80 != 0 {
+            pos += 1;
+            byte = *raw.get(pos)?;
+        }
+        pos += 1;
+
+        match code {
+            6 => {
+                // 负偏移的编码：每多一个字节先加 1 再左移 7 位
+                let mut byte = *raw.get(pos)?;
+                let mut distance = (byte & 0x7f) as u64;
+                while byte & 0x80 != 0 {
+                    pos += 1;
+                    byte = *raw.get(pos)?;
+                    distance = distance.checked_add(1)?.checked_mul(128)? | (byte & 0x7f) as u64;
+                }
+                pos += 1;
+                let data = decompress_to_vec_zlib(raw.get(pos..)?).ok()?;
+                Some(PackEntry::OfsDelta(offset.checked_sub(distance)?, data))
+            }
+            7 => {
+                let base = Oid::from_bytes(raw.get(pos..pos + 20)?)?;
+                let data = decompress_to_vec_zlib(raw.get(pos + 20..)?).ok()?;
+                Some(PackEntry::RefDelta(base, data))
+            }
+            _ => {
+                let kind = Kind::from_pack_type(code)?;
+                let data = decompress_to_vec_zlib(raw.get(pos..)?).ok()?;
+                Some(PackEntry::Object(kind, data))
+            }
+        }
+    }
+}
+
+/// 变长整数：每字节低 7 位，小端；超出 usize 的（损坏的数据）返回 None
+fn read_varint(data: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut value = 0usize;
+    let mut shift = 0;
+    loop {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        if shift >= usize::BITS {
+            return None;
+        }
+        value |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+}
+
+/// delta 格式：基对象长度、结果长度，然后是一串「从基对象复制」或「插入新数据」的指令
+fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    let mut pos = 0;
+    let base_len = read_varint(delta, &mut pos)?;
+    let result_len = read_varint(delta, &mut pos)?;
+    if base_len != base.len() {
+        return None;
+    }
+    // 结果长度来自数据本身，不可信：预分配不超过指令能产生的上限（每字节指令最多复制 64 KiB）
+    let mut out = Vec::with_capacity(result_len.min(delta.len().saturating_mul(0x10000)));
+    while pos < delta.len() {
+        let op = delta[pos];
+        pos += 1;
+        if op & 0x80 != 0 {
+            // 复制：低 4 位标记偏移的哪几个字节存在，接下来 3 位标记长度
+            let mut offset = 0usize;
+            let mut size = 0usize;
+            for i in 0..4 {
+                if op & (1 << i) != 0 {
+                    offset |= (*delta.get(pos)? as usize) << (i * 8);
+                    pos += 1;
+                }
+            }
+            for i in 0..3 {
+                if op & (0x10 << i) != 0 {
+                    size |= (*delta.get(pos)? as usize) << (i * 8);
+                    pos += 1;
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            out.extend_from_slice(base.get(offset..offset.checked_add(size)?)?);
+        } else if op != 0 {
+            let size = op as usize;
+            out.extend_from_slice(delta.get(pos..pos + size)?);
+            pos += size;
+        } else {
+            return None;
+        }
+    }
+    if out.len() != result_len {
+        return None;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+
+    /// 测试用的临时 objects 目录，drop 时删掉
+    struct TempObjects(PathBuf);
+
+    impl TempObjects {
+        fn new(name: &str) -> TempObjects {
+            let dir = std::env::temp_dir().join(format!(
+                "cc-md-preview-objects-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("pack")).unwrap();
+            TempObjects(dir)
+        }
+    }
+
+    impl Drop for TempObjects {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn tree_entry(mode: &str, name: &str, oid: u8) -> Vec<u8> {
+        let mut out = format!("{} {}\0", mode, name).into_bytes();
+        out.extend_from_slice(&[oid; 20]);
+        out
+    }
+
+    #[test]
+    fn parses_tree_entries() {
+        let data = [
+            tree_entry("100644", "README.md", 1),
+            tree_entry("40000", "docs", 2),
+            tree_entry("160000", "vendor", 3),
+        ]
+        .concat();
+        let entries = parse_tree(&data).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "README.md");
+        assert_eq!(entries[0].oid, Oid([1; 20]));
+        assert!(entries[0].is_blob());
+        assert_eq!(entries[1].name, "docs");
+        assert!(entries[1].is_tree());
+        // 子模块既不是 tree 也不是 blob
+        assert!(!entries[2].is_tree() && !entries[2].is_blob());
+        assert_eq!(parse_tree(&[]).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn rejects_corrupt_trees() {
+        // \0 出现在空格之前
+        let mut data = b"100644\0 a.md".to_vec();
+        data.extend_from_slice(&[1; 20]);
+        assert!(parse_tree(&data).is_none());
+        // 没有空格
+        let mut data = b"100644a.md\0".to_vec();
+        data.extend_from_slice(&[1; 20]);
+        assert!(parse_tree(&data).is_none());
+        // ID 不足 20 字节
+        assert!(parse_tree(b"100644 a.md\0\x01\x02").is_none());
+        // mode 不是八进制
+        let mut data = b"10064x a.md\0".to_vec();
+        data.extend_from_slice(&[1; 20]);
+        assert!(parse_tree(&data).is_none());
+    }
+
+    /// 变长整数（read_varint 的逆操作）
+    fn varint(mut value: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    /// base 复制 [offset, offset + size)，再插入 insert
+    fn delta(base_len: usize, copy: (u8, u8), insert: &[u8]) -> Vec<u8> {
+        let result_len = copy.1 as usize + insert.len();
+        let mut out = varint(base_len);
+        out.extend(varint(result_len));
+        out.extend_from_slice(&[0x80 | 0x01 | 0x10, copy.0, copy.1]);
+        out.push(insert.len() as u8);
+        out.extend_from_slice(insert);
+        out
+    }
+
+    #[test]
+    fn applies_copy_and_insert_instructions() {
+        let base = b"hello world\n";
+        let delta = delta(base.len(), (0, 6), b"there\n");
+        assert_eq!(apply_delta(base, &delta).unwrap(), b"hello there\n");
+        // 基对象长度不符
+        assert!(apply_delta(b"hello", &delta).is_none());
+        // 复制超出基对象
+        let bad = [varint(base.len()), varint(20), vec![0x91, 4, 20]].concat();
+        assert!(apply_delta(base, &bad).is_none());
+        // 指令 0 是保留的
+        let reserved = [varint(base.len()), varint(0), vec![0]].concat();
+        assert!(apply_delta(base, &reserved).is_none());
+        // 结果长度大得离谱：不能按它预分配，指令对不上就返回 None
+        let huge = [varint(base.len()), varint(usize::MAX), vec![0x91, 0, 5]].concat();
+        assert!(apply_delta(base, &huge).is_none());
+    }
+
+    #[test]
+    fn rejects_overlong_varints() {
+        let mut pos = 0;
+        assert_eq!(read_varint(&varint(usize::MAX), &mut pos), Some(usize::MAX));
+        // 全是续位字节：移位超过 usize 的宽度
+        let mut pos = 0;
+        assert_eq!(read_varint(&[0xff; 20], &mut pos), None);
+    }
+
+    /// pack 里一项的对象头：类型 + 解压后的长度（变长整数，第一个字节只有 4 位）
+    fn pack_header(code: u8, size: usize) -> Vec<u8> {
+        let mut out = vec![(code << 4) | (size & 0x0f) as u8];
+        let mut rest = size >> 4;
+        while rest > 0 {
+            *out.last_mut().unwrap() |= 0x80;
+            out.push((rest & 0x7f) as u8);
+            rest >>= 7;
+        }
+        out
+    }
+
+    /// OFS_DELTA 的负偏移编码（每多一个字节先减 1）
+    fn offset_encoding(mut distance: u64) -> Vec<u8> {
+        let mut out = vec![(distance & 0x7f) as u8];
+        distance >>= 7;
+        while distance > 0 {
+            distance -= 1;
+            out.push(0x80 | (distance & 0x7f) as u8);
+            distance >>= 7;
+        }
+        out.reverse();
+        out
+    }
+
+    /// 写一个 pack + v2 索引：objects 是 (ID, 这一项的原始字节)，按写入顺序排列。
+    /// large 为 true 的项把偏移放进大偏移表
+    fn write_pack(dir: &Path, objects: &[(Oid, Vec<u8>, bool)]) {
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&(objects.len() as u32).to_be_bytes());
+        let mut located: Vec<(Oid, u64, bool)> = Vec::new();
+        for (oid, entry, large) in objects {
+            located.push((*oid, pack.len() as u64, *large));
+            pack.extend_from_slice(entry);
+        }
+        pack.extend_from_slice(&[0; 20]);
+        located.sort_by_key(|(oid, ..)| *oid);
+
+        let mut idx = b"\xfftOc\x00\x00\x00\x02".to_vec();
+        for first in 0..256usize {
+            let count = located
+                .iter()
+                .filter(|(oid, ..)| (oid.0[0] as usize) <= first)
+                .count();
+            idx.extend_from_slice(&(count as u32).to_be_bytes());
+        }
+        for (oid, ..) in &located {
+            idx.extend_from_slice(&oid.0);
+        }
+        idx.extend(std::iter::repeat(0).take(located.len() * 4)); // CRC
+        let mut large_table = Vec::new();
+        for (_, offset, large) in &located {
+            if *large {
+                let slot = (large_table.len() / 8) as u32;
+                idx.extend_from_slice(&(0x8000_0000 | slot).to_be_bytes());
+                large_table.extend_from_slice(&offset.to_be_bytes());
+            } else {
+                idx.extend_from_slice(&(*offset as u32).to_be_bytes());
+            }
+        }
+        idx.extend(large_table);
+        idx.extend_from_slice(&[0; 40]);
+
+        fs::write(dir.join("pack/pack-test.pack"), pack).unwrap();
+        fs::write(dir.join("pack/pack-test.idx"), idx).unwrap();
+    }
+
+    #[test]
+    fn reads_packed_objects_and_deltas() {
+        let objects = TempObjects::new("pack");
+        let base: &[u8] = b"hello world\n";
+        let base_oid = Oid::hash_blob(base);
+        let ofs_oid = Oid::hash_blob(b"hello there\n");
+        let ref_oid = Oid::hash_blob(b"hello world!\n");
+
+        // 基对象在偏移 12（紧跟 pack 头）
+        let mut base_entry = pack_header(3, base.len());
+        base_entry.extend(compress_to_vec_zlib(base, 6));
+
+        let ofs_delta = delta(base.len(), (0, 6), b"there\n");
+        let mut ofs_entry = pack_header(6, ofs_delta.len());
+        let ofs_at = 12 + base_entry.len() as u64;
+        ofs_entry.extend(offset_encoding(ofs_at - 12));
+        ofs_entry.extend(compress_to_vec_zlib(&ofs_delta, 6));
+
+        let ref_delta = delta(base.len(), (0, 11), b"!\n");
+        let mut ref_entry = pack_header(7, ref_delta.len());
+        ref_entry.extend_from_slice(&base_oid.0);
+        ref_entry.extend(compress_to_vec_zlib(&ref_delta, 6));
+
+        write_pack(
+            &objects.0,
+            &[
+                (base_oid, base_entry, false),
+                (ofs_oid, ofs_entry, true),
+                (ref_oid, ref_entry, false),
+            ],
+        );
+
+        let db = ObjectDb::open(&objects.0);
+        assert_eq!(db.read(&base_oid).unwrap(), (Kind::Blob, base.to_vec()));
+        assert_eq!(
+            db.read(&ofs_oid).unwrap(),
+            (Kind::Blob, b"hello there\n".to_vec())
+        );
+        assert_eq!(
+            db.read(&ref_oid).unwrap(),
+            (Kind::Blob, b"hello world!\n".to_vec())
+        );
+        // 第二次读走缓存，结果一样
+        assert_eq!(db.blob(&ofs_oid).unwrap(), b"hello there\n");
+        assert!(db.read(&Oid([0xee; 20])).is_none());
+
+        let hex = ofs_oid.to_hex();
+        assert_eq!(db.find_prefix(&hex[..8]), Some(ofs_oid));
+    }
+
+    #[test]
+    fn offset_encoding_round_trips() {
+        // 和 Pack::entry 里的解码方式一致
+        for distance in [1u64, 127, 128, 300, 16_511, 16_512, 1 << 30] {
+            let bytes = offset_encoding(distance);
+            let mut byte = bytes[0];
+            let mut value = (byte & 0x7f) as u64;
+            let mut pos = 0;
+            while byte & 0x80 != 0 {
+                pos += 1;
+                byte = bytes[pos];
+                value = ((value + 1) << 7) | (byte & 0x7f) as u64;
+            }
+            assert_eq!(value, distance);
+            assert_eq!(pos + 1, bytes.len());
+        }
+    }
+
+    #[test]
+    fn reads_loose_objects() {
+        let objects = TempObjects::new("loose");
+        let oid = Oid::hash_blob(b"# Title\n");
+        let hex = oid.to_hex();
+        fs::create_dir_all(objects.0.join(&hex[..2])).unwrap();
+        fs::write(
+            objects.0.join(&hex[..2]).join(&hex[2..]),
+            compress_to_vec_zlib(b"blob 8\0# Title\n", 6),
+        )
+        .unwrap();
+
+        let db = ObjectDb::open(&objects.0);
+        assert_eq!(db.blob(&oid).unwrap(), b"# Title\n");
+        assert!(db.commit(&oid).is_none());
+    }
+}
@@ -0,0 +1,245 @@
+// ─────────────────────────────────────────────
+// 工作区状态 + 文件列表用的 git 信息
+// ─────────────────────────────────────────────
+//
+// 和 `git status` 的判断方式相同：
+//   - 工作区 vs 暂存区：stat 信息（大小、修改时间）和 index 里记录的一致就认为没改；
+//     不一致（或者 index 写入的同一时刻文件还可能被改过）时才读文件算 blob ID
+//   - 暂存区 vs HEAD：比较 index 里的 blob ID 和 HEAD tree 里的
+//   - 不在暂存区的文件：按 .gitignore 分成「未跟踪」和「已忽略」
+//
+// 提交历史（最近一次提交、提交数）要遍历整个历史，和状态分开：
+// 界面的列表先带着状态返回（History::Cached），历史由 file_histories 在后台算好再补上。
+
+use super::history::{self, CommitSummary, PathHistory};
+use super::ignore::Ignore;
+use super::index::{Index, IndexEntry};
+use super::{Oid, Repository};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 工作区文件相对暂存区的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileStatus {
+    /// 和暂存区一致
+    Clean,
+    /// 工作区有没暂存的改动
+    Modified,
+    /// 不在暂存区，也没被忽略
+    Untracked,
+    /// 被 .gitignore / info/exclude 忽略
+    Ignored,
+    /// 有未解决的合并冲突
+    Conflicted,
+}
+
+/// 文件列表里每个文件附带的 git 信息
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileInfo {
+    pub status: FileStatus,
+    /// 暂存区和 HEAD 不同（含新 add 的文件），和 status 独立：可能既暂存了又继续改了
+    pub staged: bool,
+    /// 最近一次改动这个文件的提交；未跟踪或还没提交过时为 null
+    pub last_commit: Option<CommitSummary>,
+    /// 改动过这个文件的提交数
+    pub commit_count: u32,
+}
+
+/// file_infos 里的提交历史怎么取
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum History {
+    /// 当场遍历提交历史（命令行）
+    Walk,
+    /// 只用已经缓存的；没有缓存时 last_commit / commit_count 留空，调用方用 file_histories 补上
+    Cached,
+}
+
+/// 给 root 下的一批文件查 git 信息；root 不在 git 仓库里时返回空表
+///
+/// 返回的表以传入的绝对路径为键
+pub fn file_infos(
+    root: &Path,
+    files: &[PathBuf],
+    history: History,
+) -> HashMap<PathBuf, GitFileInfo> {
+    let Some(repo) = Repository::discover(root) else {
+        return HashMap::new();
+    };
+    let Some(index) = Index::read(&repo.git_dir) else {
+        return HashMap::new();
+    };
+    let mut status = StatusReader::new(&repo, &index);
+
+    let paths: Vec<(&PathBuf, String)> = files
+        .iter()
+        .filter_map(|file| Some((file, repo.relative_path(file)?)))
+        .collect();
+    let tracked: Vec<String> = paths
+        .iter()
+        .filter(|(_, rel)| status.entries.contains_key(rel.as_str()))
+        .map(|(_, rel)| rel.clone())
+        .collect();
+    let histories = match history {
+        History::Walk => history::path_histories(&repo, &tracked),
+        History::Cached => history::cached_histories(&repo, &tracked).unwrap_or_default(),
+    };
+
+    paths
+        .into_iter()
+        .map(|(file, rel)| {
+            let (file_status, staged) = status.status(&rel, file);
+            let history = histories.get(&rel).cloned().unwrap_or_default();
+            let info = GitFileInfo {
+                status: file_status,
+                staged,
+                last_commit: history.last_commit,
+                commit_count: history.commit_count,
+            };
+            (file.clone(), info)
+        })
+        .collect()
+}
+
+/// 一批文件的提交历史（会遍历提交历史，结果按 HEAD 缓存）；root 不在 git 仓库里时返回 None
+///
+/// 返回的表以传入的绝对路径为键，只含已跟踪的文件
+pub fn file_histories(root: &Path, files: &[PathBuf]) -> Option<HashMap<PathBuf, PathHistory>> {
+    let repo = Repository::discover(root)?;
+    let index = Index::read(&repo.git_dir)?;
+    let in_index: HashSet<&str> = index
+        .entries
+        .iter()
+        .filter(|e| e.stage == 0)
+        .map(|e| e.path.as_str())
+        .collect();
+    let tracked: Vec<(&PathBuf, String)> = files
+        .iter()
+        .filter_map(|file| Some((file, repo.relative_path(file)?)))
+        .filter(|(_, rel)| in_index.contains(rel.as_str()))
+        .collect();
+    let rels: Vec<String> = tracked.iter().map(|(_, rel)| rel.clone()).collect();
+    let histories = history::path_histories(&repo, &rels);
+    Some(
+        tracked
+            .into_iter()
+            .map(|(file, rel)| {
+                (
+                    file.clone(),
+                    histories.get(&rel).cloned().unwrap_or_default(),
+                )
+            })
+            .collect(),
+    )
+}
+
+struct StatusReader<'a> {
+    repo: &'a Repository,
+    /// 仓库内路径 → 阶段 0 的项；冲突的文件只有阶段 1~3，单独记在 conflicted 里
+    entries: HashMap<&'a str, &'a IndexEntry>,
+    conflicted: HashSet<&'a str>,
+    index_modified: Option<SystemTime>,
+    head_tree: Option<Oid>,
+    ignore: Ignore,
+}
+
+impl<'a> StatusReader<'a> {
+    fn new(repo: &'a Repository, index: &'a Index) -> StatusReader<'a> {
+        let mut entries = HashMap::new();
+        let mut conflicted = HashSet::new();
+        for entry in &index.entries {
+            if entry.stage == 0 {
+                entries.insert(entry.path.as_str(), entry);
+            } else {
+                conflicted.insert(entry.path.as_str());
+            }
+        }
+        StatusReader {
+            repo,
+            entries,
+            conflicted,
+            index_modified: index.modified,
+            head_tree: repo
+                .head()
+                .and_then(|head| repo.objects.commit(&head))
+                .map(|commit| commit.tree),
+            ignore: Ignore::new(&repo.work_tree, &repo.common_dir),
+        }
+    }
+
+    /// (工作区状态, 是否有暂存的改动)
+    fn status(&mut self, rel: &str, file: &Path) -> (FileStatus, bool) {
+        if self.conflicted.contains(rel) {
+            return (FileStatus::Conflicted, false);
+        }
+        let Some(entry) = self.entries.get(rel).copied() else {
+            let status = if self.ignore.is_ignored(rel) {
+                FileStatus::Ignored
+            } else {
+                FileStatus::Untracked
+            };
+            return (status, false);
+        };
+
+        let head_blob = self
+            .head_tree
+            .and_then(|tree| self.repo.objects.entry_at(&tree, rel))
+            .map(|e| (e.mode, e.oid));
+        let staged = !entry.intent_to_add && head_blob != Some((entry.mode, entry.oid));
+
+        let status = if entry.intent_to_add || self.worktree_changed(entry, file) {
+            FileStatus::Modified
+        } else {
+            FileStatus::Clean
+        };
+        (status, staged)
+    }
+
+    fn worktree_changed(&self, entry: &IndexEntry, file: &Path) -> bool {
+        if entry.assume_unchanged {
+            return false;
+        }
+        let Ok(metadata) = fs::metadata(file) else {
+            return true;
+        };
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| (d.as_secs() as u32, d.subsec_nanos()));
+
+        // 有的平台 / 文件系统不记录纳秒，index 里是 0 时只比较秒
+        let same_mtime = mtime.is_some_and(|(secs, nanos)| {
+            secs == entry.mtime.0 && (entry.mtime.1 == 0 || nanos == entry.mtime.1)
+        });
+        // 文件修改时间不早于 index 写入时间：同一时刻还可能被改过，stat 不可信
+        let racy = match (metadata.modified(), self.index_modified) {
+            (Ok(file_time), Some(index_time)) => file_time >= index_time,
+            _ => true,
+        };
+        if metadata.len() as u32 == entry.size && same_mtime && !racy {
+            return false;
+        }
+
+        let Ok(content) = fs::read(file) else {
+            return true;
+        };
+        if Oid::hash_blob(&content) == entry.oid {
+            return false;
+        }
+        // core.autocrlf 检出的文件工作区是 CRLF、仓库里是 LF
+        if content.contains(&b'\r') {
+            let normalized: Vec<u8> = content
+                .windows(2)
+                .filter(|w| w[..] != b"\r\n"[..])
+                .map(|w| w[0])
+                .chain(content.last().copied())
+                .collect();
+            return Oid::hash_blob(&normalized) != entry.oid;
+        }
+        true
+    }
+}
@@ -0,0 +1,297 @@
+// ============================
+// 无窗口子命令：给脚本 / CI 用
+// ============================
+//
+// 在 tauri::Builder 之前执行，不创建窗口、不连接显示服务器。
+// 复用 IPC 命令背后的同一套函数（scan_folder / cache_info / clean / export_*），
+// 输出和界面里看到的一致。
+//
+// 输出约定：
+//   - 结果写到 stdout；加 --json 时输出 JSON（字段名与前端收到的一样，camelCase）
+//   - 错误信息写到 stderr，格式 "cc-md-preview: <原因>"
+//
+// 退出码：
+//   0 — 成功
+//   1 — 失败（路径不存在、读取出错、清理时有文件删不掉……）
+//   2 — 参数错误（由 clap 在解析阶段直接退出）
+
+use crate::cli::{self, CacheCommand, Command, ExportCommand};
+use crate::commands::chrome_cache::{self, CacheInfo, CleanResult};
+use crate::commands::markdown::{self, MarkdownFileInfo};
+use crate::export::epub::{self, EpubOptions};
+use crate::export::html::{self, HtmlOptions};
+use crate::export::pdf::{self, FontPaths, PdfOptions};
+use crate::export::site::{self, SiteOptions};
+use crate::git::History;
+use crate::markdown::links::normalize_path;
+use crate::markdown::stats::{self, DocumentStats};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+
+/// 执行子命令，返回进程退出码
+pub fn run(command: Command) -> i32 {
+    let result = match command {
+        Command::List { dir, json, filter } => list(&dir, json, filter.as_deref()),
+        Command::Stats { file, json } => file_stats(&file, json),
+        Command::Cache(CacheCommand::Info { target, json }) => {
+            let info = chrome_cache::cache_info(&target.browser, &target.profile);
+            print_cache_info(&info, json)
+        }
+        Command::Cache(CacheCommand::Clean {
+            target,
+            categories,
+            all,
+            dry_run,
+            json,
+        }) => {
+            // 不会默认全部清理：--all 和 --category 必须给一个（clap 已经检查过）
+            let categories = if all {
+                chrome_cache::cache_info(&target.browser, &target.profile)
+                    .categories
+                    .into_iter()
+                    .map(|c| c.name)
+                    .collect()
+            } else {
+                categories
+            };
+            let result =
+                chrome_cache::clean(&categories, &target.browser, &target.profile, dry_run);
+            print_clean_result(&result, dry_run, json)
+        }
+        Command::Export(ExportCommand::Html {
+            file,
+            output,
+            theme,
+            mermaid,
+        }) => export_html(&file, output.as_deref(), HtmlOptions { theme, mermaid }),
+        Command::Export(ExportCommand::Pdf {
+            file,
+            output,
+            page_size,
+            margins,
+            header,
+            footer,
+            font,
+            mono_font,
+            cjk_font,
+            json,
+        }) => {
+            let fonts = FontPaths {
+                regular: font,
+                mono: mono_font,
+                cjk: cjk_font,
+            };
+            PdfOptions::parse(&page_size, &margins, header, Some(footer), fonts)
+                .and_then(|options| export_pdf(&file, output.as_deref(), &options, json))
+        }
+        Command::Export(ExportCommand::Site {
+            root,
+            output,
+            theme,
+            json,
+        }) => export_site(&root, &output, SiteOptions { theme }, json),
+        Command::Export(ExportCommand::Epub {
+            path,
+            output,
+            theme,
+            json,
+        }) => export_epub(&path, output.as_deref(), EpubOptions { theme }, json),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("cc-md-preview: {}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn list(dir: &Path, json: bool, filter: Option<&str>) -> Result<i32, String> {
+    if !dir.is_dir() {
+        return Err(format!("'{}' is not a directory", dir.display()));
+    }
+    let mut files: Vec<MarkdownFileInfo> = markdown::scan_folder(dir, History::Walk);
+    if let Some(filter) = filter {
+        files = markdown::filter_files(dir, files, filter)?;
+    }
+    if json {
+        print_json(&files)?;
+    } else {
+        // 每行一个文件：相对路径、大小、字数，用 Tab 分隔，方便 cut / awk 处理
+        for file in &files {
+            println!("{}\t{}\t{}", file.relative_path, file.size, file.word_count);
+        }
+    }
+    Ok(EXIT_OK)
+}
+
+/// stats --json 的输出：文件路径 + 统计字段
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileStats<'a> {
+    path: &'a str,
+    #[serde(flatten)]
+    stats: DocumentStats,
+}
+
+fn file_stats(file: &Path, json: bool) -> Result<i32, String> {
+    let source = fs::read_to_string(file)
+        .map_err(|e| format!("failed to read '{}': {}", file.display(), e))?;
+    let stats = stats::document_stats(&source);
+    if json {
+        let path = file.to_string_lossy();
+        print_json(&FileStats { path: &path, stats })?;
+    } else {
+        println!("lines\t{}", stats.lines);
+        println!("characters\t{}", stats.characters);
+        println!("words\t{}", stats.words);
+        println!("cjkCharacters\t{}", stats.cjk_characters);
+        println!("readingMinutes\t{}", stats.reading_minutes);
+        println!("headings\t{}", stats.headings);
+        println!("links\t{}", stats.links);
+        println!("images\t{}", stats.images);
+        println!("codeBlocks\t{}", stats.code_blocks);
+        println!("tables\t{}", stats.tables);
+        println!("tasks\t{}/{}", stats.tasks_done, stats.tasks);
+    }
+    Ok(EXIT_OK)
+}
+
+fn export_html(file: &Path, output: Option<&Path>, options: HtmlOptions) -> Result<i32, String> {
+    // 图片和链接按文档所在目录解析，需要绝对路径
+    let file = normalize_path(&cli::current_dir().join(file));
+    if !file.is_file() {
+        return Err(format!("'{}' is not a file", file.display()));
+    }
+    let output = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| html::default_output(&file));
+    html::export_html(&file, &output, &options)?;
+    // 只输出写出的文件路径，方便脚本接着处理
+    println!("{}", output.display());
+    Ok(EXIT_OK)
+}
+
+fn export_pdf(
+    file: &Path,
+    output: Option<&Path>,
+    options: &PdfOptions,
+    json: bool,
+) -> Result<i32, String> {
+    let file = normalize_path(&cli::current_dir().join(file));
+    if !file.is_file() {
+        return Err(format!("'{}' is not a file", file.display()));
+    }
+    let output = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| pdf::default_output(&file));
+    let result = pdf::export_pdf(&file, &output, options)?;
+    if json {
+        print_json(&result)?;
+    } else {
+        println!("{}", result.output);
+    }
+    for warning in &result.warnings {
+        eprintln!("cc-md-preview: {}", warning);
+    }
+    Ok(EXIT_OK)
+}
+
+fn export_site(
+    root: &Path,
+    output: &Path,
+    options: SiteOptions,
+    json: bool,
+) -> Result<i32, String> {
+    let cwd = cli::current_dir();
+    let result = site::export_site(&cwd.join(root), &cwd.join(output), &options)?;
+    if json {
+        print_json(&result)?;
+    } else {
+        println!(
+            "{} pages ({} rendered, {} removed), {} assets copied -> {}",
+            result.pages, result.rendered, result.removed, result.assets_copied, result.out_dir
+        );
+    }
+    for warning in &result.warnings {
+        eprintln!("cc-md-preview: {}", warning);
+    }
+    Ok(EXIT_OK)
+}
+
+fn export_epub(
+    path: &Path,
+    output: Option<&Path>,
+    options: EpubOptions,
+    json: bool,
+) -> Result<i32, String> {
+    let path = normalize_path(&cli::current_dir().join(path));
+    if !path.exists() {
+        return Err(format!("'{}' does not exist", path.display()));
+    }
+    let output = output
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| epub::default_output(&path));
+    let result = epub::export_epub(&path, &output, &options)?;
+    if json {
+        print_json(&result)?;
+    } else {
+        println!("{}", result.output);
+    }
+    for warning in &result.warnings {
+        eprintln!("cc-md-preview: {}", warning);
+    }
+    Ok(EXIT_OK)
+}
+
+fn print_cache_info(info: &CacheInfo, json: bool) -> Result<i32, String> {
+    if json {
+        print_json(info)?;
+    } else {
+        for category in &info.categories {
+            let size = if category.exists {
+                category.size.to_string()
+            } else {
+                "-".to_string()
+            };
+            println!(
+                "{}\t{}\t{}\t{}",
+                category.name, size, category.file_count, category.path
+            );
+        }
+        println!("Total\t{}\t{}", info.total_size, info.total_files);
+    }
+    Ok(EXIT_OK)
+}
+
+fn print_clean_result(result: &CleanResult, dry_run: bool, json: bool) -> Result<i32, String> {
+    if json {
+        print_json(result)?;
+    } else {
+        let verb = if dry_run { "would delete" } else { "deleted" };
+        println!(
+            "{} {} entries, {} bytes",
+            verb, result.deleted_files, result.freed_bytes
+        );
+    }
+    for error in &result.errors {
+        eprintln!("cc-md-preview: {}", error);
+    }
+    // 部分删除失败也算失败，脚本可以据此重试或报警
+    Ok(if result.errors.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_FAILURE
+    })
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", text);
+    Ok(())
+}
@@ -1,22 +1,155 @@
+pub mod cli;
 pub mod commands;
+pub mod docsite;
+pub mod export;
+pub mod git;
+pub mod headless;
+pub mod markdown;
+pub mod menu;
 pub mod platform;
+pub mod state;
+
+use clap::Parser;
+use platform::single_instance;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let mut args = cli::Cli::parse();
+
+    // 子命令：在终端里执行完就退出，不初始化 Tauri（不需要显示服务器）
+    if let Some(command) = args.command.take() {
+        platform::console::attach_parent_console();
+        std::process::exit(headless::run(command));
+    }
+    let standalone = args.standalone;
+
+    // "-"：先把 stdin 读完（管道那头的程序结束才会返回），转发给已运行的实例时一起带过去
+    let stdin = if args.reads_stdin() {
+        match std::io::read_to_string(std::io::stdin()) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                eprintln!("cc-md-preview: failed to read stdin: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // 单实例：已有实例在运行时，把参数和 cwd 交给它，自己直接退出；
+    // 否则在建窗口之前就占住 socket，同时启动的另一个进程会转发给我们
+    let mut listener = None;
+    if !standalone {
+        let forwarded = single_instance::Forwarded {
+            cwd: cli::current_dir(),
+            args: std::env::args_os()
+                .skip(1)
+                .map(|a| a.to_string_lossy().to_string())
+                .collect(),
+            stdin: stdin.clone(),
+        };
+        match single_instance::claim(&forwarded) {
+            single_instance::Claim::Forwarded => return,
+            single_instance::Claim::Primary(l) => listener = Some(l),
+            single_instance::Claim::Unavailable => {}
+        }
+    }
+
+    // 第一次启动总是用主窗口，--new-window 只在转发给已运行的实例时有意义
+    let mut launch = args.into_request(&cli::current_dir(), stdin);
+    launch.new_window = false;
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(commands::markdown::SectionCache::default())
+        .manage(cli::PendingLaunch::default())
+        .manage(state::collections::CollectionIndex::default())
+        .manage(state::workspace::WorkspaceIndex::default())
+        .setup(move |app| {
+            let settings = state::settings::SettingsStore::init(app.handle())?;
+            app.manage(settings);
+
+            let recent = state::recent::RecentStore::init(app.handle())?;
+            app.manage(recent);
+            let menu = menu::build(app.handle())?;
+            app.set_menu(menu)?;
+            state::recent::notify_changed(app.handle());
+
+            let session = state::session::SessionStore::init(app.handle())?;
+            app.manage(session);
+            state::session::spawn_autosave(app.handle());
+
+            cli::dispatch(app.handle(), launch)?;
+            if let Some(listener) = listener {
+                single_instance::listen(app.handle(), listener);
+            }
+            Ok(())
+        })
+        .on_menu_event(menu::handle_menu_event)
+        .on_window_event(|window, event| {
+            // 附加窗口关掉后不再保留会话，免得被之后复用同一 label 的新窗口恢复
+            if let tauri::WindowEvent::Destroyed = event {
+                if let Some(session) = window.try_state::<state::session::SessionStore>() {
+                    session.remove(window.label());
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::markdown::read_markdown_file,
             commands::markdown::open_markdown_dialog,
             commands::markdown::open_folder_dialog,
             commands::markdown::list_markdown_files,
+            commands::markdown::read_markdown_section,
+            commands::markdown::diff_markdown,
+            commands::markdown::get_markdown_tree,
+            commands::link_graph::build_link_graph,
+            commands::docsite::get_doc_site,
+            commands::tasks::collect_tasks,
+            commands::facets::get_facets,
+            commands::collections::open_collections,
+            commands::collections::get_collection_files,
+            commands::collections::save_collection,
+            commands::collections::delete_collection,
+            commands::workspace::quick_open,
+            commands::workspace::search_headings,
+            commands::git::get_file_history,
+            commands::git::read_file_at_revision,
+            commands::git::diff_file_revisions,
+            commands::settings::get_settings,
+            commands::settings::update_settings,
+            commands::recent::get_recent,
+            commands::recent::pin_recent,
+            commands::recent::remove_recent,
+            commands::recent::clear_recent,
+            commands::session::save_session,
+            commands::session::restore_session,
+            commands::cli::take_launch_request,
+            commands::export::export_html,
+            commands::export::export_pdf,
+            commands::export::export_site,
+            commands::export::export_epub,
+            commands::highlight::highlight_code_blocks,
+            commands::highlight::get_code_highlight_css,
             commands::chrome_cache::detect_browsers,
             commands::chrome_cache::get_cache_info,
             commands::chrome_cache::list_cache_entries,
             commands::chrome_cache::clean_cache,
             commands::chrome_cache::get_chrome_profiles,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // 正常退出前把还没落盘的会话和最近打开写进去，并释放单实例 socket
+            if let tauri::RunEvent::Exit = event {
+                if let Some(session) = app.try_state::<state::session::SessionStore>() {
+                    let _ = session.flush();
+                }
+                if let Some(recent) = app.try_state::<state::recent::RecentStore>() {
+                    let _ = recent.flush();
+                }
+                single_instance::cleanup();
+            }
+        });
 }
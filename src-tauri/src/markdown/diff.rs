@@ -0,0 +1,885 @@
+// ─────────────────────────────────────────────
+// 块级 Markdown diff：先按标题对齐章节，再比较章节里的块
+// ─────────────────────────────────────────────
+//
+// 按行 diff 对文档来说太碎：一段话重新折行，整段每一行都算改了；
+// 在文档中间插一节，后面的段落也可能被错位地配对到别的章节里。这里换成两层对齐：
+//   1. 章节：每个标题开始一节（第一个标题之前的内容算第 0 节）。标题按 (级别, 文本) 求最长公共子序列，
+//      剩下没配上的标题在同一个空隙里按顺序、同级别配对 —— 通常是标题改了名
+//   2. 块：配上的两节里，段落、列表、代码块、表格等顶层块按规范化后的文本再求一次最长公共子序列
+//
+// 规范化：空白折叠成一个空格（重新折行、表格对齐不算改动）；代码块只去掉行尾空白。
+// 相邻的删除 / 新增块合并成一个 hunk，两边都有内容的算「修改」。hunk 不会跨章节。
+//
+// 之后再做两件事：
+//   - 移动：一边删除、另一边新增的块文本完全相同，合成一个「移动」hunk，挂在新位置上
+//   - 词级 diff：「修改」hunk 里（代码块除外）再按词比较，中日韩文字没有空格分词，逐字比较
+use super::headings::{self, Heading};
+use super::stats::is_cjk;
+use super::{parser_options, LineIndex};
+use pulldown_cmark::{Event, Parser, Tag};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// 词级 diff 的规模上限（两边词数之积），超过时不做词级 diff
+const MAX_WORD_DIFF_CELLS: usize = 4_000_000;
+/// 章节 / 块对齐的动态规划表上限（去掉公共前后缀后两边个数之积，约 16 MB），
+/// 超过时中间部分不再求 LCS，整段算删除 + 新增（移动检测照常）
+const MAX_ALIGN_CELLS: usize = 4_000_000;
+
+/// 顶层块的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockKind {
+    FrontMatter,
+    Heading,
+    Paragraph,
+    List,
+    Code,
+    Table,
+    Quote,
+    Html,
+    Rule,
+    Footnote,
+    Other,
+}
+
+/// 文档里的一个顶层块
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub kind: BlockKind,
+    /// 源文本里的字节范围
+    pub range: Range<usize>,
+    /// 规范化后的文本，用来判断两个块是否相同
+    pub key: String,
+}
+
+/// 切出文档的全部顶层块（按出现顺序）
+pub fn parse_blocks(source: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut current: Option<(BlockKind, usize)> = None;
+
+    for (event, range) in Parser::new_ext(source, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(tag) => {
+                if depth == 0 {
+                    current = Some((block_kind(&tag), range.start));
+                }
+                depth += 1;
+            }
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    if let Some((kind, start)) = current.take() {
+                        // 范围取「块开始」到「结束事件的终点」，去掉末尾换行
+                        let end = range.end.max(start);
+                        blocks.push(make_block(source, kind, start..end));
+                    }
+                }
+            }
+            Event::Rule if depth == 0 => {
+                blocks.push(make_block(source, BlockKind::Rule, range));
+            }
+            Event::Html(_) if depth == 0 => {
+                blocks.push(make_block(source, BlockKind::Html, range));
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+fn block_kind(tag: &Tag) -> BlockKind {
+    match tag {
+        Tag::MetadataBlock(_) => BlockKind::FrontMatter,
+        Tag::Heading { .. } => BlockKind::Heading,
+        Tag::Paragraph => BlockKind::Paragraph,
+        Tag::List(_) => BlockKind::List,
+        Tag::CodeBlock(_) => BlockKind::Code,
+        Tag::Table(_) => BlockKind::Table,
+        Tag::BlockQuote(_) => BlockKind::Quote,
+        Tag::HtmlBlock => BlockKind::Html,
+        Tag::FootnoteDefinition(_) => BlockKind::Footnote,
+        _ => BlockKind::Other,
+    }
+}
+
+fn make_block(source: &str, kind: BlockKind, range: Range<usize>) -> Block {
+    let text = source[range.clone()].trim_end_matches(['\n', '\r']);
+    let range = range.start..range.start + text.len();
+    let key = if kind == BlockKind::Code {
+        text.lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+    Block { kind, range, key }
+}
+
+// ─────────────────────────────────────────────
+// diff 结果
+// ─────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HunkKind {
+    Added,
+    Removed,
+    Changed,
+    /// 内容没变，只是换了位置：old 是原位置，new 是新位置
+    Moved,
+}
+
+/// hunk 在某一边文档里的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HunkSide {
+    /// 起止行号（1-based，含）
+    pub start_line: usize,
+    pub end_line: usize,
+    /// 这几个块的原始 Markdown
+    pub markdown: String,
+    pub blocks: Vec<BlockKind>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    pub kind: HunkKind,
+    /// 所在章节的标题路径，从顶层到最近的标题；第一个标题之前的内容为空数组
+    pub section: Vec<String>,
+    /// 所在章节标题的锚点（新版本里的；整节被删除时是旧版本里的）
+    pub anchor: Option<String>,
+    /// 旧版本里的内容，新增时为 null
+    pub old: Option<HunkSide>,
+    /// 新版本里的内容，删除时为 null
+    pub new: Option<HunkSide>,
+    /// 修改 hunk 的词级 diff：按顺序拼起来，去掉 added 是旧文本，去掉 removed 是新文本；
+    /// 其他 hunk、含代码块或太长时为 null
+    pub words: Option<Vec<WordSpan>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WordKind {
+    Equal,
+    Added,
+    Removed,
+}
+
+/// 词级 diff 的一段文字
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordSpan {
+    pub kind: WordKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownDiff {
+    pub hunks: Vec<DiffHunk>,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub moved: usize,
+}
+
+// ─────────────────────────────────────────────
+// 章节对齐
+// ─────────────────────────────────────────────
+
+/// 解析好的一边文档
+struct Doc<'a> {
+    source: &'a str,
+    lines: LineIndex,
+    blocks: Vec<Block>,
+    headings: Vec<Heading>,
+    sections: Vec<Section>,
+}
+
+/// 一节：标题块（第 0 节没有）+ 到下一个标题之前的块
+struct Section {
+    heading: Option<usize>,
+    blocks: Range<usize>,
+}
+
+impl<'a> Doc<'a> {
+    fn new(source: &'a str) -> Doc<'a> {
+        let blocks = parse_blocks(source);
+        let headings = headings::parse_headings(source);
+        let mut sections = vec![Section {
+            heading: None,
+            blocks: 0..0,
+        }];
+        for (i, block) in blocks.iter().enumerate() {
+            if block.kind == BlockKind::Heading {
+                let heading = headings.iter().position(|h| h.start == block.range.start);
+                sections.push(Section {
+                    heading,
+                    blocks: i..i,
+                });
+            }
+            if let Some(section) = sections.last_mut() {
+                section.blocks.end = i + 1;
+            }
+        }
+        Doc {
+            source,
+            lines: LineIndex::new(source),
+            blocks,
+            headings,
+            sections,
+        }
+    }
+
+    /// 章节对齐用的键：(级别, 小写标题文本)
+    fn section_key(&self, section: usize) -> Option<(u8, String)> {
+        let heading = &self.headings[self.sections[section].heading?];
+        Some((heading.level, heading.text.to_lowercase()))
+    }
+
+    /// 标题路径：从顶层标题到这一节的标题
+    fn section_path(&self, section: usize) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut current = self.sections[section].heading;
+        while let Some(i) = current {
+            path.push(self.headings[i].text.clone());
+            current = self.headings[i].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn section_anchor(&self, section: usize) -> Option<String> {
+        let heading = self.sections[section].heading?;
+        Some(self.headings[heading].slug.clone())
+    }
+
+    /// 连续几个块合成 hunk 的一边
+    fn side(&self, blocks: Range<usize>) -> Option<HunkSide> {
+        if blocks.is_empty() {
+            return None;
+        }
+        let first = &self.blocks[blocks.start];
+        let last = &self.blocks[blocks.end - 1];
+        let end = last.range.end.max(first.range.start + 1);
+        Some(HunkSide {
+            start_line: self.lines.line_of(first.range.start),
+            end_line: self.lines.line_of(end - 1),
+            markdown: self.source[first.range.start..last.range.end].to_string(),
+            blocks: self.blocks[blocks].iter().map(|b| b.kind).collect(),
+        })
+    }
+}
+
+/// 比较两个版本的 Markdown
+pub fn diff_markdown(old: &str, new: &str) -> MarkdownDiff {
+    let old = Doc::new(old);
+    let new = Doc::new(new);
+    let mut pending = Vec::new();
+
+    for step in align_sections(&old, &new) {
+        match step {
+            Step::Same(i, j) => diff_section(&old, &new, i, j, &mut pending),
+            Step::Removed(i) => pending.push(Pending {
+                section: old.section_path(i),
+                anchor: old.section_anchor(i),
+                removed: old.sections[i].blocks.clone(),
+                added: 0..0,
+            }),
+            Step::Added(j) => pending.push(Pending {
+                section: new.section_path(j),
+                anchor: new.section_anchor(j),
+                removed: 0..0,
+                added: new.sections[j].blocks.clone(),
+            }),
+        }
+    }
+    let hunks = build_hunks(&old, &new, pending);
+
+    let count = |kind: HunkKind| hunks.iter().filter(|h| h.kind == kind).count();
+    MarkdownDiff {
+        added: count(HunkKind::Added),
+        removed: count(HunkKind::Removed),
+        changed: count(HunkKind::Changed),
+        moved: count(HunkKind::Moved),
+        hunks,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// 章节配对：第 0 节总是配对；其余按标题求 LCS，空隙里同级别的标题按顺序配对
+///
+/// 两边都有、只是位置变了的标题不参与空隙配对，留给后面的移动检测
+fn align_sections(old: &Doc, new: &Doc) -> Vec<Step> {
+    let old_keys: Vec<_> = (1..old.sections.len())
+        .map(|i| old.section_key(i))
+        .collect();
+    let new_keys: Vec<_> = (1..new.sections.len())
+        .map(|j| new.section_key(j))
+        .collect();
+
+    let aligned = align(&old_keys, &new_keys);
+    let removed_keys: HashSet<_> = aligned
+        .iter()
+        .filter_map(|step| match step {
+            Step::Removed(i) => old_keys[*i].as_ref(),
+            _ => None,
+        })
+        .collect();
+    let moved: HashSet<(u8, String)> = aligned
+        .iter()
+        .filter_map(|step| match step {
+            Step::Added(j) => new_keys[*j].clone(),
+            _ => None,
+        })
+        .filter(|key| removed_keys.contains(key))
+        .collect();
+
+    let mut steps = vec![Step::Same(0, 0)];
+    let mut removed: Vec<usize> = Vec::new();
+    let mut added: Vec<usize> = Vec::new();
+    for step in aligned {
+        match step {
+            Step::Same(i, j) => {
+                pair_gap(old, new, &moved, &mut removed, &mut added, &mut steps);
+                steps.push(Step::Same(i + 1, j + 1));
+            }
+            Step::Removed(i) => removed.push(i + 1),
+            Step::Added(j) => added.push(j + 1),
+        }
+    }
+    pair_gap(old, new, &moved, &mut removed, &mut added, &mut steps);
+    steps
+}
+
+/// 一个空隙里没配上的标题：同级别的按顺序配对（标题改名），其余照旧算删除 / 新增
+fn pair_gap(
+    old: &Doc,
+    new: &Doc,
+    moved: &HashSet<(u8, String)>,
+    removed: &mut Vec<usize>,
+    added: &mut Vec<usize>,
+    steps: &mut Vec<Step>,
+) {
+    let level = |doc: &Doc, section: usize| doc.section_key(section).map(|(level, _)| level);
+    let is_moved = |doc: &Doc, section: usize| {
+        doc.section_key(section)
+            .is_some_and(|key| moved.contains(&key))
+    };
+    let mut j = 0;
+    for &i in removed.iter() {
+        if is_moved(old, i) {
+            steps.push(Step::Removed(i));
+            continue;
+        }
+        match added[j..]
+            .iter()
+            .position(|&a| !is_moved(new, a) && level(new, a) == level(old, i))
+        {
+            Some(offset) => {
+                steps.extend(added[j..j + offset].iter().map(|&a| Step::Added(a)));
+                steps.push(Step::Same(i, added[j + offset]));
+                j += offset + 1;
+            }
+            None => steps.push(Step::Removed(i)),
+        }
+    }
+    steps.extend(added[j..].iter().map(|&a| Step::Added(a)));
+    removed.clear();
+    added.clear();
+}
+
+/// 还没拆出移动块的 hunk：两边各是一段连续的块
+struct Pending {
+    section: Vec<String>,
+    anchor: Option<String>,
+    removed: Range<usize>,
+    added: Range<usize>,
+}
+
+/// 配对的两节里逐块比较，连续的删除 / 新增合并成一个 hunk
+fn diff_section(old: &Doc, new: &Doc, i: usize, j: usize, out: &mut Vec<Pending>) {
+    let old_range = old.sections[i].blocks.clone();
+    let new_range = new.sections[j].blocks.clone();
+    let old_keys: Vec<&str> = old.blocks[old_range.clone()]
+        .iter()
+        .map(|b| b.key.as_str())
+        .collect();
+    let new_keys: Vec<&str> = new.blocks[new_range.clone()]
+        .iter()
+        .map(|b| b.key.as_str())
+        .collect();
+
+    let mut pending: Option<(Range<usize>, Range<usize>)> = None;
+    let mut flush = |pending: &mut Option<(Range<usize>, Range<usize>)>| {
+        if let Some((removed, added)) = pending.take() {
+            out.push(Pending {
+                section: new.section_path(j),
+                anchor: new.section_anchor(j),
+                removed,
+                added,
+            });
+        }
+    };
+
+    // 每个 hunk 的两边各是一段连续的块：删除段挂在上一个相同块之后，新增段同理
+    let mut old_pos = old_range.start;
+    let mut new_pos = new_range.start;
+    for step in align(&old_keys, &new_keys) {
+        match step {
+            Step::Same(a, b) => {
+                flush(&mut pending);
+                old_pos = old_range.start + a + 1;
+                new_pos = new_range.start + b + 1;
+            }
+            Step::Removed(a) => {
+                let (removed, _) = pending.get_or_insert((old_pos..old_pos, new_pos..new_pos));
+                removed.end = old_range.start + a + 1;
+            }
+            Step::Added(b) => {
+                let (_, added) = pending.get_or_insert((old_pos..old_pos, new_pos..new_pos));
+                added.end = new_range.start + b + 1;
+            }
+        }
+    }
+    flush(&mut pending);
+}
+
+// ─────────────────────────────────────────────
+// 移动检测
+// ─────────────────────────────────────────────
+
+/// 找出移动的块，再把每个 Pending 拆成最终的 hunk
+///
+/// 新增块和文本相同的删除块按出现顺序一对一配对（不限章节）。
+/// 一个 Pending 拆开后，新增一侧按「普通段 / 移动段」切开，普通段依次和删除一侧剩下的段配成修改
+fn build_hunks(old: &Doc, new: &Doc, pending: Vec<Pending>) -> Vec<DiffHunk> {
+    let movable = |block: &Block| block.kind != BlockKind::Rule && !block.key.is_empty();
+
+    // 规范化文本 → 删除块（倒序存放，pop 出来的是最靠前的）
+    let mut candidates: HashMap<&str, Vec<usize>> = HashMap::new();
+    for p in &pending {
+        for a in p.removed.clone() {
+            if movable(&old.blocks[a]) {
+                candidates
+                    .entry(old.blocks[a].key.as_str())
+                    .or_default()
+                    .push(a);
+            }
+        }
+    }
+    for list in candidates.values_mut() {
+        list.reverse();
+    }
+    // 新增块 → 它原来的位置
+    let mut source: HashMap<usize, usize> = HashMap::new();
+    for p in &pending {
+        for b in p.added.clone() {
+            let block = &new.blocks[b];
+            if !movable(block) {
+                continue;
+            }
+            if let Some(a) = candidates.get_mut(block.key.as_str()).and_then(Vec::pop) {
+                source.insert(b, a);
+            }
+        }
+    }
+    let moved_old: HashSet<usize> = source.values().copied().collect();
+
+    let mut hunks = Vec::new();
+    for p in pending {
+        let hunk = |kind, old_side, new_side, words| DiffHunk {
+            kind,
+            section: p.section.clone(),
+            anchor: p.anchor.clone(),
+            old: old_side,
+            new: new_side,
+            words,
+        };
+
+        let mut removed_runs = split_runs(p.removed.clone(), |a| {
+            moved_old.contains(&a) != moved_old.contains(&(a - 1))
+        })
+        .into_iter()
+        .filter(|run| !moved_old.contains(&run.start));
+        // 移动段在原位置上也要连续，否则拆成几个 hunk
+        let added_runs = split_runs(p.added.clone(), |b| {
+            match (source.get(&(b - 1)), source.get(&b)) {
+                (Some(&before), Some(&current)) => current != before + 1,
+                (None, None) => false,
+                _ => true,
+            }
+        });
+
+        for run in added_runs {
+            if let Some(&from) = source.get(&run.start) {
+                let old_side = old.side(from..from + run.len());
+                hunks.push(hunk(HunkKind::Moved, old_side, new.side(run), None));
+                continue;
+            }
+            match removed_runs.next() {
+                Some(removed) => {
+                    let words = changed_words(old, new, removed.clone(), run.clone());
+                    hunks.push(hunk(
+                        HunkKind::Changed,
+                        old.side(removed),
+                        new.side(run),
+                        words,
+                    ));
+                }
+                None => hunks.push(hunk(HunkKind::Added, None, new.side(run), None)),
+            }
+        }
+        for removed in removed_runs {
+            hunks.push(hunk(HunkKind::Removed, old.side(removed), None, None));
+        }
+    }
+    hunks
+}
+
+/// 把 range 切成几段：starts_run(x) 为 true 时 x 开始新的一段（只对第一个之后的元素调用）
+fn split_runs(range: Range<usize>, starts_run: impl Fn(usize) -> bool) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for x in range {
+        match runs.last_mut() {
+            Some(run) if !starts_run(x) => run.end = x + 1,
+            _ => runs.push(x..x + 1),
+        }
+    }
+    runs
+}
+
+// ─────────────────────────────────────────────
+// 词级 diff
+// ─────────────────────────────────────────────
+
+/// 修改 hunk 的词级 diff；任一边含代码块时返回 None（代码按行看更清楚）
+fn changed_words(
+    old: &Doc,
+    new: &Doc,
+    removed: Range<usize>,
+    added: Range<usize>,
+) -> Option<Vec<WordSpan>> {
+    let has_code = |doc: &Doc, range: Range<usize>| {
+        doc.blocks[range].iter().any(|b| b.kind == BlockKind::Code)
+    };
+    if has_code(old, removed.clone()) || has_code(new, added.clone()) {
+        return None;
+    }
+    let old_text = old.side(removed)?.markdown;
+    let new_text = new.side(added)?.markdown;
+    word_diff(&old_text, &new_text)
+}
+
+/// 按词比较两段文字；空白只要都是空白就算相同（重新折行不算改动）
+pub fn word_diff(old: &str, new: &str) -> Option<Vec<WordSpan>> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len().saturating_mul(new_tokens.len()) > MAX_WORD_DIFF_CELLS {
+        return None;
+    }
+    let old_keys: Vec<&str> = old_tokens.iter().map(|t| token_key(t)).collect();
+    let new_keys: Vec<&str> = new_tokens.iter().map(|t| token_key(t)).collect();
+
+    // 相同的连续词合成一段，两处相同之间的删除 / 新增合成一处改动
+    let mut regions: Vec<Region> = Vec::new();
+    for step in align(&old_keys, &new_keys) {
+        match step {
+            Step::Same(x, y) => match regions.last_mut() {
+                Some(Region::Equal(old_text, new_text)) => {
+                    old_text.push_str(old_tokens[x]);
+                    new_text.push_str(new_tokens[y]);
+                }
+                _ => regions.push(Region::Equal(
+                    old_tokens[x].to_string(),
+                    new_tokens[y].to_string(),
+                )),
+            },
+            Step::Removed(x) => change_region(&mut regions).0.push_str(old_tokens[x]),
+            Step::Added(y) => change_region(&mut regions).1.push_str(new_tokens[y]),
+        }
+    }
+
+    let mut spans = Vec::new();
+    let mut push = |kind: WordKind, text: String| {
+        if !text.is_empty() {
+            spans.push(WordSpan { kind, text });
+        }
+    };
+    for region in merge_gaps(regions) {
+        match region {
+            Region::Equal(_, text) => push(WordKind::Equal, text),
+            Region::Change(removed, added) => {
+                push(WordKind::Removed, removed);
+                push(WordKind::Added, added);
+            }
+        }
+    }
+    Some(spans)
+}
+
+/// 比较用的词：空白一律当作一个空格
+fn token_key(token: &str) -> &str {
+    if token.trim().is_empty() {
+        " "
+    } else {
+        token
+    }
+}
+
+/// 词级 diff 的一段：相同（旧文本, 新文本，只有空白可能不同）或改动（删除的, 新增的）
+enum Region {
+    Equal(String, String),
+    Change(String, String),
+}
+
+fn change_region(regions: &mut Vec<Region>) -> (&mut String, &mut String) {
+    if !matches!(regions.last(), Some(Region::Change(..))) {
+        regions.push(Region::Change(String::new(), String::new()));
+    }
+    match regions.last_mut() {
+        Some(Region::Change(removed, added)) => (removed, added),
+        _ => unreachable!(),
+    }
+}
+
+/// 夹在两处改动之间、只有空白的相同段并进改动：
+/// "删 foo、留空格、删 bar" 读起来是三段，合成一段 "删 foo bar" 更清楚
+fn merge_gaps(regions: Vec<Region>) -> Vec<Region> {
+    let mut merged: Vec<Region> = Vec::new();
+    for region in regions {
+        let gap = match (&region, merged.as_slice()) {
+            (Region::Change(..), [.., Region::Change(..), Region::Equal(old, _)]) => {
+                old.trim().is_empty()
+            }
+            _ => false,
+        };
+        if !gap {
+            merged.push(region);
+            continue;
+        }
+        let (Some(Region::Equal(old_space, new_space)), Region::Change(removed, added)) =
+            (merged.pop(), region)
+        else {
+            unreachable!()
+        };
+        if let Some(Region::Change(prev_removed, prev_added)) = merged.last_mut() {
+            prev_removed.push_str(&old_space);
+            prev_removed.push_str(&removed);
+            prev_added.push_str(&new_space);
+            prev_added.push_str(&added);
+        }
+    }
+    merged
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Space,
+    Single,
+}
+
+/// 分词：连续的字母数字算一个词，连续空白算一个词；中日韩文字和标点每个字符单独成词
+fn tokenize(text: &str) -> Vec<&str> {
+    let class = |c: char| {
+        if c.is_whitespace() {
+            CharClass::Space
+        } else if is_cjk(c) {
+            CharClass::Single
+        } else if c.is_alphanumeric() || c == '_' {
+            CharClass::Word
+        } else {
+            CharClass::Single
+        }
+    };
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous = CharClass::Single;
+    for (i, c) in text.char_indices() {
+        let current = class(c);
+        let joins = current == previous && current != CharClass::Single;
+        if !joins && i > start {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        previous = current;
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// 最长公共子序列对齐，输出按顺序的相同 / 删除 / 新增步骤
+///
+/// 先去掉公共前缀和后缀，中间部分用 O(n·m) 动态规划；同一位置删除排在新增前面。
+/// 中间部分超过 MAX_ALIGN_CELLS 时退化成线性的：前缀、整段删除、整段新增、后缀
+fn align<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Step> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    let old_tail = old.len() - suffix;
+    let new_tail = new.len() - suffix;
+
+    let mut steps: Vec<Step> = (0..prefix).map(|k| Step::Same(k, k)).collect();
+    if a.len().saturating_mul(b.len()) > MAX_ALIGN_CELLS {
+        steps.extend((prefix..old_tail).map(Step::Removed));
+        steps.extend((prefix..new_tail).map(Step::Added));
+        steps.extend((0..suffix).map(|k| Step::Same(old_tail + k, new_tail + k)));
+        return steps;
+    }
+
+    // table[x][y]：a[x..] 和 b[y..] 的 LCS 长度
+    let width = b.len() + 1;
+    let mut table = vec![0u32; (a.len() + 1) * width];
+    for x in (0..a.len()).rev() {
+        for y in (0..b.len()).rev() {
+            table[x * width + y] = if a[x] == b[y] {
+                table[(x + 1) * width + y + 1] + 1
+            } else {
+                table[(x + 1) * width + y].max(table[x * width + y + 1])
+            };
+        }
+    }
+
+    let (mut x, mut y) = (0, 0);
+    while x < a.len() || y < b.len() {
+        if x < a.len() && y < b.len() && a[x] == b[y] {
+            steps.push(Step::Same(prefix + x, prefix + y));
+            x += 1;
+            y += 1;
+        } else if y == b.len()
+            || (x < a.len() && table[(x + 1) * width + y] >= table[x * width + y + 1])
+        {
+            steps.push(Step::Removed(prefix + x));
+            x += 1;
+        } else {
+            steps.push(Step::Added(prefix + y));
+            y += 1;
+        }
+    }
+    steps.extend((0..suffix).map(|k| Step::Same(old_tail + k, new_tail + k)));
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(words: &[WordSpan]) -> Vec<(WordKind, &str)> {
+        words.iter().map(|w| (w.kind, w.text.as_str())).collect()
+    }
+
+    #[test]
+    fn pairs_renamed_heading_in_gap() {
+        let old = "# Guide\n\n## Intro\n\nSame text.\n\n## End\n\nBye.\n";
+        let new = "# Guide\n\n## Overview\n\nSame text.\n\n## End\n\nBye.\n";
+        let diff = diff_markdown(old, new);
+        // 改名的标题和旧标题配成一节：只有标题本身是修改，节里的段落不算删除 + 新增
+        assert_eq!(diff.hunks.len(), 1);
+        let hunk = &diff.hunks[0];
+        assert_eq!(hunk.kind, HunkKind::Changed);
+        assert_eq!(hunk.section, ["Guide", "Overview"]);
+        assert_eq!(hunk.anchor.as_deref(), Some("overview"));
+        assert_eq!(hunk.old.as_ref().unwrap().markdown, "## Intro");
+        assert_eq!(hunk.new.as_ref().unwrap().markdown, "## Overview");
+    }
+
+    #[test]
+    fn moved_block_becomes_moved_hunk() {
+        let old = "First paragraph.\n\nSecond paragraph.\n\nThird paragraph.\n";
+        let new = "Second paragraph.\n\nThird paragraph.\n\nFirst paragraph.\n";
+        let diff = diff_markdown(old, new);
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!((diff.added, diff.removed, diff.moved), (0, 0, 1));
+        let hunk = &diff.hunks[0];
+        assert_eq!(hunk.kind, HunkKind::Moved);
+        assert_eq!(hunk.old.as_ref().unwrap().start_line, 1);
+        assert_eq!(hunk.new.as_ref().unwrap().start_line, 5);
+        assert!(hunk.words.is_none());
+    }
+
+    #[test]
+    fn rewrapped_paragraph_is_unchanged() {
+        let diff = diff_markdown("one two\nthree\n", "one\ntwo three\n");
+        assert!(diff.hunks.is_empty());
+    }
+
+    #[test]
+    fn word_diff_compares_cjk_by_character() {
+        let words = word_diff("今天天气很好", "今天天气不好").unwrap();
+        assert_eq!(
+            spans(&words),
+            [
+                (WordKind::Equal, "今天天气"),
+                (WordKind::Removed, "很"),
+                (WordKind::Added, "不"),
+                (WordKind::Equal, "好"),
+            ]
+        );
+        // 英文按词：整个词替换，不拆成字母
+        let words = word_diff("the quick fox", "the slow fox").unwrap();
+        assert_eq!(
+            spans(&words),
+            [
+                (WordKind::Equal, "the "),
+                (WordKind::Removed, "quick"),
+                (WordKind::Added, "slow"),
+                (WordKind::Equal, " fox"),
+            ]
+        );
+    }
+
+    #[test]
+    fn align_falls_back_when_too_large() {
+        // 公共前后缀之外两边各 2001 个，超过 MAX_ALIGN_CELLS：
+        // 中间即使有相同的元素也不再求 LCS，整段删除 + 整段新增
+        let n = 2001;
+        let mut old: Vec<i64> = vec![-1];
+        old.extend(0..n);
+        old.push(-2);
+        let mut new: Vec<i64> = vec![-1];
+        new.extend((0..n).map(|k| if k == n / 2 { n / 2 } else { n + k }));
+        new.push(-2);
+        assert!((n as usize).pow(2) > MAX_ALIGN_CELLS);
+
+        let steps = align(&old, &new);
+        let same: Vec<_> = steps
+            .iter()
+            .filter(|s| matches!(s, Step::Same(..)))
+            .collect();
+        assert_eq!(
+            same,
+            [
+                &Step::Same(0, 0),
+                &Step::Same(n as usize + 1, n as usize + 1)
+            ]
+        );
+        assert_eq!(steps.len(), 2 + 2 * n as usize);
+        assert_eq!(steps[1], Step::Removed(1));
+        assert_eq!(steps[1 + n as usize], Step::Added(1));
+
+        // 规模小时照常求 LCS，中间相同的元素能对上
+        let steps = align(&[-1, 0, 1, 2, -2], &[-1, 9, 1, 8, -2]);
+        assert!(steps.contains(&Step::Same(2, 2)));
+    }
+}
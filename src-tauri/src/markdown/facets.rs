@@ -0,0 +1,277 @@
+// ─────────────────────────────────────────────
+// 元数据分面：标签、分类、#话题、front matter 日期
+// ─────────────────────────────────────────────
+//
+// 标签有两个来源：front matter 的 tags / tag，和正文里的 #话题（Obsidian、Bear 的写法）。
+// #话题的规则：
+//   - 前面是行首、空白或标点（"C#"、"page#anchor"、"/#x" 都不算）
+//   - 由字母、数字、_、-、/ 组成，不能全是数字（"#123" 通常是 issue 编号）
+//   - 代码块、行内代码、HTML 里的不算；标题里的算
+// 标签不区分大小写：同一个标签的不同写法合并计数，显示用第一次见到的写法。
+
+use super::front_matter::FrontMatter;
+use super::headings::parse_headings;
+use super::parser_options;
+use chrono::{DateTime, Local, NaiveDate};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 单个文档的元数据，分面统计和过滤查询都用它
+#[derive(Debug, Clone, Default)]
+pub struct DocumentMeta {
+    pub front_matter: FrontMatter,
+    /// front matter 的 tags / tag
+    pub tags: Vec<String>,
+    /// front matter 的 categories / category
+    pub categories: Vec<String>,
+    /// 正文里的 #话题（不含 #），按出现顺序去重
+    pub hashtags: Vec<String>,
+    /// 全文（小写），全文搜索用
+    pub text: String,
+    /// 所有标题的文字
+    pub headings: Vec<String>,
+    /// 相对打开的根目录的路径，用 "/" 分隔；parse() 得到的为空
+    pub relative_path: String,
+    /// 文件修改日期（本地时区）；parse() 得到的为 None
+    pub modified: Option<NaiveDate>,
+}
+
+impl DocumentMeta {
+    pub fn parse(source: &str) -> DocumentMeta {
+        let front_matter = FrontMatter::parse(source);
+        let list = |keys: [&str; 2]| {
+            let mut values: Vec<String> = Vec::new();
+            for key in keys {
+                for value in front_matter.list(key) {
+                    // YAML 里写成 "#rfc" 的也当成 rfc
+                    let value = value.trim_start_matches('#').to_string();
+                    if !values.iter().any(|v| v.eq_ignore_ascii_case(&value)) {
+                        values.push(value);
+                    }
+                }
+            }
+            values
+        };
+        DocumentMeta {
+            tags: list(["tags", "tag"]),
+            categories: list(["categories", "category"]),
+            hashtags: hashtags(source),
+            text: source.to_lowercase(),
+            headings: parse_headings(source).into_iter().map(|h| h.text).collect(),
+            relative_path: String::new(),
+            modified: None,
+            front_matter,
+        }
+    }
+
+    /// 读取 root 下的文件，带上相对路径和修改日期；读不了的文件（权限、非 UTF-8）返回 None
+    pub fn read(root: &Path, path: &Path) -> Option<DocumentMeta> {
+        let source = fs::read_to_string(path).ok()?;
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        Some(DocumentMeta {
+            relative_path: relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            modified: fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| DateTime::<Local>::from(t).date_naive()),
+            ..DocumentMeta::parse(&source)
+        })
+    }
+
+    /// front matter 里所有像日期的字段：(字段名, 日期)
+    pub fn dates(&self) -> Vec<(String, NaiveDate)> {
+        self.front_matter
+            .fields
+            .keys()
+            .filter_map(|key| Some((key.clone(), parse_date(&self.front_matter.text(key)?)?)))
+            .collect()
+    }
+}
+
+/// "2026-01-05"、"2026-01-05 10:00"、"2026-01-05T10:00:00+08:00" → 日期
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    let head = value.trim().get(..10)?;
+    let rest = &value.trim()[10..];
+    if !(rest.is_empty() || rest.starts_with([' ', 'T', 't'])) {
+        return None;
+    }
+    NaiveDate::parse_from_str(head, "%Y-%m-%d").ok()
+}
+
+/// 正文里的 #话题，按出现顺序去重（不区分大小写）
+pub fn hashtags(source: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    // pulldown-cmark 可能把一段文字拆成几个 Text 事件，攒齐了再找
+    let mut buffer = String::new();
+    // 代码块和 front matter 里的文字不算
+    let mut skipping = false;
+    for event in Parser::new_ext(source, parser_options()) {
+        match event {
+            Event::Text(text) if !skipping => buffer.push_str(&text),
+            Event::Start(Tag::CodeBlock(_)) | Event::Start(Tag::MetadataBlock(_)) => {
+                skipping = true
+            }
+            Event::End(TagEnd::CodeBlock) | Event::End(TagEnd::MetadataBlock(_)) => {
+                skipping = false
+            }
+            // 行内代码等其他事件把文字隔开，两边不能拼成一个话题
+            _ => flush_hashtags(&mut buffer, &mut tags),
+        }
+    }
+    flush_hashtags(&mut buffer, &mut tags);
+    tags
+}
+
+fn flush_hashtags(buffer: &mut String, tags: &mut Vec<String>) {
+    for tag in scan_hashtags(buffer) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    buffer.clear();
+}
+
+fn scan_hashtags(text: &str) -> Vec<&str> {
+    let is_tag_char = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '/');
+    let mut tags = Vec::new();
+    let mut previous: Option<char> = None;
+    for (i, c) in text.char_indices() {
+        let boundary = previous.map_or(true, |p| {
+            !(p.is_alphanumeric() || matches!(p, '#' | '&' | '/' | '_' | '-'))
+        });
+        previous = Some(c);
+        if c != '#' || !boundary {
+            continue;
+        }
+        let rest = &text[i + 1..];
+        let len = rest.find(|c: char| !is_tag_char(c)).unwrap_or(rest.len());
+        let tag = rest[..len].trim_end_matches(['-', '/']);
+        if tag.chars().any(|c| !c.is_ascii_digit()) && !tag.starts_with(['-', '/']) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// 一个取值和它出现在多少个文档里
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// 一个日期字段的分布
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateFacet {
+    /// front matter 字段名（date、created、updated……）
+    pub field: String,
+    pub earliest: String,
+    pub latest: String,
+    /// 按月计数 "YYYY-MM"，从早到晚
+    pub months: Vec<FacetCount>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Facets {
+    pub documents: usize,
+    /// front matter 标签，按文档数从多到少
+    pub tags: Vec<FacetCount>,
+    pub categories: Vec<FacetCount>,
+    /// 正文 #话题
+    pub hashtags: Vec<FacetCount>,
+    pub dates: Vec<DateFacet>,
+}
+
+/// 不区分大小写的计数器，显示第一次见到的写法
+#[derive(Default)]
+struct Counter {
+    counts: HashMap<String, (String, usize)>,
+}
+
+impl Counter {
+    fn add(&mut self, value: &str) {
+        self.counts
+            .entry(value.to_lowercase())
+            .or_insert_with(|| (value.to_string(), 0))
+            .1 += 1;
+    }
+
+    /// 按次数从多到少，次数相同按字母
+    fn into_counts(self) -> Vec<FacetCount> {
+        let mut counts: Vec<FacetCount> = self
+            .counts
+            .into_values()
+            .map(|(value, count)| FacetCount { value, count })
+            .collect();
+        counts.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.value.to_lowercase().cmp(&b.value.to_lowercase()))
+        });
+        counts
+    }
+}
+
+/// 汇总多个文档的分面
+pub fn collect_facets<'a>(docs: impl IntoIterator<Item = &'a DocumentMeta>) -> Facets {
+    let (mut tags, mut categories, mut hashtags) =
+        (Counter::default(), Counter::default(), Counter::default());
+    let mut dates: HashMap<String, Vec<NaiveDate>> = HashMap::new();
+    let mut documents = 0;
+    for doc in docs {
+        documents += 1;
+        doc.tags.iter().for_each(|t| tags.add(t));
+        doc.categories.iter().for_each(|c| categories.add(c));
+        doc.hashtags.iter().for_each(|h| hashtags.add(h));
+        for (field, date) in doc.dates() {
+            dates.entry(field).or_default().push(date);
+        }
+    }
+
+    let mut dates: Vec<DateFacet> = dates
+        .into_iter()
+        .map(|(field, mut values)| {
+            values.sort();
+            let mut months: Vec<FacetCount> = Vec::new();
+            for date in &values {
+                let month = date.format("%Y-%m").to_string();
+                match months.last_mut() {
+                    Some(last) if last.value == month => last.count += 1,
+                    _ => months.push(FacetCount {
+                        value: month,
+                        count: 1,
+                    }),
+                }
+            }
+            DateFacet {
+                field,
+                earliest: values[0].to_string(),
+                latest: values[values.len() - 1].to_string(),
+                months,
+            }
+        })
+        .collect();
+    // 出现得多的字段排前面（通常是 date）
+    dates.sort_by(|a, b| {
+        let total = |d: &DateFacet| d.months.iter().map(|m| m.count).sum::<usize>();
+        total(b).cmp(&total(a)).then_with(|| a.field.cmp(&b.field))
+    });
+
+    Facets {
+        documents,
+        tags: tags.into_counts(),
+        categories: categories.into_counts(),
+        hashtags: hashtags.into_counts(),
+        dates,
+    }
+}
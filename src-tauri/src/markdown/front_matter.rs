@@ -0,0 +1,129 @@
+// ─────────────────────────────────────────────
+// YAML front matter 解析
+// ─────────────────────────────────────────────
+//
+// 文件开头 `---` 包裹的 YAML 块转成 JSON 值，方便序列化给前端，也方便统一取字段。
+// 只认 YAML（和 strip_front_matter / pulldown-cmark 的元数据块一致），
+// YAML 写错时当作没有 front matter，不影响文档本身的渲染。
+
+use serde_json::{Map, Number, Value};
+use yaml_rust::{Yaml, YamlLoader};
+
+/// 文档的 front matter，字段名保持原样
+#[derive(Debug, Clone, Default)]
+pub struct FrontMatter {
+    pub fields: Map<String, Value>,
+}
+
+impl FrontMatter {
+    /// 解析文档开头的 front matter；没有、未闭合或不是键值对时返回空
+    pub fn parse(source: &str) -> FrontMatter {
+        let fields = front_matter_block(source)
+            .and_then(|block| YamlLoader::load_from_str(block).ok())
+            .and_then(|docs| docs.into_iter().next())
+            .map(yaml_to_json);
+        match fields {
+            Some(Value::Object(fields)) => FrontMatter { fields },
+            _ => FrontMatter::default(),
+        }
+    }
+
+    /// 单个文本字段；数字、布尔值也转成文本
+    pub fn text(&self, key: &str) -> Option<String> {
+        scalar_text(self.fields.get(key)?).filter(|s| !s.is_empty())
+    }
+
+    /// 列表字段：YAML 列表，或者逗号分隔的一行文本（"tags: a, b"）
+    pub fn list(&self, key: &str) -> Vec<String> {
+        match self.fields.get(key) {
+            Some(Value::Array(items)) => items.iter().filter_map(scalar_text).collect(),
+            Some(value) => scalar_text(value)
+                .map(|s| s.split(',').map(|t| t.trim().to_string()).collect())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect()
+    }
+
+    pub fn title(&self) -> Option<String> {
+        self.text("title")
+    }
+
+    /// 作者：author / authors，可以是文本、列表，或者带 name 字段的对象
+    pub fn authors(&self) -> Vec<String> {
+        let mut authors = Vec::new();
+        for key in ["author", "authors"] {
+            match self.fields.get(key) {
+                Some(Value::Array(items)) => authors.extend(items.iter().filter_map(person_name)),
+                Some(Value::String(s)) => {
+                    authors.extend(s.split(',').map(|a| a.trim().to_string()))
+                }
+                Some(value) => authors.extend(person_name(value)),
+                None => {}
+            }
+        }
+        authors.retain(|a| !a.is_empty());
+        authors
+    }
+
+    /// 语言：lang / language
+    pub fn language(&self) -> Option<String> {
+        self.text("lang").or_else(|| self.text("language"))
+    }
+}
+
+/// 取出 front matter 的 YAML 文本（不含 `---` 分隔行）
+fn front_matter_block(source: &str) -> Option<&str> {
+    let rest = source
+        .strip_prefix("---\n")
+        .or_else(|| source.strip_prefix("---\r\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some(&rest[..offset]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn yaml_to_json(yaml: Yaml) -> Value {
+    match yaml {
+        Yaml::String(s) => Value::String(s),
+        Yaml::Integer(i) => Value::Number(i.into()),
+        // 无法表示成 JSON 数字的（.nan / .inf）保留原文
+        Yaml::Real(s) => s
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or(Value::String(s)),
+        Yaml::Boolean(b) => Value::Bool(b),
+        Yaml::Array(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        Yaml::Hash(hash) => Value::Object(
+            hash.into_iter()
+                .filter_map(|(k, v)| Some((scalar_text(&yaml_to_json(k))?, yaml_to_json(v))))
+                .collect(),
+        ),
+        Yaml::Null | Yaml::Alias(_) | Yaml::BadValue => Value::Null,
+    }
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// 人名：文本，或者 `{ name: ..., email: ... }` 形式的对象
+fn person_name(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(fields) => fields.get("name").and_then(scalar_text),
+        other => scalar_text(other),
+    }
+}
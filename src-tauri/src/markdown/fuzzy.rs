@@ -0,0 +1,263 @@
+// ─────────────────────────────────────────────
+// 模糊匹配：子序列打分（快速打开等搜索框共用）
+// ─────────────────────────────────────────────
+//
+// 查询的每个字符按顺序出现在文本里就算匹配（"rdme" 匹配 "README.md"），不区分大小写。
+// 同一个查询在一段文本里往往有多种对齐方式，用动态规划找分数最高的那种：
+//   - 每个匹配的字符得 MATCH 分
+//   - 落在「词首」额外加分：文本开头 > 路径分隔符之后 > 空格、-、_、. 等之后 > 驼峰的大写字母
+//   - 紧挨着上一个匹配字符再加分；中间跳过的字符每个扣 GAP 分，第一个匹配之前的也扣（有上限）
+//   - 大小写也一致时多加一点
+// 先贪心扫一遍判断是不是子序列，不是的直接跳过，大部分候选走不到动态规划。
+
+const MATCH: i32 = 16;
+const BONUS_START: i32 = 10;
+const BONUS_SEGMENT: i32 = 9;
+const BONUS_WORD: i32 = 8;
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 5;
+const BONUS_CASE: i32 = 1;
+const GAP: i32 = 1;
+/// 第一个匹配之前的字符最多扣这么多分，长路径里靠后的文件名不至于吃亏太多
+const MAX_LEADING_GAP: i32 = 8;
+/// 只在文本的前这么多个字符里找，超长的标题不值得整段跑动态规划
+const MAX_TEXT: usize = 256;
+
+const NONE: i32 = i32::MIN / 2;
+
+/// 预处理过的文本：候选列表里常驻，查询时不用每次都转小写、拆字符
+#[derive(Debug, Clone)]
+pub struct Prepared {
+    pub text: String,
+    chars: Vec<char>,
+    lower: Vec<char>,
+}
+
+impl Prepared {
+    pub fn new(text: &str) -> Prepared {
+        let chars: Vec<char> = text.chars().collect();
+        // 逐字符转小写，保证下标和原文一一对应（to_lowercase 对个别字符会变成多个）
+        let lower = chars
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+        Prepared {
+            text: text.to_string(),
+            chars,
+            lower,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+}
+
+/// pattern 在 text 里的最高分；不是子序列时返回 None
+pub fn score(pattern: &Prepared, text: &Prepared) -> Option<i32> {
+    align(pattern, text, false).map(|(score, _)| score)
+}
+
+/// 同 score，另外返回匹配到的字符下标（Unicode 字符下标，升序），前端用来高亮
+pub fn match_indices(pattern: &Prepared, text: &Prepared) -> Option<(i32, Vec<usize>)> {
+    align(pattern, text, true)
+}
+
+fn align(pattern: &Prepared, text: &Prepared, want_indices: bool) -> Option<(i32, Vec<usize>)> {
+    let m = pattern.lower.len();
+    if m == 0 {
+        return Some((0, Vec::new()));
+    }
+    let n = text.lower.len().min(MAX_TEXT);
+    let t = &text.lower[..n];
+    let mut rest = t.iter();
+    if !pattern.lower.iter().all(|p| rest.any(|c| c == p)) {
+        return None;
+    }
+
+    // row[j]：pattern[..=i] 匹配完、且 pattern[i] 落在 text[j] 时的最高分
+    let mut prev = vec![NONE; n];
+    let mut row = vec![NONE; n];
+    // from[i * n + j]：上面这个最高分里 pattern[i - 1] 落在哪，回溯下标用
+    let mut from = if want_indices {
+        vec![usize::MAX; m * n]
+    } else {
+        Vec::new()
+    };
+    for i in 0..m {
+        // pattern[i - 1] 落在 j - 2 及之前、中间隔着字符时的最高分（已扣掉间隔）
+        let mut gap = NONE;
+        let mut gap_from = usize::MAX;
+        for j in 0..n {
+            let mut best = NONE;
+            let mut best_from = usize::MAX;
+            if t[j] == pattern.lower[i] {
+                let mut gain = MATCH + bonus(text, j);
+                if text.chars[j] == pattern.chars[i] {
+                    gain += BONUS_CASE;
+                }
+                if i == 0 {
+                    best = gain - (j as i32).min(MAX_LEADING_GAP) * GAP;
+                } else {
+                    if j > 0 && prev[j - 1] > NONE {
+                        best = prev[j - 1] + gain + BONUS_CONSECUTIVE;
+                        best_from = j - 1;
+                    }
+                    if gap > NONE && gap + gain > best {
+                        best = gap + gain;
+                        best_from = gap_from;
+                    }
+                }
+            }
+            row[j] = best;
+            if want_indices {
+                from[i * n + j] = best_from;
+            }
+            // 挪到 j + 1：已有的间隔再长一格，pattern[i - 1] 落在 j - 1 的情况也变成隔一格
+            if i > 0 {
+                if gap > NONE {
+                    gap -= GAP;
+                }
+                if j > 0 && prev[j - 1] > NONE && prev[j - 1] - GAP > gap {
+                    gap = prev[j - 1] - GAP;
+                    gap_from = j - 1;
+                }
+            }
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+
+    // 同分时取最靠前的结尾
+    let mut end = 0;
+    for j in 1..n {
+        if prev[j] > prev[end] {
+            end = j;
+        }
+    }
+    let score = prev[end];
+    if score <= NONE {
+        return None;
+    }
+    let mut indices = Vec::new();
+    if want_indices {
+        indices = vec![0; m];
+        let mut j = end;
+        for i in (0..m).rev() {
+            indices[i] = j;
+            j = from[i * n + j];
+        }
+    }
+    Some((score, indices))
+}
+
+/// 落在 text[j] 的匹配字符能拿到的「词首」加分
+fn bonus(text: &Prepared, j: usize) -> i32 {
+    if j == 0 {
+        return BONUS_START;
+    }
+    let prev = text.chars[j - 1];
+    let c = text.chars[j];
+    if prev == '/' || prev == '\\' {
+        BONUS_SEGMENT
+    } else if prev.is_whitespace() || matches!(prev, '-' | '_' | '.') {
+        BONUS_WORD
+    } else if prev.is_lowercase() && c.is_uppercase() {
+        BONUS_CAMEL
+    } else if !prev.is_alphanumeric() && c.is_alphanumeric() {
+        BONUS_WORD
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score_of(pattern: &str, text: &str) -> Option<i32> {
+        score(&Prepared::new(pattern), &Prepared::new(text))
+    }
+
+    fn indices(pattern: &str, text: &str) -> Vec<usize> {
+        match_indices(&Prepared::new(pattern), &Prepared::new(text))
+            .expect("should match")
+            .1
+    }
+
+    /// texts 按 pattern 的得分从高到低排好，分数也严格递减
+    fn assert_ranked(pattern: &str, texts: &[&str]) {
+        let scores: Vec<i32> = texts
+            .iter()
+            .map(|t| score_of(pattern, t).unwrap_or_else(|| panic!("{:?} ~ {:?}", pattern, t)))
+            .collect();
+        for (pair, texts) in scores.windows(2).zip(texts.windows(2)) {
+            assert!(
+                pair[0] > pair[1],
+                "{:?}: {:?} ({}) should beat {:?} ({})",
+                pattern,
+                texts[0],
+                pair[0],
+                texts[1],
+                pair[1]
+            );
+        }
+    }
+
+    #[test]
+    fn matches_subsequences_only() {
+        assert!(score_of("rdme", "README.md").is_some());
+        assert!(score_of("RDME", "readme.md").is_some());
+        assert_eq!(score_of("", "anything"), Some(0));
+        assert_eq!(score_of("mr", "README.md"), None);
+        assert_eq!(score_of("readmes", "README.md"), None);
+        assert_eq!(score_of("a", ""), None);
+        // 只看前 MAX_TEXT 个字符
+        let long = format!("{}z", "a".repeat(MAX_TEXT));
+        assert_eq!(score_of("z", &long), None);
+        assert!(score_of("z", &long[1..]).is_some());
+    }
+
+    #[test]
+    fn ranks_word_starts_and_runs() {
+        // 连续 > 分散；开头 > 词首 > 中间
+        assert_ranked("doc", &["docs.md", "dxoc.md", "xdxoxc.md"]);
+        // 每个字符都落在词首时比连续的一段还好
+        assert_ranked("doc", &["d-o-c.md", "docs.md"]);
+        assert_ranked("api", &["api.md", "my/api.md", "my-api.md", "rapid.md"]);
+        // 文件名在路径里靠后不吃亏太多：命中文件名的词首比命中目录名中间好
+        assert_ranked("guide", &["guide.md", "a/b/c/d/guide.md", "misguided/x.md"]);
+        // 驼峰的大写字母算词首
+        assert_ranked("qo", &["QuickOpen.vue", "quickopen.vue"]);
+        // 大小写一致时多一点
+        assert_ranked("Readme", &["Readme.md", "readme.md", "README.md"]);
+    }
+
+    #[test]
+    fn indices_follow_the_best_alignment() {
+        assert_eq!(indices("rdme", "README.md"), [0, 3, 4, 5]);
+        // 贪心会取 t(2)，最优的是词首的 t(4)
+        assert_eq!(indices("mt", "mat_time"), [0, 4]);
+        // 连续的一段比零散的几个好
+        assert_eq!(indices("open", "oxpxexn/opener.md"), [8, 9, 10, 11]);
+        assert_eq!(indices("open", "o_p_e_n/opener.md"), [0, 2, 4, 6]);
+        assert_eq!(indices("qo", "QuickOpen"), [0, 5]);
+        // 下标是字符下标，不是字节下标
+        assert_eq!(indices("fé", "Café.md"), [2, 3]);
+        assert_eq!(indices("", "x"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn indices_agree_with_score() {
+        for (pattern, text) in [
+            ("rdme", "README.md"),
+            ("wsi", "src/state/workspace_index.rs"),
+            ("abc", "aXbXc abc"),
+        ] {
+            let (with_indices, found) =
+                match_indices(&Prepared::new(pattern), &Prepared::new(text)).unwrap();
+            assert_eq!(Some(with_indices), score_of(pattern, text));
+            assert_eq!(found.len(), pattern.chars().count());
+            assert!(found.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+}
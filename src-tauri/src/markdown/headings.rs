@@ -0,0 +1,131 @@
+// ─────────────────────────────────────────────
+// 标题结构解析 + GitHub 风格 slug
+// ─────────────────────────────────────────────
+//
+// 为什么不用正则找 `#` 开头的行：
+//   - 代码块里的 `# comment` 不是标题
+//   - setext 标题（下一行是 === / ---）没有 `#`
+//   - front matter 结尾的 `---` 会被误认成 H2
+// 所以直接用 pulldown-cmark 解析，拿到每个标题的字节范围。
+
+use super::{parser_options, LineIndex};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use std::collections::HashMap;
+
+/// 文档中的一个标题
+#[derive(Debug, Clone)]
+pub struct Heading {
+    /// 标题级别 1~6
+    pub level: u8,
+    /// 纯文本标题（去掉了 `**`、`` ` `` 等行内标记）
+    pub text: String,
+    /// GitHub 风格锚点（如 "getting-started"），同一文档内唯一
+    pub slug: String,
+    /// 标题所在行号（1-based）
+    pub line: usize,
+    /// 标题在源文本中的起始字节偏移
+    pub start: usize,
+    /// 本章节结束的字节偏移：下一个同级或更高级标题的起点，没有则为文末
+    pub end: usize,
+    /// 父标题在列表中的下标（最近的、级别更高的标题），顶层为 None
+    pub parent: Option<usize>,
+}
+
+/// 生成 GitHub 风格的 slug（与 github-slugger 的规则一致）
+///
+/// 规则：转小写 → 空格变 `-` → 去掉除字母、数字、`-`、`_` 之外的字符。
+/// 中文等非 ASCII 字母会保留（`char::is_alphanumeric` 是 Unicode 感知的）。
+/// 例如 "Hello, World!" → "hello-world"，"安装 指南" → "安装-指南"
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 同一文档内的 slug 去重器
+///
+/// GitHub 对重复标题依次追加 -1、-2……：
+///   "Usage" → "usage"，第二个 "Usage" → "usage-1"
+#[derive(Default)]
+pub struct SlugCounter {
+    seen: HashMap<String, usize>,
+}
+
+impl SlugCounter {
+    pub fn unique(&mut self, base: &str) -> String {
+        let mut slug = base.to_string();
+        while self.seen.contains_key(&slug) {
+            let count = self.seen.entry(base.to_string()).or_insert(0);
+            *count += 1;
+            slug = format!("{}-{}", base, count);
+        }
+        self.seen.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+/// 解析出文档中的全部标题（按出现顺序）
+///
+/// 同时计算每个标题的章节范围（start..end）和父标题，
+/// 供「按标题读取章节」「面包屑」「兄弟导航」等功能使用。
+pub fn parse_headings(source: &str) -> Vec<Heading> {
+    let lines = LineIndex::new(source);
+    let mut slugs = SlugCounter::default();
+    let mut headings: Vec<Heading> = Vec::new();
+    // 当前正在读取的标题：(级别, 起始偏移, 已收集的文本)
+    let mut current: Option<(u8, usize, String)> = None;
+
+    for (event, range) in Parser::new_ext(source, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some((level as u8, range.start, String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, buf)) = current.as_mut() {
+                    buf.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, start, text)) = current.take() {
+                    let text = text.trim().to_string();
+                    let slug = slugs.unique(&slugify(&text));
+                    headings.push(Heading {
+                        level,
+                        text,
+                        slug,
+                        line: lines.line_of(start),
+                        start,
+                        end: source.len(),
+                        parent: None,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 第二遍：用一个「祖先栈」计算章节结束位置和父标题
+    // 遇到新标题时，栈顶所有级别 >= 它的标题都在这里结束
+    let mut stack: Vec<usize> = Vec::new();
+    for i in 0..headings.len() {
+        let level = headings[i].level;
+        while let Some(&top) = stack.last() {
+            if headings[top].level < level {
+                break;
+            }
+            headings[top].end = headings[i].start;
+            stack.pop();
+        }
+        headings[i].parent = stack.last().copied();
+        stack.push(i);
+    }
+
+    headings
+}
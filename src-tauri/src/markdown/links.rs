@@ -0,0 +1,267 @@
+// ─────────────────────────────────────────────
+// 链接提取 + 路径处理
+// ─────────────────────────────────────────────
+//
+// 只负责「文档里写了哪些链接」，不碰文件系统；
+// 链接能不能解析到真实文件由调用方（commands/link_graph.rs）判断。
+
+use super::{parser_options, LineIndex};
+use pulldown_cmark::{Event, LinkType, Parser, Tag};
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+/// 文档中的一个链接或图片引用
+#[derive(Debug, Clone)]
+pub struct Link {
+    /// 原始目标（如 "../guide/intro.md#install"），引用式链接已被展开
+    pub dest: String,
+    /// 链接所在行号（1-based）
+    pub line: usize,
+    /// 是否是图片 `![alt](src)`
+    pub is_image: bool,
+}
+
+/// 提取文档中所有的链接和图片
+///
+/// 用 pulldown-cmark 解析而不是正则，代码块 / 行内代码里的 `[x](y)` 不会被误认成链接。
+pub fn extract_links(source: &str) -> Vec<Link> {
+    let lines = LineIndex::new(source);
+    Parser::new_ext(source, parser_options())
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::Link { dest_url, .. }) => Some(Link {
+                dest: dest_url.to_string(),
+                line: lines.line_of(range.start),
+                is_image: false,
+            }),
+            Event::Start(Tag::Image { dest_url, .. }) => Some(Link {
+                dest: dest_url.to_string(),
+                line: lines.line_of(range.start),
+                is_image: true,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 是否是外部链接（带 URL scheme，如 https:、mailto:、tel:）
+///
+/// scheme 至少两个字符，避免把 Windows 盘符 "C:\docs" 当成 scheme
+pub fn is_external(dest: &str) -> bool {
+    match dest.find(':') {
+        Some(i) if i >= 2 => {
+            let scheme = &dest[..i];
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        _ => dest.starts_with("//"),
+    }
+}
+
+/// 把链接拆成「路径部分」和「锚点部分」
+///
+/// "a.md#intro" → ("a.md", Some("intro"))，"#intro" → ("", Some("intro"))
+/// 查询参数 "?x=1" 对本地文件没有意义，直接丢掉
+pub fn split_fragment(dest: &str) -> (&str, Option<&str>) {
+    let (path, fragment) = match dest.split_once('#') {
+        Some((p, f)) => (p, Some(f)),
+        None => (dest, None),
+    };
+    let path = path.split_once('?').map_or(path, |(p, _)| p);
+    (path, fragment)
+}
+
+/// 解码 URL 里的 %XX 转义（如 "my%20notes.md" → "my notes.md"）
+///
+/// 非法的转义序列原样保留，解码结果不是合法 UTF-8 时用 U+FFFD 替代
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = |b: u8| (b as char).to_digit(16);
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                out.push((hi * 16 + lo) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 纯字面量地规范化路径：去掉 `.`，把 `a/b/../c` 折叠成 `a/c`
+///
+/// 不访问文件系统（不像 fs::canonicalize 那样要求文件存在、也不解析符号链接），
+/// 所以可以用来判断一个「不存在的」链接目标是否跑出了根目录。
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                // 已经到根（或前缀）了就不能再往上，保留 ".." 让调用方判定越界
+                let at_parent = out.file_name().is_none() || out.ends_with("..");
+                if at_parent || !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// 计算从目录 base 到 target 的相对路径（两者都应是规范化的绝对路径）
+///
+/// 例如 base="/vault/notes"，target="/vault/assets/a.png" → "../assets/a.png"
+/// 结果统一用 `/` 分隔，可以直接写进 Markdown 链接
+pub fn relative_link(base: &Path, target: &Path) -> String {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); base.len() - common];
+    parts.extend(
+        target[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+/// 逐个改写行内链接、图片和引用定义的目标：f 收到解析后的目标，返回 None 表示保持原样
+///
+/// 只替换原文里目标那一段，其余文字一个字节都不动
+pub fn rewrite_links(source: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    rewrite_link_targets(source, |dest, _| f(dest))
+}
+
+/// 同 rewrite_links，f 的第二个参数表示目标是不是图片
+///
+/// 引用定义被 `![x][id]` 用到时算图片
+pub fn rewrite_link_targets(
+    source: &str,
+    mut f: impl FnMut(&str, bool) -> Option<String>,
+) -> String {
+    let parser = Parser::new_ext(source, parser_options());
+    let definitions: Vec<(Range<usize>, String)> = parser
+        .reference_definitions()
+        .iter()
+        .filter_map(|(_, def)| {
+            let start = def.span.start + source[def.span.clone()].find("]:")? + 2;
+            locate_dest(source, start..def.span.end, &def.dest)
+                .map(|range| (range, def.dest.to_string()))
+        })
+        .collect();
+
+    let mut edits: Vec<(Range<usize>, String, bool)> = Vec::new();
+    let mut image_dests: Vec<String> = Vec::new();
+    for (event, range) in parser.into_offset_iter() {
+        let (dest_url, image) = match event {
+            Event::Start(Tag::Link {
+                link_type: LinkType::Inline,
+                dest_url,
+                ..
+            }) => (dest_url, false),
+            Event::Start(Tag::Image {
+                link_type: LinkType::Inline,
+                dest_url,
+                ..
+            }) => (dest_url, true),
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                image_dests.push(dest_url.to_string());
+                continue;
+            }
+            _ => continue,
+        };
+        // 链接文字里可能还有别的 "](" （比如链接里套图片），取最后一个后面紧跟目标的位置
+        let text = &source[range.clone()];
+        let found = text
+            .rmatch_indices("](")
+            .find_map(|(i, _)| locate_dest(source, range.start + i + 2..range.end, &dest_url));
+        if let Some(dest_range) = found {
+            edits.push((dest_range, dest_url.to_string(), image));
+        }
+    }
+    edits.extend(definitions.into_iter().map(|(range, dest)| {
+        let image = image_dests.contains(&dest);
+        (range, dest, image)
+    }));
+
+    edits.sort_by_key(|(range, _, _)| range.start);
+    let mut out = String::with_capacity(source.len());
+    let mut last = 0;
+    for (range, dest, image) in edits {
+        let Some(rebased) = f(&dest, image) else {
+            continue;
+        };
+        if range.start < last {
+            continue;
+        }
+        out.push_str(&source[last..range.start]);
+        out.push_str(&rebased);
+        last = range.end;
+    }
+    out.push_str(&source[last..]);
+    out
+}
+
+/// 在 source[within] 的开头（跳过空白）找到链接目标 dest 的原文范围，`<dest>` 形式包含尖括号
+///
+/// 原文里有反斜杠转义等写法时和解析后的 dest 对不上，返回 None（这个链接就不改写）
+fn locate_dest(source: &str, within: Range<usize>, dest: &str) -> Option<Range<usize>> {
+    if dest.is_empty() {
+        return None;
+    }
+    let text = &source[within.clone()];
+    let rest = text.trim_start();
+    let start = within.start + (text.len() - rest.len());
+    let len = if let Some(inner) = rest.strip_prefix('<') {
+        if !inner.starts_with(dest) || !inner[dest.len()..].starts_with('>') {
+            return None;
+        }
+        dest.len() + 2
+    } else if rest.starts_with(dest) {
+        dest.len()
+    } else {
+        return None;
+    };
+    Some(start..start + len)
+}
+
+/// 把相对链接目标改写成基于 base_dir 的绝对路径：`<绝对路径#锚点>`；不需要改写的返回 None
+///
+/// 用于没有文件位置的文档（比如从 stdin 读进来的）：调用方指定一个目录，
+/// 相对链接就像文档放在这个目录里一样解析，配合 rewrite_links 使用。
+/// 外部链接、纯锚点、已经是绝对路径的保持不变。
+/// 改写后的目标用 `<...>` 包起来，路径里有空格也不会断开。
+pub fn rebase_dest(dest: &str, base_dir: &Path) -> Option<String> {
+    if dest.starts_with('#') || is_external(dest) {
+        return None;
+    }
+    let (path, fragment) = split_fragment(dest);
+    let path = percent_decode(path);
+    if Path::new(&path).is_absolute() || path.starts_with('/') {
+        return None;
+    }
+    let absolute = normalize_path(&base_dir.join(&path))
+        .to_string_lossy()
+        .replace('\\', "/");
+    if absolute.contains(['<', '>']) {
+        return None;
+    }
+    Some(match fragment {
+        Some(f) => format!("<{}#{}>", absolute, f),
+        None => format!("<{}>", absolute),
+    })
+}
@@ -0,0 +1,77 @@
+// ============================
+// Markdown 解析工具（纯逻辑，无 Tauri 依赖）
+// ============================
+//
+// 这里放多个命令共用的解析逻辑（标题结构、slug 生成等），
+// commands/ 下的 IPC 命令只负责参数处理和文件 I/O，再调用这里的函数。
+
+pub mod diff;
+pub mod facets;
+pub mod front_matter;
+pub mod fuzzy;
+pub mod headings;
+pub mod links;
+pub mod query;
+pub mod stats;
+pub mod tasks;
+pub mod vault;
+pub mod wikilinks;
+
+use pulldown_cmark::Options;
+
+/// 后端统一使用的 pulldown-cmark 解析选项
+///
+/// 与前端 markdown-it 的行为尽量保持一致：表格、任务列表、删除线、脚注都开启。
+/// YAML front matter（文件开头 `---` 包裹的块）单独识别，
+/// 否则结尾的 `---` 会被当成 setext 标题的下划线，误把元数据识别成 H2。
+pub fn parser_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+}
+
+/// 字节偏移 → 行号（从 1 开始）的换算表
+///
+/// pulldown-cmark 给出的是字节范围，而前端 / 报告里需要行号。
+/// 预先记录每一行的起始偏移，查询时二分查找，避免每次都从头数换行符。
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Self { line_starts }
+    }
+
+    /// 返回 offset 所在的行号（1-based）
+    pub fn line_of(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        }
+    }
+}
+
+/// 去掉文件开头的 YAML front matter（`---` 包裹的块），返回正文部分
+///
+/// 没有 front matter 或者没有闭合的 `---` 时原样返回
+pub fn strip_front_matter(source: &str) -> &str {
+    let Some(rest) = source
+        .strip_prefix("---\n")
+        .or_else(|| source.strip_prefix("---\r\n"))
+    else {
+        return source;
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        if matches!(line.trim_end(), "---" | "...") {
+            return &rest[offset..];
+        }
+    }
+    source
+}
@@ -0,0 +1,547 @@
+// ─────────────────────────────────────────────
+// 元数据过滤查询："tag:rfc AND status:accepted AND date>=2026-01-01"
+// ─────────────────────────────────────────────
+//
+// 语法（和 GitHub / Obsidian 的搜索框类似）：
+//   tag:rfc              字段等于某个值（不区分大小写）；字段是列表时任一项相等即可
+//   date>=2026-01-01     比较：>= <= > < =，两边都是日期按日期比，都是数字按数字比，否则按文本比
+//   title:"hello world"  值里有空格时加引号
+//   kubernetes           不带字段的词：全文包含（不区分大小写）
+//   a AND b、a b         与（AND 可以省略）
+//   a OR b               或，优先级比 AND 低
+//   NOT a、-a            非
+//   ( ... )              分组
+// AND / OR / NOT 必须大写，小写的当作普通词。
+//
+// 特殊字段：
+//   tag / tags           front matter 标签或正文 #话题
+//   hashtag              只看正文 #话题
+//   category / categories
+//   path:rfcs/**         相对路径的通配符（gitignore 规则：不含 "/" 时匹配任意一级的名字，
+//                        匹配到目录时包括其中所有文件）
+//   heading:summary      有标题包含这段文字（不区分大小写）
+//   modified             文件修改日期
+// 其他字段直接取 front matter 里的同名字段（字段名不区分大小写）。
+//
+// 日期除了 YYYY-MM-DD 还可以写相对日期，每次求值时按当天换算，保存下来的查询不会过期：
+//   today、yesterday、this-week（本周一）、this-month、this-year、-7d、-2w、-3m、-1y
+
+use super::facets::{parse_date, DocumentMeta};
+use crate::git::ignore::wildmatch;
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ge,
+    Le,
+    Gt,
+    Lt,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// 空查询，匹配所有文档
+    All,
+    Text(String),
+    Field {
+        key: String,
+        op: Op,
+        value: String,
+        /// value 是日期（包括换算后的相对日期）时的值
+        date: Option<NaiveDate>,
+    },
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Term(Query),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, String> {
+        Query::parse_at(input, Local::now().date_naive())
+    }
+
+    /// 相对日期按 today 换算
+    pub fn parse_at(input: &str, today: NaiveDate) -> Result<Query, String> {
+        let tokens = tokenize(input, today)?;
+        if tokens.is_empty() {
+            return Ok(Query::All);
+        }
+        let mut parser = QueryParser { tokens, pos: 0 };
+        let query = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(query),
+            Some(Token::Close) => Err("Unbalanced ')' in filter".to_string()),
+            Some(_) => Err("Unexpected token in filter".to_string()),
+        }
+    }
+
+    pub fn matches(&self, doc: &DocumentMeta) -> bool {
+        match self {
+            Query::All => true,
+            Query::Text(word) => doc.text.contains(&word.to_lowercase()),
+            Query::Field {
+                key,
+                op,
+                value,
+                date,
+            } => match key.to_lowercase().as_str() {
+                "path" => *op == Op::Eq && path_matches(&doc.relative_path, value),
+                "heading" => {
+                    let value = value.to_lowercase();
+                    *op == Op::Eq
+                        && doc
+                            .headings
+                            .iter()
+                            .any(|h| h.to_lowercase().contains(&value))
+                }
+                _ => field_values(doc, key)
+                    .iter()
+                    .any(|actual| compare(actual, *op, value, *date)),
+            },
+            Query::Not(inner) => !inner.matches(doc),
+            Query::And(items) => items.iter().all(|q| q.matches(doc)),
+            Query::Or(items) => items.iter().any(|q| q.matches(doc)),
+        }
+    }
+}
+
+/// 字段在文档里的所有取值
+fn field_values(doc: &DocumentMeta, key: &str) -> Vec<String> {
+    match key.to_lowercase().as_str() {
+        "tag" | "tags" => doc.tags.iter().chain(&doc.hashtags).cloned().collect(),
+        "hashtag" | "hashtags" => doc.hashtags.clone(),
+        "category" | "categories" => doc.categories.clone(),
+        "modified" => doc.modified.iter().map(NaiveDate::to_string).collect(),
+        _ => {
+            let fields = &doc.front_matter.fields;
+            let value = fields.get(key).or_else(|| {
+                fields
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                    .map(|(_, v)| v)
+            });
+            match value {
+                Some(Value::Array(items)) => items.iter().filter_map(scalar).collect(),
+                Some(value) => scalar(value).into_iter().collect(),
+                None => Vec::new(),
+            }
+        }
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// gitignore 风格：不含 "/" 的模式匹配任意一级的名字，含 "/" 的从根目录匹配；
+/// 匹配到某个上级目录也算（"docs/rfcs" 包括其中所有文件）；结尾的 "/" 表示只匹配目录
+fn path_matches(relative_path: &str, pattern: &str) -> bool {
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/').as_bytes();
+    let segments: Vec<&str> = relative_path.split('/').collect();
+    // 只匹配目录时不看最后一段（文件名）
+    let count = if dir_only {
+        segments.len() - 1
+    } else {
+        segments.len()
+    };
+    (0..count).any(|i| {
+        if anchored {
+            wildmatch(pattern, segments[..=i].join("/").as_bytes())
+        } else {
+            wildmatch(pattern, segments[i].as_bytes())
+        }
+    })
+}
+
+/// today / this-month / -7d 这类相对日期
+fn relative_date(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match value.to_lowercase().as_str() {
+        "today" => return Some(today),
+        "yesterday" => return today.pred_opt(),
+        "this-week" => {
+            return today.checked_sub_days(Days::new(today.weekday().num_days_from_monday().into()))
+        }
+        "this-month" => return today.with_day(1),
+        "this-year" => return NaiveDate::from_ymd_opt(today.year(), 1, 1),
+        _ => {}
+    }
+    let amount = value.strip_prefix('-')?;
+    let (number, unit) = amount.split_at(amount.len().checked_sub(1)?);
+    let n: u32 = number.parse().ok()?;
+    match unit {
+        "d" => today.checked_sub_days(Days::new(n.into())),
+        "w" => today.checked_sub_days(Days::new(u64::from(n) * 7)),
+        "m" => today.checked_sub_months(Months::new(n)),
+        "y" => today.checked_sub_months(Months::new(n.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+fn compare(actual: &str, op: Op, expected: &str, expected_date: Option<NaiveDate>) -> bool {
+    let ordering = match (parse_date(actual), expected_date) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => match (actual.parse::<f64>(), expected.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            _ => actual.to_lowercase().cmp(&expected.to_lowercase()),
+        },
+    };
+    match op {
+        Op::Eq => ordering == Ordering::Equal,
+        Op::Ge => ordering != Ordering::Less,
+        Op::Le => ordering != Ordering::Greater,
+        Op::Gt => ordering == Ordering::Greater,
+        Op::Lt => ordering == Ordering::Less,
+    }
+}
+
+fn tokenize(input: &str, today: NaiveDate) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                // 一个词：引号里的空白和括号都算词的一部分
+                let mut word = String::new();
+                let mut quoted = false;
+                // 第一个比较符之前是字段名
+                let mut split: Option<(String, Op)> = None;
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        quoted = true;
+                        for c in chars.by_ref() {
+                            if c == '"' {
+                                break;
+                            }
+                            word.push(c);
+                        }
+                        continue;
+                    }
+                    if split.is_none() && !quoted && !word.is_empty() {
+                        let op = match c {
+                            ':' | '=' => Some(Op::Eq),
+                            '>' if chars.next_if_eq(&'=').is_some() => Some(Op::Ge),
+                            '<' if chars.next_if_eq(&'=').is_some() => Some(Op::Le),
+                            '>' => Some(Op::Gt),
+                            '<' => Some(Op::Lt),
+                            _ => None,
+                        };
+                        if let Some(op) = op {
+                            split = Some((std::mem::take(&mut word), op));
+                            continue;
+                        }
+                    }
+                    word.push(c);
+                }
+                let token = match (split, quoted) {
+                    (Some((key, op)), _) => {
+                        if word.is_empty() {
+                            return Err(format!("Missing value after '{}' in filter", key));
+                        }
+                        let date = parse_date(&word).or_else(|| relative_date(&word, today));
+                        Token::Term(Query::Field {
+                            key,
+                            op,
+                            value: word,
+                            date,
+                        })
+                    }
+                    (None, false) if word == "AND" => Token::And,
+                    (None, false) if word == "OR" => Token::Or,
+                    (None, false) if word == "NOT" => Token::Not,
+                    (None, _) => Token::Term(Query::Text(word)),
+                };
+                tokens.push(token);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// 递归下降：or := and (OR and)*；and := unary (AND? unary)*；unary := NOT unary | ( or ) | 词
+struct QueryParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl QueryParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn or(&mut self) -> Result<Query, String> {
+        let mut items = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            items.push(self.and()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Query::Or(items)
+        })
+    }
+
+    fn and(&mut self) -> Result<Query, String> {
+        let mut items = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                Some(Token::Open | Token::Not | Token::Term(_)) => {}
+                _ => break,
+            }
+            items.push(self.unary()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Query::And(items)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Query, String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Not) => Ok(Query::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let inner = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err("Missing ')' in filter".to_string());
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(Token::Term(term)) => Ok(term),
+            Some(_) => Err("Expected a search term in filter".to_string()),
+            None => Err("Filter ends unexpectedly".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        // 星期三
+        NaiveDate::from_ymd_opt(2026, 3, 18).unwrap()
+    }
+
+    fn parse(input: &str) -> Query {
+        Query::parse_at(input, today()).unwrap()
+    }
+
+    fn text(word: &str) -> Query {
+        Query::Text(word.to_string())
+    }
+
+    fn field(key: &str, op: Op, value: &str) -> Query {
+        Query::Field {
+            key: key.to_string(),
+            op,
+            value: value.to_string(),
+            date: parse_date(value),
+        }
+    }
+
+    fn date(query: &Query) -> Option<NaiveDate> {
+        match query {
+            Query::Field { date, .. } => *date,
+            other => panic!("not a field: {:?}", other),
+        }
+    }
+
+    fn doc(path: &str, source: &str) -> DocumentMeta {
+        let mut doc = DocumentMeta::parse(source);
+        doc.relative_path = path.to_string();
+        doc
+    }
+
+    #[test]
+    fn parses_terms_and_fields() {
+        assert_eq!(parse(""), Query::All);
+        assert_eq!(parse("   "), Query::All);
+        assert_eq!(parse("kubernetes"), text("kubernetes"));
+        assert_eq!(parse("tag:rfc"), field("tag", Op::Eq, "rfc"));
+        assert_eq!(parse("status=draft"), field("status", Op::Eq, "draft"));
+        assert_eq!(
+            parse("date>=2026-01-01"),
+            field("date", Op::Ge, "2026-01-01")
+        );
+        assert_eq!(parse("priority<=2"), field("priority", Op::Le, "2"));
+        assert_eq!(parse("priority>2"), field("priority", Op::Gt, "2"));
+        assert_eq!(parse("priority<2"), field("priority", Op::Lt, "2"));
+        // 只有第一个比较符分开字段名和值
+        assert_eq!(
+            parse("url:https://a.b"),
+            field("url", Op::Eq, "https://a.b")
+        );
+        // 引号里的空白、括号、比较符都是值的一部分
+        assert_eq!(
+            parse(r#"title:"hello (big) world""#),
+            field("title", Op::Eq, "hello (big) world")
+        );
+        assert_eq!(parse(r#""a:b c""#), text("a:b c"));
+        // 小写的 and / or / not 和词中间的 "-" 是普通词
+        assert_eq!(
+            parse("and or not a-b"),
+            Query::And(vec![text("and"), text("or"), text("not"), text("a-b")])
+        );
+    }
+
+    #[test]
+    fn operator_precedence() {
+        // AND 可以省略，OR 优先级比 AND 低
+        assert_eq!(
+            parse("a b OR c AND d"),
+            Query::Or(vec![
+                Query::And(vec![text("a"), text("b")]),
+                Query::And(vec![text("c"), text("d")]),
+            ])
+        );
+        // NOT / - 只作用于紧跟的一项
+        assert_eq!(
+            parse("NOT a b"),
+            Query::And(vec![Query::Not(Box::new(text("a"))), text("b")])
+        );
+        assert_eq!(
+            parse("-tag:draft OR b"),
+            Query::Or(vec![
+                Query::Not(Box::new(field("tag", Op::Eq, "draft"))),
+                text("b"),
+            ])
+        );
+        assert_eq!(
+            parse("NOT -a"),
+            Query::Not(Box::new(Query::Not(Box::new(text("a")))))
+        );
+        // 括号改变优先级
+        assert_eq!(
+            parse("a (b OR c)"),
+            Query::And(vec![text("a"), Query::Or(vec![text("b"), text("c")])])
+        );
+        assert_eq!(
+            parse("-(a OR b) c"),
+            Query::And(vec![
+                Query::Not(Box::new(Query::Or(vec![text("a"), text("b")]))),
+                text("c"),
+            ])
+        );
+        assert_eq!(parse("((a))"), text("a"));
+    }
+
+    #[test]
+    fn reports_malformed_queries() {
+        for input in [
+            "tag:",
+            "(a",
+            "a)",
+            "a OR",
+            "NOT",
+            "AND a",
+            "a AND AND b",
+            "()",
+            "-",
+        ] {
+            assert!(
+                Query::parse_at(input, today()).is_err(),
+                "{:?} should not parse",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn relative_dates() {
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+        assert_eq!(date(&parse("modified>=today")), day(2026, 3, 18));
+        assert_eq!(date(&parse("modified>=yesterday")), day(2026, 3, 17));
+        assert_eq!(date(&parse("modified>=this-week")), day(2026, 3, 16));
+        assert_eq!(date(&parse("modified>=this-month")), day(2026, 3, 1));
+        assert_eq!(date(&parse("modified>=this-year")), day(2026, 1, 1));
+        // 值里的 "-" 不是 NOT
+        assert_eq!(date(&parse("modified>=-7d")), day(2026, 3, 11));
+        assert_eq!(date(&parse("modified>=-2w")), day(2026, 3, 4));
+        assert_eq!(date(&parse("modified>=-1m")), day(2026, 2, 18));
+        assert_eq!(date(&parse("modified>=-1y")), day(2025, 3, 18));
+        assert_eq!(date(&parse("modified>=-7x")), None);
+        assert_eq!(date(&parse("status:draft")), None);
+    }
+
+    #[test]
+    fn matches_documents() {
+        let rfc = doc(
+            "docs/rfcs/0001-intro.md",
+            "---\ntags: [rfc, Design]\nstatus: accepted\ndate: 2026-02-01\npriority: 10\n---\n\
+             # Summary\n\nUse Kubernetes everywhere. #infra\n",
+        );
+        let note = doc(
+            "notes/meeting.md",
+            "---\ntags: meeting\npriority: 9\n---\n# Agenda\n\nNothing about k8s.\n",
+        );
+        let hits = |input: &str| -> Vec<bool> {
+            let query = parse(input);
+            vec![query.matches(&rfc), query.matches(&note)]
+        };
+
+        assert_eq!(hits(""), [true, true]);
+        assert_eq!(hits("KUBERNETES"), [true, false]);
+        assert_eq!(hits("tag:design"), [true, false]);
+        assert_eq!(hits("tag:infra"), [true, false]);
+        assert_eq!(hits("hashtag:rfc"), [false, false]);
+        assert_eq!(hits("tag:rfc OR tag:meeting"), [true, true]);
+        assert_eq!(hits("tag:rfc status:accepted"), [true, false]);
+        assert_eq!(hits("-tag:rfc"), [false, true]);
+        assert_eq!(hits("date>=2026-01-01 AND date<2026-03-01"), [true, false]);
+        // 两边都是数字时按数字比：10 > 9
+        assert_eq!(hits("priority>9"), [true, false]);
+        assert_eq!(hits("heading:summ"), [true, false]);
+        assert_eq!(hits("path:rfcs"), [true, false]);
+        assert_eq!(hits("path:docs/**/*.md"), [true, false]);
+        assert_eq!(hits("path:meeting.md"), [false, true]);
+        assert_eq!(hits("path:rfcs/"), [true, false]);
+        assert_eq!(hits("path:notes (tag:rfc OR tag:meeting)"), [false, true]);
+    }
+}
@@ -0,0 +1,135 @@
+// ─────────────────────────────────────────────
+// 文档统计：字数、阅读时间、标题 / 链接 / 代码块等结构计数
+// ─────────────────────────────────────────────
+//
+// 字数只统计渲染后可见的正文（front matter、代码块、HTML 不算），
+// 中日韩文字每个字算一个词，其他文字按空白和标点分隔的连续片段算一个词。
+
+use super::{parser_options, strip_front_matter};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::Serialize;
+
+/// 英文等按词计的阅读速度（词 / 分钟）
+const WORDS_PER_MINUTE: f64 = 200.0;
+/// 中日韩文字的阅读速度（字 / 分钟）
+const CJK_CHARS_PER_MINUTE: f64 = 400.0;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentStats {
+    /// 文件总行数
+    pub lines: usize,
+    /// 文件总字符数（Unicode 字符，不是字节）
+    pub characters: usize,
+    /// 正文字数（中日韩文字逐字计数）
+    pub words: usize,
+    /// 其中中日韩文字的个数
+    pub cjk_characters: usize,
+    /// 预计阅读时间（分钟，向上取整；空文档为 0）
+    pub reading_minutes: u32,
+    pub headings: usize,
+    /// 链接数（不含图片）
+    pub links: usize,
+    pub images: usize,
+    /// 围栏 / 缩进代码块数
+    pub code_blocks: usize,
+    pub tables: usize,
+    /// 任务列表项总数
+    pub tasks: usize,
+    /// 已勾选的任务数
+    pub tasks_done: usize,
+}
+
+/// 统计一篇文档
+pub fn document_stats(source: &str) -> DocumentStats {
+    let mut stats = DocumentStats {
+        lines: source.lines().count(),
+        characters: source.chars().count(),
+        ..Default::default()
+    };
+
+    let mut latin_words = 0;
+    // 代码块里的文字不计入字数
+    let mut in_code_block = false;
+    // 上一段文字是否停在词的中间（`**bold**text` 这种被拆成多个事件的情况不重复计数）
+    let mut mid_word = false;
+
+    for event in Parser::new_ext(strip_front_matter(source), parser_options()) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => stats.headings += 1,
+            Event::Start(Tag::Link { .. }) => stats.links += 1,
+            Event::Start(Tag::Image { .. }) => stats.images += 1,
+            Event::Start(Tag::Table(_)) => stats.tables += 1,
+            Event::Start(Tag::CodeBlock(_)) => {
+                stats.code_blocks += 1;
+                in_code_block = true;
+            }
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::TaskListMarker(done) => {
+                stats.tasks += 1;
+                if done {
+                    stats.tasks_done += 1;
+                }
+            }
+            Event::Text(text) | Event::Code(text) if !in_code_block => {
+                let (latin, cjk, ends_mid_word) = count_words(&text, mid_word);
+                latin_words += latin;
+                stats.cjk_characters += cjk;
+                mid_word = ends_mid_word;
+            }
+            // 行内格式的边界不断词（`**Rust**acean` 算一个词），块级边界和换行一定断词
+            Event::End(
+                TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Link
+                | TagEnd::Image,
+            ) => {}
+            Event::End(_) | Event::SoftBreak | Event::HardBreak => mid_word = false,
+            _ => {}
+        }
+    }
+
+    stats.words = latin_words + stats.cjk_characters;
+    let minutes = latin_words as f64 / WORDS_PER_MINUTE
+        + stats.cjk_characters as f64 / CJK_CHARS_PER_MINUTE;
+    stats.reading_minutes = minutes.ceil() as u32;
+    stats
+}
+
+/// 数一段文字里的词：返回 (非 CJK 词数, CJK 字数, 结尾是否停在词中间)
+///
+/// continues_word 为 true 表示上一段文字停在词中间，这段开头的字母接着算同一个词
+fn count_words(text: &str, continues_word: bool) -> (usize, usize, bool) {
+    let mut latin = 0;
+    let mut cjk = 0;
+    let mut in_word = continues_word;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() || c == '\'' || c == '’' {
+            if !in_word {
+                latin += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+    (latin, cjk, in_word)
+}
+
+/// 是否是中日韩文字（汉字、假名、谚文）
+///
+/// 这些文字之间没有空格分隔，按字计数 / 比较
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}' // CJK 扩展 A
+        | '\u{4E00}'..='\u{9FFF}' // CJK 统一汉字
+        | '\u{AC00}'..='\u{D7AF}' // 谚文音节
+        | '\u{F900}'..='\u{FAFF}' // CJK 兼容汉字
+        | '\u{20000}'..='\u{2FA1F}' // CJK 扩展 B 及以后
+    )
+}
@@ -0,0 +1,202 @@
+// ─────────────────────────────────────────────
+// GFM 任务列表：`- [ ] 待办` / `- [x] 已完成`
+// ─────────────────────────────────────────────
+//
+// 每个带复选框的列表项是一个任务，文字只取它自己的内容（嵌套的子任务单独算）。
+// 任务文字里可以写两种约定的记号：
+//   @alice              负责人，可以有多个；前面必须是空白或行首（邮箱地址不算）
+//   due:2026-03-01      截止日期，必须是合法的 YYYY-MM-DD
+// 行内代码里的内容不识别记号。
+
+use super::headings::parse_headings;
+use super::{parser_options, LineIndex};
+use chrono::NaiveDate;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+/// 文档中的一个任务
+#[derive(Debug, Clone)]
+pub struct TaskItem {
+    pub checked: bool,
+    /// 任务文字（保留 @负责人、due: 等记号，多行合并成一行）
+    pub text: String,
+    /// 列表项所在行号（1-based）
+    pub line: usize,
+    /// 最近的上级标题：(文字, slug)
+    pub heading: Option<(String, String)>,
+    /// 负责人，不含 @，按出现顺序去重
+    pub owners: Vec<String>,
+    pub due: Option<NaiveDate>,
+}
+
+/// 正在读取的列表项；不是任务的列表项也要占一层，保证嵌套关系正确
+struct OpenItem {
+    start: usize,
+    task: Option<(bool, String, String)>,
+}
+
+/// 按出现顺序列出文档里的全部任务
+pub fn extract_tasks(source: &str) -> Vec<TaskItem> {
+    let lines = LineIndex::new(source);
+    let headings = parse_headings(source);
+    let mut open: Vec<OpenItem> = Vec::new();
+    let mut tasks = Vec::new();
+
+    for (event, range) in Parser::new_ext(source, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Item) => open.push(OpenItem {
+                start: range.start,
+                task: None,
+            }),
+            Event::TaskListMarker(checked) => {
+                if let Some(item) = open.last_mut() {
+                    item.task = Some((checked, String::new(), String::new()));
+                }
+            }
+            // 第二个 String 只收普通文字，用来找记号
+            Event::Text(t) => {
+                if let Some((_, text, plain)) = open.last_mut().and_then(|i| i.task.as_mut()) {
+                    text.push_str(&t);
+                    plain.push_str(&t);
+                }
+            }
+            Event::Code(t) => {
+                if let Some((_, text, plain)) = open.last_mut().and_then(|i| i.task.as_mut()) {
+                    text.push_str(&t);
+                    plain.push(' ');
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, text, plain)) = open.last_mut().and_then(|i| i.task.as_mut()) {
+                    text.push(' ');
+                    plain.push(' ');
+                }
+            }
+            Event::End(TagEnd::Item) => {
+                let Some(OpenItem {
+                    start,
+                    task: Some((checked, text, plain)),
+                }) = open.pop()
+                else {
+                    continue;
+                };
+                // 标题按起点排序，最后一个起点在任务之前的就是它所在的章节
+                let heading = headings[..headings.partition_point(|h| h.start <= start)]
+                    .last()
+                    .map(|h| (h.text.clone(), h.slug.clone()));
+                let (owners, due) = tokens(&plain);
+                tasks.push(TaskItem {
+                    checked,
+                    text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                    line: lines.line_of(start),
+                    heading,
+                    owners,
+                    due,
+                });
+            }
+            _ => {}
+        }
+    }
+    // 子任务比父任务先结束，按行号恢复文档顺序
+    tasks.sort_by_key(|t| t.line);
+    tasks
+}
+
+/// 从任务文字里找出 @负责人 和 due:日期（有多个 due: 时取第一个合法的）
+fn tokens(text: &str) -> (Vec<String>, Option<NaiveDate>) {
+    let mut owners: Vec<String> = Vec::new();
+    let mut due = None;
+    for word in text.split_whitespace() {
+        if let Some(name) = word.strip_prefix('@') {
+            // 句末的标点不算名字的一部分："@alice," → "alice"
+            let name = name.trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '_'));
+            let valid = name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+            if !name.is_empty() && valid && !owners.iter().any(|o| o == name) {
+                owners.push(name.to_string());
+            }
+        } else if let Some(date) = word.strip_prefix("due:") {
+            if due.is_none() {
+                due = date
+                    .get(..10)
+                    .filter(|_| !date[10..].starts_with(|c: char| c.is_ascii_digit()))
+                    .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+            }
+        }
+    }
+    (owners, due)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn trims_punctuation_after_owners() {
+        let (owners, _) = tokens("ask @alice, then @bob. and @carol) or @alice!");
+        assert_eq!(owners, ["alice", "bob", "carol"]);
+        // 名字中间的 . 和 - 保留
+        let (owners, _) = tokens("@j.doe-2: review");
+        assert_eq!(owners, ["j.doe-2"]);
+        // 只有标点、前面不是空白（邮箱）的都不算
+        let (owners, _) = tokens("@! mail bob@example.com");
+        assert!(owners.is_empty());
+    }
+
+    #[test]
+    fn accepts_only_valid_due_dates() {
+        assert_eq!(tokens("due:2026-03-01").1, date(2026, 3, 1));
+        // 后面跟标点可以，跟数字不行
+        assert_eq!(tokens("by due:2026-03-01, ok").1, date(2026, 3, 1));
+        assert_eq!(tokens("due:2026-03-011").1, None);
+        // 记号必须在词首
+        assert_eq!(tokens("(due:2026-03-01)").1, None);
+        // 不存在的日期、不补零的写法
+        assert_eq!(tokens("due:2026-02-30").1, None);
+        assert_eq!(tokens("due:2026-3-1").1, None);
+        // 多个 due: 取第一个合法的
+        assert_eq!(
+            tokens("due:tomorrow due:2026-04-02 due:2026-05-01").1,
+            date(2026, 4, 2)
+        );
+    }
+
+    #[test]
+    fn lists_nested_tasks_in_document_order() {
+        let source = "\
+# Plan
+
+- [ ] parent @alice
+  - [x] child `@not-an-owner` due:2026-01-15
+  - plain item
+    - [ ] grandchild
+- [x] sibling
+";
+        let tasks = extract_tasks(source);
+        let summary: Vec<_> = tasks
+            .iter()
+            .map(|t| (t.line, t.checked, t.text.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (3, false, "parent @alice"),
+                (4, true, "child @not-an-owner due:2026-01-15"),
+                (6, false, "grandchild"),
+                (7, true, "sibling"),
+            ]
+        );
+        // 子任务的文字不算进父任务；行内代码里的 @ 不是负责人
+        assert_eq!(tasks[0].owners, ["alice"]);
+        assert!(tasks[1].owners.is_empty());
+        assert_eq!(tasks[1].due, date(2026, 1, 15));
+        assert_eq!(
+            tasks[2].heading,
+            Some(("Plan".to_string(), "plan".to_string()))
+        );
+    }
+}
@@ -0,0 +1,270 @@
+// ─────────────────────────────────────────────
+// Obsidian vault：wiki 链接解析 + 笔记嵌入展开
+// ─────────────────────────────────────────────
+//
+// vault 就是一个带 `.obsidian/` 配置目录的文件夹。
+// 打开 vault 里的文档时，把 Obsidian 专有语法改写成标准 Markdown，前端不需要任何改动：
+//   [[Note#Heading|alias]] → [alias](<../Note.md#heading>)
+//   ![[image.png]]         → ![image.png](<../attachments/image.png>)
+//   ![[Other Note]]        → 直接替换为那篇笔记的正文（递归展开，有深度上限）
+//
+// 名称解析规则（与 Obsidian 一致）：
+//   链接里只写文件名，或写能唯一确定文件的最短路径；
+//   有多个同名文件时，优先当前笔记所在目录，其次路径最短的那个。
+
+use super::headings;
+use super::links::{normalize_path, relative_link};
+use super::strip_front_matter;
+use super::wikilinks::{find_wikilinks, WikiLink};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// 笔记嵌入的最大递归深度（A 嵌入 B，B 再嵌入 C……）
+const MAX_EMBED_DEPTH: usize = 4;
+
+/// vault 扫描时跳过的目录
+const IGNORED_DIRS: [&str; 3] = [".obsidian", ".git", ".trash"];
+
+struct VaultFile {
+    /// 相对 vault 根目录的路径，小写、`/` 分隔，用于匹配
+    key: String,
+    /// 规范化的绝对路径
+    path: PathBuf,
+}
+
+pub struct Vault {
+    root: PathBuf,
+    files: Vec<VaultFile>,
+    /// files 里的所有路径，判断文件在不在索引里用
+    paths: HashSet<PathBuf>,
+    /// `.obsidian/app.json` 里的 attachmentFolderPath（附件默认存放位置）
+    attachment_folder: Option<String>,
+}
+
+impl Vault {
+    /// 从文件所在目录往上找，第一个包含 `.obsidian/` 的目录就是 vault 根目录
+    pub fn detect(file: &Path) -> Option<Vault> {
+        Vault::find_root(file).map(|root| Vault::open(&root))
+    }
+
+    /// 文件所在 vault 的根目录（规范化的路径），不扫描文件
+    pub fn find_root(file: &Path) -> Option<PathBuf> {
+        file.ancestors()
+            .skip(1)
+            .find(|dir| dir.join(".obsidian").is_dir())
+            .map(normalize_path)
+    }
+
+    /// 扫描 vault 下的所有文件（不只是 Markdown，附件也要能被解析到）
+    pub fn open(root: &Path) -> Vault {
+        let root = normalize_path(root);
+        let files: Vec<VaultFile> = WalkDir::new(&root)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                !(e.file_type().is_dir() && IGNORED_DIRS.contains(&name.as_ref()))
+            })
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| {
+                let path = normalize_path(e.path());
+                let key = path
+                    .strip_prefix(&root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .replace('\\', "/")
+                    .to_lowercase();
+                VaultFile { key, path }
+            })
+            .collect();
+
+        Vault {
+            attachment_folder: read_attachment_folder(&root),
+            paths: files.iter().map(|f| f.path.clone()).collect(),
+            root,
+            files,
+        }
+    }
+
+    /// 文件是否已经在名称索引里
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.contains(&normalize_path(path))
+    }
+
+    /// 把 wiki 链接的目标名解析为 vault 中的文件
+    ///
+    /// 没写扩展名时按笔记（.md）处理：[[Note]] 匹配 Note.md
+    pub fn resolve(&self, name: &str, from_file: &Path) -> Option<&Path> {
+        let name = name
+            .trim()
+            .trim_start_matches("./")
+            .trim_start_matches('/')
+            .replace('\\', "/")
+            .to_lowercase();
+        if name.is_empty() {
+            return None;
+        }
+
+        let with_md = format!("{}.md", name);
+        let from_dir = from_file.parent();
+        // 先按「加 .md」找笔记，找不到再按原名找（带扩展名的附件、.markdown 文件等）
+        [with_md.as_str(), name.as_str()]
+            .iter()
+            .find_map(|wanted| {
+                self.files
+                    .iter()
+                    .filter(|f| f.key == *wanted || f.key.ends_with(&format!("/{}", wanted)))
+                    .min_by_key(|f| (f.path.parent() != from_dir, f.key.len(), f.key.clone()))
+            })
+            .map(|f| f.path.as_path())
+    }
+
+    /// 解析嵌入的附件：先看 Obsidian 配置的附件目录，再退回全 vault 按名称查找
+    fn resolve_attachment(&self, name: &str, from_file: &Path) -> Option<PathBuf> {
+        if let Some(folder) = &self.attachment_folder {
+            // "./xxx" 表示相对当前笔记所在目录，其余相对 vault 根目录（"/" 就是根目录本身）
+            let base = match folder.strip_prefix("./") {
+                Some(rel) => from_file.parent().unwrap_or(&self.root).join(rel),
+                None => self.root.join(folder.trim_start_matches('/')),
+            };
+            let candidate = normalize_path(&base.join(name));
+            // "../" 跳出 vault 的不算：嵌入的笔记会被读出来内联，可能跟着导出出去
+            if candidate.starts_with(&self.root) && candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        self.resolve(name, from_file).map(Path::to_path_buf)
+    }
+
+    /// 把文档中的 wiki 链接 / 嵌入改写成标准 Markdown
+    pub fn expand(&self, file: &Path, source: &str) -> String {
+        let file = normalize_path(file);
+        let out_dir = file.parent().unwrap_or(&self.root).to_path_buf();
+        let mut stack = vec![file.clone()];
+        self.expand_inner(&file, source, &out_dir, &mut stack)
+    }
+
+    /// 递归展开
+    ///
+    /// out_dir 始终是「最外层文档」的目录：嵌入进来的笔记最终是在外层文档里渲染的，
+    /// 所以其中的链接也要相对外层文档来写。stack 记录当前嵌入链，用来发现循环嵌入。
+    fn expand_inner(
+        &self,
+        file: &Path,
+        source: &str,
+        out_dir: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> String {
+        let mut out = String::with_capacity(source.len());
+        let mut last = 0;
+        for link in find_wikilinks(source) {
+            out.push_str(&source[last..link.range.start]);
+            out.push_str(&self.rewrite(&link, file, out_dir, stack));
+            last = link.range.end;
+        }
+        out.push_str(&source[last..]);
+        out
+    }
+
+    /// 改写单个 wiki 链接；解析不到目标时保留原文，方便用户看出是哪个链接坏了
+    fn rewrite(
+        &self,
+        link: &WikiLink,
+        file: &Path,
+        out_dir: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> String {
+        let original = || {
+            let prefix = if link.embed { "!" } else { "" };
+            let mut inner = link.target.clone();
+            if let Some(h) = &link.heading {
+                inner.push('#');
+                inner.push_str(h);
+            }
+            if let Some(a) = &link.alias {
+                inner.push('|');
+                inner.push_str(a);
+            }
+            format!("{}[[{}]]", prefix, inner)
+        };
+        let anchor = link
+            .heading
+            .as_ref()
+            .map(|h| format!("#{}", headings::slugify(h)))
+            .unwrap_or_default();
+
+        // [[#Heading]]：同一文档内的标题
+        if link.target.is_empty() {
+            return format!("[{}]({})", link.label(), anchor);
+        }
+
+        let is_note = |p: &Path| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("md"))
+        };
+
+        if link.embed {
+            let Some(target) = self.resolve_attachment(&link.target, file) else {
+                return original();
+            };
+            if !is_note(&target) {
+                // 图片 / 附件：别名通常是尺寸，不适合当 alt 文本
+                return format!("![{}](<{}>)", link.target, relative_link(out_dir, &target));
+            }
+            // 超过深度或出现循环嵌入时，退化为普通链接
+            if stack.len() > MAX_EMBED_DEPTH || stack.contains(&target) {
+                return format!(
+                    "[{}](<{}{}>)",
+                    link.label(),
+                    relative_link(out_dir, &target),
+                    anchor
+                );
+            }
+            let Ok(content) = fs::read_to_string(&target) else {
+                return original();
+            };
+            let body = embedded_section(strip_front_matter(&content), link.heading.as_deref());
+            stack.push(target.clone());
+            let expanded = self.expand_inner(&target, body, out_dir, stack);
+            stack.pop();
+            // 前后空行：嵌入内容按块级元素渲染，不和上下文的段落粘在一起
+            return format!("\n\n{}\n\n", expanded.trim());
+        }
+
+        match self.resolve(&link.target, file) {
+            Some(target) => format!(
+                "[{}](<{}{}>)",
+                link.label(),
+                relative_link(out_dir, target),
+                anchor
+            ),
+            None => original(),
+        }
+    }
+}
+
+/// 取嵌入的部分：指定了标题就只取该标题下的章节，找不到标题时取全文
+fn embedded_section<'a>(body: &'a str, heading: Option<&str>) -> &'a str {
+    let Some(heading) = heading else {
+        return body;
+    };
+    let slug = headings::slugify(heading);
+    headings::parse_headings(body)
+        .into_iter()
+        .find(|h| h.slug == slug)
+        .map_or(body, |h| &body[h.start..h.end])
+}
+
+/// 读取 `.obsidian/app.json` 中的 attachmentFolderPath，没有配置时返回 None
+fn read_attachment_folder(root: &Path) -> Option<String> {
+    let text = fs::read_to_string(root.join(".obsidian/app.json")).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&text).ok()?;
+    json.get("attachmentFolderPath")?
+        .as_str()
+        .map(str::to_string)
+        .filter(|s| !s.is_empty())
+}
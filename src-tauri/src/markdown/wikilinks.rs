@@ -0,0 +1,129 @@
+// ─────────────────────────────────────────────
+// Obsidian 风格 wiki 链接的识别
+// ─────────────────────────────────────────────
+//
+// 支持的写法：
+//   [[Note Name]]              普通链接
+//   [[Note#Heading|alias]]     链接到标题，显示别名
+//   [[Note#^block-id]]         块引用（只能定位到文件）
+//   ![[image.png]]             嵌入图片 / 附件
+//   ![[Other Note#Heading]]    嵌入另一篇笔记（的某一节）
+//
+// CommonMark 不认识 [[ ]]，所以只能在源文本上扫描；
+// 但代码块、行内代码、front matter 里的 [[ ]] 不能动，这些区域先用 pulldown-cmark 找出来。
+
+use super::parser_options;
+use pulldown_cmark::{Event, Parser, Tag};
+use std::ops::Range;
+
+/// 源文本中的一个 wiki 链接
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiLink {
+    /// 在源文本中的字节范围（包含开头的 `!` 和两侧的括号）
+    pub range: Range<usize>,
+    /// 是否是嵌入 `![[...]]`
+    pub embed: bool,
+    /// 目标名称或路径（如 "Note Name"、"folder/Note"、"image.png"）
+    pub target: String,
+    /// `#` 后面的标题（多级 `#A#B` 取最后一级），块引用 `#^id` 不算
+    pub heading: Option<String>,
+    /// `|` 后面的别名；图片嵌入时通常是尺寸（如 "300"）
+    pub alias: Option<String>,
+}
+
+impl WikiLink {
+    /// 链接显示的文字：有别名用别名，否则用目标名（带标题时为 "Note > Heading"）
+    pub fn label(&self) -> String {
+        if let Some(alias) = &self.alias {
+            return alias.clone();
+        }
+        match (&self.heading, self.target.is_empty()) {
+            (Some(h), true) => h.clone(),
+            (Some(h), false) => format!("{} > {}", self.target, h),
+            (None, _) => self.target.clone(),
+        }
+    }
+}
+
+/// 找出源文本中所有的 wiki 链接（按出现顺序）
+pub fn find_wikilinks(source: &str) -> Vec<WikiLink> {
+    let protected = protected_ranges(source);
+    let is_protected = |pos: usize| protected.iter().any(|r| r.contains(&pos));
+
+    let mut found = Vec::new();
+    let mut search_from = 0;
+    while let Some(offset) = source[search_from..].find("[[") {
+        let open = search_from + offset;
+        search_from = open + 2;
+        if is_protected(open) {
+            continue;
+        }
+
+        // 只在同一行内找闭合的 ]]
+        let line_end = source[open..].find('\n').map_or(source.len(), |i| open + i);
+        let Some(close) = source[open + 2..line_end].find("]]") else {
+            continue;
+        };
+        let inner = &source[open + 2..open + 2 + close];
+        let end = open + 2 + close + 2;
+        let embed = open > 0 && source.as_bytes()[open - 1] == b'!';
+        let start = if embed { open - 1 } else { open };
+
+        if let Some(mut link) = parse_inner(inner) {
+            link.range = start..end;
+            link.embed = embed;
+            found.push(link);
+        }
+        search_from = end;
+    }
+    found
+}
+
+/// 解析 [[ ]] 内部的文本：`目标#标题|别名`
+fn parse_inner(inner: &str) -> Option<WikiLink> {
+    // 表格里的 wiki 链接要把 | 写成 \|，这里两种都认
+    let (target_part, alias) = match inner.find("\\|").or_else(|| inner.find('|')) {
+        Some(i) => {
+            let skip = if inner[i..].starts_with("\\|") { 2 } else { 1 };
+            (&inner[..i], Some(inner[i + skip..].trim().to_string()))
+        }
+        None => (inner, None),
+    };
+
+    let (target, heading) = match target_part.split_once('#') {
+        Some((t, rest)) => {
+            let last = rest.rsplit('#').next().unwrap_or(rest).trim();
+            // #^block-id 是块引用，HTML 里没有对应的锚点，只保留文件部分
+            let heading = (!last.is_empty() && !last.starts_with('^')).then(|| last.to_string());
+            (t.trim(), heading)
+        }
+        None => (target_part.trim(), None),
+    };
+
+    if target.is_empty() && heading.is_none() {
+        return None;
+    }
+    Some(WikiLink {
+        range: 0..0,
+        embed: false,
+        target: target.to_string(),
+        heading,
+        alias: alias.filter(|a| !a.is_empty()),
+    })
+}
+
+/// 不应该被改写的区域：代码块、行内代码、front matter、原始 HTML
+fn protected_ranges(source: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    for (event, range) in Parser::new_ext(source, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::CodeBlock(_))
+            | Event::Start(Tag::MetadataBlock(_))
+            | Event::Start(Tag::HtmlBlock)
+            | Event::Code(_)
+            | Event::InlineHtml(_) => ranges.push(range),
+            _ => {}
+        }
+    }
+    ranges
+}
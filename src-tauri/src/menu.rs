@@ -0,0 +1,105 @@
+// ============================
+// 原生菜单：在系统默认菜单的 File 下挂一个「Open Recent」子菜单
+// ============================
+//
+// 菜单项 id 约定：
+//   "recent:open:<绝对路径>" — 打开某个最近条目
+//   "recent:clear"           — 清空最近打开（固定的保留）
+// 点击后通过 recent://open 事件通知前端，由前端复用已有的打开文件 / 文件夹逻辑。
+
+use crate::state::recent::{self, RecentEntry, RecentStore};
+use std::path::Path;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+const RECENT_SUBMENU_ID: &str = "open-recent";
+const OPEN_PREFIX: &str = "recent:open:";
+const CLEAR_ID: &str = "recent:clear";
+
+/// 点击最近条目时发给前端的事件，payload 是对应的 RecentEntry
+pub const RECENT_OPEN_EVENT: &str = "recent://open";
+
+/// 「Open Recent」子菜单的句柄，manage() 之后可以随时往里增删菜单项
+pub struct RecentMenu(pub Submenu<Wry>);
+
+/// 构建应用菜单：系统默认菜单 + File > Open Recent
+pub fn build(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::default(app)?;
+    let recent = Submenu::with_id(app, RECENT_SUBMENU_ID, "Open Recent", true)?;
+
+    // 默认菜单在 Windows / Linux 上已经有 File，macOS 上没有（只有应用名菜单）
+    let file_menu = menu.items()?.into_iter().find_map(|item| {
+        item.as_submenu()
+            .filter(|s| s.text().is_ok_and(|t| t == "File"))
+            .cloned()
+    });
+    match file_menu {
+        Some(file_menu) => file_menu.insert(&recent, 0)?,
+        None => {
+            let file_menu = Submenu::with_items(app, "File", true, &[&recent])?;
+            // macOS 第一个菜单固定是应用名菜单，File 放在它后面
+            let position = if cfg!(target_os = "macos") { 1 } else { 0 };
+            menu.insert(&file_menu, position)?;
+        }
+    }
+
+    app.manage(RecentMenu(recent));
+    Ok(menu)
+}
+
+/// 用最新的列表重建「Open Recent」的菜单项
+pub fn update_recent_menu(app: &AppHandle, entries: &[RecentEntry]) -> tauri::Result<()> {
+    let Some(menu) = app.try_state::<RecentMenu>() else {
+        return Ok(());
+    };
+    let submenu = &menu.0;
+    for item in submenu.items()? {
+        submenu.remove(&item)?;
+    }
+
+    for entry in entries.iter().filter(|e| e.exists) {
+        let id = format!("{}{}", OPEN_PREFIX, entry.path);
+        let item = MenuItem::with_id(app, id, menu_label(entry), true, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+    if !entries.is_empty() {
+        submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    let clear = MenuItem::with_id(app, CLEAR_ID, "Clear Recent", !entries.is_empty(), None::<&str>)?;
+    submenu.append(&clear)?;
+    Ok(())
+}
+
+/// 菜单点击事件的统一入口（在 lib.rs 里通过 on_menu_event 注册）
+pub fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    if id == CLEAR_ID {
+        if let Some(store) = app.try_state::<RecentStore>() {
+            if store.clear().is_ok() {
+                recent::notify_changed(app);
+            }
+        }
+    } else if let Some(path) = id.strip_prefix(OPEN_PREFIX) {
+        let entry = app
+            .try_state::<RecentStore>()
+            .and_then(|store| store.list().into_iter().find(|e| e.path == path));
+        if let Some(entry) = entry {
+            let _ = app.emit(RECENT_OPEN_EVENT, &entry);
+        }
+    }
+}
+
+/// 菜单上显示的文字：「名称 — 所在目录」，固定的条目前面加 📌
+fn menu_label(entry: &RecentEntry) -> String {
+    let path = Path::new(&entry.path);
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| entry.path.clone());
+    let parent = path
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let pin = if entry.pinned { "📌 " } else { "" };
+    format!("{}{} — {}", pin, name, parent)
+}
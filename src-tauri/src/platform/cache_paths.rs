@@ -1,8 +1,22 @@
 use std::path::PathBuf;
 
+/// A profile is a single directory name under the browser's user data dir, e.g. "Default"
+/// or "Profile 1". Anything that could step outside that dir (separators, "..", a Windows
+/// drive prefix) is rejected.
+pub fn is_valid_profile(profile: &str) -> bool {
+    !profile.is_empty()
+        && profile != "."
+        && profile != ".."
+        && !profile.contains(['/', '\\', ':'])
+}
+
 /// Returns (category_name, path) pairs for cache directories of a given browser.
+/// An invalid profile name yields no categories.
 pub fn get_cache_categories_for_browser(browser: &str, profile: &str) -> Vec<(String, PathBuf)> {
     let mut categories = Vec::new();
+    if !is_valid_profile(profile) {
+        return categories;
+    }
     let home = dirs::home_dir().unwrap_or_default();
 
     #[cfg(target_os = "macos")]
@@ -46,6 +60,23 @@ pub fn get_cache_categories_for_browser(browser: &str, profile: &str) -> Vec<(St
         ));
     }
 
+    #[cfg(target_os = "linux")]
+    {
+        // Linux has no Canary channel; "Chrome Canary" maps to the unstable (dev) build.
+        let name = match browser {
+            "Chrome Canary" => "google-chrome-unstable",
+            _ => "google-chrome",
+        };
+        let cache_home = dirs::cache_dir().unwrap_or_else(|| home.join(".cache"));
+        let config_home = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+        let caches = cache_home.join(name).join(profile);
+        let user_data = config_home.join(name).join(profile);
+
+        categories.push(("HTTP Cache".to_string(), caches.join("Cache/Cache_Data")));
+        categories.push(("Code Cache".to_string(), caches.join("Code Cache")));
+        categories.push(("GPU Cache".to_string(), user_data.join("GPUCache")));
+    }
+
     categories
 }
 
@@ -71,6 +102,17 @@ pub fn get_browser_base_dir(browser: &str) -> PathBuf {
         };
         local_app_data.join(name)
     }
+
+    #[cfg(target_os = "linux")]
+    {
+        let name = match browser {
+            "Chrome Canary" => "google-chrome-unstable",
+            _ => "google-chrome",
+        };
+        dirs::config_dir()
+            .unwrap_or_else(|| home.join(".config"))
+            .join(name)
+    }
 }
 
 /// Detects which browsers are installed.
@@ -0,0 +1,32 @@
+// ─────────────────────────────────────────────
+// 控制台（Windows）：让无窗口子命令的输出能在终端里看到
+// ─────────────────────────────────────────────
+//
+// release 构建用 windows_subsystem = "windows"（双击启动时不弹黑框），
+// 代价是进程没有控制台，list / stats / cache / export 的 stdout、stderr 都被丢掉。
+// 子命令执行前挂到启动它的终端（父进程）的控制台上，输出就能正常显示。
+//
+// 限制：cmd.exe 不会等 GUI 子系统的程序结束，输出可能和下一个提示符交错；
+// 需要可靠的输出（重定向、管道、脚本里用退出码）时用 `start /wait` 或从 PowerShell 调用。
+//
+// 其他平台上是空操作。
+
+/// 挂到父进程的控制台；父进程没有控制台（比如从资源管理器启动）时什么也不做
+#[cfg(windows)]
+pub fn attach_parent_console() {
+    /// AttachConsole 的「父进程」参数，即 (DWORD)-1
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // 失败（没有父控制台、已经有控制台）都不影响子命令本身
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_parent_console() {}
@@ -1 +1,3 @@
 pub mod cache_paths;
+pub mod console;
+pub mod single_instance;
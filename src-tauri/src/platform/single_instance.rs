@@ -0,0 +1,235 @@
+// ─────────────────────────────────────────────
+// 单实例（Linux）：第二次启动时把参数转发给已运行的实例
+// ─────────────────────────────────────────────
+//
+// 机制：Unix domain socket，路径 $XDG_RUNTIME_DIR/cc-md-preview.sock
+//   1. 启动时先尝试连接 socket：连得上说明已有实例在运行，
+//      把 { cwd, args, stdin } 作为一行 JSON 发过去，等对方回 "ok" 后直接退出
+//   2. 连不上就自己成为主实例：在建窗口之前就 bind socket，
+//      窗口建好后后台线程开始接收其他进程转发来的参数
+//
+// 两个进程同时启动时不能都当主实例：上面两步在同目录下 cc-md-preview.lock 的文件锁（flock）里做，
+// 后拿到锁的进程一定能连上先拿到锁的那个。已有的 socket 文件只在连接被拒绝
+// （上次崩溃留下的）时才删掉，活着的主实例的 socket 不会被别人删掉。
+//
+// XDG_RUNTIME_DIR 只有当前用户可读写（0700），其他用户无法连上来。
+// 没有这个变量时退回临时目录下的 cc-md-preview-<用户名>/，目录以 0700 创建，
+// 已存在时要求属于当前用户且别人没有任何权限，否则不启用单实例。
+//
+// 其他平台上这些函数都是空操作（总是作为独立实例运行）。
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// 转发给主实例的内容
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Forwarded {
+    /// 第二个进程的工作目录，用来解析相对路径
+    pub cwd: PathBuf,
+    /// 第二个进程的参数（不含程序名）
+    pub args: Vec<String>,
+    /// 参数里有 "-" 时，第二个进程从 stdin 读到的内容
+    #[serde(default)]
+    pub stdin: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{Claim, Forwarded};
+    use crate::cli::{self, Cli};
+    use clap::Parser;
+    use std::fs::{self, DirBuilder, File, OpenOptions};
+    use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+    use tauri::AppHandle;
+
+    const SOCKET_NAME: &str = "cc-md-preview.sock";
+    const LOCK_NAME: &str = "cc-md-preview.lock";
+
+    /// 本进程是否绑定了 socket；--standalone 启动的实例退出时不能删掉主实例的 socket
+    static LISTENING: AtomicBool = AtomicBool::new(false);
+
+    /// 主实例已经 bind 好、还没开始接收的 socket
+    pub struct Listener(UnixListener);
+
+    /// socket 和锁文件所在的目录，只有当前用户能访问
+    fn socket_dir() -> io::Result<PathBuf> {
+        if let Some(dir) = dirs::runtime_dir() {
+            return Ok(dir);
+        }
+        // 没有 XDG_RUNTIME_DIR：用 $USER 区分不同用户的目录
+        let user = std::env::var("USER").unwrap_or_else(|_| "default".to_string());
+        let dir = std::env::temp_dir().join(format!("cc-md-preview-{}", user));
+        match DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+        // 别人抢先建的同名目录（或指向别处的符号链接）不能用
+        let meta = fs::symlink_metadata(&dir)?;
+        // SAFETY: geteuid 没有参数，也不会失败
+        let uid = unsafe { libc::geteuid() };
+        if !meta.is_dir() || meta.uid() != uid || meta.mode() & 0o077 != 0 {
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                format!("'{}' is not a private directory", dir.display()),
+            ));
+        }
+        Ok(dir)
+    }
+
+    /// 拿到目录下锁文件的排他锁；File 被 drop（关闭）时自动释放
+    fn lock(dir: &Path) -> io::Result<File> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .mode(0o600)
+            .open(dir.join(LOCK_NAME))?;
+        // SAFETY: fd 在 file 的生命周期内一直有效
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(file)
+    }
+
+    pub fn claim(message: &Forwarded) -> Claim<Listener> {
+        match try_claim(message) {
+            Ok(claim) => claim,
+            Err(e) => {
+                eprintln!("cc-md-preview: single-instance socket disabled: {}", e);
+                Claim::Unavailable
+            }
+        }
+    }
+
+    fn try_claim(message: &Forwarded) -> io::Result<Claim<Listener>> {
+        let dir = socket_dir()?;
+        let path = dir.join(SOCKET_NAME);
+        let _lock = lock(&dir)?;
+        if forward(&path, message) {
+            return Ok(Claim::Forwarded);
+        }
+        match UnixStream::connect(&path) {
+            // 没人在监听：上次崩溃留下的 socket 文件，删掉重建
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                let _ = fs::remove_file(&path);
+            }
+            Err(_) => {}
+            // 有实例在监听，但没有按时确认（还在启动或卡住了）：不抢它的 socket
+            Ok(_) => {
+                return Err(io::Error::new(
+                    ErrorKind::AddrInUse,
+                    "the running instance did not respond",
+                ))
+            }
+        }
+        let listener = UnixListener::bind(&path)?;
+        LISTENING.store(true, Ordering::SeqCst);
+        Ok(Claim::Primary(Listener(listener)))
+    }
+
+    fn forward(path: &Path, message: &Forwarded) -> bool {
+        let Ok(mut stream) = UnixStream::connect(path) else {
+            return false;
+        };
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+        let Ok(mut line) = serde_json::to_string(message) else {
+            return false;
+        };
+        line.push('\n');
+        if stream.write_all(line.as_bytes()).is_err() {
+            return false;
+        }
+
+        // 等主实例确认收到；超时或连接被关闭都当作转发失败
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == "ok"
+    }
+
+    pub fn listen(app: &AppHandle, listener: Listener) {
+        let handle = app.clone();
+        thread::spawn(move || {
+            for stream in listener.0.incoming().flatten() {
+                handle_client(&handle, stream);
+            }
+        });
+    }
+
+    fn handle_client(app: &AppHandle, stream: UnixStream) {
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+        let mut reader = BufReader::new(&stream);
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() {
+            return;
+        }
+        let Ok(message) = serde_json::from_str::<Forwarded>(&line) else {
+            return;
+        };
+        let _ = (&stream).write_all(b"ok\n");
+
+        // 参数解析失败（比如拼错了选项）时忽略这次转发，错误已经在第二个进程那边报过了
+        let args = std::iter::once("cc-md-preview".to_string()).chain(message.args);
+        if let Ok(parsed) = Cli::try_parse_from(args) {
+            let _ = cli::dispatch(app, parsed.into_request(&message.cwd, message.stdin));
+        }
+    }
+
+    pub fn cleanup() {
+        if LISTENING.load(Ordering::SeqCst) {
+            if let Ok(dir) = socket_dir() {
+                let _ = fs::remove_file(dir.join(SOCKET_NAME));
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::{Claim, Forwarded};
+    use tauri::AppHandle;
+
+    pub struct Listener;
+
+    pub fn claim(_message: &Forwarded) -> Claim<Listener> {
+        Claim::Unavailable
+    }
+
+    pub fn listen(_app: &AppHandle, _listener: Listener) {}
+
+    pub fn cleanup() {}
+}
+
+pub use imp::Listener;
+
+/// claim() 的结果
+pub enum Claim<L> {
+    /// 参数已经交给运行中的实例，当前进程应该退出
+    Forwarded,
+    /// 当前进程成为主实例，窗口建好后把 socket 交给 listen()
+    Primary(L),
+    /// 单实例不可用（目录不安全、socket 被占着等），作为独立实例运行
+    Unavailable,
+}
+
+/// 把参数转发给已经在运行的实例；没有的话 bind socket 成为主实例
+pub fn claim(message: &Forwarded) -> Claim<Listener> {
+    imp::claim(message)
+}
+
+/// 主实例：开始接收其他进程转发来的参数
+pub fn listen(app: &AppHandle, listener: Listener) {
+    imp::listen(app, listener)
+}
+
+/// 退出时删除 socket 文件
+pub fn cleanup() {
+    imp::cleanup()
+}
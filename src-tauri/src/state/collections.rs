@@ -0,0 +1,179 @@
+// ─────────────────────────────────────────────
+// 智能集合：对打开的文件夹实时求值
+// ─────────────────────────────────────────────
+//
+// 集合的定义（名字 + 查询）存在设置里（settings.collections），这里维护的是打开的文件夹的索引：
+// 每个 Markdown 文件的元数据（DocumentMeta）常驻内存，求值时不用再读文件。
+//
+// 文件变化后只重新读变化了的文件（见 watch.rs），处理完广播 collections://changed，
+// payload 是各集合的最新计数。
+
+use super::settings::{SettingsStore, SmartCollection};
+use super::watch::{watch_folder, Changes};
+use crate::commands::markdown::collect_markdown_paths;
+use crate::markdown::facets::DocumentMeta;
+use crate::markdown::links::normalize_path;
+use crate::markdown::query::Query;
+use notify::RecommendedWatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 集合计数变化时广播的事件名，payload 是 CollectionsUpdate
+pub const COLLECTIONS_CHANGED_EVENT: &str = "collections://changed";
+
+/// 一个集合在当前文件夹里的匹配数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionCount {
+    pub id: String,
+    pub name: String,
+    pub count: usize,
+    /// 查询写错时的错误信息，此时 count 为 0
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionsUpdate {
+    /// 求值的文件夹
+    pub root: String,
+    pub collections: Vec<CollectionCount>,
+}
+
+/// 打开的文件夹的索引
+struct Indexed {
+    root: PathBuf,
+    /// 文件 → 元数据，BTreeMap 保证结果按路径有序
+    docs: BTreeMap<PathBuf, DocumentMeta>,
+    /// 监听器被 drop 就会停止监听，所以要一直持有它
+    _watcher: Option<RecommendedWatcher>,
+}
+
+// 在 lib.rs 里 manage()；一次只索引一个文件夹，打开别的文件夹时替换
+#[derive(Default)]
+pub struct CollectionIndex {
+    current: Mutex<Option<Indexed>>,
+}
+
+impl CollectionIndex {
+    /// 索引 root 并开始监听；已经是当前文件夹时什么都不做
+    ///
+    /// 扫描时不持有锁，扫描期间计数照常返回上一个文件夹的结果
+    pub fn open(&self, app: &AppHandle, root: &Path) -> Result<(), String> {
+        if !root.is_dir() {
+            return Err(format!("'{}' is not a directory", root.display()));
+        }
+        let root = normalize_path(root);
+        let root = root.as_path();
+        let is_current =
+            |current: &Option<Indexed>| current.as_ref().is_some_and(|c| c.root == root);
+        if is_current(&*self.current.lock().map_err(|e| e.to_string())?) {
+            return Ok(());
+        }
+        let docs = collect_markdown_paths(root)
+            .into_iter()
+            .filter_map(|path| Some((path.clone(), DocumentMeta::read(root, &path)?)))
+            .collect();
+        let watcher = watch_root(app, root);
+        let mut current = self.current.lock().map_err(|e| e.to_string())?;
+        // 扫描期间别的调用已经索引好了
+        if is_current(&current) {
+            return Ok(());
+        }
+        *current = Some(Indexed {
+            root: root.to_path_buf(),
+            docs,
+            _watcher: watcher,
+        });
+        Ok(())
+    }
+
+    /// 每个集合的匹配数；还没打开文件夹时返回 None
+    pub fn counts(&self, collections: &[SmartCollection]) -> Option<CollectionsUpdate> {
+        let current = self.current.lock().ok()?;
+        let indexed = current.as_ref()?;
+        let collections = collections
+            .iter()
+            .map(|collection| {
+                let (count, error) = match Query::parse(&collection.query) {
+                    Ok(query) => (
+                        indexed.docs.values().filter(|d| query.matches(d)).count(),
+                        None,
+                    ),
+                    Err(e) => (0, Some(e)),
+                };
+                CollectionCount {
+                    id: collection.id.clone(),
+                    name: collection.name.clone(),
+                    count,
+                    error,
+                }
+            })
+            .collect();
+        Some(CollectionsUpdate {
+            root: indexed.root.to_string_lossy().to_string(),
+            collections,
+        })
+    }
+
+    /// 集合在 root 里匹配的文件，按路径排序；root 不是当前索引的文件夹时先索引它
+    pub fn matching(
+        &self,
+        app: &AppHandle,
+        root: &Path,
+        collection: &SmartCollection,
+    ) -> Result<Vec<PathBuf>, String> {
+        let query = Query::parse(&collection.query)?;
+        self.open(app, root)?;
+        let current = self.current.lock().map_err(|e| e.to_string())?;
+        Ok(current
+            .iter()
+            .flat_map(|indexed| &indexed.docs)
+            .filter(|(_, doc)| query.matches(doc))
+            .map(|(path, _)| path.clone())
+            .collect())
+    }
+
+    /// 处理 root 下的一批文件变化，索引有改动时返回 true
+    ///
+    /// 读文件时不持有锁；读完时已经换了文件夹的话丢掉这批结果
+    fn apply(&self, root: &Path, paths: Vec<PathBuf>) -> bool {
+        let changes = Changes::read(root, paths, |path| DocumentMeta::read(root, path));
+        let Ok(mut current) = self.current.lock() else {
+            return false;
+        };
+        match current.as_mut() {
+            Some(indexed) if indexed.root == root => changes.apply(&mut indexed.docs),
+            _ => false,
+        }
+    }
+}
+
+fn watch_root(app: &AppHandle, root: &Path) -> Option<RecommendedWatcher> {
+    let handle = app.clone();
+    let watched = root.to_path_buf();
+    watch_folder(root, move |paths| {
+        let Some(index) = handle.try_state::<CollectionIndex>() else {
+            return;
+        };
+        if index.apply(&watched, paths) {
+            notify_changed(&handle);
+        }
+    })
+}
+
+/// 集合定义或文件变化后：重新计数并广播 collections://changed
+pub fn notify_changed(app: &AppHandle) {
+    let (Some(index), Some(settings)) = (
+        app.try_state::<CollectionIndex>(),
+        app.try_state::<SettingsStore>(),
+    ) else {
+        return;
+    };
+    if let Some(update) = index.counts(&settings.get().collections) {
+        let _ = app.emit(COLLECTIONS_CHANGED_EVENT, &update);
+    }
+}
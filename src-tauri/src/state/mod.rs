@@ -0,0 +1,41 @@
+// ============================
+// 持久化状态（设置等），保存为 JSON 文件
+// ============================
+//
+// 每种状态一个子模块，各自负责：数据结构、版本迁移、读写文件。
+// collections、workspace、roots 例外：它们不落盘，只在内存里维护打开的文件夹的索引。
+// 这里只放它们共用的文件读写工具。
+
+pub mod collections;
+pub mod recent;
+pub mod roots;
+pub mod session;
+pub mod settings;
+pub mod watch;
+pub mod workspace;
+
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// 原子地写入 JSON 文件
+///
+/// 先写到同目录下的临时文件并 fsync，再 rename 覆盖目标文件。
+/// rename 在同一文件系统内是原子操作：即使写到一半崩溃，
+/// 磁盘上要么是旧文件、要么是完整的新文件，不会出现被截断的 JSON。
+pub fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+
+    let tmp = path.with_extension("json.tmp");
+    let mut file =
+        fs::File::create(&tmp).map_err(|e| format!("Failed to write '{}': {}", tmp.display(), e))?;
+    file.write_all(&json)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write '{}': {}", tmp.display(), e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
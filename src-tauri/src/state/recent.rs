@@ -0,0 +1,247 @@
+// ─────────────────────────────────────────────
+// 最近打开：文件和文件夹的 MRU（最近最常用）列表
+// ─────────────────────────────────────────────
+//
+// 文件位置：<app_data_dir>/recent.json
+//
+// 记录入口：read_markdown_file、list_markdown_files（拖放进来的文件 / 文件夹最终也走这两个命令）
+// 列表规则：
+//   - 最近打开的排在最前面；再次打开已有条目时移到最前并累加次数
+//   - 未固定的条目最多保留 MAX_RECENT 个，超出的从末尾淘汰
+//   - 固定（pinned）的条目不受数量限制，也不会被淘汰
+// 保存时机：
+//   - record 只改内存并标记为 dirty；track 在 FLUSH_DELAY 后统一落盘并重建一次菜单，
+//     连续打开、监听器重读、会话恢复等短时间内的多次记录合并成一次写入
+//   - 固定 / 删除 / 清空是用户的显式操作，立即保存
+//   - 应用正常退出时再落盘一次
+
+use super::write_json_atomic;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 未固定条目的数量上限
+const MAX_RECENT: usize = 30;
+
+const RECENT_FILE: &str = "recent.json";
+
+/// 记录打开后，等待这么久再落盘和重建菜单
+const FLUSH_DELAY: Duration = Duration::from_secs(1);
+
+/// 列表变化时广播的事件名，payload 是完整的 Vec<RecentEntry>
+pub const RECENT_CHANGED_EVENT: &str = "recent://changed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecentKind {
+    File,
+    Folder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentEntry {
+    /// 绝对路径
+    pub path: String,
+    pub kind: RecentKind,
+    /// 累计打开次数
+    pub open_count: u32,
+    /// 最后一次打开的时间（序列化为 RFC 3339 字符串）
+    pub last_opened: DateTime<Local>,
+    /// 是否固定在列表顶部
+    pub pinned: bool,
+    /// 目标当前是否还存在（每次读取列表时重新检查，文件被删 / 移动后为 false）
+    #[serde(default)]
+    pub exists: bool,
+}
+
+/// recent.json 的文件格式
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RecentFile {
+    version: u32,
+    entries: Vec<RecentEntry>,
+}
+
+struct Inner {
+    entries: Vec<RecentEntry>,
+    /// 内存中有没落盘的改动
+    dirty: bool,
+    /// 已经有一个延迟落盘的线程在等待
+    scheduled: bool,
+}
+
+pub struct RecentStore {
+    path: PathBuf,
+    inner: Mutex<Inner>,
+}
+
+impl RecentStore {
+    pub fn init(app: &AppHandle) -> Result<RecentStore, String> {
+        let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+        let path = dir.join(RECENT_FILE);
+        // 文件不存在或损坏都当作空列表，最近打开不是关键数据
+        let entries = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<RecentFile>(&text).ok())
+            .map(|f| f.entries)
+            .unwrap_or_default();
+        Ok(RecentStore {
+            path,
+            inner: Mutex::new(Inner {
+                entries,
+                dirty: false,
+                scheduled: false,
+            }),
+        })
+    }
+
+    /// 当前列表：固定的在前，其余按最近打开排序；顺便刷新 exists
+    pub fn list(&self) -> Vec<RecentEntry> {
+        let Ok(inner) = self.inner.lock() else {
+            return Vec::new();
+        };
+        let mut list: Vec<RecentEntry> = inner
+            .entries
+            .iter()
+            .cloned()
+            .map(|mut e| {
+                e.exists = Path::new(&e.path).exists();
+                e
+            })
+            .collect();
+        // sort_by_key 是稳定排序：同一组内保持原有的最近优先顺序
+        list.sort_by_key(|e| !e.pinned);
+        list
+    }
+
+    /// 记录一次打开（只改内存，由 flush 负责落盘）
+    pub fn record(&self, path: &str, kind: RecentKind) -> Result<(), String> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        let entries = &mut inner.entries;
+        let mut entry = match entries.iter().position(|e| e.path == path) {
+            Some(i) => entries.remove(i),
+            None => RecentEntry {
+                path: path.to_string(),
+                kind,
+                open_count: 0,
+                last_opened: Local::now(),
+                pinned: false,
+                exists: true,
+            },
+        };
+        entry.kind = kind;
+        entry.open_count += 1;
+        entry.last_opened = Local::now();
+        entries.insert(0, entry);
+
+        // 淘汰超出上限的未固定条目（从最旧的开始）
+        let mut unpinned = 0;
+        entries.retain(|e| {
+            if e.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= MAX_RECENT
+        });
+        inner.dirty = true;
+        Ok(())
+    }
+
+    pub fn set_pinned(&self, path: &str, pinned: bool) -> Result<(), String> {
+        self.modify(|entries| {
+            if let Some(e) = entries.iter_mut().find(|e| e.path == path) {
+                e.pinned = pinned;
+            }
+        })
+    }
+
+    pub fn remove(&self, path: &str) -> Result<(), String> {
+        self.modify(|entries| entries.retain(|e| e.path != path))
+    }
+
+    /// 清空列表（固定的条目保留）
+    pub fn clear(&self) -> Result<(), String> {
+        self.modify(|entries| entries.retain(|e| e.pinned))
+    }
+
+    /// 有未保存的改动时写入磁盘
+    pub fn flush(&self) -> Result<(), String> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        if !inner.dirty {
+            return Ok(());
+        }
+        self.save(&mut inner)
+    }
+
+    /// 标记有延迟落盘在等待；已经有了就返回 false
+    fn schedule(&self) -> bool {
+        let Ok(mut inner) = self.inner.lock() else {
+            return false;
+        };
+        !std::mem::replace(&mut inner.scheduled, true)
+    }
+
+    /// 延迟时间到：落盘并清除等待标记
+    fn flush_scheduled(&self) -> Result<(), String> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        inner.scheduled = false;
+        if !inner.dirty {
+            return Ok(());
+        }
+        self.save(&mut inner)
+    }
+
+    /// 修改列表并立即保存
+    fn modify(&self, f: impl FnOnce(&mut Vec<RecentEntry>)) -> Result<(), String> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        f(&mut inner.entries);
+        self.save(&mut inner)
+    }
+
+    fn save(&self, inner: &mut Inner) -> Result<(), String> {
+        write_json_atomic(
+            &self.path,
+            &RecentFile {
+                version: 1,
+                entries: inner.entries.clone(),
+            },
+        )?;
+        inner.dirty = false;
+        Ok(())
+    }
+}
+
+/// 记录一次打开，FLUSH_DELAY 后再落盘并同步原生菜单和前端
+///
+/// 记录失败（磁盘写不进去等）不应该影响文件本身的打开，所以这里不返回错误
+pub fn track(app: &AppHandle, path: &str, kind: RecentKind) {
+    let Some(store) = app.try_state::<RecentStore>() else {
+        return;
+    };
+    if store.record(path, kind).is_err() || !store.schedule() {
+        return;
+    }
+    let handle = app.clone();
+    thread::spawn(move || {
+        thread::sleep(FLUSH_DELAY);
+        if let Some(store) = handle.try_state::<RecentStore>() {
+            let _ = store.flush_scheduled();
+            notify_changed(&handle);
+        }
+    });
+}
+
+/// 列表变化后：重建「Open Recent」菜单 + 广播 recent://changed
+pub fn notify_changed(app: &AppHandle) {
+    let Some(store) = app.try_state::<RecentStore>() else {
+        return;
+    };
+    let entries = store.list();
+    let _ = crate::menu::update_recent_menu(app, &entries);
+    let _ = app.emit(RECENT_CHANGED_EVENT, &entries);
+}
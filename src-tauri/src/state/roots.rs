@@ -0,0 +1,270 @@
+// ─────────────────────────────────────────────
+// 按根目录缓存的文件夹信息：vault 名称索引、文档站点、文件树的 stat 信息
+// ─────────────────────────────────────────────
+//
+// 几份缓存都是进程级的静态变量，读取和建立监听都在锁外面做，
+// 监听到变化只标记过期（监听器保留），下次用到时重新读取。
+// 每份缓存最多保留 MAX_CACHED_ROOTS 个根目录，超出时去掉最久没用过的，它的监听器也随之停止。
+//
+// vault：
+// load_document 每读一篇 vault 里的文档都要用 vault 的文件名索引解析 wiki 链接，
+// 每次都重新扫描的话，几千篇笔记的 vault 打开一篇文档就要遍历全部文件。
+// 这里按 vault 根目录缓存扫描结果，并监听整个 vault：
+//   - 只是文件内容变了：名称索引不受影响，忽略
+//   - 新增、删除、改名，或 .obsidian/app.json 变了：标记过期，下次用到时重新扫描（监听器保留）
+// load_document 没有 AppHandle（导出、CLI 也调用它），所以不放在 Tauri 的托管状态里。
+//
+// 文档站点：
+// load_document 每读一篇文档都要知道它在不在文档站点里。找站点根目录只看配置文件在不在，
+// 打开站点（读配置、解析 JS / YAML）的结果按根目录缓存；根目录下 Markdown 以外的文件
+// （配置文件、目录）有变化时标记过期。Docusaurus 的路由还取决于文档的 front matter（id、slug），
+// 所以 Docusaurus 站点里 Markdown 文件有变化也要标记过期。
+//
+// 文件树（get_markdown_tree）：
+// 目录的文件数、总大小、最新修改时间要看整个子树，每展开一层都遍历一遍的话，
+// 大文件夹里逐层点开就是反复遍历同一批文件。这里按根目录缓存全部 Markdown 文件的
+// (路径, 大小, 修改时间)，展开时只在内存里筛选；Markdown 文件或目录有变化就标记过期
+// （.git 里的变化、其他类型的文件不算）。
+
+use super::watch::watch_folder;
+use crate::commands::markdown::{collect_markdown_paths, is_markdown_path};
+use crate::docsite::{DocSite, SiteKind};
+use crate::markdown::vault::Vault;
+use notify::RecommendedWatcher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// 每份缓存最多保留的根目录数
+const MAX_CACHED_ROOTS: usize = 8;
+
+struct Cached<T> {
+    value: T,
+    stale: bool,
+    /// 最后一次用到时 RootCache::clock 的值，超出上限时去掉最小的
+    last_used: u64,
+    /// 监听器被 drop 就会停止监听，所以要一直持有它
+    _watcher: Option<RecommendedWatcher>,
+}
+
+/// 按根目录缓存的一份数据
+struct RootCache<T> {
+    entries: BTreeMap<PathBuf, Cached<T>>,
+    /// 每用一次加 1
+    clock: u64,
+}
+
+impl<T: Clone> RootCache<T> {
+    const fn new() -> Self {
+        RootCache {
+            entries: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// 没过期的缓存值；没有或已过期时返回 Err(是否已经在监听)
+    fn fresh(&mut self, root: &Path) -> Result<T, bool> {
+        self.clock += 1;
+        match self.entries.get_mut(root) {
+            Some(cached) if !cached.stale => {
+                cached.last_used = self.clock;
+                Ok(cached.value.clone())
+            }
+            Some(cached) => Err(cached._watcher.is_some()),
+            None => Err(false),
+        }
+    }
+
+    /// 存入重新读取的值；watched 是读取之前 fresh() 说的「已经在监听」
+    fn store(&mut self, root: &Path, value: T, watcher: Option<RecommendedWatcher>, watched: bool) {
+        self.clock += 1;
+        if let Some(cached) = self.entries.get_mut(root) {
+            cached.value = value;
+            cached.stale = false;
+            cached.last_used = self.clock;
+            if cached._watcher.is_none() {
+                cached._watcher = watcher;
+            }
+            return;
+        }
+        // 读取期间被挤出去了：没有监听器，先记成过期，下次用到时重新监听
+        self.entries.insert(
+            root.to_path_buf(),
+            Cached {
+                value,
+                stale: watched && watcher.is_none(),
+                last_used: self.clock,
+                _watcher: watcher,
+            },
+        );
+        while self.entries.len() > MAX_CACHED_ROOTS {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(root, _)| root.clone());
+            match oldest {
+                Some(oldest) => self.entries.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+}
+
+/// root 的缓存值；没有或已过期时在锁外用 load 重新读取，第一次读取时用 watch 开始监听
+fn cached<T: Clone>(
+    cache: &Mutex<RootCache<T>>,
+    root: &Path,
+    load: impl FnOnce(&Path) -> T,
+    watch: impl FnOnce(&Path) -> Option<RecommendedWatcher>,
+) -> T {
+    let watched = match cache.lock() {
+        Ok(mut cache) => match cache.fresh(root) {
+            Ok(value) => return value,
+            Err(watched) => watched,
+        },
+        Err(_) => return load(root),
+    };
+    let value = load(root);
+    let watcher = if watched { None } else { watch(root) };
+    if let Ok(mut cache) = cache.lock() {
+        cache.store(root, value.clone(), watcher, watched);
+    }
+    value
+}
+
+/// 监听到 root 下有变化：把它的缓存标记过期
+fn mark_stale<T>(cache: &Mutex<RootCache<T>>, root: &Path) {
+    if let Ok(mut cache) = cache.lock() {
+        if let Some(cached) = cache.entries.get_mut(root) {
+            cached.stale = true;
+        }
+    }
+}
+
+static VAULTS: Mutex<RootCache<Arc<Vault>>> = Mutex::new(RootCache::new());
+
+/// file 所在的 vault；不在 vault 里时返回 None
+pub fn vault_for(file: &Path) -> Option<Arc<Vault>> {
+    let root = Vault::find_root(file)?;
+    Some(cached(
+        &VAULTS,
+        &root,
+        |root| Arc::new(Vault::open(root)),
+        watch_vault,
+    ))
+}
+
+fn watch_vault(root: &Path) -> Option<RecommendedWatcher> {
+    let root = root.to_path_buf();
+    watch_folder(&root.clone(), move |paths| {
+        // stat 在锁外面做
+        let paths: Vec<(PathBuf, bool)> = paths
+            .into_iter()
+            .map(|path| {
+                let is_file = path.is_file();
+                (path, is_file)
+            })
+            .collect();
+        let vault = match VAULTS.lock() {
+            Ok(vaults) => match vaults.entries.get(&root) {
+                Some(cached) => Arc::clone(&cached.value),
+                None => return,
+            },
+            Err(_) => return,
+        };
+        let files_changed = paths.iter().any(|(path, is_file)| {
+            path.ends_with(".obsidian/app.json") || !(*is_file && vault.contains(path))
+        });
+        if files_changed {
+            mark_stale(&VAULTS, &root);
+        }
+    })
+}
+
+/// 配置文件读不了时为 None
+static SITES: Mutex<RootCache<Option<Arc<DocSite>>>> = Mutex::new(RootCache::new());
+
+/// path（文件或目录）所在的文档站点；不在站点里时返回 None
+pub fn site_for(path: &Path) -> Option<Arc<DocSite>> {
+    let root = DocSite::find_root(path)?;
+    cached(
+        &SITES,
+        &root,
+        |root| DocSite::open(root).map(Arc::new),
+        watch_site,
+    )
+}
+
+fn watch_site(root: &Path) -> Option<RecommendedWatcher> {
+    let root = root.to_path_buf();
+    watch_folder(&root.clone(), move |paths| {
+        let docusaurus = match SITES.lock() {
+            Ok(sites) => match sites.entries.get(&root) {
+                Some(cached) => cached
+                    .value
+                    .as_ref()
+                    .is_some_and(|site| site.kind == SiteKind::Docusaurus),
+                None => return,
+            },
+            Err(_) => return,
+        };
+        let affects_site = paths.iter().any(|path| {
+            let in_git = path.components().any(|c| c.as_os_str() == ".git");
+            !in_git && (docusaurus || !is_markdown_path(path))
+        });
+        if affects_site {
+            mark_stale(&SITES, &root);
+        }
+    })
+}
+
+/// 文件树里一个文件的 stat 信息：(路径, 大小, 修改时间)
+pub type FileStat = (PathBuf, u64, Option<SystemTime>);
+
+static FOLDERS: Mutex<RootCache<Arc<Vec<FileStat>>>> = Mutex::new(RootCache::new());
+
+/// root 下全部 Markdown 文件的 stat 信息（root 应是规范化的路径）
+pub fn folder_files(root: &Path) -> Arc<Vec<FileStat>> {
+    cached(
+        &FOLDERS,
+        root,
+        |root| Arc::new(stat_files(root)),
+        watch_tree,
+    )
+}
+
+fn stat_files(root: &Path) -> Vec<FileStat> {
+    collect_markdown_paths(root)
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some((path, metadata.len(), metadata.modified().ok()))
+        })
+        .collect()
+}
+
+fn watch_tree(root: &Path) -> Option<RecommendedWatcher> {
+    let root = root.to_path_buf();
+    watch_folder(&root.clone(), move |paths| {
+        let affects_tree = paths.iter().any(|path| {
+            let in_git = path.components().any(|c| c.as_os_str() == ".git");
+            // 不存在了的可能是整个目录
+            !in_git && (is_markdown_path(path) || path.is_dir() || !path.exists())
+        });
+        if affects_tree {
+            mark_stale(&FOLDERS, &root);
+        }
+    })
+}
+
+/// 扫描选项（忽略的目录、是否跟随符号链接）变了：缓存的文件树全部作废
+pub fn invalidate_folders() {
+    if let Ok(mut folders) = FOLDERS.lock() {
+        for cached in folders.entries.values_mut() {
+            cached.stale = true;
+        }
+    }
+}
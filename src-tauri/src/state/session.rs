@@ -0,0 +1,162 @@
+// ─────────────────────────────────────────────
+// 会话：每个窗口打开的标签页、当前标签、滚动位置、打开的文件夹
+// ─────────────────────────────────────────────
+//
+// 文件位置：<app_data_dir>/session.json
+//
+// 保存时机：
+//   - 前端在标签 / 滚动变化后调用 save_session，只更新内存并标记为 dirty
+//   - 后台线程每 SAVE_INTERVAL 检查一次，dirty 就落盘（崩溃最多丢这么久的改动）
+//   - 应用正常退出时再落盘一次
+// 这样频繁滚动不会频繁写磁盘。
+//
+// 跨次启动只恢复主窗口：window-2、window-3…… 这些 label 会被之后无关的 --new-window 复用，
+// 所以启动时丢掉它们的旧会话，窗口关闭时也删掉对应条目。
+
+use super::write_json_atomic;
+use crate::cli::MAIN_WINDOW;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const SESSION_FILE: &str = "session.json";
+
+/// 后台自动保存的间隔
+const SAVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 滚动位置：离视口顶部最近的标题 + 相对它的像素偏移
+///
+/// 不直接存 scrollTop：窗口宽度、主题、字体变化都会让同一个像素位置对应不同的内容，
+/// 而「某个标题往下 120px」在这些变化下基本稳定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollAnchor {
+    /// 标题的 slug；在第一个标题之前时为 None（相对文档顶部）
+    pub heading: Option<String>,
+    /// 相对标题（或文档顶部）的偏移，单位 px
+    pub offset: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabState {
+    /// 文档的绝对路径
+    pub path: String,
+    #[serde(default)]
+    pub scroll: Option<ScrollAnchor>,
+}
+
+/// 单个窗口的会话
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowSession {
+    pub tabs: Vec<TabState>,
+    pub active_index: usize,
+    /// 打开的文件夹（卡片网格），没有则为 None
+    pub folder: Option<String>,
+}
+
+/// session.json 的文件格式，windows 的键是窗口 label（如 "main"）
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SessionFile {
+    version: u32,
+    saved_at: Option<DateTime<Local>>,
+    windows: BTreeMap<String, WindowSession>,
+}
+
+struct Inner {
+    windows: BTreeMap<String, WindowSession>,
+    /// 内存中有没落盘的改动
+    dirty: bool,
+}
+
+pub struct SessionStore {
+    path: PathBuf,
+    inner: Mutex<Inner>,
+}
+
+impl SessionStore {
+    pub fn init(app: &AppHandle) -> Result<SessionStore, String> {
+        let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+        let path = dir.join(SESSION_FILE);
+        let mut windows = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<SessionFile>(&text).ok())
+            .map(|f| f.windows)
+            .unwrap_or_default();
+        let count = windows.len();
+        windows.retain(|label, _| label == MAIN_WINDOW);
+        Ok(SessionStore {
+            path,
+            inner: Mutex::new(Inner {
+                dirty: windows.len() != count,
+                windows,
+            }),
+        })
+    }
+
+    /// 上次保存的某个窗口的会话
+    pub fn get(&self, window: &str) -> Option<WindowSession> {
+        self.inner.lock().ok()?.windows.get(window).cloned()
+    }
+
+    /// 更新某个窗口的会话（只改内存，由 flush 负责落盘）
+    pub fn update(&self, window: &str, session: WindowSession) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if inner.windows.get(window) != Some(&session) {
+            inner.windows.insert(window.to_string(), session);
+            inner.dirty = true;
+        }
+    }
+
+    /// 窗口关闭后删掉它的会话（主窗口除外，它的会话要留给下次启动）
+    pub fn remove(&self, window: &str) {
+        if window == MAIN_WINDOW {
+            return;
+        }
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        if inner.windows.remove(window).is_some() {
+            inner.dirty = true;
+        }
+    }
+
+    /// 有未保存的改动时写入磁盘
+    pub fn flush(&self) -> Result<(), String> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        if !inner.dirty {
+            return Ok(());
+        }
+        write_json_atomic(
+            &self.path,
+            &SessionFile {
+                version: 1,
+                saved_at: Some(Local::now()),
+                windows: inner.windows.clone(),
+            },
+        )?;
+        inner.dirty = false;
+        Ok(())
+    }
+}
+
+/// 启动后台自动保存线程（线程随进程退出而结束）
+pub fn spawn_autosave(app: &AppHandle) {
+    let handle = app.clone();
+    thread::spawn(move || loop {
+        thread::sleep(SAVE_INTERVAL);
+        if let Some(store) = handle.try_state::<SessionStore>() {
+            let _ = store.flush();
+        }
+    });
+}
@@ -0,0 +1,335 @@
+// ─────────────────────────────────────────────
+// 应用设置：类型化、带版本号、持久化为 JSON
+// ─────────────────────────────────────────────
+//
+// 文件位置：<app_config_dir>/settings.json
+//   macOS:   ~/Library/Application Support/com.legend.cc-md-preview/settings.json
+//   Windows: %APPDATA%\com.legend.cc-md-preview\settings.json
+//   Linux:   ~/.config/com.legend.cc-md-preview/settings.json
+//
+// 读取流程：JSON 文本 → serde_json::Value → 按 version 逐级迁移 → Settings
+// 每次变化（命令修改 / 用户手动编辑文件）都会广播 settings://changed 事件
+//
+// 不认识的字段（新版本写的文件被旧版本打开）收在各结构的 extra 里，保存时原样写回，
+// 版本号也不往回改，用户降级再升级时新版本的设置不会丢
+
+use super::roots;
+use super::write_json_atomic;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 当前的设置文件版本。改动结构时 +1，并在 migrate() 里补一段迁移
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// 设置变化时广播的事件名，payload 是完整的 Settings
+pub const SETTINGS_CHANGED_EVENT: &str = "settings://changed";
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// 全部应用设置
+///
+/// 所有字段都有默认值（#[serde(default)]）：文件里缺了某个字段，
+/// 或者新版本加了字段，读取时都会自动补上默认值，不会报错
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    /// 预览主题 id（与前端 MarkdownTheme 一致，如 "github"、"dracula"）
+    pub theme: String,
+    pub scan: ScanSettings,
+    pub cache: CacheSettings,
+    /// 智能集合，按用户排列的顺序
+    pub collections: Vec<SmartCollection>,
+    /// 不认识的字段
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 文件夹扫描选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScanSettings {
+    /// 扫描时是否跟随符号链接
+    pub follow_links: bool,
+    /// 扫描时跳过的目录名（如 "node_modules"）
+    pub ignored_dirs: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Chrome 缓存模块的偏好
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CacheSettings {
+    /// 默认选中的浏览器（"Chrome" / "Chrome Canary"）
+    pub browser: String,
+    /// 默认选中的浏览器 profile
+    pub profile: String,
+    /// 清理前是否需要确认
+    pub confirm_before_clean: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 智能集合：有名字的保存查询，对打开的文件夹实时求值（state/collections.rs）
+///
+/// 查询语法见 markdown/query.rs，路径通配、front matter、全文词、日期范围都写在一个查询里，
+/// 如 "path:rfcs/** modified>=this-month"、"tag:meeting -heading:summary"
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SmartCollection {
+    pub id: String,
+    pub name: String,
+    pub query: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            theme: "github".to_string(),
+            scan: ScanSettings::default(),
+            cache: CacheSettings::default(),
+            collections: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Default for ScanSettings {
+    fn default() -> Self {
+        ScanSettings {
+            follow_links: true,
+            ignored_dirs: Vec::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        CacheSettings {
+            browser: "Chrome".to_string(),
+            profile: "Default".to_string(),
+            confirm_before_clean: true,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// 当前的扫描选项（settings.scan）；SettingsStore 加载或修改设置时更新，
+/// 没有设置（如 CLI 的无界面模式）时用默认值
+static SCAN_OPTIONS: RwLock<Option<ScanSettings>> = RwLock::new(None);
+
+/// 扫描文件夹时用的选项（commands::markdown::collect_markdown_paths）
+pub fn scan_options() -> ScanSettings {
+    SCAN_OPTIONS
+        .read()
+        .ok()
+        .and_then(|options| options.clone())
+        .unwrap_or_default()
+}
+
+fn set_scan_options(scan: &ScanSettings) {
+    if let Ok(mut options) = SCAN_OPTIONS.write() {
+        if options.as_ref() == Some(scan) {
+            return;
+        }
+        *options = Some(scan.clone());
+    }
+    // 扫描范围变了，缓存的文件树作废
+    roots::invalidate_folders();
+}
+
+/// 把任意旧版本的设置 JSON 迁移到当前版本
+///
+/// 每个版本只负责迁移到下一个版本，循环执行直到追上 SETTINGS_VERSION。
+/// 比当前版本还新的文件（用户降级了应用）原样交给 serde，能认的字段照常读取。
+fn migrate(mut value: Value) -> Value {
+    if !value.is_object() {
+        return Value::Object(Default::default());
+    }
+    loop {
+        let version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+        if version >= SETTINGS_VERSION {
+            return value;
+        }
+        match version {
+            // v0：没有 version 字段的早期格式，缓存相关选项是平铺的
+            //   { "theme": "nord", "browser": "Chrome", "profile": "Default" }
+            0 => {
+                let obj = value.as_object_mut().expect("checked above");
+                let mut cache = serde_json::Map::new();
+                for key in ["browser", "profile"] {
+                    if let Some(v) = obj.remove(key) {
+                        cache.insert(key.to_string(), v);
+                    }
+                }
+                if !cache.is_empty() {
+                    obj.insert("cache".to_string(), Value::Object(cache));
+                }
+                obj.insert("version".to_string(), Value::from(1));
+            }
+            _ => unreachable!("missing settings migration from v{}", version),
+        }
+    }
+}
+
+/// 把 patch 递归合并进 target：对象按字段合并，其他类型直接覆盖
+///
+/// 这样前端只需要传变化的部分：{ theme: "nord" } 不会清空 scan / cache
+fn merge(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(t), Value::Object(p)) => {
+            for (key, value) in p {
+                merge(t.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (t, p) => *t = p,
+    }
+}
+
+/// 读取设置文件；文件不存在或内容不是合法 JSON 时返回 None
+fn read_settings_file(path: &Path) -> Option<Settings> {
+    let text = fs::read_to_string(path).ok()?;
+    let value: Value = serde_json::from_str(&text).ok()?;
+    serde_json::from_value(migrate(value)).ok()
+}
+
+// ─────────────────────────────────────────────
+// SettingsStore：内存中的当前设置 + 文件路径 + 文件监听器
+// ─────────────────────────────────────────────
+// 在 lib.rs 的 setup() 里通过 SettingsStore::init() 创建并 manage()
+pub struct SettingsStore {
+    path: PathBuf,
+    current: Mutex<Settings>,
+    /// 监听器被 drop 就会停止监听，所以要一直持有它
+    _watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl SettingsStore {
+    /// 读取（必要时迁移并回写）设置文件，并开始监听手动修改
+    pub fn init(app: &AppHandle) -> Result<SettingsStore, String> {
+        let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+        let path = dir.join(SETTINGS_FILE);
+        let on_disk: Option<Value> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok());
+        // 文件不存在、损坏或是旧版本时，立即以当前版本写一份，之后读到的就都是新格式了。
+        // 比当前版本新的文件（用户降级了应用）不回写，否则新版本才认识的字段会被悄悄丢掉
+        let (settings, write_back) = match read_settings_file(&path) {
+            Some(settings) => {
+                let version = on_disk
+                    .as_ref()
+                    .and_then(|v| v.get("version"))
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0);
+                (settings, version < u64::from(SETTINGS_VERSION))
+            }
+            None => {
+                // 文件损坏：先备份成 settings.json.bak 再用默认值，不悄悄丢掉用户手写的内容
+                if path.exists() {
+                    let _ = fs::rename(&path, path.with_extension("json.bak"));
+                }
+                (Settings::default(), true)
+            }
+        };
+        if write_back {
+            write_json_atomic(&path, &settings)?;
+        }
+        set_scan_options(&settings.scan);
+
+        Ok(SettingsStore {
+            _watcher: Mutex::new(watch_settings_file(app, &dir)),
+            path,
+            current: Mutex::new(settings),
+        })
+    }
+
+    pub fn get(&self) -> Settings {
+        self.current.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// 合并 patch、保存到文件，返回更新后的设置
+    pub fn update(&self, patch: Value) -> Result<Settings, String> {
+        let mut current = self.current.lock().map_err(|e| e.to_string())?;
+        let mut value = serde_json::to_value(&*current).map_err(|e| e.to_string())?;
+        merge(&mut value, patch);
+        let updated: Settings =
+            serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))?;
+        self.save(&mut current, updated)
+    }
+
+    /// 在锁里基于当前设置修改、保存，返回更新后的设置
+    ///
+    /// 读出来改一部分再写回（如往集合列表里加一项）要用这个：先 get() 再 update() 的话，
+    /// 两个同时进行的修改后写的会覆盖先写的
+    pub fn modify(&self, edit: impl FnOnce(&mut Settings)) -> Result<Settings, String> {
+        let mut current = self.current.lock().map_err(|e| e.to_string())?;
+        let mut updated = current.clone();
+        edit(&mut updated);
+        self.save(&mut current, updated)
+    }
+
+    /// 写文件并替换内存中的设置（调用方持有 current 的锁）
+    ///
+    /// 文件是更新的版本写的时保留它的版本号，extra 里的字段也会原样写回
+    fn save(&self, current: &mut Settings, mut updated: Settings) -> Result<Settings, String> {
+        updated.version = updated.version.max(current.version).max(SETTINGS_VERSION);
+        write_json_atomic(&self.path, &updated)?;
+        set_scan_options(&updated.scan);
+        *current = updated.clone();
+        Ok(updated)
+    }
+
+    /// 文件被外部修改后重新读取；内容确实变了才返回新设置
+    ///
+    /// 自己调用 update() 写文件也会触发监听，但那时内存和文件已经一致，这里会返回 None，
+    /// 避免同一次修改广播两遍。手动编辑到一半（JSON 暂时不合法）时也返回 None，保留当前设置
+    fn reload(&self) -> Option<Settings> {
+        let fresh = read_settings_file(&self.path)?;
+        let mut current = self.current.lock().ok()?;
+        if *current == fresh {
+            return None;
+        }
+        set_scan_options(&fresh.scan);
+        *current = fresh.clone();
+        Some(fresh)
+    }
+}
+
+/// 监听配置目录（而不是设置文件本身）
+///
+/// 原子写入是「写临时文件 + rename」，文件会被替换成新的 inode，
+/// 直接监听文件的话第一次替换后就收不到事件了。
+/// 监听失败（如系统 inotify 上限）不影响正常使用，只是没有热重载。
+fn watch_settings_file(app: &AppHandle, dir: &Path) -> Option<RecommendedWatcher> {
+    let handle = app.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        let touches_settings = event
+            .paths
+            .iter()
+            .any(|p| p.file_name().is_some_and(|n| n == SETTINGS_FILE));
+        if !touches_settings {
+            return;
+        }
+        // setup() 里 manage() 之前就可能收到事件，所以用 try_state 而不是 state
+        let Some(store) = handle.try_state::<SettingsStore>() else {
+            return;
+        };
+        if let Some(settings) = store.reload() {
+            let _ = handle.emit(SETTINGS_CHANGED_EVENT, &settings);
+            // 手动改了集合的查询，计数也要跟着变
+            super::collections::notify_changed(&handle);
+        }
+    })
+    .ok()?;
+    watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+    Some(watcher)
+}
@@ -0,0 +1,124 @@
+// ─────────────────────────────────────────────
+// 文件夹监听：内存索引的增量更新
+// ─────────────────────────────────────────────
+//
+// 智能集合（collections.rs）、快速打开（workspace.rs）和 vault 缓存（roots.rs）
+// 都在内存里按文件维护一份索引，靠监听文件夹保持最新：
+//   - 递归监听，变化攒一小段时间再统一处理，git checkout 这类批量改动只处理一次
+//   - Access 类事件（打开、读取文件）忽略：索引自己读文件也会触发，不忽略会无限循环
+//   - 只重新读变化了的文件；目录被删掉 / 移走时去掉其中所有文件，新出现的目录整个扫一遍
+//   - 读文件（Changes::read）不持有索引的锁，读完再在锁里合进去（Changes::apply），
+//     读大批文件时搜索、计数照常进行；同一个监听器的各批变化按顺序处理，旧内容不会盖掉新内容
+
+use crate::commands::markdown::{collect_markdown_paths, is_markdown_path};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 收到第一个变化后等多久再处理（期间的变化合并成一次）
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 递归监听 root，把一批变化的路径交给 on_change
+///
+/// 监听器被 drop 就会停止监听，调用方要一直持有返回值。
+/// 监听失败（如系统 inotify 上限）返回 None：索引照常可用，只是不会自动更新
+pub fn watch_folder(
+    root: &Path,
+    on_change: impl Fn(Vec<PathBuf>) + Send + Sync + 'static,
+) -> Option<RecommendedWatcher> {
+    let on_change = Arc::new(on_change);
+    // 还没处理的变化路径；从空变成非空时启动一次延迟处理
+    let pending: Arc<Mutex<Vec<PathBuf>>> = Arc::default();
+    // 上一批还没处理完时，下一批等它处理完再开始
+    let serial: Arc<Mutex<()>> = Arc::default();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        let Ok(mut queued) = pending.lock() else {
+            return;
+        };
+        let first = queued.is_empty();
+        queued.extend(event.paths);
+        if !first {
+            return;
+        }
+        let pending = Arc::clone(&pending);
+        let serial = Arc::clone(&serial);
+        let on_change = Arc::clone(&on_change);
+        thread::spawn(move || {
+            thread::sleep(DEBOUNCE);
+            let Ok(_serial) = serial.lock() else {
+                return;
+            };
+            let paths = match pending.lock() {
+                Ok(mut queued) => mem::take(&mut *queued),
+                Err(_) => return,
+            };
+            if !paths.is_empty() {
+                on_change(paths);
+            }
+        });
+    })
+    .ok()?;
+    watcher.watch(root, RecursiveMode::Recursive).ok()?;
+    Some(watcher)
+}
+
+/// 一批变化读到的结果，再用 apply 合进以文件路径为键的索引
+pub struct Changes<T> {
+    /// 新增或内容变了的文件
+    updated: Vec<(PathBuf, T)>,
+    /// 不存在了的路径：可能是文件，也可能是整个目录
+    removed: Vec<PathBuf>,
+}
+
+impl<T> Changes<T> {
+    /// 读取 root 下变化了的 Markdown 文件（调用方不要持有索引的锁）
+    ///
+    /// read 读取单个 Markdown 文件的索引内容，读不了时返回 None（该文件不进索引）
+    pub fn read(root: &Path, paths: Vec<PathBuf>, read: impl Fn(&Path) -> Option<T>) -> Changes<T> {
+        let mut changes = Changes {
+            updated: Vec::new(),
+            removed: Vec::new(),
+        };
+        for path in paths {
+            // 切换文件夹之前排队的旧事件
+            if !path.starts_with(root) {
+                continue;
+            }
+            if path.is_dir() {
+                for file in collect_markdown_paths(&path) {
+                    if let Some(entry) = read(&file) {
+                        changes.updated.push((file, entry));
+                    }
+                }
+            } else if path.is_file() {
+                if is_markdown_path(&path) {
+                    if let Some(entry) = read(&path) {
+                        changes.updated.push((path, entry));
+                    }
+                }
+            } else {
+                changes.removed.push(path);
+            }
+        }
+        changes
+    }
+
+    /// 合进索引，有改动时返回 true
+    pub fn apply(self, files: &mut BTreeMap<PathBuf, T>) -> bool {
+        let before = files.len();
+        for path in &self.removed {
+            files.retain(|p, _| !p.starts_with(path));
+        }
+        let changed = files.len() != before || !self.updated.is_empty();
+        files.extend(self.updated);
+        changed
+    }
+}
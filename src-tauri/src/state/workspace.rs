@@ -0,0 +1,571 @@
+// ─────────────────────────────────────────────
+// 工作区索引：快速打开（Ctrl+P）和跳转到标题
+// ─────────────────────────────────────────────
+//
+// 这次运行里打开过的文件夹（list_markdown_files）都会加进来，在后台线程里扫一遍：
+// 每个 Markdown 文件记下相对路径、文件名、front matter 标题和所有标题（级别、文本、锚点、行号），
+// 预先转好小写常驻内存，搜索时只做打分，不碰磁盘。
+// 文件变化后只重新读变化了的文件（见 watch.rs）。
+// 打开的文件夹在已索引的文件夹里面时不再单独索引，包含已索引的文件夹时把它们换掉，
+// 同一个文件只会出现一次。最多索引 MAX_ROOTS 个文件夹，超出时去掉最久没打开过的；
+// 文件夹本身被删掉或移走时也从索引里去掉。
+//
+// 排序：各个查询词（空格分开）都要匹配上，每个词取得分最高的字段（文件名 > 标题 > H1 > 路径），
+// 分数相加，再加上「最近打开」的加成。
+// 连续输入时（新查询以上一次查询开头、索引也没变）只在上一次的结果里继续筛，
+// 输入越长候选越少。
+//
+// 标题搜索：查询词都要匹配标题文本，高级别标题和最近打开的文件略微靠前；
+// 可以只搜当前文件，当前文件不在任何打开的文件夹里时直接读它。
+
+use super::watch::{watch_folder, Changes};
+use crate::commands::markdown::collect_markdown_paths;
+use crate::markdown::front_matter::FrontMatter;
+use crate::markdown::fuzzy::{self, Prepared};
+use crate::markdown::headings::parse_headings;
+use crate::markdown::links::normalize_path;
+use notify::RecommendedWatcher;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+/// 查询词落在各字段时的额外加分：文件名最像用户要找的东西
+const NAME_BONUS: i32 = 12;
+const TITLE_BONUS: i32 = 8;
+const HEADING_BONUS: i32 = 6;
+
+/// 「最近打开」列表里排第一的文件的加分，往后每位少 1 分
+const RECENT_BONUS: i32 = 30;
+
+/// 标题搜索里 H1 的加分，每低一级少 1 分
+const LEVEL_BONUS: i32 = 6;
+
+/// 最多同时索引的文件夹数
+const MAX_ROOTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchField {
+    Name,
+    Path,
+    Title,
+    Heading,
+}
+
+/// 某个字段里匹配到的字符
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldMatch {
+    pub field: MatchField,
+    /// Unicode 字符下标，升序
+    pub indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickOpenMatch {
+    /// 绝对路径
+    pub path: String,
+    /// 所在的打开的文件夹
+    pub root: String,
+    /// 相对 root 的路径，统一用 /
+    pub relative_path: String,
+    pub name: String,
+    /// front matter 里的 title
+    pub title: Option<String>,
+    /// 第一个 H1
+    pub heading: Option<String>,
+    pub score: i32,
+    /// 每个字段一条，没匹配到的字段不出现
+    pub matches: Vec<FieldMatch>,
+}
+
+/// 标题搜索的一条结果；前端用 path + slug 打开文档并跳到锚点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadingMatch {
+    /// 绝对路径
+    pub path: String,
+    /// 相对所在文件夹的路径，统一用 /；不在打开的文件夹里时是文件名
+    pub relative_path: String,
+    pub level: u8,
+    pub text: String,
+    /// GitHub 风格锚点，同一文档内唯一
+    pub slug: String,
+    /// 标题所在行号（1-based）
+    pub line: usize,
+    pub score: i32,
+    /// 标题文本里匹配到的字符（Unicode 字符下标，升序）
+    pub indices: Vec<usize>,
+}
+
+/// 索引里的一个标题
+struct IndexedHeading {
+    level: u8,
+    text: Prepared,
+    slug: String,
+    line: usize,
+}
+
+impl IndexedHeading {
+    fn parse(source: &str) -> Vec<IndexedHeading> {
+        parse_headings(source)
+            .into_iter()
+            .map(|h| IndexedHeading {
+                level: h.level,
+                text: Prepared::new(&h.text),
+                slug: h.slug,
+                line: h.line,
+            })
+            .collect()
+    }
+
+    /// 各查询词都匹配上时的分数（不含文件的加成）
+    fn score(&self, terms: &[Prepared]) -> Option<i32> {
+        let mut total = LEVEL_BONUS + 1 - i32::from(self.level);
+        for term in terms {
+            total += fuzzy::score(term, &self.text)?;
+        }
+        Some(total)
+    }
+
+    /// 各查询词匹配到的字符位置，合并去重
+    fn indices(&self, terms: &[Prepared]) -> Vec<usize> {
+        let mut indices: Vec<usize> = terms
+            .iter()
+            .filter_map(|term| fuzzy::match_indices(term, &self.text))
+            .flat_map(|(_, matched)| matched)
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+}
+
+/// 一个文件的候选信息
+struct Candidate {
+    relative_path: Prepared,
+    name: Prepared,
+    title: Option<Prepared>,
+    /// 第一个 H1
+    heading: Option<Prepared>,
+    headings: Vec<IndexedHeading>,
+}
+
+impl Candidate {
+    fn read(root: &Path, path: &Path) -> Option<Candidate> {
+        let source = fs::read_to_string(path).ok()?;
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let name = path.file_name()?.to_string_lossy();
+        let title = FrontMatter::parse(&source).title();
+        let headings = IndexedHeading::parse(&source);
+        Some(Candidate {
+            relative_path: Prepared::new(&relative.to_string_lossy().replace('\\', "/")),
+            name: Prepared::new(&name),
+            title: title.as_deref().map(Prepared::new),
+            heading: headings
+                .iter()
+                .find(|h| h.level == 1)
+                .map(|h| h.text.clone()),
+            headings,
+        })
+    }
+
+    fn fields(&self) -> impl Iterator<Item = (MatchField, &Prepared, i32)> {
+        [
+            Some((MatchField::Name, &self.name, NAME_BONUS)),
+            self.title
+                .as_ref()
+                .map(|t| (MatchField::Title, t, TITLE_BONUS)),
+            self.heading
+                .as_ref()
+                .map(|h| (MatchField::Heading, h, HEADING_BONUS)),
+            Some((MatchField::Path, &self.relative_path, 0)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// 每个查询词在哪个字段得分最高；有词匹配不上时返回 None
+    fn best_fields(&self, terms: &[Prepared]) -> Option<(i32, Vec<MatchField>)> {
+        let mut total = 0;
+        let mut fields = Vec::with_capacity(terms.len());
+        for term in terms {
+            let (score, field) = self
+                .fields()
+                .filter_map(|(field, text, bonus)| Some((fuzzy::score(term, text)? + bonus, field)))
+                .max_by_key(|(score, _)| *score)?;
+            total += score;
+            fields.push(field);
+        }
+        Some((total, fields))
+    }
+
+    fn field(&self, field: MatchField) -> Option<&Prepared> {
+        match field {
+            MatchField::Name => Some(&self.name),
+            MatchField::Path => Some(&self.relative_path),
+            MatchField::Title => self.title.as_ref(),
+            MatchField::Heading => self.heading.as_ref(),
+        }
+    }
+}
+
+struct RootIndex {
+    files: BTreeMap<PathBuf, Candidate>,
+    /// 最后一次打开时 Roots::opens 的值，超出 MAX_ROOTS 时去掉最小的
+    last_opened: u64,
+    /// 监听器被 drop 就会停止监听，所以要一直持有它
+    _watcher: Option<RecommendedWatcher>,
+}
+
+/// 上一次查询匹配到的文件，连续输入时在它们里面继续筛
+struct LastQuery {
+    query: String,
+    generation: u64,
+    hits: Vec<(PathBuf, PathBuf)>,
+}
+
+#[derive(Default)]
+struct Roots {
+    roots: BTreeMap<PathBuf, RootIndex>,
+    /// 索引每改一次加 1，上一次的结果只在没改过时可以复用
+    generation: u64,
+    /// 打开文件夹的次数
+    opens: u64,
+}
+
+// 在 lib.rs 里 manage()
+#[derive(Default)]
+pub struct WorkspaceIndex {
+    roots: Mutex<Roots>,
+    last: Mutex<Option<LastQuery>>,
+}
+
+impl WorkspaceIndex {
+    /// 索引 root 并开始监听；root 已经在索引里（或在已索引的文件夹里面）时只记一次打开
+    ///
+    /// 扫描时不持有锁，大文件夹扫描期间搜索照常返回已有文件夹的结果
+    pub fn add_root(&self, app: &AppHandle, root: &Path) {
+        let root = normalize_path(root);
+        if self
+            .roots
+            .lock()
+            .map_or(true, |mut roots| roots.touch(&root))
+        {
+            return;
+        }
+        let files = collect_markdown_paths(&root)
+            .into_iter()
+            .filter_map(|path| Some((path.clone(), Candidate::read(&root, &path)?)))
+            .collect();
+        let watcher = watch_root(app, &root);
+        let Ok(mut roots) = self.roots.lock() else {
+            return;
+        };
+        // 扫描期间别的调用已经索引好了
+        if roots.touch(&root) {
+            return;
+        }
+        // 已索引的子文件夹的文件都在新的索引里了
+        roots.roots.retain(|indexed, _| !indexed.starts_with(&root));
+        let last_opened = roots.opens;
+        roots.roots.insert(
+            root,
+            RootIndex {
+                files,
+                last_opened,
+                _watcher: watcher,
+            },
+        );
+        while roots.roots.len() > MAX_ROOTS {
+            let oldest = roots
+                .roots
+                .iter()
+                .min_by_key(|(_, index)| index.last_opened)
+                .map(|(root, _)| root.clone());
+            if let Some(oldest) = oldest {
+                roots.roots.remove(&oldest);
+            }
+        }
+        roots.generation += 1;
+    }
+
+    /// 处理 root 下的一批文件变化；读文件时不持有锁
+    fn apply(&self, root: &Path, paths: Vec<PathBuf>) {
+        let changes = root
+            .is_dir()
+            .then(|| Changes::read(root, paths, |path| Candidate::read(root, path)));
+        let Ok(mut roots) = self.roots.lock() else {
+            return;
+        };
+        let Some(changes) = changes else {
+            // 文件夹本身被删掉或移走了
+            if roots.roots.remove(root).is_some() {
+                roots.generation += 1;
+            }
+            return;
+        };
+        let Some(index) = roots.roots.get_mut(root) else {
+            return;
+        };
+        if changes.apply(&mut index.files) {
+            roots.generation += 1;
+        }
+    }
+
+    /// 按分数从高到低返回前 limit 个匹配
+    ///
+    /// recent 是「最近打开」的文件路径，最近的在前。
+    /// 查询为空时按最近打开的顺序列出索引里的文件
+    pub fn search(&self, query: &str, recent: &[PathBuf], limit: usize) -> Vec<QuickOpenMatch> {
+        let Ok(roots) = self.roots.lock() else {
+            return Vec::new();
+        };
+        let recency = recency(recent);
+        let terms: Vec<Prepared> = query.split_whitespace().map(Prepared::new).collect();
+
+        if terms.is_empty() {
+            return recent
+                .iter()
+                .filter_map(|path| {
+                    let (root, candidate) = roots.find(path)?;
+                    Some(to_match(root, path, candidate, 0, &[], &[]))
+                })
+                .take(limit)
+                .collect();
+        }
+
+        let Ok(mut last) = self.last.lock() else {
+            return Vec::new();
+        };
+        let previous = last
+            .take()
+            .filter(|l| l.generation == roots.generation && query.starts_with(&l.query));
+        let candidates: Box<dyn Iterator<Item = (&Path, &Path, &Candidate)>> = match &previous {
+            Some(previous) => Box::new(previous.hits.iter().filter_map(|(root, path)| {
+                let candidate = roots.roots.get(root)?.files.get(path)?;
+                Some((root.as_path(), path.as_path(), candidate))
+            })),
+            None => Box::new(roots.roots.iter().flat_map(|(root, index)| {
+                index
+                    .files
+                    .iter()
+                    .map(move |(path, c)| (root.as_path(), path.as_path(), c))
+            })),
+        };
+
+        let mut hits: Vec<(i32, &Path, &Path, &Candidate, Vec<MatchField>)> = candidates
+            .filter_map(|(root, path, candidate)| {
+                let (score, fields) = candidate.best_fields(&terms)?;
+                let bonus = recency.get(path).copied().unwrap_or(0);
+                Some((score + bonus, root, path, candidate, fields))
+            })
+            .collect();
+        *last = Some(LastQuery {
+            query: query.to_string(),
+            generation: roots.generation,
+            hits: hits
+                .iter()
+                .map(|(_, root, path, _, _)| (root.to_path_buf(), path.to_path_buf()))
+                .collect(),
+        });
+
+        // 同分时路径短的在前
+        hits.sort_by_key(|(score, _, path, candidate, _)| {
+            (Reverse(*score), candidate.relative_path.text.len(), *path)
+        });
+        hits.into_iter()
+            .take(limit)
+            .map(|(score, root, path, candidate, fields)| {
+                to_match(root, path, candidate, score, &terms, &fields)
+            })
+            .collect()
+    }
+
+    /// 按标题搜索，分数从高到低返回前 limit 个
+    ///
+    /// file 不为空时只搜这个文件；此时查询为空会按文档顺序列出它的所有标题
+    pub fn search_headings(
+        &self,
+        query: &str,
+        file: Option<&Path>,
+        recent: &[PathBuf],
+        limit: usize,
+    ) -> Vec<HeadingMatch> {
+        let terms: Vec<Prepared> = query.split_whitespace().map(Prepared::new).collect();
+        if terms.is_empty() && file.is_none() {
+            return Vec::new();
+        }
+        // 当前文件不在索引里（单独打开的文件）时现读，读文件不占着索引锁
+        let indexed = |file: &Path| self.roots.lock().is_ok_and(|r| r.find(file).is_some());
+        let outside = match file {
+            Some(file) if !indexed(file) => {
+                IndexedHeading::parse(&fs::read_to_string(file).unwrap_or_default())
+            }
+            _ => Vec::new(),
+        };
+        let Ok(roots) = self.roots.lock() else {
+            return Vec::new();
+        };
+        let recency = recency(recent);
+
+        let files: Vec<(&Path, &str, &[IndexedHeading])> = match file {
+            Some(file) => match roots.find(file) {
+                Some((_, candidate)) => vec![(
+                    file,
+                    candidate.relative_path.text.as_str(),
+                    candidate.headings.as_slice(),
+                )],
+                None => {
+                    let name = file
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or_default();
+                    vec![(file, name, outside.as_slice())]
+                }
+            },
+            None => roots
+                .roots
+                .values()
+                .flat_map(|index| &index.files)
+                .map(|(path, c)| {
+                    (
+                        path.as_path(),
+                        c.relative_path.text.as_str(),
+                        c.headings.as_slice(),
+                    )
+                })
+                .collect(),
+        };
+
+        let terms = terms.as_slice();
+        let mut hits: Vec<(i32, &Path, &str, &IndexedHeading)> = files
+            .into_iter()
+            .flat_map(|(path, relative, headings)| {
+                let bonus = recency.get(path).copied().unwrap_or(0);
+                headings.iter().filter_map(move |heading| {
+                    let score = if terms.is_empty() {
+                        0
+                    } else {
+                        heading.score(terms)?
+                    };
+                    Some((score + bonus, path, relative, heading))
+                })
+            })
+            .collect();
+        // 同分时按文件、行号排，只搜一个文件且查询为空时就是文档顺序
+        hits.sort_by_key(|(score, path, _, heading)| (Reverse(*score), *path, heading.line));
+        hits.into_iter()
+            .take(limit)
+            .map(|(score, path, relative, heading)| HeadingMatch {
+                path: path.to_string_lossy().to_string(),
+                relative_path: relative.to_string(),
+                level: heading.level,
+                text: heading.text.text.clone(),
+                slug: heading.slug.clone(),
+                line: heading.line,
+                score,
+                indices: heading.indices(terms),
+            })
+            .collect()
+    }
+}
+
+impl Roots {
+    /// root 本身或它的某个上级文件夹已经在索引里时，记一次打开并返回 true
+    fn touch(&mut self, root: &Path) -> bool {
+        self.opens += 1;
+        let opens = self.opens;
+        match self
+            .roots
+            .iter_mut()
+            .find(|(indexed, _)| root.starts_with(indexed))
+        {
+            Some((_, index)) => {
+                index.last_opened = opens;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn find(&self, path: &Path) -> Option<(&Path, &Candidate)> {
+        self.roots
+            .iter()
+            .find_map(|(root, index)| Some((root.as_path(), index.files.get(path)?)))
+    }
+}
+
+/// 最近打开的文件 → 加分
+fn recency(recent: &[PathBuf]) -> HashMap<&Path, i32> {
+    recent
+        .iter()
+        .enumerate()
+        .map(|(rank, path)| (path.as_path(), (RECENT_BONUS - rank as i32).max(0)))
+        .collect()
+}
+
+/// 组装结果；只对最终返回的这几个重新求匹配位置
+fn to_match(
+    root: &Path,
+    path: &Path,
+    candidate: &Candidate,
+    score: i32,
+    terms: &[Prepared],
+    fields: &[MatchField],
+) -> QuickOpenMatch {
+    let mut matches: Vec<FieldMatch> = Vec::new();
+    for (term, &field) in terms.iter().zip(fields) {
+        let Some((_, indices)) = candidate
+            .field(field)
+            .and_then(|text| fuzzy::match_indices(term, text))
+        else {
+            continue;
+        };
+        match matches.iter_mut().find(|m| m.field == field) {
+            Some(existing) => {
+                existing.indices.extend(indices);
+                existing.indices.sort_unstable();
+                existing.indices.dedup();
+            }
+            None => matches.push(FieldMatch { field, indices }),
+        }
+    }
+    QuickOpenMatch {
+        path: path.to_string_lossy().to_string(),
+        root: root.to_string_lossy().to_string(),
+        relative_path: candidate.relative_path.text.clone(),
+        name: candidate.name.text.clone(),
+        title: candidate.title.as_ref().map(|t| t.text.clone()),
+        heading: candidate.heading.as_ref().map(|h| h.text.clone()),
+        score,
+        matches,
+    }
+}
+
+fn watch_root(app: &AppHandle, root: &Path) -> Option<RecommendedWatcher> {
+    let handle = app.clone();
+    let watched = root.to_path_buf();
+    watch_folder(root, move |paths| {
+        if let Some(index) = handle.try_state::<WorkspaceIndex>() {
+            index.apply(&watched, paths);
+        }
+    })
+}
+
+/// 把打开的文件夹加进索引；在后台线程里扫描，不拖慢打开文件夹本身
+pub fn track_root(app: &AppHandle, root: &Path) {
+    let handle = app.clone();
+    let root = root.to_path_buf();
+    thread::spawn(move || {
+        if let Some(index) = handle.try_state::<WorkspaceIndex>() {
+            index.add_root(&handle, &root);
+        }
+    });
+}
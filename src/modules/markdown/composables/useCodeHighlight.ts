@@ -0,0 +1,88 @@
+import { invoke } from "@tauri-apps/api/core";
+import { watch, nextTick, type Ref } from "vue";
+
+interface CodeBlockInput {
+  code: string;
+  info: string;
+}
+
+// Highlighted HTML keyed by fence info + code, so re-renders (tab switches,
+// file reloads) only send the blocks that actually changed
+const cache = new Map<string, string>();
+const CACHE_LIMIT = 2000;
+
+let cssLoaded: Promise<void> | null = null;
+
+function cacheKey(block: CodeBlockInput) {
+  return `${block.info}\u0000${block.code}`;
+}
+
+/** Token colours for every theme, scoped by `.theme-*`; loaded once */
+function ensureHighlightCss() {
+  if (!cssLoaded) {
+    cssLoaded = invoke<string>("get_code_highlight_css")
+      .then((css) => {
+        const style = document.createElement("style");
+        style.id = "code-highlight";
+        style.textContent = css;
+        document.head.appendChild(style);
+      })
+      .catch(() => {
+        cssLoaded = null;
+      });
+  }
+  return cssLoaded;
+}
+
+export function useCodeHighlight(
+  containerRef: Ref<HTMLElement | null>,
+  renderedHtml: Ref<string>,
+) {
+  let generation = 0;
+
+  async function highlightBlocks() {
+    const container = containerRef.value;
+    if (!container) return;
+
+    const blocks = Array.from(container.querySelectorAll<HTMLElement>("pre.code-pending"));
+    if (blocks.length === 0) return;
+    const current = ++generation;
+    ensureHighlightCss();
+
+    const inputs: CodeBlockInput[] = blocks.map((block) => ({
+      code: block.textContent ?? "",
+      info: block.dataset.info ?? "",
+    }));
+    const missing = inputs.filter((input) => !cache.has(cacheKey(input)));
+    if (missing.length > 0) {
+      try {
+        // One round trip for the whole document
+        const html = await invoke<string[]>("highlight_code_blocks", { blocks: missing });
+        missing.forEach((input, i) => cache.set(cacheKey(input), html[i]));
+      } catch {
+        // Leave the blocks as plain text
+        return;
+      }
+      for (const key of cache.keys()) {
+        if (cache.size <= CACHE_LIMIT) break;
+        cache.delete(key);
+      }
+    }
+    // A newer render has already replaced these blocks
+    if (current !== generation) return;
+
+    blocks.forEach((block, i) => {
+      const html = cache.get(cacheKey(inputs[i]));
+      if (!html || !block.isConnected) return;
+      const template = document.createElement("template");
+      template.innerHTML = html;
+      const pre = template.content.firstElementChild as HTMLElement | null;
+      if (!pre) return;
+      if (block.dataset.line) pre.dataset.line = block.dataset.line;
+      block.replaceWith(pre);
+    });
+  }
+
+  // immediate: a document that is already rendered when the view mounts needs highlighting too
+  watch(renderedHtml, () => nextTick(highlightBlocks), { flush: "post", immediate: true });
+}
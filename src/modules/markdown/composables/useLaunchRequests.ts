@@ -0,0 +1,71 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { onMounted, onUnmounted } from "vue";
+import { useMarkdownStore } from "@/stores/markdown";
+
+/** Mirrors `InlineDocument` in src-tauri/src/cli.rs */
+interface InlineDocument {
+  id: string;
+  title: string;
+  content: string;
+  baseDir: string;
+}
+
+/** Mirrors `OpenRequest` in src-tauri/src/cli.rs */
+interface OpenRequest {
+  files: string[];
+  documents: InlineDocument[];
+  folder: string | null;
+  line: number | null;
+  anchor: string | null;
+  newWindow: boolean;
+}
+
+/**
+ * Opens files and folders passed on the command line. The backend keeps the
+ * request until this window takes it, so it is fetched once on mount and
+ * again every time `cli://open` fires.
+ */
+export function useLaunchRequests(
+  loadFile: (path: string) => Promise<void>,
+  openFolder: (path: string) => Promise<void>,
+  ready: Promise<void>,
+) {
+  const store = useMarkdownStore();
+  let unlisten: (() => void) | null = null;
+
+  async function takeRequest() {
+    await ready;
+    const request = await invoke<OpenRequest | null>("take_launch_request");
+    if (!request) return;
+
+    if (request.folder) {
+      await openFolder(request.folder);
+    }
+    for (const path of request.files) {
+      await loadFile(path);
+    }
+    for (const doc of request.documents) {
+      store.addDocument(doc.id, doc.title, doc.content);
+      store.setViewMode("preview");
+    }
+    const first = request.files[0] ?? request.documents[0]?.id;
+    if (first && (request.line !== null || request.anchor !== null)) {
+      store.pendingJump = { path: first, line: request.line, anchor: request.anchor };
+      const index = store.openFiles.findIndex((f) => f.path === first);
+      store.switchTab(index);
+    }
+  }
+
+  onMounted(async () => {
+    // Listen to this window only: --new-window requests target another label
+    unlisten = await getCurrentWebviewWindow().listen("cli://open", () => {
+      takeRequest().catch((e) => console.error("Failed to open from CLI:", e));
+    });
+    await takeRequest().catch((e) => console.error("Failed to open from CLI:", e));
+  });
+
+  onUnmounted(() => {
+    unlisten?.();
+  });
+}
@@ -1,26 +1,16 @@
 import MarkdownIt from "markdown-it";
-import hljs from "highlight.js";
 import { computed, type Ref } from "vue";
 
 const md = new MarkdownIt({
   html: false,
   linkify: true,
   typographer: true,
-  highlight(str: string, lang: string) {
-    if (lang && hljs.getLanguage(lang)) {
-      try {
-        return hljs.highlight(str, { language: lang }).value;
-      } catch {
-        // fallback
-      }
-    }
-    return "";
-  },
 });
 
-// Override fence rule to handle mermaid code blocks
-const defaultFence = md.renderer.rules.fence!;
-md.renderer.rules.fence = (tokens, idx, options, env, self) => {
+// Override fence rule: mermaid blocks become diagram placeholders; other code
+// blocks are rendered as plain text and highlighted by the backend afterwards
+// (see useCodeHighlight), keeping the full info string for `{2,5-7}` etc.
+md.renderer.rules.fence = (tokens, idx, _options, _env, self) => {
   const token = tokens[idx];
   const info = token.info.trim();
   if (info === "mermaid") {
@@ -31,9 +21,21 @@ md.renderer.rules.fence = (tokens, idx, options, env, self) => {
       .replace(/"/g, "&quot;");
     return `<div class="mermaid-block" data-source="${encoded}"></div>`;
   }
-  return defaultFence(tokens, idx, options, env, self);
+  const attrs = self.renderAttrs(token);
+  const code = md.utils.escapeHtml(token.content);
+  return `<pre class="code-block code-pending" data-info="${md.utils.escapeHtml(info)}"${attrs}><code>${code}</code></pre>\n`;
 };
 
+// Tag block elements with their 1-based source line so the preview can
+// scroll to a line (e.g. `cc-md-preview spec.md --line 120`)
+md.core.ruler.push("source_line", (state) => {
+  for (const token of state.tokens) {
+    if (token.map && token.nesting === 1) {
+      token.attrSet("data-line", String(token.map[0] + 1));
+    }
+  }
+});
+
 export function useMarkdownRenderer(source: Ref<string>) {
   const rendered = computed(() => {
     if (!source.value) return "";
@@ -0,0 +1,88 @@
+import { invoke } from "@tauri-apps/api/core";
+import { onMounted, watch } from "vue";
+import {
+  useMarkdownStore,
+  type MissingPath,
+  type ScrollAnchor,
+} from "@/stores/markdown";
+
+interface RestoredSession {
+  tabs: { path: string; content: string; scroll: ScrollAnchor | null }[];
+  activeIndex: number;
+  folder: string | null;
+  missing: MissingPath[];
+}
+
+const SAVE_DEBOUNCE_MS = 1000;
+
+/**
+ * Restores the previous session on mount and pushes changes back to the
+ * backend. The backend only flushes to disk periodically and on exit, so
+ * calling save_session often is cheap.
+ */
+export function useSessionPersistence(openFolder: (path: string) => Promise<void>) {
+  const store = useMarkdownStore();
+  let restored = false;
+  let timer: ReturnType<typeof setTimeout> | null = null;
+  let markRestored: () => void = () => {};
+  /** Resolves once the previous session has been applied (or failed to) */
+  const ready = new Promise<void>((resolve) => (markRestored = resolve));
+
+  onMounted(async () => {
+    try {
+      const session = await invoke<RestoredSession>("restore_session");
+      if (session.folder) {
+        await openFolder(session.folder);
+      }
+      if (session.tabs.length > 0) {
+        store.restoreTabs(
+          session.tabs.map((t) => ({
+            path: t.path,
+            content: t.content,
+            scroll: t.scroll ?? undefined,
+          })),
+          session.activeIndex,
+        );
+        if (!session.folder) {
+          store.setViewMode("preview");
+        }
+      }
+      store.sessionMissing = session.missing;
+    } catch (e) {
+      console.error("Failed to restore session:", e);
+    } finally {
+      restored = true;
+      markRestored();
+    }
+  });
+
+  function save() {
+    // In-memory documents (stdin) can't be reopened, so they are left out
+    const saved = store.openFiles.filter((f) => !f.inMemory);
+    const active = store.openFiles[store.activeIndex];
+    invoke("save_session", {
+      session: {
+        tabs: saved.map((f) => ({ path: f.path, scroll: f.scroll ?? null })),
+        activeIndex: Math.max(active ? saved.indexOf(active) : 0, 0),
+        folder: store.folderPath,
+      },
+    }).catch((e) => console.error("Failed to save session:", e));
+  }
+
+  watch(
+    () => [
+      store.openFiles.map((f) => [f.path, f.scroll?.heading, f.scroll?.offset]),
+      store.activeIndex,
+      store.folderPath,
+    ],
+    () => {
+      // Don't overwrite the saved session with the empty startup state
+      if (!restored) return;
+      if (timer) clearTimeout(timer);
+      timer = setTimeout(save, SAVE_DEBOUNCE_MS);
+    },
+    { deep: true },
+  );
+
+  return { ready };
+}
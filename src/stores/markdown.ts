@@ -1,5 +1,7 @@
 import { defineStore } from "pinia";
 import { ref, computed } from "vue";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 export type MarkdownTheme =
   | "github"
@@ -13,9 +15,36 @@ export type MarkdownTheme =
   | "sunset"
   | "arctic";
 
+/** Nearest heading (slug) above the viewport top, plus pixel offset from it */
+export interface ScrollAnchor {
+  heading: string | null;
+  offset: number;
+}
+
 export interface TabFile {
+  /** Absolute path, or a `stdin://N` id for in-memory documents */
   path: string;
   content: string;
+  scroll?: ScrollAnchor;
+  /** Tab label for in-memory documents (defaults to the file name) */
+  title?: string;
+  /** Not backed by a file: never reloaded or saved to the session */
+  inMemory?: boolean;
+}
+
+/** Where to scroll once a file has been rendered (from the command line) */
+export interface PendingJump {
+  path: string;
+  line: number | null;
+  anchor: string | null;
+}
+
+/** A tab or folder from the last session that could not be reopened */
+export interface MissingPath {
+  path: string;
+  kind: "file" | "folder";
+  reason: string;
+  suggestion: string | null;
 }
 
 export interface MarkdownFileInfo {
@@ -26,6 +55,49 @@ export interface MarkdownFileInfo {
   modified: string;
   preview: string;
   wordCount: number;
+  /** Present when the folder is inside a git repository */
+  git: GitFileInfo | null;
+}
+
+/** Mirrors `GitFileInfo` in src-tauri/src/git/status.rs */
+export interface GitFileInfo {
+  status: "clean" | "modified" | "untracked" | "ignored" | "conflicted";
+  staged: boolean;
+  lastCommit: CommitSummary | null;
+  commitCount: number;
+}
+
+/** Mirrors `CommitSummary` in src-tauri/src/git/history.rs */
+export interface CommitSummary {
+  id: string;
+  date: string;
+  author: string;
+  email: string;
+  subject: string;
+}
+
+/** Payload of `git://history`: commit history computed after the listing returned */
+export interface GitHistoryUpdate {
+  root: string;
+  files: { path: string; lastCommit: CommitSummary | null; commitCount: number }[];
+}
+
+/** Mirrors `RecentEntry` in src-tauri/src/state/recent.rs */
+export interface RecentEntry {
+  path: string;
+  kind: "file" | "folder";
+  openCount: number;
+  lastOpened: string;
+  pinned: boolean;
+  exists: boolean;
+}
+
+/** Mirrors `Settings` in src-tauri/src/state/settings.rs */
+export interface AppSettings {
+  version: number;
+  theme: MarkdownTheme;
+  scan: { followLinks: boolean; ignoredDirs: string[] };
+  cache: { browser: string; profile: string; confirmBeforeClean: boolean };
 }
 
 export type ViewMode = "preview" | "theme-selection" | "folder";
@@ -42,9 +114,15 @@ export const useMarkdownStore = defineStore("markdown", () => {
   const folderFiles = ref<MarkdownFileInfo[]>([]);
   const isScanningFolder = ref(false);
 
+  // Session restore
+  const sessionMissing = ref<MissingPath[]>([]);
+  const pendingJump = ref<PendingJump | null>(null);
+
   const filePath = computed(() => openFiles.value[activeIndex.value]?.path ?? null);
   const rawContent = computed(() => openFiles.value[activeIndex.value]?.content ?? "");
   const fileName = computed(() => {
+    const title = openFiles.value[activeIndex.value]?.title;
+    if (title) return title;
     if (!filePath.value) return null;
     const parts = filePath.value.replace(/\\/g, "/").split("/");
     return parts[parts.length - 1];
@@ -65,6 +143,22 @@ export const useMarkdownStore = defineStore("markdown", () => {
     ].slice(0, 10);
   }
 
+  /** Opens a document that only exists in memory (e.g. piped on stdin) */
+  function addDocument(id: string, title: string, content: string) {
+    openFiles.value.push({ path: id, content, title, inMemory: true });
+    activeIndex.value = openFiles.value.length - 1;
+  }
+
+  function restoreTabs(tabs: TabFile[], active: number) {
+    openFiles.value = tabs;
+    activeIndex.value = Math.min(active, Math.max(tabs.length - 1, 0));
+  }
+
+  function setScroll(anchor: ScrollAnchor) {
+    const tab = openFiles.value[activeIndex.value];
+    if (tab) tab.scroll = anchor;
+  }
+
   function switchTab(index: number) {
     if (index >= 0 && index < openFiles.value.length) {
       activeIndex.value = index;
@@ -101,6 +195,47 @@ export const useMarkdownStore = defineStore("markdown", () => {
 
   function setTheme(theme: MarkdownTheme) {
     currentTheme.value = theme;
+    invoke("update_settings", { patch: { theme } }).catch((e) =>
+      console.error("Failed to save theme:", e),
+    );
+  }
+
+  function applySettings(settings: AppSettings) {
+    currentTheme.value = settings.theme;
+  }
+
+  /** Restore persisted settings and follow later changes (other windows, hand edits) */
+  async function loadSettings() {
+    try {
+      applySettings(await invoke<AppSettings>("get_settings"));
+      await listen<AppSettings>("settings://changed", (e) => applySettings(e.payload));
+    } catch (e) {
+      console.error("Failed to load settings:", e);
+    }
+  }
+
+  /** Fill in commit history that the backend computes after listing a folder */
+  async function listenGitHistory() {
+    try {
+      await listen<GitHistoryUpdate>("git://history", (e) => applyGitHistory(e.payload));
+    } catch (e) {
+      console.error("Failed to listen for git history:", e);
+    }
+  }
+
+  // The event can arrive before the listing it belongs to has been stored
+  let lastGitHistory: GitHistoryUpdate | null = null;
+
+  function applyGitHistory(update: GitHistoryUpdate) {
+    lastGitHistory = update;
+    if (update.root !== folderPath.value) return;
+    const byPath = new Map(update.files.map((f) => [f.path, f]));
+    for (const file of folderFiles.value) {
+      const history = byPath.get(file.path);
+      if (!file.git || !history) continue;
+      file.git.lastCommit = history.lastCommit;
+      file.git.commitCount = history.commitCount;
+    }
   }
 
   function clear() {
@@ -124,6 +259,7 @@ export const useMarkdownStore = defineStore("markdown", () => {
     folderPath.value = path;
     folderFiles.value = files;
     viewMode.value = "folder";
+    if (lastGitHistory) applyGitHistory(lastGitHistory);
   }
 
   function clearFolder() {
@@ -146,13 +282,20 @@ export const useMarkdownStore = defineStore("markdown", () => {
     folderPath,
     folderFiles,
     isScanningFolder,
+    sessionMissing,
+    pendingJump,
     addFile,
+    addDocument,
+    restoreTabs,
+    setScroll,
     switchTab,
     closeTab,
     nextTab,
     prevTab,
     setFile,
     setTheme,
+    loadSettings,
+    listenGitHistory,
     clear,
     toggleViewMode,
     setViewMode,